@@ -3,13 +3,45 @@ use solana_program::{pubkey, pubkey::Pubkey};
 pub const BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX: &str = "mmm_buyside_sol_escrow_account";
 pub const POOL_PREFIX: &str = "mmm_pool";
 pub const SELL_STATE_PREFIX: &str = "mmm_sell_state";
+pub const TAKER_FEE_EXEMPTION_PREFIX: &str = "mmm_taker_fee_exemption";
 
 pub const MAX_TOTAL_PRICE: u64 = 8_000_000 * 1_000_000_000; // 8_000_000 SOL
 pub const MAX_METADATA_CREATOR_ROYALTY_BP: u16 = 3000;
 pub const MAX_REFERRAL_FEE_BP: i16 = 500;
 pub const MAX_LP_FEE_BP: u16 = 1000;
-pub const ALLOWLIST_MAX_LEN: usize = 6;
+pub const ALLOWLIST_MAX_LEN: usize = 8;
+// the first POOL_ALLOWLISTS_LEN entries are Pool::allowlists, the rest are Pool::allowlists_ext
+pub const POOL_ALLOWLISTS_LEN: usize = 6;
+pub const POOL_ALLOWLISTS_EXT_LEN: usize = 2;
+pub const LP_FEE_TIERS_MAX_LEN: usize = 4;
+pub const EXTRA_ROYALTY_RECIPIENTS_MAX_LEN: usize = 2;
+// each creator is paid with its own transfer CPI (~2k CU for sol, ~6k CU for spl), same cap as mpl-token-metadata
+pub const MAX_ROYALTY_CREATORS: usize = 5;
 pub const MIN_SOL_ESCROW_BALANCE_BP: u16 = 100;
+pub const POOL_VERSION: u8 = 2;
+pub const EXP_CURVE_PRECISION: u128 = 1_000_000_000; // extra decimals of the exp curve prices between steps
+pub const MAX_EXP_CURVE_DELTA: u64 = 90_000; // 10x per step
+pub const STEEP_EXP_CURVE_CHECK_STEPS: u32 = 5; // fulfill_sell steps that a steep exp curve has to price
+pub const MAX_FULFILL_SELL_BATCH_SIZE: u64 = 5; // keeps sol_fulfill_sell_batch under the compute limit
+pub const MAX_DEPOSIT_SELL_BATCH_SIZE: u64 = 5; // keeps deposit_sell_batch under the compute limit
+pub const MAX_FILL_ITEMS: u64 = 500; // keeps the exp and log curve loops under the compute limit
+
+// the action tag of the PoolEvent logged by create_pool and update_pool
+pub const POOL_EVENT_ACTION_CREATED: u8 = 0;
+pub const POOL_EVENT_ACTION_UPDATED: u8 = 1;
+
+// TokenMetadata additional_metadata keys of the token-2022 royalty config, the value of a
+// creator key is its share of the royalty
+pub const TOKEN_METADATA_ROYALTY_BP_KEY: &str = "seller_fee_basis_points";
+pub const TOKEN_METADATA_CREATOR_KEY_PREFIX: &str = "creator:";
+
+// allowlist_aux markers for metadata allowlists, an unmarked allowlist_aux is a URI prefix
+pub const METADATA_URI_SUFFIX_MARKER: &str = "suffix:";
+pub const METADATA_URI_CONTAINS_MARKER: &str = "contains:";
+pub const ALLOWLIST_AUX_MAX_LEN: usize = 32; // the allowlist_aux stored on the pool
+
+// an oracle account has its price in lamports as a little endian u64 at the start of its data
+pub const ORACLE_PRICE_LEN: usize = 8;
 
 pub const CANCEL_AUTHORITY: Pubkey = if cfg!(feature = "anchor-test") {
     pubkey!("testZY18qdvfWNn1mTn7PvywdLdwWWsgqLXvvztKAtD")
@@ -21,3 +53,5 @@ pub const MPL_TOKEN_AUTH_RULES: Pubkey = pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAg
 pub const M2_PROGRAM: Pubkey = pubkey!("M2mx93ekt1fmXSVkTrUL9xVFHkmME8HTUi5Cyc5aF7K");
 pub const M2_PREFIX: &str = "m2";
 pub const M2_AUCTION_HOUSE: Pubkey = pubkey!("E8cU1WiRWjanGxmn96ewBgk9vPTcL6AEZ1t6F6fkgUWe");
+
+pub const TRANSFER_HOOK_EXTRA_ACCOUNT_METAS_PREFIX: &str = "extra-account-metas";
@@ -66,4 +66,84 @@ pub enum MMMErrorCode {
     InvalidTokenMetadataExtension, // 0x178e
     #[msg("Invalid token member extensions")]
     InvalidTokenMemberExtension, // 0x178f
+    #[msg("invalid price_floor")]
+    InvalidPriceFloor, // 0x1790
+    #[msg("invalid price_ceiling")]
+    InvalidPriceCeiling, // 0x1791
+    #[msg("expiry must be in the future")]
+    InvalidExpiry, // 0x1792
+    #[msg("invalid lp fee decay")]
+    InvalidLpFeeDecay, // 0x1793
+    #[msg("invalid lp fee tiers")]
+    InvalidLpFeeTiers, // 0x1794
+    #[msg("invalid extra royalty recipients")]
+    InvalidExtraRoyaltyRecipients, // 0x1795
+    #[msg("price slippage exceeded")]
+    PriceSlippageExceeded, // 0x1796
+    #[msg("invalid batch size")]
+    InvalidBatchSize, // 0x1797
+    #[msg("transfer fee exceeds the transferred amount")]
+    TransferFeeExceedsAmount, // 0x1798
+    #[msg("missing transfer hook accounts")]
+    MissingTransferHookAccounts, // 0x1799
+    #[msg("pool already migrated")]
+    PoolAlreadyMigrated, // 0x179a
+    #[msg("too many royalty creators")]
+    TooManyRoyaltyCreators, // 0x179b
+    #[msg("spot price move exceeds max_spot_move_bp")]
+    SpotPriceMoveExceeded, // 0x179c
+    #[msg("invalid lp fee rounding")]
+    InvalidLpFeeRounding, // 0x179d
+    #[msg("buyside spend exceeds max_buyside_spend")]
+    BuysideSpendExceeded, // 0x179e
+    #[msg("invalid pool side")]
+    InvalidPoolSide, // 0x179f
+    #[msg("the pool does not fulfill this side")]
+    PoolSideNotAllowed, // 0x17a0
+    #[msg("collection is not a sized collection")]
+    UnsizedCollection, // 0x17a1
+    #[msg("referral can not be the pool owner or the counterparty")]
+    SelfReferral, // 0x17a2
+    #[msg("maker rebate can not exceed the taker fee")]
+    MakerRebateExceedsTakerFee, // 0x17a3
+    #[msg("wsol balance is below the payment amount")]
+    InsufficientWsolBalance, // 0x17a4
+    #[msg("wsol settlement is not supported by this fulfill")]
+    WsolSettlementNotSupported, // 0x17a5
+    #[msg("master edition allows prints, the pool only takes 1/1s")]
+    NotOneOfOne, // 0x17a6
+    #[msg("shared escrow balance is below the withdraw amount")]
+    InsufficientSharedEscrowBalance, // 0x17a7
+    #[msg("invalid royalty enforcement mode")]
+    InvalidRoyaltyEnforcementMode, // 0x17a8
+    #[msg("max total price is below the spot price")]
+    InvalidMaxTotalPrice, // 0x17a9
+    #[msg("fill asset amount is below the pool min_fill_amount")]
+    FillBelowMinFillAmount, // 0x17aa
+    #[msg("invalid fill cooldown")]
+    InvalidFillCooldown, // 0x17ab
+    #[msg("the pool was filled within its fill cooldown")]
+    FillCooldownActive, // 0x17ac
+    #[msg("invalid allowlist aux")]
+    InvalidAllowlistAux, // 0x17ad
+    #[msg("creator shares must sum to 100")]
+    InvalidCreatorShares, // 0x17ae
+    #[msg("invalid oracle account")]
+    InvalidOracle, // 0x17af
+    #[msg("spot price deviates from the oracle price")]
+    OraclePriceDeviation, // 0x17b0
+    #[msg("a royalty share is below the rent of its recipient account")]
+    CreatorNotPaid, // 0x17b1
+    #[msg("invalid curve direction")]
+    InvalidCurveDirection, // 0x17b2
+    #[msg("buyside escrow balance is below the total price of the fill")]
+    EscrowUnderfunded, // 0x17b3
+    #[msg("token program does not match the mint")]
+    InvalidTokenProgram, // 0x17b4
+    #[msg("fill asset amount is above the max items of a single fill")]
+    FillExceedsMaxItems, // 0x17b5
+    #[msg("royalty bp override is below the royalty bp of the mint")]
+    InvalidRoyaltyBpOverride, // 0x17b6
+    #[msg("pool account layout can not be migrated")]
+    InvalidPoolLayout, // 0x17b7
 }
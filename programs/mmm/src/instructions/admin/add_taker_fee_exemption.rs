@@ -0,0 +1,42 @@
+use super::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AddTakerFeeExemptionArgs {
+    pub taker: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(args:AddTakerFeeExemptionArgs)]
+pub struct AddTakerFeeExemption<'info> {
+    #[account(mut)]
+    pub cosigner: Signer<'info>,
+    /// CHECK: Owner is only used for seed derivation. Cosigner has_one constraint is checked in the pool account.
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        bump,
+        has_one = cosigner @ MMMErrorCode::InvalidCosigner,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    #[account(
+        init,
+        payer = cosigner,
+        seeds = [
+            TAKER_FEE_EXEMPTION_PREFIX.as_bytes(),
+            pool.key().as_ref(),
+            args.taker.as_ref(),
+        ],
+        space = TakerFeeExemption::LEN,
+        bump
+    )]
+    pub taker_fee_exemption: Account<'info, TakerFeeExemption>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddTakerFeeExemption>, args: AddTakerFeeExemptionArgs) -> Result<()> {
+    let taker_fee_exemption = &mut ctx.accounts.taker_fee_exemption;
+    taker_fee_exemption.pool = ctx.accounts.pool.key();
+    taker_fee_exemption.taker = args.taker;
+
+    Ok(())
+}
@@ -13,11 +13,47 @@ pub struct CreatePoolArgs {
     pub referral: Pubkey,
     pub cosigner_annotation: [u8; 32],
     pub buyside_creator_royalty_bp: u16,
+    pub price_floor: Option<u64>,
+    pub price_ceiling: Option<u64>,
+    pub lp_fee_decay_end: Option<i64>,
+    pub lp_fee_bp_floor: Option<u16>,
+    pub lp_fee_tiers: Option<Vec<LpFeeTier>>,
+    pub min_escrow_balance_bp: Option<u16>,
+    pub extra_royalty_recipients: Option<Vec<RoyaltyRecipient>>,
+    pub max_spot_move_bp: Option<u16>,
+    pub lp_fee_rounding: Option<u8>,
+    pub max_fill_depth: Option<u16>,
+    pub max_buyside_spend: Option<u64>,
+    pub pool_side: Option<u8>,
+    pub require_sized_collection: Option<bool>,
+    pub reject_self_referral: Option<bool>,
+    pub settle_in_wsol: Option<bool>,
+    pub require_one_of_one: Option<bool>,
+    pub require_programmable: Option<bool>,
+    pub curve_delta_sell: Option<u64>,
+    pub royalty_enforcement_mode: Option<u8>,
+    pub max_total_price: Option<u64>,
+    pub min_fill_amount: Option<u64>,
+    pub fill_cooldown_secs: Option<i64>,
+    pub sell_state_rent_to_taker: Option<bool>,
+    pub allowlist_aux: Option<String>,
+    pub royalty_fallback_account: Option<Pubkey>,
+    pub profit_skim_bp: Option<u16>,
+    pub oracle: Option<Pubkey>,
+    pub max_oracle_deviation_bp: Option<u16>,
+    pub require_all_creators_paid: Option<bool>,
+    pub curve_direction: Option<u8>,
+    pub require_collection_metadata: Option<bool>,
+    pub static_spot: Option<bool>,
+    pub allow_royalty_fallback: Option<bool>,
 
     // immutable
-    pub uuid: Pubkey, // randomly generated keypair
+    // randomly generated keypair, or the collection mint of a single-collection pool, so that
+    // the pool of an (owner, collection) pair can be derived without tracking the uuid
+    pub uuid: Pubkey,
     pub payment_mint: Pubkey,
     pub allowlists: [Allowlist; ALLOWLIST_MAX_LEN],
+    pub allowlist_match_mode: Option<u8>,
 }
 
 #[derive(Accounts)]
@@ -35,9 +71,21 @@ pub struct CreatePool<'info> {
         space = Pool::LEN,
         constraint = args.lp_fee_bp <= MAX_LP_FEE_BP @ MMMErrorCode::InvalidBP,
         constraint = args.buyside_creator_royalty_bp <= 10000 @ MMMErrorCode::InvalidBP,
+        constraint = args.min_escrow_balance_bp.unwrap_or(0) <= 10000 @ MMMErrorCode::InvalidBP,
+        constraint = args.max_spot_move_bp.unwrap_or(0) <= 10000 @ MMMErrorCode::InvalidBP,
+        constraint = args.profit_skim_bp.unwrap_or(0) <= 10000 @ MMMErrorCode::InvalidBP,
+        constraint = args.lp_fee_rounding.unwrap_or(LP_FEE_ROUNDING_FLOOR) <= LP_FEE_ROUNDING_NEAREST @ MMMErrorCode::InvalidLpFeeRounding,
+        constraint = args.pool_side.unwrap_or(POOL_SIDE_TWO_SIDED) <= POOL_SIDE_SELL_ONLY @ MMMErrorCode::InvalidPoolSide,
+        constraint = args.royalty_enforcement_mode.unwrap_or(ROYALTY_ENFORCEMENT_MODE_OPTIONAL) <= ROYALTY_ENFORCEMENT_MODE_BYPASS @ MMMErrorCode::InvalidRoyaltyEnforcementMode,
         constraint = args.spot_price > 0 @ MMMErrorCode::InvalidSpotPrice,
+        constraint = args.fill_cooldown_secs.unwrap_or(0) >= 0 @ MMMErrorCode::InvalidFillCooldown,
+        constraint = args.expiry == 0 || args.expiry > Clock::get().unwrap().unix_timestamp @ MMMErrorCode::InvalidExpiry,
+        constraint = args.price_floor.unwrap_or(0) <= args.spot_price @ MMMErrorCode::InvalidPriceFloor,
+        constraint = args.price_ceiling.unwrap_or(0) == 0 || args.price_ceiling.unwrap_or(0) >= args.spot_price @ MMMErrorCode::InvalidPriceCeiling,
+        constraint = args.max_total_price.unwrap_or(0) == 0 || args.max_total_price.unwrap_or(0) >= args.spot_price @ MMMErrorCode::InvalidMaxTotalPrice,
         constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint, // remove this when we have spl token support
         constraint = args.referral.ne(owner.key) @ MMMErrorCode::InvalidReferral,
+        constraint = args.allowlist_match_mode.unwrap_or(ALLOWLIST_MATCH_MODE_UNION) <= ALLOWLIST_MATCH_MODE_INTERSECTION @ MMMErrorCode::InvalidAllowLists,
     )]
     pub pool: Box<Account<'info, Pool>>,
     pub system_program: Program<'info, System>,
@@ -48,11 +96,33 @@ pub fn handler(ctx: Context<CreatePool>, args: CreatePoolArgs) -> Result<()> {
     let owner = &ctx.accounts.owner;
     let cosigner = &ctx.accounts.cosigner;
 
-    check_allowlists(&args.allowlists)?;
-    check_curve(args.curve_type, args.curve_delta)?;
+    check_allowlists(
+        &args.allowlists,
+        args.allowlist_match_mode
+            .unwrap_or(ALLOWLIST_MATCH_MODE_UNION),
+    )?;
+    check_curve(
+        args.curve_type,
+        args.curve_delta,
+        args.spot_price,
+        args.max_fill_depth.unwrap_or(0),
+        args.curve_direction.unwrap_or(CURVE_DIRECTION_NORMAL),
+        get_max_total_price(args.max_total_price.unwrap_or(0)),
+    )?;
+    let curve_delta_sell = args.curve_delta_sell.unwrap_or(0);
+    check_curve_delta_sell(args.curve_type, curve_delta_sell, args.spot_price)?;
+    check_lp_fee_decay(
+        args.lp_fee_bp,
+        args.lp_fee_bp_floor.unwrap_or(0),
+        args.lp_fee_decay_end.unwrap_or(0),
+    )?;
+    let lp_fee_tiers = get_lp_fee_tiers(&args.lp_fee_tiers.unwrap_or_default())?;
+    let extra_royalty_recipients =
+        get_extra_royalty_recipients(&args.extra_royalty_recipients.unwrap_or_default())?;
 
     // mutable
     pool.spot_price = args.spot_price;
+    pool.log_curve_base_price = args.spot_price;
     pool.curve_type = args.curve_type;
     pool.curve_delta = args.curve_delta;
     pool.reinvest_fulfill_buy = args.reinvest_fulfill_buy;
@@ -62,20 +132,65 @@ pub fn handler(ctx: Context<CreatePool>, args: CreatePoolArgs) -> Result<()> {
     pool.referral = args.referral;
     pool.cosigner_annotation = args.cosigner_annotation;
     pool.buyside_creator_royalty_bp = args.buyside_creator_royalty_bp;
+    pool.price_floor = args.price_floor.unwrap_or(0);
+    pool.price_ceiling = args.price_ceiling.unwrap_or(0);
+    pool.lp_fee_decay_start = Clock::get()?.unix_timestamp;
+    pool.lp_fee_decay_end = args.lp_fee_decay_end.unwrap_or(0);
+    pool.lp_fee_bp_floor = args.lp_fee_bp_floor.unwrap_or(0);
+    pool.lp_fee_tiers = lp_fee_tiers;
+    pool.min_escrow_balance_bp = args.min_escrow_balance_bp.unwrap_or(0);
+    pool.extra_royalty_recipients = extra_royalty_recipients;
+    pool.max_spot_move_bp = args.max_spot_move_bp.unwrap_or(0);
+    pool.lp_fee_rounding = args.lp_fee_rounding.unwrap_or(LP_FEE_ROUNDING_FLOOR);
+    pool.max_fill_depth = args.max_fill_depth.unwrap_or(0);
+    pool.max_buyside_spend = args.max_buyside_spend.unwrap_or(0);
+    pool.pool_side = args.pool_side.unwrap_or(POOL_SIDE_TWO_SIDED);
+    pool.require_sized_collection = args.require_sized_collection.unwrap_or(false);
+    pool.reject_self_referral = args.reject_self_referral.unwrap_or(false);
+    pool.settle_in_wsol = args.settle_in_wsol.unwrap_or(false);
+    pool.require_one_of_one = args.require_one_of_one.unwrap_or(false);
+    pool.require_programmable = args.require_programmable.unwrap_or(false);
+    pool.curve_delta_sell = curve_delta_sell;
+    pool.royalty_enforcement_mode = args
+        .royalty_enforcement_mode
+        .unwrap_or(ROYALTY_ENFORCEMENT_MODE_OPTIONAL);
+    pool.max_total_price = args.max_total_price.unwrap_or(0);
+    pool.min_fill_amount = args.min_fill_amount.unwrap_or(0);
+    pool.fill_cooldown_secs = args.fill_cooldown_secs.unwrap_or(0);
+    pool.sell_state_rent_to_taker = args.sell_state_rent_to_taker.unwrap_or(false);
+    pool.allowlist_aux = get_allowlist_aux_bytes(&args.allowlist_aux.unwrap_or_default())?;
+    pool.royalty_fallback_account = args.royalty_fallback_account.unwrap_or_default();
+    pool.profit_skim_bp = args.profit_skim_bp.unwrap_or(0);
+    pool.oracle = args.oracle.unwrap_or_default();
+    pool.max_oracle_deviation_bp = args.max_oracle_deviation_bp.unwrap_or(0);
+    pool.require_all_creators_paid = args.require_all_creators_paid.unwrap_or(false);
+    pool.curve_direction = args.curve_direction.unwrap_or(CURVE_DIRECTION_NORMAL);
+    pool.require_collection_metadata = args.require_collection_metadata.unwrap_or(false);
+    pool.static_spot = args.static_spot.unwrap_or(false);
+    pool.allow_royalty_fallback = args.allow_royalty_fallback.unwrap_or(false);
+    pool.version = POOL_VERSION;
 
     // state variables
     pool.sellside_asset_amount = 0; // always equal to the number of NFTs in the pool
     pool.buyside_payment_amount = 0;
     pool.lp_fee_earned = 0;
+    pool.buyside_spent = 0;
+    pool.total_volume_sol = 0;
+    pool.fill_count = 0;
+    pool.last_fill_ts = 0;
 
     // immutable
     pool.owner = owner.key();
     pool.cosigner = cosigner.key();
     pool.uuid = args.uuid;
     pool.payment_mint = args.payment_mint;
-    pool.allowlists = args.allowlists;
+    pool.set_allowlists(&args.allowlists);
+    pool.allowlist_match_mode = args
+        .allowlist_match_mode
+        .unwrap_or(ALLOWLIST_MATCH_MODE_UNION);
 
     log_pool("post_create_pool", pool)?;
+    log_pool_event("pool_created", POOL_EVENT_ACTION_CREATED, pool)?;
 
     Ok(())
 }
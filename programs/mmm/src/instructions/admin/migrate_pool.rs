@@ -0,0 +1,69 @@
+use super::*;
+use anchor_lang::Discriminator;
+
+// MigratePool brings a pool created under an older layout up to POOL_VERSION.
+// The pool is taken as an unchecked account because an old layout can be shorter
+// than Pool::LEN, in which case it's grown first. New fields live in the zeroed tail
+// of the account, so the realloc leaves all of them at their zero default, and
+// the allowlists_ext of a Pool::LEGACY_LEN pool are empty.
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// CHECK: the discriminator, the layout length and the owner are checked before the realloc
+    #[account(mut, owner = crate::ID)]
+    pub pool: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigratePool>) -> Result<()> {
+    let owner = &ctx.accounts.owner;
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let system_program = &ctx.accounts.system_program;
+
+    let legacy_len = pool_info.data_len();
+    {
+        // the legacy layout is checked before the realloc, so that no other
+        // account of the program can be grown as if it was a pool
+        let data = pool_info.try_borrow_data()?;
+        if legacy_len < 8 || data[..8] != Pool::discriminator() {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        if legacy_len != Pool::LEN && legacy_len != Pool::LEGACY_LEN {
+            return Err(MMMErrorCode::InvalidPoolLayout.into());
+        }
+        if data[Pool::OWNER_OFFSET..Pool::OWNER_OFFSET + 32] != owner.key().to_bytes() {
+            return Err(MMMErrorCode::InvalidOwner.into());
+        }
+    }
+    if legacy_len < Pool::LEN {
+        let rent_top_up = Rent::get()?
+            .minimum_balance(Pool::LEN)
+            .saturating_sub(pool_info.lamports());
+        if rent_top_up > 0 {
+            anchor_lang::solana_program::program::invoke(
+                &anchor_lang::solana_program::system_instruction::transfer(
+                    owner.key,
+                    pool_info.key,
+                    rent_top_up,
+                ),
+                &[
+                    owner.to_account_info(),
+                    pool_info.clone(),
+                    system_program.to_account_info(),
+                ],
+            )?;
+        }
+        pool_info.realloc(Pool::LEN, true)?;
+    }
+
+    let mut pool = Pool::try_deserialize(&mut &pool_info.data.borrow()[..])?;
+    if pool.version >= POOL_VERSION {
+        return Err(MMMErrorCode::PoolAlreadyMigrated.into());
+    }
+    pool.version = POOL_VERSION;
+    pool.try_serialize(&mut &mut pool_info.data.borrow_mut()[..])?;
+
+    log_pool("post_migrate_pool", &pool)?;
+    Ok(())
+}
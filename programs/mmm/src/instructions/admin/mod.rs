@@ -2,14 +2,30 @@
 
 use super::*;
 
+pub mod add_taker_fee_exemption;
 pub mod create_pool;
+pub mod migrate_pool;
+pub mod owner_drain_and_close;
+pub mod reconcile_shared_escrow;
+pub mod remove_taker_fee_exemption;
+pub mod reprice_pool;
+pub mod reset_pool_stats;
 pub mod set_shared_escrow;
 pub mod sol_close_pool;
+pub mod transfer_pool_ownership;
 pub mod update_allowlists;
 pub mod update_pool;
 
+pub use add_taker_fee_exemption::*;
 pub use create_pool::*;
+pub use migrate_pool::*;
+pub use owner_drain_and_close::*;
+pub use reconcile_shared_escrow::*;
+pub use remove_taker_fee_exemption::*;
+pub use reprice_pool::*;
+pub use reset_pool_stats::*;
 pub use set_shared_escrow::*;
 pub use sol_close_pool::*;
+pub use transfer_pool_ownership::*;
 pub use update_allowlists::*;
 pub use update_pool::*;
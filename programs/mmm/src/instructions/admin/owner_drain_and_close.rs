@@ -0,0 +1,152 @@
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use super::*;
+use crate::{ata::init_if_needed_ata, state::SellState};
+
+// the accounts of each sellside mint in the remaining accounts:
+//   asset_mint, sellside_escrow_token_account, owner_asset_account, sell_state
+const DRAIN_MINT_ACCOUNTS_LEN: usize = 4;
+
+// OwnerDrainAndClose withdraws everything the pool holds back to the owner and closes it,
+// so the owner doesn't need a withdraw_sell per mint, a sol_withdraw_buy and a close.
+// Every sellside mint has to be passed in the remaining accounts, because the pool
+// is only closed when the sellside asset amount reaches zero.
+#[derive(Accounts)]
+pub struct OwnerDrainAndClose<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub cosigner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+        has_one = cosigner @ MMMErrorCode::InvalidCosigner,
+        constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    /// CHECK: it's a pda, and the private key is owned by the seeds
+    #[account(
+        mut,
+        seeds = [BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump,
+    )]
+    pub buyside_sol_escrow_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, OwnerDrainAndClose<'info>>) -> Result<()> {
+    let owner = &ctx.accounts.owner;
+    let buyside_sol_escrow_account = &ctx.accounts.buyside_sol_escrow_account;
+    let system_program = &ctx.accounts.system_program;
+    let token_program = &ctx.accounts.token_program;
+    let associated_token_program = &ctx.accounts.associated_token_program;
+    let rent = &ctx.accounts.rent;
+    let pool = &mut ctx.accounts.pool;
+    let pool_key = pool.key();
+    let pool_uuid = pool.uuid;
+    let pool_seeds: &[&[&[u8]]] = &[&[
+        POOL_PREFIX.as_bytes(),
+        owner.key.as_ref(),
+        pool_uuid.as_ref(),
+        &[ctx.bumps.pool],
+    ]];
+    let buyside_sol_escrow_account_seeds: &[&[&[u8]]] = &[&[
+        BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX.as_bytes(),
+        pool_key.as_ref(),
+        &[ctx.bumps.buyside_sol_escrow_account],
+    ]];
+
+    let remaining_accounts = ctx.remaining_accounts.chunks_exact(DRAIN_MINT_ACCOUNTS_LEN);
+    if !remaining_accounts.remainder().is_empty() {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    }
+    for mint_accounts in remaining_accounts {
+        let asset_mint = InterfaceAccount::<Mint>::try_from(&mint_accounts[0])?;
+        let sellside_escrow_token_account_info = &mint_accounts[1];
+        let owner_asset_account = &mint_accounts[2];
+        let sell_state_info = &mint_accounts[3];
+
+        if sellside_escrow_token_account_info.key()
+            != get_associated_token_address_with_program_id(
+                &pool_key,
+                &asset_mint.key(),
+                token_program.key,
+            )
+        {
+            return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+        }
+        let sellside_escrow_token_account =
+            InterfaceAccount::<TokenAccount>::try_from(sellside_escrow_token_account_info)?;
+        let mut sell_state = Account::<SellState>::try_from(sell_state_info)?;
+        if sell_state.pool != pool_key || sell_state.asset_mint != asset_mint.key() {
+            return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+        }
+
+        init_if_needed_ata(
+            owner_asset_account.to_account_info(),
+            owner.to_account_info(),
+            owner.to_account_info(),
+            asset_mint.to_account_info(),
+            associated_token_program.to_account_info(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+        )?;
+
+        // like withdraw_sell, but the whole escrow token account is returned and closed
+        anchor_spl::token_2022::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                anchor_spl::token_2022::TransferChecked {
+                    from: sellside_escrow_token_account.to_account_info(),
+                    mint: asset_mint.to_account_info(),
+                    to: owner_asset_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                pool_seeds,
+            ),
+            sellside_escrow_token_account.amount,
+            asset_mint.decimals,
+        )?;
+        anchor_spl::token_2022::close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            anchor_spl::token_2022::CloseAccount {
+                account: sellside_escrow_token_account.to_account_info(),
+                destination: owner.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            pool_seeds,
+        ))?;
+
+        pool.sellside_asset_amount = pool
+            .sellside_asset_amount
+            .checked_sub(sell_state.asset_amount)
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+        sell_state.asset_amount = 0;
+        try_close_sell_state(&sell_state, owner.to_account_info())?;
+    }
+    if pool.sellside_asset_amount != 0 {
+        return Err(MMMErrorCode::NotEmptySellsideAssetAmount.into());
+    }
+
+    // the escrow lamports go to the pool first, and then to the owner when the pool closes
+    close_escrow(
+        &buyside_sol_escrow_account.to_account_info(),
+        pool,
+        system_program,
+        buyside_sol_escrow_account_seeds,
+    )?;
+
+    pool.buyside_payment_amount = buyside_sol_escrow_account.lamports();
+    log_pool("post_owner_drain_and_close", pool)?;
+    try_close_pool(pool, owner.to_account_info())?;
+    Ok(())
+}
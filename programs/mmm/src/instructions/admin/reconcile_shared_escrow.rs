@@ -0,0 +1,47 @@
+use super::*;
+
+// ReconcileSharedEscrow corrects the shared_escrow_count of a pool against the actual
+// balance of its M2 shared escrow. The shared escrow can be drained out of band, and then
+// the count is stuck above the number of fills the escrow can still pay for, which keeps
+// try_close_pool from closing the pool. The count is lowered to the number of fills that
+// the balance covers at the current curve, maker fees aside, and it's never raised, that's
+// still up to set_shared_escrow. It's signed by the owner alone.
+// Remaining accounts
+//   0: m2_program
+//   1: shared_escrow_account
+#[derive(Accounts)]
+pub struct ReconcileSharedEscrow<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        bump,
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+        constraint = pool.using_shared_escrow() @ MMMErrorCode::InvalidAccountState,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+pub fn handler(ctx: Context<ReconcileSharedEscrow>) -> Result<()> {
+    let remaining_accounts = ctx.remaining_accounts;
+    let pool = &mut ctx.accounts.pool;
+
+    check_remaining_accounts_for_m2(remaining_accounts, &pool.owner)?;
+    let shared_escrow_balance = remaining_accounts[1].lamports();
+
+    // binary search for the last count within the balance, the cost only grows with it.
+    // a fill that overflows is never affordable
+    let (mut lo, mut hi) = (0, pool.shared_escrow_count);
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        match get_sol_total_price_and_next_price(pool, mid, true) {
+            Ok((total_price, _)) if total_price <= shared_escrow_balance => lo = mid,
+            _ => hi = mid - 1,
+        }
+    }
+
+    pool.shared_escrow_count = lo;
+    log_pool("post_reconcile_shared_escrow", pool)?;
+
+    Ok(())
+}
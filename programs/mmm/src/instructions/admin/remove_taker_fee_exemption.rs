@@ -0,0 +1,30 @@
+use super::*;
+
+#[derive(Accounts)]
+pub struct RemoveTakerFeeExemption<'info> {
+    #[account(mut)]
+    pub cosigner: Signer<'info>,
+    /// CHECK: Owner is only used for seed derivation. Cosigner has_one constraint is checked in the pool account.
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        bump,
+        has_one = cosigner @ MMMErrorCode::InvalidCosigner,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    #[account(
+        mut,
+        seeds = [
+            TAKER_FEE_EXEMPTION_PREFIX.as_bytes(),
+            pool.key().as_ref(),
+            taker_fee_exemption.taker.as_ref(),
+        ],
+        bump,
+        close = cosigner
+    )]
+    pub taker_fee_exemption: Account<'info, TakerFeeExemption>,
+}
+
+pub fn handler(_ctx: Context<RemoveTakerFeeExemption>) -> Result<()> {
+    Ok(())
+}
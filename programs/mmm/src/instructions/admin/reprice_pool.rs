@@ -0,0 +1,56 @@
+use super::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RepricePoolArgs {
+    pub spot_price: u64,
+    pub curve_delta: Option<u64>, // None means unchanged
+}
+
+// RepricePool only moves the spot price and optionally the curve delta, so that an owner
+// running a strategy can re-peg the pool without rewriting the rest of it through
+// update_pool. It's signed by the owner alone.
+#[derive(Accounts)]
+#[instruction(args:RepricePoolArgs)]
+pub struct RepricePool<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        bump,
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+        constraint = args.spot_price > 0 @ MMMErrorCode::InvalidSpotPrice,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+pub fn handler(ctx: Context<RepricePool>, args: RepricePoolArgs) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let curve_delta = args.curve_delta.unwrap_or(pool.curve_delta);
+    check_curve(
+        pool.curve_type,
+        curve_delta,
+        args.spot_price,
+        pool.max_fill_depth,
+        pool.curve_direction,
+        get_max_total_price(pool.max_total_price),
+    )?;
+
+    pool.spot_price = args.spot_price;
+    pool.log_curve_base_price = args.spot_price;
+    pool.curve_delta = curve_delta;
+    if pool.price_floor > pool.spot_price {
+        return Err(MMMErrorCode::InvalidPriceFloor.into());
+    }
+    if pool.price_ceiling != 0 && pool.price_ceiling < pool.spot_price {
+        return Err(MMMErrorCode::InvalidPriceCeiling.into());
+    }
+    // the whole sellside inventory still needs to be priceable from the new spot price
+    if pool.sellside_asset_amount > 0 {
+        get_sol_total_price_and_next_price(pool, pool.sellside_asset_amount, false)?;
+    }
+
+    log_pool("post_reprice_pool", pool)?;
+    log_pool_event("pool_updated", POOL_EVENT_ACTION_UPDATED, pool)?;
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::solana_program::log::sol_log_data;
+
+use super::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ResetPoolStatsArgs {
+    pub total_volume_sol: bool,
+    pub fill_count: bool,
+    pub lp_fee_earned: bool,
+}
+
+// the counters of the pool before a reset, whether or not each of them was reset
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StatsReset {
+    pub pool: Pubkey,
+    pub total_volume_sol: u128,
+    pub fill_count: u64,
+    pub lp_fee_earned: u64,
+}
+
+// ResetPoolStats zeroes the chosen fill counters, so that an owner reporting per epoch can
+// start each epoch from zero. It's signed by the owner alone, and logs the counters from
+// before the reset.
+#[derive(Accounts)]
+pub struct ResetPoolStats<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        bump,
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+pub fn handler(ctx: Context<ResetPoolStats>, args: ResetPoolStatsArgs) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let stats_reset = StatsReset {
+        pool: pool.key(),
+        total_volume_sol: pool.total_volume_sol,
+        fill_count: pool.fill_count,
+        lp_fee_earned: pool.lp_fee_earned,
+    };
+    if args.total_volume_sol {
+        pool.total_volume_sol = 0;
+    }
+    if args.fill_count {
+        pool.fill_count = 0;
+    }
+    if args.lp_fee_earned {
+        pool.lp_fee_earned = 0;
+    }
+    msg!("stats_reset");
+    sol_log_data(&[&stats_reset.try_to_vec()?]);
+
+    log_pool("post_reset_pool_stats", pool)?;
+
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use super::*;
+use crate::{ata::init_if_needed_ata, state::SellState};
+
+// the accounts of each sellside mint in the remaining accounts:
+//   asset_mint, sellside_escrow_token_account, new_sellside_escrow_token_account,
+//   sell_state, new_sell_state
+const TRANSFER_MINT_ACCOUNTS_LEN: usize = 5;
+
+// TransferPoolOwnership hands the pool to new_owner. The owner is part of the pool seeds,
+// and the escrow, the sellside token accounts and the sell states are all derived from the
+// pool key, so the pool can't just change its owner field. Instead the pool is reopened
+// at the new_owner derived PDA with the same uuid and state, everything it holds is moved
+// over, and the old pool is closed to the owner.
+// Every sellside mint has to be passed in the remaining accounts so that the old pool
+// can be closed, and pools on a shared escrow can't be transferred because the shared
+// escrow belongs to the owner.
+#[derive(Accounts)]
+pub struct TransferPoolOwnership<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        constraint = new_owner.key() != owner.key() @ MMMErrorCode::InvalidOwner,
+        constraint = new_owner.key() != pool.referral @ MMMErrorCode::InvalidReferral,
+    )]
+    pub new_owner: Signer<'info>,
+    pub cosigner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+        has_one = cosigner @ MMMErrorCode::InvalidCosigner,
+        constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        constraint = !pool.using_shared_escrow() @ MMMErrorCode::InvalidAccountState,
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [POOL_PREFIX.as_bytes(), new_owner.key().as_ref(), pool.uuid.as_ref()],
+        bump,
+        space = Pool::LEN,
+    )]
+    pub new_pool: Box<Account<'info, Pool>>,
+    /// CHECK: it's a pda, and the private key is owned by the seeds
+    #[account(
+        mut,
+        seeds = [BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump,
+    )]
+    pub buyside_sol_escrow_account: UncheckedAccount<'info>,
+    /// CHECK: it's a pda, and the private key is owned by the seeds
+    #[account(
+        mut,
+        seeds = [BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX.as_bytes(), new_pool.key().as_ref()],
+        bump,
+    )]
+    pub new_buyside_sol_escrow_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, TransferPoolOwnership<'info>>,
+) -> Result<()> {
+    let owner = &ctx.accounts.owner;
+    let new_owner = &ctx.accounts.new_owner;
+    let buyside_sol_escrow_account = &ctx.accounts.buyside_sol_escrow_account;
+    let new_buyside_sol_escrow_account = &ctx.accounts.new_buyside_sol_escrow_account;
+    let system_program = &ctx.accounts.system_program;
+    let token_program = &ctx.accounts.token_program;
+    let associated_token_program = &ctx.accounts.associated_token_program;
+    let rent = &ctx.accounts.rent;
+    let pool = &mut ctx.accounts.pool;
+    let new_pool = &mut ctx.accounts.new_pool;
+    let pool_key = pool.key();
+    let new_pool_key = new_pool.key();
+    let pool_uuid = pool.uuid;
+    let pool_seeds: &[&[&[u8]]] = &[&[
+        POOL_PREFIX.as_bytes(),
+        owner.key.as_ref(),
+        pool_uuid.as_ref(),
+        &[ctx.bumps.pool],
+    ]];
+
+    let mut transferred_pool = Pool::clone(pool);
+    transferred_pool.owner = new_owner.key();
+    // a pool without a cosigner is cosigned by its owner
+    if pool.cosigner == owner.key() {
+        transferred_pool.cosigner = new_owner.key();
+    }
+
+    let remaining_accounts = ctx
+        .remaining_accounts
+        .chunks_exact(TRANSFER_MINT_ACCOUNTS_LEN);
+    if !remaining_accounts.remainder().is_empty() {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    }
+    let mut transferred_asset_amount: u64 = 0;
+    for mint_accounts in remaining_accounts {
+        let asset_mint = InterfaceAccount::<Mint>::try_from(&mint_accounts[0])?;
+        let sellside_escrow_token_account_info = &mint_accounts[1];
+        let new_sellside_escrow_token_account = &mint_accounts[2];
+        let sell_state_info = &mint_accounts[3];
+        let new_sell_state_info = &mint_accounts[4];
+
+        if sellside_escrow_token_account_info.key()
+            != get_associated_token_address_with_program_id(
+                &pool_key,
+                &asset_mint.key(),
+                token_program.key,
+            )
+        {
+            return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+        }
+        let sellside_escrow_token_account =
+            InterfaceAccount::<TokenAccount>::try_from(sellside_escrow_token_account_info)?;
+        let mut sell_state = Account::<SellState>::try_from(sell_state_info)?;
+        if sell_state.pool != pool_key || sell_state.asset_mint != asset_mint.key() {
+            return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+        }
+
+        init_if_needed_ata(
+            new_sellside_escrow_token_account.to_account_info(),
+            owner.to_account_info(),
+            new_pool.to_account_info(),
+            asset_mint.to_account_info(),
+            associated_token_program.to_account_info(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+        )?;
+        anchor_spl::token_2022::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                anchor_spl::token_2022::TransferChecked {
+                    from: sellside_escrow_token_account.to_account_info(),
+                    mint: asset_mint.to_account_info(),
+                    to: new_sellside_escrow_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                pool_seeds,
+            ),
+            sellside_escrow_token_account.amount,
+            asset_mint.decimals,
+        )?;
+        anchor_spl::token_2022::close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            anchor_spl::token_2022::CloseAccount {
+                account: sellside_escrow_token_account.to_account_info(),
+                destination: owner.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            pool_seeds,
+        ))?;
+
+        // the sell state is reopened under the new pool with the same asset amount
+        let (new_sell_state_key, new_sell_state_bump) = Pubkey::find_program_address(
+            &[
+                SELL_STATE_PREFIX.as_bytes(),
+                new_pool_key.as_ref(),
+                asset_mint.key().as_ref(),
+            ],
+            ctx.program_id,
+        );
+        if new_sell_state_info.key() != new_sell_state_key {
+            return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+        }
+        invoke_signed(
+            &anchor_lang::solana_program::system_instruction::create_account(
+                owner.key,
+                new_sell_state_info.key,
+                rent.minimum_balance(SellState::LEN),
+                SellState::LEN as u64,
+                ctx.program_id,
+            ),
+            &[
+                owner.to_account_info(),
+                new_sell_state_info.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            &[&[
+                SELL_STATE_PREFIX.as_bytes(),
+                new_pool_key.as_ref(),
+                asset_mint.key().as_ref(),
+                &[new_sell_state_bump],
+            ]],
+        )?;
+        let new_sell_state = SellState {
+            pool: new_pool_key,
+            pool_owner: new_owner.key(),
+            asset_mint: asset_mint.key(),
+            asset_amount: sell_state.asset_amount,
+            cosigner_annotation: sell_state.cosigner_annotation,
+        };
+        new_sell_state.try_serialize(&mut &mut new_sell_state_info.try_borrow_mut_data()?[..])?;
+
+        transferred_asset_amount = transferred_asset_amount
+            .checked_add(sell_state.asset_amount)
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+        sell_state.asset_amount = 0;
+        try_close_sell_state(&sell_state, owner.to_account_info())?;
+    }
+    if transferred_asset_amount != pool.sellside_asset_amount {
+        return Err(MMMErrorCode::NotEmptySellsideAssetAmount.into());
+    }
+
+    let escrow_lamports = buyside_sol_escrow_account.lamports();
+    if escrow_lamports > 0 {
+        invoke_signed(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                buyside_sol_escrow_account.key,
+                new_buyside_sol_escrow_account.key,
+                escrow_lamports,
+            ),
+            &[
+                buyside_sol_escrow_account.to_account_info(),
+                new_buyside_sol_escrow_account.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            // seeds should be the PDA of 'buyside_sol_escrow_account'
+            &[&[
+                BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX.as_bytes(),
+                pool_key.as_ref(),
+                &[ctx.bumps.buyside_sol_escrow_account],
+            ]],
+        )?;
+    }
+
+    transferred_pool.buyside_payment_amount = new_buyside_sol_escrow_account.lamports();
+    new_pool.set_inner(transferred_pool);
+    log_pool("post_transfer_pool_ownership", new_pool)?;
+
+    pool.sellside_asset_amount = 0;
+    pool.buyside_payment_amount = 0;
+    try_close_pool(pool, owner.to_account_info())?;
+    Ok(())
+}
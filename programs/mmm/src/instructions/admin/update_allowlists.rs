@@ -3,6 +3,7 @@ use super::*;
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct UpdateAllowlistsArgs {
     pub allowlists: [Allowlist; ALLOWLIST_MAX_LEN],
+    pub allowlist_match_mode: Option<u8>, // None means unchanged
 }
 
 #[derive(Accounts)]
@@ -24,9 +25,15 @@ pub struct UpdateAllowlists<'info> {
 pub fn handler(ctx: Context<UpdateAllowlists>, args: UpdateAllowlistsArgs) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
 
-    check_allowlists(&args.allowlists)?;
+    if let Some(allowlist_match_mode) = args.allowlist_match_mode {
+        if allowlist_match_mode > ALLOWLIST_MATCH_MODE_INTERSECTION {
+            return Err(MMMErrorCode::InvalidAllowLists.into());
+        }
+        pool.allowlist_match_mode = allowlist_match_mode;
+    }
+    check_allowlists(&args.allowlists, pool.allowlist_match_mode)?;
 
-    pool.allowlists = args.allowlists;
+    pool.set_allowlists(&args.allowlists);
 
     Ok(())
 }
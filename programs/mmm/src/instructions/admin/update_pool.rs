@@ -13,6 +13,39 @@ pub struct UpdatePoolArgs {
     pub referral: Pubkey,
     pub cosigner_annotation: [u8; 32],
     pub buyside_creator_royalty_bp: u16,
+    pub price_floor: Option<u64>,             // None means unchanged
+    pub price_ceiling: Option<u64>,           // None means unchanged
+    pub lp_fee_decay_end: Option<i64>, // None means unchanged, otherwise the decay restarts now
+    pub lp_fee_bp_floor: Option<u16>,  // None means unchanged
+    pub lp_fee_tiers: Option<Vec<LpFeeTier>>, // None means unchanged
+    pub min_escrow_balance_bp: Option<u16>, // None means unchanged, 0 means the default
+    pub extra_royalty_recipients: Option<Vec<RoyaltyRecipient>>, // None means unchanged
+    pub max_spot_move_bp: Option<u16>, // None means unchanged
+    pub lp_fee_rounding: Option<u8>,   // None means unchanged
+    pub max_fill_depth: Option<u16>,   // None means unchanged
+    pub max_buyside_spend: Option<u64>, // None means unchanged, buyside_spent is kept
+    pub pool_side: Option<u8>,         // None means unchanged
+    pub require_sized_collection: Option<bool>, // None means unchanged
+    pub reject_self_referral: Option<bool>, // None means unchanged
+    pub settle_in_wsol: Option<bool>,  // None means unchanged
+    pub require_one_of_one: Option<bool>, // None means unchanged
+    pub require_programmable: Option<bool>, // None means unchanged
+    pub curve_delta_sell: Option<u64>, // None means unchanged
+    pub royalty_enforcement_mode: Option<u8>, // None means unchanged
+    pub max_total_price: Option<u64>,  // None means unchanged, 0 means MAX_TOTAL_PRICE
+    pub min_fill_amount: Option<u64>,  // None means unchanged
+    pub fill_cooldown_secs: Option<i64>, // None means unchanged, last_fill_ts is kept
+    pub sell_state_rent_to_taker: Option<bool>, // None means unchanged
+    pub allowlist_aux: Option<String>, // None means unchanged, an empty string clears it
+    pub royalty_fallback_account: Option<Pubkey>, // None means unchanged, default clears it
+    pub profit_skim_bp: Option<u16>,   // None means unchanged
+    pub oracle: Option<Pubkey>,        // None means unchanged, default clears it
+    pub max_oracle_deviation_bp: Option<u16>, // None means unchanged
+    pub require_all_creators_paid: Option<bool>, // None means unchanged
+    pub curve_direction: Option<u8>,   // None means unchanged
+    pub require_collection_metadata: Option<bool>, // None means unchanged
+    pub static_spot: Option<bool>,     // None means unchanged
+    pub allow_royalty_fallback: Option<bool>, // None means unchanged
 }
 
 #[derive(Accounts)]
@@ -29,7 +62,16 @@ pub struct UpdatePool<'info> {
         has_one = cosigner @ MMMErrorCode::InvalidCosigner,
         constraint = args.lp_fee_bp <= MAX_LP_FEE_BP @ MMMErrorCode::InvalidBP,
         constraint = args.buyside_creator_royalty_bp <= 10000 @ MMMErrorCode::InvalidBP,
+        constraint = args.min_escrow_balance_bp.unwrap_or(0) <= 10000 @ MMMErrorCode::InvalidBP,
+        constraint = args.max_spot_move_bp.unwrap_or(0) <= 10000 @ MMMErrorCode::InvalidBP,
+        constraint = args.profit_skim_bp.unwrap_or(0) <= 10000 @ MMMErrorCode::InvalidBP,
+        constraint = args.lp_fee_rounding.unwrap_or(LP_FEE_ROUNDING_FLOOR) <= LP_FEE_ROUNDING_NEAREST @ MMMErrorCode::InvalidLpFeeRounding,
+        constraint = args.pool_side.unwrap_or(POOL_SIDE_TWO_SIDED) <= POOL_SIDE_SELL_ONLY @ MMMErrorCode::InvalidPoolSide,
+        constraint = args.royalty_enforcement_mode.unwrap_or(ROYALTY_ENFORCEMENT_MODE_OPTIONAL) <= ROYALTY_ENFORCEMENT_MODE_BYPASS @ MMMErrorCode::InvalidRoyaltyEnforcementMode,
         constraint = args.spot_price > 0 @ MMMErrorCode::InvalidSpotPrice,
+        constraint = args.fill_cooldown_secs.unwrap_or(0) >= 0 @ MMMErrorCode::InvalidFillCooldown,
+        // an unchanged expiry is allowed, so that an expired pool can still be updated
+        constraint = args.expiry == 0 || args.expiry == pool.expiry || args.expiry > Clock::get().unwrap().unix_timestamp @ MMMErrorCode::InvalidExpiry,
         constraint = args.referral.ne(owner.key) @ MMMErrorCode::InvalidReferral,
     )]
     pub pool: Box<Account<'info, Pool>>,
@@ -37,17 +79,136 @@ pub struct UpdatePool<'info> {
 
 pub fn handler(ctx: Context<UpdatePool>, args: UpdatePoolArgs) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
-    check_curve(args.curve_type, args.curve_delta)?;
+    let max_fill_depth = args.max_fill_depth.unwrap_or(pool.max_fill_depth);
+    let curve_direction = args.curve_direction.unwrap_or(pool.curve_direction);
+    let max_total_price = args.max_total_price.unwrap_or(pool.max_total_price);
+    check_curve(
+        args.curve_type,
+        args.curve_delta,
+        args.spot_price,
+        max_fill_depth,
+        curve_direction,
+        get_max_total_price(max_total_price),
+    )?;
+    let curve_delta_sell = args.curve_delta_sell.unwrap_or(pool.curve_delta_sell);
+    check_curve_delta_sell(args.curve_type, curve_delta_sell, args.spot_price)?;
 
     // mutable
     pool.spot_price = args.spot_price;
+    pool.log_curve_base_price = args.spot_price;
     pool.curve_type = args.curve_type;
     pool.curve_delta = args.curve_delta;
+    pool.curve_delta_sell = curve_delta_sell;
+    pool.curve_direction = curve_direction;
     pool.expiry = args.expiry;
     pool.lp_fee_bp = args.lp_fee_bp;
     pool.referral = args.referral;
     pool.cosigner_annotation = args.cosigner_annotation;
     pool.buyside_creator_royalty_bp = args.buyside_creator_royalty_bp;
+    if let Some(price_floor) = args.price_floor {
+        pool.price_floor = price_floor;
+    }
+    if let Some(price_ceiling) = args.price_ceiling {
+        pool.price_ceiling = price_ceiling;
+    }
+    if pool.price_floor > pool.spot_price {
+        return Err(MMMErrorCode::InvalidPriceFloor.into());
+    }
+    if pool.price_ceiling != 0 && pool.price_ceiling < pool.spot_price {
+        return Err(MMMErrorCode::InvalidPriceCeiling.into());
+    }
+    pool.max_total_price = max_total_price;
+    if pool.max_total_price != 0 && pool.max_total_price < pool.spot_price {
+        return Err(MMMErrorCode::InvalidMaxTotalPrice.into());
+    }
+    if let Some(lp_fee_bp_floor) = args.lp_fee_bp_floor {
+        pool.lp_fee_bp_floor = lp_fee_bp_floor;
+    }
+    check_lp_fee_decay(
+        pool.lp_fee_bp,
+        pool.lp_fee_bp_floor,
+        args.lp_fee_decay_end.unwrap_or(0),
+    )?;
+    if let Some(lp_fee_decay_end) = args.lp_fee_decay_end {
+        pool.lp_fee_decay_start = Clock::get()?.unix_timestamp;
+        pool.lp_fee_decay_end = lp_fee_decay_end;
+    }
+    if let Some(lp_fee_tiers) = args.lp_fee_tiers {
+        pool.lp_fee_tiers = get_lp_fee_tiers(&lp_fee_tiers)?;
+    }
+    if let Some(min_escrow_balance_bp) = args.min_escrow_balance_bp {
+        pool.min_escrow_balance_bp = min_escrow_balance_bp;
+    }
+    if let Some(extra_royalty_recipients) = args.extra_royalty_recipients {
+        pool.extra_royalty_recipients = get_extra_royalty_recipients(&extra_royalty_recipients)?;
+    }
+    if let Some(max_spot_move_bp) = args.max_spot_move_bp {
+        pool.max_spot_move_bp = max_spot_move_bp;
+    }
+    if let Some(lp_fee_rounding) = args.lp_fee_rounding {
+        pool.lp_fee_rounding = lp_fee_rounding;
+    }
+    pool.max_fill_depth = max_fill_depth;
+    if let Some(max_buyside_spend) = args.max_buyside_spend {
+        pool.max_buyside_spend = max_buyside_spend;
+    }
+    if let Some(pool_side) = args.pool_side {
+        pool.pool_side = pool_side;
+    }
+    if let Some(require_sized_collection) = args.require_sized_collection {
+        pool.require_sized_collection = require_sized_collection;
+    }
+    if let Some(reject_self_referral) = args.reject_self_referral {
+        pool.reject_self_referral = reject_self_referral;
+    }
+    if let Some(settle_in_wsol) = args.settle_in_wsol {
+        pool.settle_in_wsol = settle_in_wsol;
+    }
+    if let Some(require_one_of_one) = args.require_one_of_one {
+        pool.require_one_of_one = require_one_of_one;
+    }
+    if let Some(require_programmable) = args.require_programmable {
+        pool.require_programmable = require_programmable;
+    }
+    if let Some(royalty_enforcement_mode) = args.royalty_enforcement_mode {
+        pool.royalty_enforcement_mode = royalty_enforcement_mode;
+    }
+    if let Some(min_fill_amount) = args.min_fill_amount {
+        pool.min_fill_amount = min_fill_amount;
+    }
+    if let Some(fill_cooldown_secs) = args.fill_cooldown_secs {
+        pool.fill_cooldown_secs = fill_cooldown_secs;
+    }
+    if let Some(sell_state_rent_to_taker) = args.sell_state_rent_to_taker {
+        pool.sell_state_rent_to_taker = sell_state_rent_to_taker;
+    }
+    if let Some(allowlist_aux) = args.allowlist_aux {
+        pool.allowlist_aux = get_allowlist_aux_bytes(&allowlist_aux)?;
+    }
+    if let Some(royalty_fallback_account) = args.royalty_fallback_account {
+        pool.royalty_fallback_account = royalty_fallback_account;
+    }
+    if let Some(profit_skim_bp) = args.profit_skim_bp {
+        pool.profit_skim_bp = profit_skim_bp;
+    }
+    if let Some(oracle) = args.oracle {
+        pool.oracle = oracle;
+    }
+    if let Some(max_oracle_deviation_bp) = args.max_oracle_deviation_bp {
+        pool.max_oracle_deviation_bp = max_oracle_deviation_bp;
+    }
+    if let Some(require_all_creators_paid) = args.require_all_creators_paid {
+        pool.require_all_creators_paid = require_all_creators_paid;
+    }
+    if let Some(require_collection_metadata) = args.require_collection_metadata {
+        pool.require_collection_metadata = require_collection_metadata;
+    }
+    if let Some(static_spot) = args.static_spot {
+        pool.static_spot = static_spot;
+    }
+    if let Some(allow_royalty_fallback) = args.allow_royalty_fallback {
+        pool.allow_royalty_fallback = allow_royalty_fallback;
+    }
 
     // if we are using shared escrow, we can't change the reinvest setting
     if !pool.using_shared_escrow() {
@@ -56,6 +217,7 @@ pub fn handler(ctx: Context<UpdatePool>, args: UpdatePoolArgs) -> Result<()> {
     }
 
     log_pool("post_update_pool", pool)?;
+    log_pool_event("pool_updated", POOL_EVENT_ACTION_UPDATED, pool)?;
 
     Ok(())
 }
@@ -10,7 +10,10 @@ use crate::{
     constants::*,
     errors::MMMErrorCode,
     state::{Pool, SellState},
-    util::{check_allowlists_for_mint_ext, log_pool},
+    util::{
+        assert_token_program_matches_mint, check_allowlists_for_mint_ext, check_one_of_one,
+        check_programmable, check_transfer_hook_accounts, log_pool,
+    },
     DepositSellArgs,
 };
 
@@ -30,7 +33,8 @@ pub struct ExtDepositeSell<'info> {
     pub pool: Box<Account<'info, Pool>>,
     #[account(
         mint::token_program = token_program,
-        constraint = asset_mint.supply == 1 && asset_mint.decimals == 0 @ MMMErrorCode::InvalidTokenMint,
+        constraint = asset_mint.decimals == 0 @ MMMErrorCode::InvalidTokenMint,
+        constraint = pool.accepts_mint_supply(&asset_mint.key(), asset_mint.supply) @ MMMErrorCode::InvalidTokenMint,
     )]
     pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
@@ -63,6 +67,8 @@ pub struct ExtDepositeSell<'info> {
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    // Remaining accounts
+    //   0+: transfer hook accounts
 }
 
 pub fn handler<'info>(
@@ -74,6 +80,10 @@ pub fn handler<'info>(
     let asset_mint = &ctx.accounts.asset_mint;
     let sellside_escrow_token_account = &ctx.accounts.sellside_escrow_token_account;
     let token_program = &ctx.accounts.token_program;
+    assert_token_program_matches_mint(
+        &ctx.accounts.asset_mint.to_account_info(),
+        token_program.key,
+    )?;
     let pool = &mut ctx.accounts.pool;
     let sell_state = &mut ctx.accounts.sell_state;
 
@@ -81,12 +91,23 @@ pub fn handler<'info>(
         return Err(MMMErrorCode::InvalidAccountState.into());
     }
 
-    check_allowlists_for_mint_ext(
-        &pool.allowlists,
+    let (_, transfer_fee) = check_allowlists_for_mint_ext(
+        &pool.get_allowlists(),
+        pool.allowlist_match_mode,
         &asset_mint.to_account_info(),
-        args.allowlist_aux,
+        pool.get_allowlist_aux(args.allowlist_aux),
+        args.asset_amount,
     )?;
+    check_one_of_one(pool, None)?;
+    check_programmable(pool, None)?;
+    // the escrow only receives the amount net of the withheld transfer fee
+    let received_amount = args
+        .asset_amount
+        .checked_sub(transfer_fee)
+        .filter(|amount| *amount > 0)
+        .ok_or(MMMErrorCode::TransferFeeExceedsAmount)?;
 
+    check_transfer_hook_accounts(&asset_mint.to_account_info(), ctx.remaining_accounts)?;
     invoke_transfer_checked(
         token_program.key,
         asset_token_account.to_account_info(),
@@ -117,7 +138,7 @@ pub fn handler<'info>(
 
     pool.sellside_asset_amount = pool
         .sellside_asset_amount
-        .checked_add(args.asset_amount)
+        .checked_add(received_amount)
         .ok_or(MMMErrorCode::NumericOverflow)?;
 
     sell_state.pool = pool.key();
@@ -126,7 +147,7 @@ pub fn handler<'info>(
     sell_state.cosigner_annotation = pool.cosigner_annotation;
     sell_state.asset_amount = sell_state
         .asset_amount
-        .checked_add(args.asset_amount)
+        .checked_add(received_amount)
         .ok_or(MMMErrorCode::NumericOverflow)?;
     log_pool("post_ext_deposit_sell", pool)?;
 
@@ -10,7 +10,10 @@ use crate::{
     constants::*,
     errors::MMMErrorCode,
     state::{Pool, SellState},
-    util::{log_pool, try_close_pool, try_close_sell_state},
+    util::{
+        check_transfer_hook_accounts, has_withheld_transfer_fee, log_pool, try_close_pool,
+        try_close_sell_state,
+    },
     WithdrawSellArgs,
 };
 
@@ -28,9 +31,10 @@ pub struct ExtWithdrawSell<'info> {
         bump
     )]
     pub pool: Box<Account<'info, Pool>>,
+    // no supply check, only what the pool already escrows for the mint can be withdrawn
     #[account(
         mint::token_program = token_program,
-        constraint = asset_mint.supply == 1 && asset_mint.decimals == 0 @ MMMErrorCode::InvalidTokenMint,
+        constraint = asset_mint.decimals == 0 @ MMMErrorCode::InvalidTokenMint,
     )]
     pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
@@ -70,9 +74,14 @@ pub struct ExtWithdrawSell<'info> {
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    // Remaining accounts
+    //   0+: transfer hook accounts
 }
 
-pub fn handler(ctx: Context<ExtWithdrawSell>, args: WithdrawSellArgs) -> Result<()> {
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExtWithdrawSell<'info>>,
+    args: WithdrawSellArgs,
+) -> Result<()> {
     let owner = &ctx.accounts.owner;
     let asset_token_account = &ctx.accounts.asset_token_account;
     let sellside_escrow_token_account = &ctx.accounts.sellside_escrow_token_account;
@@ -94,6 +103,7 @@ pub fn handler(ctx: Context<ExtWithdrawSell>, args: WithdrawSellArgs) -> Result<
     // Note that check_allowlists_for_mint_ext is optional for withdraw_sell
     // because sometimes the nft might be moved out of the collection
     // and we'd still like to enable the withdraw of those items for the pool owner.
+    check_transfer_hook_accounts(&asset_mint.to_account_info(), ctx.remaining_accounts)?;
 
     invoke_transfer_checked(
         token_program.key,
@@ -101,14 +111,16 @@ pub fn handler(ctx: Context<ExtWithdrawSell>, args: WithdrawSellArgs) -> Result<
         asset_mint.to_account_info(),
         asset_token_account.to_account_info(),
         pool.to_account_info(),
-        &[], // additional_accounts
+        ctx.remaining_accounts,
         args.asset_amount,
         0, // decimals
         pool_seeds,
     )?;
 
     // we can close the sellside_escrow_token_account if no amount left
-    if sellside_escrow_token_account.amount == args.asset_amount {
+    if sellside_escrow_token_account.amount == args.asset_amount
+        && !has_withheld_transfer_fee(&sellside_escrow_token_account.to_account_info())?
+    {
         close_account(CpiContext::new_with_signer(
             token_program.to_account_info(),
             CloseAccount {
@@ -6,19 +6,26 @@ use anchor_spl::{
 };
 use solana_program::{program::invoke_signed, system_instruction};
 use spl_token_2022::onchain::invoke_transfer_checked;
-use std::convert::TryFrom;
 
 use crate::{
     ata::init_if_needed_ata,
     constants::*,
     errors::MMMErrorCode,
     index_ra,
-    instructions::{check_remaining_accounts_for_m2, log_pool, try_close_pool, withdraw_m2},
+    instructions::{
+        check_remaining_accounts_for_m2, log_fill_summary, log_pool, try_close_pool, withdraw_m2,
+        FillSummary,
+    },
     state::{Pool, SellState},
     util::{
-        assert_valid_fees_bp, check_allowlists_for_mint_ext, get_buyside_seller_receives,
-        get_lp_fee_bp, get_sol_fee, get_sol_lp_fee, get_sol_total_price_and_next_price,
-        try_close_escrow, try_close_sell_state,
+        add_buyside_spent, add_pool_volume, assert_token_program_matches_mint,
+        assert_valid_fees_bp, check_allowlists_for_mint_ext, check_buyside_escrow_funded,
+        check_fill_cooldown, check_min_fill_amount, check_one_of_one, check_oracle_deviation,
+        check_pool_side, check_price_slippage, check_programmable, check_referrals,
+        check_transfer_hook_accounts, get_buyside_seller_receives, get_enforced_creator_royalty_bp,
+        get_ext_royalty_accounts_len, get_lp_fee_bp, get_metadata_royalty_bp_ext, get_sol_fee,
+        get_sol_lp_fee, get_sol_total_price_and_next_price, pay_creator_fees_in_sol_ext,
+        pay_referral_fees, try_close_escrow, try_close_sell_state, update_pool_curve,
     },
     SolFulfillBuyArgs,
 };
@@ -37,9 +44,12 @@ pub struct ExtSolFulfillBuy<'info> {
     #[account(mut)]
     pub owner: UncheckedAccount<'info>,
     pub cosigner: Signer<'info>,
+    /// CHECK: we will check that the referral matches the pool's referral, it receives the maker fee
     #[account(mut)]
-    /// CHECK: we will check that the referral matches the pool's referral
     pub referral: UncheckedAccount<'info>,
+    /// CHECK: the taker's referral receives the taker fee, it will be checked by the cosigner
+    #[account(mut)]
+    pub taker_referral: UncheckedAccount<'info>,
     #[account(
         mut,
         seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
@@ -47,6 +57,7 @@ pub struct ExtSolFulfillBuy<'info> {
         has_one = referral @ MMMErrorCode::InvalidReferral,
         has_one = cosigner @ MMMErrorCode::InvalidCosigner,
         constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        constraint = !pool.settle_in_wsol @ MMMErrorCode::WsolSettlementNotSupported,
         constraint = pool.expiry == 0 || pool.expiry > Clock::get().unwrap().unix_timestamp @ MMMErrorCode::Expired,
         bump
     )]
@@ -60,7 +71,8 @@ pub struct ExtSolFulfillBuy<'info> {
     pub buyside_sol_escrow_account: UncheckedAccount<'info>,
     #[account(
         mint::token_program = token_program,
-        constraint = asset_mint.supply == 1 && asset_mint.decimals == 0 @ MMMErrorCode::InvalidTokenMint,
+        constraint = asset_mint.decimals == 0 @ MMMErrorCode::InvalidTokenMint,
+        constraint = pool.accepts_mint_supply(&asset_mint.key(), asset_mint.supply) @ MMMErrorCode::InvalidTokenMint,
     )]
     pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
@@ -98,9 +110,12 @@ pub struct ExtSolFulfillBuy<'info> {
     // Branch: using shared escrow accounts
     //   0: m2_program
     //   1: shared_escrow_account
-    //   2+: transfer hook accounts
+    //   2..N: creator accounts, one for each creator in the royalty config of the metadata,
+    //        followed by the extra royalty recipient accounts when there are creators
+    //   N+: transfer hook accounts
     // Branch: not using shared escrow accounts
-    //   0+: transfer hook accounts
+    //   0..N: creator accounts
+    //   N+: transfer hook accounts
 }
 
 pub fn handler<'info>(
@@ -108,13 +123,27 @@ pub fn handler<'info>(
     args: SolFulfillBuyArgs,
 ) -> Result<()> {
     let token_program = &ctx.accounts.token_program;
+    assert_token_program_matches_mint(
+        &ctx.accounts.asset_mint.to_account_info(),
+        token_program.key,
+    )?;
     let system_program = &ctx.accounts.system_program;
     let associated_token_program = &ctx.accounts.associated_token_program;
     let rent = &ctx.accounts.rent;
     let pool = &mut ctx.accounts.pool;
+    check_pool_side(pool, true)?;
+    check_fill_cooldown(pool)?;
+    check_min_fill_amount(pool, args.asset_amount)?;
+    check_referrals(
+        pool,
+        ctx.accounts.payer.key,
+        ctx.accounts.referral.key,
+        ctx.accounts.taker_referral.key,
+    )?;
     let sell_state = &mut ctx.accounts.sell_state;
     let owner = &ctx.accounts.owner;
     let referral = &ctx.accounts.referral;
+    let taker_referral = &ctx.accounts.taker_referral;
     let payer = &ctx.accounts.payer;
     let payer_asset_account = &ctx.accounts.payer_asset_account;
     let asset_mint = &ctx.accounts.asset_mint;
@@ -125,44 +154,68 @@ pub fn handler<'info>(
         pool_key.as_ref(),
         &[ctx.bumps.buyside_sol_escrow_account],
     ]];
-    let remaining_accounts = ctx.remaining_accounts;
+    let remaining_accounts = check_oracle_deviation(pool, ctx.remaining_accounts)?;
 
-    check_allowlists_for_mint_ext(
-        &pool.allowlists,
+    let (parsed_metadata, transfer_fee) = check_allowlists_for_mint_ext(
+        &pool.get_allowlists(),
+        pool.allowlist_match_mode,
         &asset_mint.to_account_info(),
-        args.allowlist_aux,
+        pool.get_allowlist_aux(args.allowlist_aux),
+        args.asset_amount,
     )?;
+    check_one_of_one(pool, None)?;
+    check_programmable(pool, None)?;
+    // the receiving account only gets the amount net of the withheld transfer fee
+    let received_amount = args
+        .asset_amount
+        .checked_sub(transfer_fee)
+        .filter(|amount| *amount > 0)
+        .ok_or(MMMErrorCode::TransferFeeExceedsAmount)?;
+    let (metadata_royalty_bp, creators) = get_metadata_royalty_bp_ext(&parsed_metadata);
+    let buyside_creator_royalty_bp =
+        get_enforced_creator_royalty_bp(pool, pool.buyside_creator_royalty_bp);
 
     let (total_price, next_price) =
         get_sol_total_price_and_next_price(pool, args.asset_amount, true)?;
+    check_price_slippage(
+        total_price,
+        args.asset_amount,
+        args.min_price_per_item,
+        None,
+    )?;
     // TODO: update lp_fee_bp when shared escrow for both side is enabled
+    // the royalty config of token-2022 metadata is set by the seller, so the fees are priced
+    // without it, and the royalty comes out of what the seller receives instead of the fees
     let seller_receives = {
-        let lp_fee_bp = get_lp_fee_bp(pool, buyside_sol_escrow_account.lamports());
-        get_buyside_seller_receives(
-            total_price,
-            lp_fee_bp,
-            0, // metadata_royalty_bp
-            0, // buyside_creator_royalty_bp,
-        )
+        let lp_fee_bp = get_lp_fee_bp(
+            pool,
+            buyside_sol_escrow_account.lamports(),
+            args.asset_amount,
+        )?;
+        get_buyside_seller_receives(total_price, lp_fee_bp, 0, 0)
     }?;
 
     assert_valid_fees_bp(args.maker_fee_bp, args.taker_fee_bp)?;
     let maker_fee = get_sol_fee(seller_receives, args.maker_fee_bp)?;
     let taker_fee = get_sol_fee(seller_receives, args.taker_fee_bp)?;
-    let referral_fee = u64::try_from(
-        maker_fee
-            .checked_add(taker_fee)
-            .ok_or(MMMErrorCode::NumericOverflow)?,
-    )
-    .map_err(|_| MMMErrorCode::NumericOverflow)?;
 
     // TODO: update lp_fee when shared escrow for both side is enabled
-    let lp_fee = get_sol_lp_fee(pool, buyside_sol_escrow_account.lamports(), seller_receives)?;
+    let lp_fee = get_sol_lp_fee(
+        pool,
+        buyside_sol_escrow_account.lamports(),
+        seller_receives,
+        args.asset_amount,
+    )?;
 
     // withdraw sol from M2 first if shared escrow is enabled
     let remaining_account_without_m2 = if pool.using_shared_escrow() {
         check_remaining_accounts_for_m2(remaining_accounts, &pool.owner.key())?;
 
+        check_buyside_escrow_funded(
+            index_ra!(remaining_accounts, 1).lamports(),
+            total_price,
+            maker_fee,
+        )?;
         let amount: u64 = (total_price as i64 + maker_fee) as u64;
         withdraw_m2(
             pool,
@@ -180,8 +233,21 @@ pub fn handler<'info>(
             .ok_or(MMMErrorCode::NumericOverflow)?;
         &remaining_accounts[2..]
     } else {
+        check_buyside_escrow_funded(
+            buyside_sol_escrow_account.lamports(),
+            total_price,
+            maker_fee,
+        )?;
         remaining_accounts
     };
+    let royalty_accounts_len =
+        get_ext_royalty_accounts_len(&creators, &pool.extra_royalty_recipients);
+    if remaining_account_without_m2.len() < royalty_accounts_len {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    }
+    let (creator_accounts, transfer_hook_accounts) =
+        remaining_account_without_m2.split_at(royalty_accounts_len);
+    check_transfer_hook_accounts(&asset_mint.to_account_info(), transfer_hook_accounts)?;
 
     if pool.reinvest_fulfill_buy {
         if pool.using_shared_escrow() {
@@ -205,7 +271,7 @@ pub fn handler<'info>(
             asset_mint.to_account_info(),
             sellside_escrow_token_account.to_account_info(),
             payer.to_account_info(),
-            remaining_account_without_m2,
+            transfer_hook_accounts,
             args.asset_amount,
             0,   // decimals
             &[], // seeds
@@ -213,7 +279,7 @@ pub fn handler<'info>(
 
         pool.sellside_asset_amount = pool
             .sellside_asset_amount
-            .checked_add(args.asset_amount)
+            .checked_add(received_amount)
             .ok_or(MMMErrorCode::NumericOverflow)?;
         sell_state.pool = pool.key();
         sell_state.pool_owner = owner.key();
@@ -221,7 +287,7 @@ pub fn handler<'info>(
         sell_state.cosigner_annotation = pool.cosigner_annotation;
         sell_state.asset_amount = sell_state
             .asset_amount
-            .checked_add(args.asset_amount)
+            .checked_add(received_amount)
             .ok_or(MMMErrorCode::NumericOverflow)?;
     } else {
         let owner_token_account = ctx.accounts.owner_token_account.to_account_info();
@@ -242,7 +308,7 @@ pub fn handler<'info>(
             asset_mint.to_account_info(),
             owner_token_account.to_account_info(),
             payer.to_account_info(),
-            remaining_account_without_m2,
+            transfer_hook_accounts,
             args.asset_amount,
             0,   // decimals
             &[], // seeds
@@ -261,11 +327,30 @@ pub fn handler<'info>(
         ))?;
     }
 
+    // the seller pays the royalties out of the payment amount
+    let royalty_paid = pay_creator_fees_in_sol_ext(
+        buyside_creator_royalty_bp,
+        seller_receives,
+        &creators,
+        creator_accounts,
+        buyside_sol_escrow_account.to_account_info(),
+        metadata_royalty_bp,
+        metadata_royalty_bp,
+        buyside_sol_escrow_account_seeds,
+        system_program.to_account_info(),
+        &pool.extra_royalty_recipients,
+        pool.get_royalty_fallback_account(),
+        pool.require_all_creators_paid,
+    )?;
+
     // prevent frontrun by pool config changes
+    // the seller will see the price after adjusting the royalties.
     let payment_amount = total_price
         .checked_sub(lp_fee)
         .ok_or(MMMErrorCode::NumericOverflow)?
         .checked_sub(taker_fee as u64)
+        .ok_or(MMMErrorCode::NumericOverflow)?
+        .checked_sub(royalty_paid)
         .ok_or(MMMErrorCode::NumericOverflow)?;
 
     if payment_amount < args.min_payment_amount {
@@ -291,26 +376,24 @@ pub fn handler<'info>(
             buyside_sol_escrow_account_seeds,
         )?;
     }
-    if referral_fee > 0 {
-        invoke_signed(
-            &system_instruction::transfer(
-                buyside_sol_escrow_account.key,
-                referral.key,
-                referral_fee,
-            ),
-            &[
-                buyside_sol_escrow_account.to_account_info(),
-                referral.to_account_info(),
-            ],
-            buyside_sol_escrow_account_seeds,
-        )?;
-    }
+    pay_referral_fees(
+        maker_fee,
+        taker_fee,
+        &buyside_sol_escrow_account.to_account_info(),
+        &referral.to_account_info(),
+        &taker_referral.to_account_info(),
+        buyside_sol_escrow_account_seeds,
+        &system_program.to_account_info(),
+    )?;
 
     pool.lp_fee_earned = pool
         .lp_fee_earned
         .checked_add(lp_fee)
         .ok_or(MMMErrorCode::NumericOverflow)?;
-    pool.spot_price = next_price;
+    add_buyside_spent(pool, total_price)?;
+    add_pool_volume(pool, total_price)?;
+    let spot_price_before = pool.spot_price;
+    update_pool_curve(pool, args.asset_amount, next_price, true)?;
 
     try_close_escrow(
         &buyside_sol_escrow_account.to_account_info(),
@@ -324,9 +407,11 @@ pub fn handler<'info>(
     if pool.using_shared_escrow() {
         let min_rent = Rent::get()?.minimum_balance(0);
         let shared_escrow_account = index_ra!(remaining_accounts, 1).to_account_info();
-        if shared_escrow_account.lamports() + buyside_sol_escrow_account.lamports() > min_rent
-            && buyside_sol_escrow_account.lamports() > 0
-        {
+        let combined_lamports = shared_escrow_account
+            .lamports()
+            .checked_add(buyside_sol_escrow_account.lamports())
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+        if combined_lamports > min_rent && buyside_sol_escrow_account.lamports() > 0 {
             invoke_signed(
                 &system_instruction::transfer(
                     buyside_sol_escrow_account.key,
@@ -353,7 +438,24 @@ pub fn handler<'info>(
     log_pool("post_ext_sol_fulfill_buy", pool)?;
     try_close_pool(pool, owner.to_account_info())?;
 
-    msg!("{{\"lp_fee\":{},\"total_price\":{}}}", lp_fee, total_price,);
+    msg!(
+        "{{\"lp_fee\":{},\"royalty_paid\":{},\"total_price\":{}}}",
+        lp_fee,
+        royalty_paid,
+        total_price,
+    );
+
+    log_fill_summary(&FillSummary {
+        asset_mint: asset_mint.key(),
+        asset_amount: args.asset_amount,
+        total_price,
+        lp_fee,
+        royalty_paid,
+        maker_fee,
+        taker_fee,
+        spot_price_before,
+        spot_price_after: pool.spot_price,
+    })?;
 
     Ok(())
 }
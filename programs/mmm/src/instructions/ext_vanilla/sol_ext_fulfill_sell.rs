@@ -6,17 +6,24 @@ use anchor_spl::{
 };
 use solana_program::{program::invoke, system_instruction};
 use spl_token_2022::onchain::invoke_transfer_checked;
-use std::convert::TryFrom;
 
 use crate::{
     constants::*,
     errors::MMMErrorCode,
     instructions::{
-        get_sell_fulfill_pool_price_info, log_pool, try_close_pool, try_close_sell_state,
-        PoolPriceInfo,
+        check_price_slippage, get_sell_fulfill_pool_price_info, log_fill_summary, log_pool,
+        try_close_pool, try_close_sell_state, update_pool_curve, FillSummary, PoolPriceInfo,
     },
     state::{Pool, SellState},
-    util::check_allowlists_for_mint_ext,
+    util::{
+        add_pool_volume, assert_token_program_matches_mint, check_allowlists_for_mint_ext,
+        check_fill_cooldown, check_min_fill_amount, check_oracle_deviation, check_pool_side,
+        check_referrals, check_transfer_hook_accounts, get_enforced_creator_royalty_bp,
+        get_ext_royalty_accounts_len, get_metadata_royalty_bp_ext, get_sell_fill_asset_amount,
+        get_sell_state_rent_recipient, get_taker_royalty_bp, has_withheld_transfer_fee,
+        pay_creator_fees_in_sol_ext, pay_referral_fees, pay_sell_proceeds,
+        split_taker_fee_exemption,
+    },
     SolFulfillSellArgs,
 };
 
@@ -34,9 +41,12 @@ pub struct ExtSolFulfillSell<'info> {
     #[account(mut)]
     pub owner: UncheckedAccount<'info>,
     pub cosigner: Signer<'info>,
-    /// CHECK: we will check that the referral matches the pool's referral
+    /// CHECK: we will check that the referral matches the pool's referral, it receives the maker fee
     #[account(mut)]
     pub referral: UncheckedAccount<'info>,
+    /// CHECK: the taker's referral receives the taker fee, it will be checked by the cosigner
+    #[account(mut)]
+    pub taker_referral: UncheckedAccount<'info>,
     #[account(
         mut,
         seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
@@ -44,6 +54,7 @@ pub struct ExtSolFulfillSell<'info> {
         has_one = referral @ MMMErrorCode::InvalidReferral,
         has_one = cosigner @ MMMErrorCode::InvalidCosigner,
         constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        constraint = !pool.settle_in_wsol @ MMMErrorCode::WsolSettlementNotSupported,
         constraint = pool.expiry == 0 || pool.expiry > Clock::get().unwrap().unix_timestamp @ MMMErrorCode::Expired,
         constraint = args.buyside_creator_royalty_bp <= 10000 @ MMMErrorCode::InvalidBP,
         bump
@@ -59,7 +70,8 @@ pub struct ExtSolFulfillSell<'info> {
     /// CHECK: check_allowlists_for_mint_ext
     #[account(
         mint::token_program = token_program,
-        constraint = asset_mint.supply == 1 && asset_mint.decimals == 0 @ MMMErrorCode::InvalidTokenMint,
+        constraint = asset_mint.decimals == 0 @ MMMErrorCode::InvalidTokenMint,
+        constraint = pool.accepts_mint_supply(&asset_mint.key(), asset_mint.supply) @ MMMErrorCode::InvalidTokenMint,
     )]
     pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
@@ -90,6 +102,10 @@ pub struct ExtSolFulfillSell<'info> {
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    // Remaining accounts
+    //   0..N: creator accounts, one for each creator in the royalty config of the metadata,
+    //        followed by the extra royalty recipient accounts when there are creators
+    //   N+: transfer hook accounts
 }
 
 pub fn handler<'info>(
@@ -97,10 +113,23 @@ pub fn handler<'info>(
     args: SolFulfillSellArgs,
 ) -> Result<()> {
     let token_program = &ctx.accounts.token_program;
+    assert_token_program_matches_mint(
+        &ctx.accounts.asset_mint.to_account_info(),
+        token_program.key,
+    )?;
     let system_program = &ctx.accounts.system_program;
     let owner = &ctx.accounts.owner;
     let referral = &ctx.accounts.referral;
+    let taker_referral = &ctx.accounts.taker_referral;
     let pool = &mut ctx.accounts.pool;
+    check_pool_side(pool, false)?;
+    check_fill_cooldown(pool)?;
+    check_referrals(
+        pool,
+        ctx.accounts.payer.key,
+        ctx.accounts.referral.key,
+        ctx.accounts.taker_referral.key,
+    )?;
     let sell_state = &mut ctx.accounts.sell_state;
 
     let payer = &ctx.accounts.payer;
@@ -115,64 +144,78 @@ pub fn handler<'info>(
         pool.uuid.as_ref(),
         &[ctx.bumps.pool],
     ]];
+    let asset_amount =
+        get_sell_fill_asset_amount(args.asset_amount, args.partial_fill_ok, pool, sell_state);
+    check_min_fill_amount(pool, asset_amount)?;
+    let (remaining_accounts, taker_fee_exempt) =
+        split_taker_fee_exemption(&pool.key(), payer.key, ctx.remaining_accounts);
+    let remaining_accounts = check_oracle_deviation(pool, remaining_accounts)?;
 
-    check_allowlists_for_mint_ext(
-        &pool.allowlists,
+    let (parsed_metadata, _) = check_allowlists_for_mint_ext(
+        &pool.get_allowlists(),
+        pool.allowlist_match_mode,
         &asset_mint.to_account_info(),
-        args.allowlist_aux,
+        pool.get_allowlist_aux(args.allowlist_aux),
+        asset_amount,
     )?;
 
     let PoolPriceInfo {
         total_price,
         next_price,
+        seller_receives: _,
         lp_fee,
         maker_fee,
         taker_fee,
-        referral_fee,
+        referral_fee: _,
         transfer_sol_to,
     } = get_sell_fulfill_pool_price_info(
         pool,
         owner,
         buyside_sol_escrow_account,
-        args.asset_amount,
+        asset_amount,
         args.maker_fee_bp,
         args.taker_fee_bp,
+        taker_fee_exempt,
     )?;
+    check_price_slippage(total_price, asset_amount, None, args.max_price_per_item)?;
 
     // TODO: make sure that the lp fee is paid with the correct amount
-    invoke(
-        &system_instruction::transfer(
-            payer.key,
-            transfer_sol_to.key,
-            u64::try_from(
-                i64::try_from(total_price)
-                    .map_err(|_| MMMErrorCode::NumericOverflow)?
-                    .checked_sub(maker_fee)
-                    .ok_or(MMMErrorCode::NumericOverflow)?,
-            )
-            .map_err(|_| MMMErrorCode::NumericOverflow)?,
-        ),
-        &[
-            payer.to_account_info(),
-            transfer_sol_to,
-            system_program.to_account_info(),
-        ],
+    pay_sell_proceeds(
+        pool,
+        &payer.to_account_info(),
+        &owner.to_account_info(),
+        transfer_sol_to,
+        total_price,
+        maker_fee,
+        &system_program.to_account_info(),
     )?;
 
+    let (metadata_royalty_bp, creators) = get_metadata_royalty_bp_ext(&parsed_metadata);
+    let royalty_bp = get_taker_royalty_bp(metadata_royalty_bp, args.royalty_bp_override)?;
+    let royalty_accounts_len =
+        get_ext_royalty_accounts_len(&creators, &pool.extra_royalty_recipients);
+    if remaining_accounts.len() < royalty_accounts_len {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    }
+    let (creator_accounts, transfer_hook_accounts) =
+        remaining_accounts.split_at(royalty_accounts_len);
+    check_transfer_hook_accounts(&asset_mint.to_account_info(), transfer_hook_accounts)?;
     invoke_transfer_checked(
         token_program.key,
         sellside_escrow_token_account.to_account_info(),
         asset_mint.to_account_info(),
         payer_asset_account.to_account_info(),
         pool.to_account_info(),
-        &[],
-        args.asset_amount,
+        transfer_hook_accounts,
+        asset_amount,
         0,
         pool_seeds,
     )?;
 
     // we can close the sellside_escrow_token_account if no amount left
-    if sellside_escrow_token_account.amount == args.asset_amount {
+    if sellside_escrow_token_account.amount == asset_amount
+        && !has_withheld_transfer_fee(&sellside_escrow_token_account.to_account_info())?
+    {
         close_account(CpiContext::new_with_signer(
             token_program.to_account_info(),
             anchor_spl::token_2022::CloseAccount {
@@ -195,32 +238,50 @@ pub fn handler<'info>(
         )?;
     }
 
-    if referral_fee > 0 {
-        invoke(
-            &system_instruction::transfer(payer.key, referral.key, referral_fee),
-            &[
-                payer.to_account_info(),
-                referral.to_account_info(),
-                system_program.to_account_info(),
-            ],
-        )?;
-    }
+    pay_referral_fees(
+        maker_fee,
+        taker_fee,
+        &payer.to_account_info(),
+        &referral.to_account_info(),
+        &taker_referral.to_account_info(),
+        &[],
+        &system_program.to_account_info(),
+    )?;
 
-    pool.spot_price = next_price;
+    add_pool_volume(pool, total_price)?;
+    let spot_price_before = pool.spot_price;
+    update_pool_curve(pool, asset_amount, next_price, false)?;
     pool.sellside_asset_amount = pool
         .sellside_asset_amount
-        .checked_sub(args.asset_amount)
+        .checked_sub(asset_amount)
         .ok_or(MMMErrorCode::NumericOverflow)?;
     pool.lp_fee_earned = pool
         .lp_fee_earned
         .checked_add(lp_fee)
         .ok_or(MMMErrorCode::NumericOverflow)?;
 
+    let royalty_paid = pay_creator_fees_in_sol_ext(
+        get_enforced_creator_royalty_bp(pool, args.buyside_creator_royalty_bp),
+        total_price,
+        &creators,
+        creator_accounts,
+        payer.to_account_info(),
+        metadata_royalty_bp,
+        royalty_bp,
+        &[&[&[]]],
+        system_program.to_account_info(),
+        &pool.extra_royalty_recipients,
+        pool.get_royalty_fallback_account(),
+        pool.require_all_creators_paid,
+    )?;
+
     // prevent frontrun by pool config changes
     let payment_amount = total_price
         .checked_add(lp_fee)
         .ok_or(MMMErrorCode::NumericOverflow)?
         .checked_add(taker_fee as u64)
+        .ok_or(MMMErrorCode::NumericOverflow)?
+        .checked_add(royalty_paid)
         .ok_or(MMMErrorCode::NumericOverflow)?;
     if payment_amount > args.max_payment_amount {
         return Err(MMMErrorCode::InvalidRequestedPrice.into());
@@ -228,15 +289,35 @@ pub fn handler<'info>(
 
     sell_state.asset_amount = sell_state
         .asset_amount
-        .checked_sub(args.asset_amount)
+        .checked_sub(asset_amount)
         .ok_or(MMMErrorCode::NumericOverflow)?;
-    try_close_sell_state(sell_state, owner.to_account_info())?;
+    try_close_sell_state(
+        sell_state,
+        get_sell_state_rent_recipient(pool, owner.to_account_info(), payer.to_account_info()),
+    )?;
 
     pool.buyside_payment_amount = buyside_sol_escrow_account.lamports();
     log_pool("post_ext_sol_fulfill_sell", pool)?;
     try_close_pool(pool, owner.to_account_info())?;
 
-    msg!("{{\"lp_fee\":{},\"total_price\":{}}}", lp_fee, total_price);
+    msg!(
+        "{{\"lp_fee\":{},\"royalty_paid\":{},\"total_price\":{}}}",
+        lp_fee,
+        royalty_paid,
+        total_price,
+    );
+
+    log_fill_summary(&FillSummary {
+        asset_mint: asset_mint.key(),
+        asset_amount,
+        total_price,
+        lp_fee,
+        royalty_paid,
+        maker_fee,
+        taker_fee,
+        spot_price_before,
+        spot_price_after: pool.spot_price,
+    })?;
 
     Ok(())
 }
@@ -15,7 +15,10 @@ use crate::{
     errors::MMMErrorCode,
     instructions::vanilla::DepositSellArgs,
     state::{Pool, SellState},
-    util::{assert_is_programmable, check_allowlists_for_mint, log_pool},
+    util::{
+        assert_is_programmable, assert_token_program_matches_mint, check_allowlists_for_mint,
+        check_one_of_one, log_pool, split_merkle_proof,
+    },
 };
 
 #[derive(Accounts)]
@@ -110,6 +113,10 @@ pub fn handler(ctx: Context<Mip1DepositSell>, args: DepositSellArgs) -> Result<(
     let asset_mint = &ctx.accounts.asset_mint;
     let asset_metadata = &ctx.accounts.asset_metadata;
     let token_program = &ctx.accounts.token_program;
+    assert_token_program_matches_mint(
+        &ctx.accounts.asset_mint.to_account_info(),
+        token_program.key,
+    )?;
     let sellside_escrow_token_account = &ctx.accounts.sellside_escrow_token_account;
     let pool = &mut ctx.accounts.pool;
     let asset_master_edition = &ctx.accounts.asset_master_edition;
@@ -127,13 +134,19 @@ pub fn handler(ctx: Context<Mip1DepositSell>, args: DepositSellArgs) -> Result<(
         return Err(MMMErrorCode::InvalidAccountState.into());
     }
 
+    let allowlists = pool.get_allowlists();
+    let (merkle_proof, _) =
+        split_merkle_proof(&allowlists, &asset_mint.key(), ctx.remaining_accounts);
     let parsed_metadata = check_allowlists_for_mint(
-        &pool.allowlists,
+        &allowlists,
+        pool.allowlist_match_mode,
         asset_mint,
         asset_metadata,
         Some(asset_master_edition),
-        args.allowlist_aux,
+        pool.get_allowlist_aux(args.allowlist_aux),
+        merkle_proof,
     )?;
+    check_one_of_one(pool, Some(asset_master_edition))?;
     assert_is_programmable(&parsed_metadata)?;
 
     let payload = Payload {
@@ -7,16 +7,21 @@ use mpl_token_metadata::{
     instructions::TransferCpiBuilder,
     types::{AuthorizationData, Payload, PayloadType, SeedsVec, TransferArgs},
 };
-use std::{collections::HashMap, convert::TryFrom};
+use std::collections::HashMap;
 
 use crate::{
     constants::*,
     errors::MMMErrorCode,
     state::{Pool, SellState},
     util::{
-        assert_is_programmable, assert_valid_fees_bp, check_allowlists_for_mint,
-        get_metadata_royalty_bp, get_sol_fee, get_sol_lp_fee, get_sol_total_price_and_next_price,
-        log_pool, pay_creator_fees_in_sol, try_close_pool, try_close_sell_state,
+        add_pool_volume, assert_is_programmable, assert_token_program_matches_mint,
+        assert_valid_fees_bp, check_allowlists_for_mint, check_fill_cooldown,
+        check_min_fill_amount, check_oracle_deviation, check_pool_side, check_referrals,
+        get_fees_bp, get_metadata_royalty_bp, get_sell_state_rent_recipient, get_sol_fee,
+        get_sol_lp_fee, get_sol_total_price_and_next_price, log_fill_summary, log_pool,
+        pay_creator_fees_in_sol, pay_referral_fees, pay_sell_proceeds, split_merkle_proof,
+        split_taker_fee_exemption, try_close_pool, try_close_sell_state, update_pool_curve,
+        FillSummary,
     },
 };
 
@@ -42,9 +47,12 @@ pub struct SolMip1FulfillSell<'info> {
     #[account(mut)]
     pub owner: UncheckedAccount<'info>,
     pub cosigner: Signer<'info>,
-    /// CHECK: we will check that the referral matches the pool's referral
+    /// CHECK: we will check that the referral matches the pool's referral, it receives the maker fee
     #[account(mut)]
     pub referral: UncheckedAccount<'info>,
+    /// CHECK: the taker's referral receives the taker fee, it will be checked by the cosigner
+    #[account(mut)]
+    pub taker_referral: UncheckedAccount<'info>,
     #[account(
         mut,
         seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
@@ -52,6 +60,7 @@ pub struct SolMip1FulfillSell<'info> {
         has_one = referral @ MMMErrorCode::InvalidReferral,
         has_one = cosigner @ MMMErrorCode::InvalidCosigner,
         constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        constraint = !pool.settle_in_wsol @ MMMErrorCode::WsolSettlementNotSupported,
         constraint = pool.expiry == 0 || pool.expiry > Clock::get().unwrap().unix_timestamp @ MMMErrorCode::Expired,
         bump
     )]
@@ -139,10 +148,24 @@ pub fn handler<'info>(
     args: SolMip1FulfillSellArgs,
 ) -> Result<()> {
     let token_program = &ctx.accounts.token_program;
+    assert_token_program_matches_mint(
+        &ctx.accounts.asset_mint.to_account_info(),
+        token_program.key,
+    )?;
     let system_program = &ctx.accounts.system_program;
     let owner = &ctx.accounts.owner;
     let referral = &ctx.accounts.referral;
+    let taker_referral = &ctx.accounts.taker_referral;
     let pool = &mut ctx.accounts.pool;
+    check_pool_side(pool, false)?;
+    check_fill_cooldown(pool)?;
+    check_min_fill_amount(pool, args.asset_amount)?;
+    check_referrals(
+        pool,
+        ctx.accounts.payer.key,
+        ctx.accounts.referral.key,
+        ctx.accounts.taker_referral.key,
+    )?;
     let sell_state = &mut ctx.accounts.sell_state;
 
     let payer = &ctx.accounts.payer;
@@ -167,28 +190,37 @@ pub fn handler<'info>(
         &[ctx.bumps.pool],
     ]];
 
+    let (remaining_accounts, taker_fee_exempt) =
+        split_taker_fee_exemption(&pool.key(), payer.key, ctx.remaining_accounts);
+    let remaining_accounts = check_oracle_deviation(pool, remaining_accounts)?;
+    let allowlists = pool.get_allowlists();
+    let (merkle_proof, remaining_accounts) =
+        split_merkle_proof(&allowlists, &asset_mint.key(), remaining_accounts);
     let parsed_metadata = check_allowlists_for_mint(
-        &pool.allowlists,
+        &allowlists,
+        pool.allowlist_match_mode,
         asset_mint,
         asset_metadata,
         None,
-        args.allowlist_aux,
+        pool.get_allowlist_aux(args.allowlist_aux),
+        merkle_proof,
     )?;
     assert_is_programmable(&parsed_metadata)?;
 
     let (total_price, next_price) =
         get_sol_total_price_and_next_price(pool, args.asset_amount, false)?;
-    let lp_fee = get_sol_lp_fee(pool, buyside_sol_escrow_account.lamports(), total_price)?;
+    let lp_fee = get_sol_lp_fee(
+        pool,
+        buyside_sol_escrow_account.lamports(),
+        total_price,
+        args.asset_amount,
+    )?;
 
-    assert_valid_fees_bp(args.maker_fee_bp, args.taker_fee_bp)?;
-    let maker_fee = get_sol_fee(total_price, args.maker_fee_bp)?;
-    let taker_fee = get_sol_fee(total_price, args.taker_fee_bp)?;
-    let referral_fee = u64::try_from(
-        maker_fee
-            .checked_add(taker_fee)
-            .ok_or(MMMErrorCode::NumericOverflow)?,
-    )
-    .map_err(|_| MMMErrorCode::NumericOverflow)?;
+    let (maker_fee_bp, taker_fee_bp) =
+        get_fees_bp(args.maker_fee_bp, args.taker_fee_bp, taker_fee_exempt);
+    assert_valid_fees_bp(maker_fee_bp, taker_fee_bp)?;
+    let maker_fee = get_sol_fee(total_price, maker_fee_bp)?;
+    let taker_fee = get_sol_fee(total_price, taker_fee_bp)?;
 
     let transfer_sol_to = if pool.reinvest_fulfill_sell {
         buyside_sol_escrow_account.to_account_info()
@@ -196,23 +228,14 @@ pub fn handler<'info>(
         owner.to_account_info()
     };
 
-    anchor_lang::solana_program::program::invoke(
-        &anchor_lang::solana_program::system_instruction::transfer(
-            payer.key,
-            transfer_sol_to.key,
-            u64::try_from(
-                i64::try_from(total_price)
-                    .map_err(|_| MMMErrorCode::NumericOverflow)?
-                    .checked_sub(maker_fee)
-                    .ok_or(MMMErrorCode::NumericOverflow)?,
-            )
-            .map_err(|_| MMMErrorCode::NumericOverflow)?,
-        ),
-        &[
-            payer.to_account_info(),
-            transfer_sol_to,
-            system_program.to_account_info(),
-        ],
+    pay_sell_proceeds(
+        pool,
+        &payer.to_account_info(),
+        &owner.to_account_info(),
+        transfer_sol_to,
+        total_price,
+        maker_fee,
+        &system_program.to_account_info(),
     )?;
 
     let payload = Payload {
@@ -277,22 +300,19 @@ pub fn handler<'info>(
         )?;
     }
 
-    if referral_fee > 0 {
-        anchor_lang::solana_program::program::invoke(
-            &anchor_lang::solana_program::system_instruction::transfer(
-                payer.key,
-                referral.key,
-                referral_fee,
-            ),
-            &[
-                payer.to_account_info(),
-                referral.to_account_info(),
-                system_program.to_account_info(),
-            ],
-        )?;
-    }
+    pay_referral_fees(
+        maker_fee,
+        taker_fee,
+        &payer.to_account_info(),
+        &referral.to_account_info(),
+        &taker_referral.to_account_info(),
+        &[],
+        &system_program.to_account_info(),
+    )?;
 
-    pool.spot_price = next_price;
+    add_pool_volume(pool, total_price)?;
+    let spot_price_before = pool.spot_price;
+    update_pool_curve(pool, args.asset_amount, next_price, false)?;
     pool.sellside_asset_amount = pool
         .sellside_asset_amount
         .checked_sub(args.asset_amount)
@@ -307,11 +327,14 @@ pub fn handler<'info>(
         10000,
         total_price,
         &parsed_metadata,
-        ctx.remaining_accounts,
+        remaining_accounts,
         payer.to_account_info(),
         metadata_royalty_bp,
         &[&[&[]]],
         system_program.to_account_info(),
+        &pool.extra_royalty_recipients,
+        pool.get_royalty_fallback_account(),
+        pool.require_all_creators_paid,
     )?;
 
     // prevent frontrun by pool config changes
@@ -330,7 +353,10 @@ pub fn handler<'info>(
         .asset_amount
         .checked_sub(args.asset_amount)
         .ok_or(MMMErrorCode::NumericOverflow)?;
-    try_close_sell_state(sell_state, owner.to_account_info())?;
+    try_close_sell_state(
+        sell_state,
+        get_sell_state_rent_recipient(pool, owner.to_account_info(), payer.to_account_info()),
+    )?;
 
     pool.buyside_payment_amount = buyside_sol_escrow_account.lamports();
     log_pool("post_sol_mip1_fulfill_sell", pool)?;
@@ -343,5 +369,17 @@ pub fn handler<'info>(
         total_price,
     );
 
+    log_fill_summary(&FillSummary {
+        asset_mint: asset_mint.key(),
+        asset_amount: args.asset_amount,
+        total_price,
+        lp_fee,
+        royalty_paid,
+        maker_fee,
+        taker_fee,
+        spot_price_before,
+        spot_price_after: pool.spot_price,
+    })?;
+
     Ok(())
 }
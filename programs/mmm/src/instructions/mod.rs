@@ -17,6 +17,11 @@ use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
 use crate::{
     constants::*,
     errors::MMMErrorCode,
-    state::{Allowlist, Pool},
+    state::{
+        Allowlist, LpFeeTier, Pool, RoyaltyRecipient, TakerFeeExemption,
+        ALLOWLIST_MATCH_MODE_INTERSECTION, ALLOWLIST_MATCH_MODE_UNION, CURVE_DIRECTION_NORMAL,
+        LP_FEE_ROUNDING_FLOOR, LP_FEE_ROUNDING_NEAREST, POOL_SIDE_SELL_ONLY, POOL_SIDE_TWO_SIDED,
+        ROYALTY_ENFORCEMENT_MODE_BYPASS, ROYALTY_ENFORCEMENT_MODE_OPTIONAL,
+    },
     util::*,
 };
@@ -11,7 +11,10 @@ use crate::{
     errors::MMMErrorCode,
     instructions::vanilla::DepositSellArgs,
     state::{Pool, SellState},
-    util::{check_allowlists_for_mint, log_pool},
+    util::{
+        check_allowlists_for_mint, check_one_of_one, check_programmable, log_pool,
+        split_merkle_proof,
+    },
 };
 
 #[derive(Accounts)]
@@ -106,13 +109,20 @@ pub fn handler(ctx: Context<OcpDepositSell>, args: DepositSellArgs) -> Result<()
         return Err(MMMErrorCode::InvalidAccountState.into());
     }
 
-    check_allowlists_for_mint(
-        &pool.allowlists,
+    let allowlists = pool.get_allowlists();
+    let (merkle_proof, _) =
+        split_merkle_proof(&allowlists, &asset_mint.key(), ctx.remaining_accounts);
+    let parsed_metadata = check_allowlists_for_mint(
+        &allowlists,
+        pool.allowlist_match_mode,
         asset_mint,
         asset_metadata,
         None,
-        args.allowlist_aux,
+        pool.get_allowlist_aux(args.allowlist_aux),
+        merkle_proof,
     )?;
+    check_one_of_one(pool, None)?;
+    check_programmable(pool, Some(&parsed_metadata))?;
 
     init_if_needed_ocp_ata(
         ctx.accounts.ocp_program.to_account_info(),
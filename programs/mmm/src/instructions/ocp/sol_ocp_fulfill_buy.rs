@@ -5,7 +5,6 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount},
 };
 use open_creator_protocol::state::Policy;
-use std::convert::TryFrom;
 
 use crate::{
     ata::init_if_needed_ocp_ata,
@@ -17,10 +16,14 @@ use crate::{
     },
     state::{Pool, SellState},
     util::{
-        assert_valid_fees_bp, check_allowlists_for_mint, get_buyside_seller_receives,
+        add_buyside_spent, add_pool_volume, assert_valid_fees_bp, check_allowlists_for_mint,
+        check_buyside_escrow_funded, check_collection_metadata, check_fill_cooldown,
+        check_min_fill_amount, check_one_of_one, check_oracle_deviation, check_pool_side,
+        check_price_slippage, check_programmable, check_referrals, get_buyside_seller_receives,
         get_lp_fee_bp, get_metadata_royalty_bp, get_sol_fee, get_sol_lp_fee,
-        get_sol_total_price_and_next_price, log_pool, pay_creator_fees_in_sol, try_close_escrow,
-        try_close_pool, try_close_sell_state,
+        get_sol_total_price_and_next_price, log_fill_summary, log_pool, pay_creator_fees_in_sol,
+        pay_referral_fees, split_merkle_proof, try_close_escrow, try_close_pool,
+        try_close_sell_state, update_pool_curve, FillSummary,
     },
 };
 
@@ -37,9 +40,12 @@ pub struct SolOcpFulfillBuy<'info> {
     #[account(mut)]
     pub owner: UncheckedAccount<'info>,
     pub cosigner: Signer<'info>,
+    /// CHECK: we will check that the referral matches the pool's referral, it receives the maker fee
     #[account(mut)]
-    /// CHECK: we will check that the referral matches the pool's referral
     pub referral: UncheckedAccount<'info>,
+    /// CHECK: the taker's referral receives the taker fee, it will be checked by the cosigner
+    #[account(mut)]
+    pub taker_referral: UncheckedAccount<'info>,
     #[account(
         mut,
         seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
@@ -47,6 +53,7 @@ pub struct SolOcpFulfillBuy<'info> {
         has_one = referral @ MMMErrorCode::InvalidReferral,
         has_one = cosigner @ MMMErrorCode::InvalidCosigner,
         constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        constraint = !pool.settle_in_wsol @ MMMErrorCode::WsolSettlementNotSupported,
         constraint = pool.expiry == 0 || pool.expiry > Clock::get().unwrap().unix_timestamp @ MMMErrorCode::Expired,
         bump
     )]
@@ -141,9 +148,19 @@ pub fn handler<'info>(
     let system_program = &ctx.accounts.system_program;
     let associated_token_program = &ctx.accounts.associated_token_program;
     let pool = &mut ctx.accounts.pool;
+    check_pool_side(pool, true)?;
+    check_fill_cooldown(pool)?;
+    check_min_fill_amount(pool, args.asset_amount)?;
+    check_referrals(
+        pool,
+        ctx.accounts.payer.key,
+        ctx.accounts.referral.key,
+        ctx.accounts.taker_referral.key,
+    )?;
     let sell_state = &mut ctx.accounts.sell_state;
     let owner = &ctx.accounts.owner;
     let referral = &ctx.accounts.referral;
+    let taker_referral = &ctx.accounts.taker_referral;
     let payer = &ctx.accounts.payer;
     let payer_asset_account = &ctx.accounts.payer_asset_account;
     let asset_mint = &ctx.accounts.asset_mint;
@@ -156,40 +173,61 @@ pub fn handler<'info>(
         pool_key.as_ref(),
         &[ctx.bumps.buyside_sol_escrow_account],
     ]];
-    let remaining_accounts = ctx.remaining_accounts;
+    let remaining_accounts = check_oracle_deviation(pool, ctx.remaining_accounts)?;
+    let allowlists = pool.get_allowlists();
+    let (merkle_proof, remaining_accounts) =
+        split_merkle_proof(&allowlists, &asset_mint.key(), remaining_accounts);
 
     let parsed_metadata = check_allowlists_for_mint(
-        &pool.allowlists,
+        &allowlists,
+        pool.allowlist_match_mode,
         asset_mint,
         payer_asset_metadata,
         None,
-        args.allowlist_aux,
+        pool.get_allowlist_aux(args.allowlist_aux),
+        merkle_proof,
     )?;
+    check_one_of_one(pool, None)?;
+    check_programmable(pool, Some(&parsed_metadata))?;
 
     let (total_price, next_price) =
         get_sol_total_price_and_next_price(pool, args.asset_amount, true)?;
+    check_price_slippage(
+        total_price,
+        args.asset_amount,
+        args.min_price_per_item,
+        None,
+    )?;
     let metadata_royalty_bp =
         get_metadata_royalty_bp(total_price, &parsed_metadata, Some(ocp_policy));
     let seller_receives = {
-        let lp_fee_bp = get_lp_fee_bp(pool, buyside_sol_escrow_account.lamports());
+        let lp_fee_bp = get_lp_fee_bp(
+            pool,
+            buyside_sol_escrow_account.lamports(),
+            args.asset_amount,
+        )?;
         get_buyside_seller_receives(total_price, lp_fee_bp, metadata_royalty_bp, 10000)
     }?;
-    let lp_fee = get_sol_lp_fee(pool, buyside_sol_escrow_account.lamports(), seller_receives)?;
+    let lp_fee = get_sol_lp_fee(
+        pool,
+        buyside_sol_escrow_account.lamports(),
+        seller_receives,
+        args.asset_amount,
+    )?;
 
     assert_valid_fees_bp(args.maker_fee_bp, args.taker_fee_bp)?;
     let maker_fee = get_sol_fee(seller_receives, args.maker_fee_bp)?;
     let taker_fee = get_sol_fee(seller_receives, args.taker_fee_bp)?;
-    let referral_fee = u64::try_from(
-        maker_fee
-            .checked_add(taker_fee)
-            .ok_or(MMMErrorCode::NumericOverflow)?,
-    )
-    .map_err(|_| MMMErrorCode::NumericOverflow)?;
 
     // check creator_accounts and verify the remaining accounts
     let creator_accounts = if pool.using_shared_escrow() {
         check_remaining_accounts_for_m2(remaining_accounts, &pool.owner.key())?;
 
+        check_buyside_escrow_funded(
+            index_ra!(remaining_accounts, 1).lamports(),
+            total_price,
+            maker_fee,
+        )?;
         let amount: u64 = (total_price as i64 + maker_fee) as u64;
         withdraw_m2(
             pool,
@@ -208,8 +246,14 @@ pub fn handler<'info>(
 
         &remaining_accounts[2..]
     } else {
+        check_buyside_escrow_funded(
+            buyside_sol_escrow_account.lamports(),
+            total_price,
+            maker_fee,
+        )?;
         remaining_accounts
     };
+    let creator_accounts = check_collection_metadata(pool, &parsed_metadata, creator_accounts)?;
 
     let (target_token_account, target_authority) = if pool.reinvest_fulfill_buy {
         (
@@ -306,6 +350,9 @@ pub fn handler<'info>(
         metadata_royalty_bp,
         buyside_sol_escrow_account_seeds,
         system_program.to_account_info(),
+        &pool.extra_royalty_recipients,
+        pool.get_royalty_fallback_account(),
+        pool.require_all_creators_paid,
     )?;
 
     // prevent frontrun by pool config changes
@@ -352,27 +399,24 @@ pub fn handler<'info>(
             buyside_sol_escrow_account_seeds,
         )?;
     }
-    if referral_fee > 0 {
-        anchor_lang::solana_program::program::invoke_signed(
-            &anchor_lang::solana_program::system_instruction::transfer(
-                buyside_sol_escrow_account.key,
-                referral.key,
-                referral_fee,
-            ),
-            &[
-                buyside_sol_escrow_account.to_account_info(),
-                referral.to_account_info(),
-                system_program.to_account_info(),
-            ],
-            buyside_sol_escrow_account_seeds,
-        )?;
-    }
+    pay_referral_fees(
+        maker_fee,
+        taker_fee,
+        &buyside_sol_escrow_account.to_account_info(),
+        &referral.to_account_info(),
+        &taker_referral.to_account_info(),
+        buyside_sol_escrow_account_seeds,
+        &system_program.to_account_info(),
+    )?;
 
     pool.lp_fee_earned = pool
         .lp_fee_earned
         .checked_add(lp_fee)
         .ok_or(MMMErrorCode::NumericOverflow)?;
-    pool.spot_price = next_price;
+    add_buyside_spent(pool, total_price)?;
+    add_pool_volume(pool, total_price)?;
+    let spot_price_before = pool.spot_price;
+    update_pool_curve(pool, args.asset_amount, next_price, true)?;
 
     try_close_escrow(
         &buyside_sol_escrow_account.to_account_info(),
@@ -386,9 +430,11 @@ pub fn handler<'info>(
     if pool.using_shared_escrow() {
         let min_rent = Rent::get()?.minimum_balance(0);
         let shared_escrow_account = index_ra!(remaining_accounts, 1).to_account_info();
-        if shared_escrow_account.lamports() + buyside_sol_escrow_account.lamports() > min_rent
-            && buyside_sol_escrow_account.lamports() > 0
-        {
+        let combined_lamports = shared_escrow_account
+            .lamports()
+            .checked_add(buyside_sol_escrow_account.lamports())
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+        if combined_lamports > min_rent && buyside_sol_escrow_account.lamports() > 0 {
             anchor_lang::solana_program::program::invoke_signed(
                 &anchor_lang::solana_program::system_instruction::transfer(
                     buyside_sol_escrow_account.key,
@@ -423,5 +469,17 @@ pub fn handler<'info>(
         total_price,
     );
 
+    log_fill_summary(&FillSummary {
+        asset_mint: asset_mint.key(),
+        asset_amount: args.asset_amount,
+        total_price,
+        lp_fee,
+        royalty_paid,
+        maker_fee,
+        taker_fee,
+        spot_price_before,
+        spot_price_after: pool.spot_price,
+    })?;
+
     Ok(())
 }
@@ -0,0 +1,120 @@
+use anchor_lang::{
+    prelude::*, solana_program::log::sol_log_data, AnchorDeserialize, AnchorSerialize,
+};
+
+use crate::{
+    constants::*,
+    errors::MMMErrorCode,
+    state::Pool,
+    util::{
+        check_pool_side, check_remaining_accounts_for_m2, get_buy_fulfill_pool_price_info,
+        PoolPriceInfo,
+    },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CheckPoolSolvencyArgs {
+    pub fulfill_buy: bool,
+    pub asset_amount: u64,
+    pub maker_fee_bp: i16,
+    pub taker_fee_bp: i16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolSolvency {
+    pub fulfill_buy: bool,
+    pub asset_amount: u64,
+    pub required: u64,  // lamports for a fulfill buy, assets for a fulfill sell
+    pub available: u64, // the buyside escrow balance, or the sellside asset amount
+    pub solvent: bool,
+}
+
+// CheckPoolSolvency is a read-only instruction, it logs whether the pool can honor a
+// fulfill of asset_amount in the given direction, so that integrators can check a pool
+// before routing to it. A fulfill buy needs the quoted total price and the maker fee in
+// the buyside escrow, the maker fee is quoted without royalties so it's an upper bound.
+// A fulfill sell needs the asset_amount in the sellside.
+// A shared escrow pool spends from the m2 shared escrow of the owner rather than its own
+// buyside escrow, so the shared escrow balance is the available budget of a fulfill buy.
+// Remaining accounts, only when the pool is using the shared escrow
+//   0: m2_program
+//   1: shared_escrow_account
+#[derive(Accounts)]
+#[instruction(args:CheckPoolSolvencyArgs)]
+pub struct CheckPoolSolvency<'info> {
+    /// CHECK: we will check the owner field that matches the pool owner
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+        constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    /// CHECK: it's a pda, and the private key is owned by the seeds
+    #[account(
+        seeds = [BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump,
+    )]
+    pub buyside_sol_escrow_account: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<CheckPoolSolvency>, args: CheckPoolSolvencyArgs) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let buyside_sol_escrow_account = &ctx.accounts.buyside_sol_escrow_account;
+    check_pool_side(pool, args.fulfill_buy)?;
+
+    let (required, available, solvent) = if args.fulfill_buy {
+        let PoolPriceInfo {
+            total_price,
+            maker_fee,
+            ..
+        } = get_buy_fulfill_pool_price_info(
+            pool,
+            // the payer is only returned as the transfer_sol_to, which isn't used here
+            &buyside_sol_escrow_account.to_account_info(),
+            &buyside_sol_escrow_account.to_account_info(),
+            args.asset_amount,
+            0, // metadata_royalty_bp
+            args.maker_fee_bp,
+            args.taker_fee_bp,
+        )?;
+        let required = u64::try_from(
+            i64::try_from(total_price)
+                .map_err(|_| MMMErrorCode::NumericOverflow)?
+                .checked_add(maker_fee)
+                .ok_or(MMMErrorCode::NumericOverflow)?,
+        )
+        .map_err(|_| MMMErrorCode::NumericOverflow)?;
+
+        // a shared escrow pool is also bound by the number of fills set_shared_escrow allowed
+        let (available, within_count) = if pool.using_shared_escrow() {
+            check_remaining_accounts_for_m2(ctx.remaining_accounts, &pool.owner)?;
+            (
+                ctx.remaining_accounts[1].lamports(),
+                args.asset_amount <= pool.shared_escrow_count,
+            )
+        } else {
+            (buyside_sol_escrow_account.lamports(), true)
+        };
+        (required, available, within_count && available >= required)
+    } else {
+        (
+            args.asset_amount,
+            pool.sellside_asset_amount,
+            pool.sellside_asset_amount >= args.asset_amount,
+        )
+    };
+
+    let solvency = PoolSolvency {
+        fulfill_buy: args.fulfill_buy,
+        asset_amount: args.asset_amount,
+        required,
+        available,
+        solvent,
+    };
+    msg!("pool_solvency");
+    sol_log_data(&[&solvency.try_to_vec()?]);
+
+    Ok(())
+}
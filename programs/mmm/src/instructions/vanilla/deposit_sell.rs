@@ -8,7 +8,10 @@ use crate::{
     constants::*,
     errors::MMMErrorCode,
     state::{Pool, SellState},
-    util::{check_allowlists_for_mint, log_pool},
+    util::{
+        assert_token_program_matches_mint, check_allowlists_for_mint, check_one_of_one,
+        check_programmable, log_pool, split_merkle_proof,
+    },
 };
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -89,6 +92,10 @@ pub fn handler(ctx: Context<DepositSell>, args: DepositSellArgs) -> Result<()> {
     let asset_master_edition = &ctx.accounts.asset_master_edition;
     let sellside_escrow_token_account = &ctx.accounts.sellside_escrow_token_account;
     let token_program = &ctx.accounts.token_program;
+    assert_token_program_matches_mint(
+        &ctx.accounts.asset_mint.to_account_info(),
+        token_program.key,
+    )?;
     let pool = &mut ctx.accounts.pool;
     let sell_state = &mut ctx.accounts.sell_state;
 
@@ -96,13 +103,20 @@ pub fn handler(ctx: Context<DepositSell>, args: DepositSellArgs) -> Result<()> {
         return Err(MMMErrorCode::InvalidAccountState.into());
     }
 
-    check_allowlists_for_mint(
-        &pool.allowlists,
+    let allowlists = pool.get_allowlists();
+    let (merkle_proof, _) =
+        split_merkle_proof(&allowlists, &asset_mint.key(), ctx.remaining_accounts);
+    let parsed_metadata = check_allowlists_for_mint(
+        &allowlists,
+        pool.allowlist_match_mode,
         asset_mint,
         asset_metadata,
         Some(asset_master_edition),
-        args.allowlist_aux,
+        pool.get_allowlist_aux(args.allowlist_aux),
+        merkle_proof,
     )?;
+    check_one_of_one(pool, Some(asset_master_edition))?;
+    check_programmable(pool, Some(&parsed_metadata))?;
 
     anchor_spl::token_2022::transfer(
         CpiContext::new(
@@ -0,0 +1,209 @@
+use anchor_lang::{
+    prelude::*, solana_program::program::invoke_signed, AnchorDeserialize, AnchorSerialize,
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::{
+    ata::init_if_needed_ata,
+    constants::*,
+    errors::MMMErrorCode,
+    state::{Pool, SellState},
+    util::{
+        assert_token_program_matches_mint, check_one_of_one, check_programmable, log_pool,
+        AllowlistMatcher,
+    },
+};
+
+// the accounts of each mint in the remaining accounts:
+//   asset_mint, asset_metadata, asset_master_edition, asset_token_account,
+//   sellside_escrow_token_account, sell_state
+const BATCH_MINT_ACCOUNTS_LEN: usize = 6;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct DepositSellBatchArgs {
+    pub asset_amount: u64, // number of mints in the remaining accounts, one asset each
+    pub allowlist_aux: Option<String>, // TODO: use it for future allowlist_aux
+}
+
+// DepositSellBatch is the same as DepositSell, except that the owner
+// deposits one asset of each of the asset_amount mints in the remaining
+// accounts, and the sellside escrow token accounts and the sell states
+// are created as needed.
+#[derive(Accounts)]
+#[instruction(args:DepositSellBatchArgs)]
+pub struct DepositSellBatch<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub cosigner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+        has_one = cosigner @ MMMErrorCode::InvalidCosigner,
+        constraint = !pool.using_shared_escrow() @ MMMErrorCode::InvalidAccountState,
+        constraint = args.asset_amount > 0 && args.asset_amount <= MAX_DEPOSIT_SELL_BATCH_SIZE @ MMMErrorCode::InvalidBatchSize,
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    /// CHECK: will be used for allowlist checks
+    pub allowlist_aux_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositSellBatch<'info>>,
+    args: DepositSellBatchArgs,
+) -> Result<()> {
+    let owner = &ctx.accounts.owner;
+    let system_program = &ctx.accounts.system_program;
+    let token_program = &ctx.accounts.token_program;
+    let associated_token_program = &ctx.accounts.associated_token_program;
+    let rent = &ctx.accounts.rent;
+    let pool = &mut ctx.accounts.pool;
+    let pool_key = pool.key();
+
+    let remaining_accounts = ctx.remaining_accounts.chunks_exact(BATCH_MINT_ACCOUNTS_LEN);
+    if !remaining_accounts.remainder().is_empty()
+        || remaining_accounts.len() as u64 != args.asset_amount
+    {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    }
+    let allowlist_aux = pool.get_allowlist_aux(args.allowlist_aux);
+    let allowlists = pool.get_allowlists();
+    let allowlist_matcher = AllowlistMatcher::new(&allowlists, pool.allowlist_match_mode);
+    for mint_accounts in remaining_accounts {
+        assert_token_program_matches_mint(&mint_accounts[0], token_program.key)?;
+        let asset_mint = InterfaceAccount::<Mint>::try_from(&mint_accounts[0])?;
+        let asset_metadata = &mint_accounts[1];
+        let asset_master_edition = &mint_accounts[2];
+        let asset_token_account_info = &mint_accounts[3];
+        let sellside_escrow_token_account = &mint_accounts[4];
+        let sell_state_info = &mint_accounts[5];
+
+        // the remaining accounts are laid out per mint, so there is no room for a
+        // merkle proof and merkle entries don't match in a batch
+        let parsed_metadata = allowlist_matcher.check_mint(
+            &asset_mint,
+            asset_metadata,
+            Some(asset_master_edition),
+            allowlist_aux.clone(),
+            &[],
+        )?;
+        check_one_of_one(pool, Some(asset_master_edition))?;
+        check_programmable(pool, Some(&parsed_metadata))?;
+
+        if asset_token_account_info.key()
+            != get_associated_token_address_with_program_id(
+                owner.key,
+                &asset_mint.key(),
+                token_program.key,
+            )
+            || sellside_escrow_token_account.key()
+                != get_associated_token_address_with_program_id(
+                    &pool_key,
+                    &asset_mint.key(),
+                    token_program.key,
+                )
+        {
+            return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+        }
+        let asset_token_account =
+            InterfaceAccount::<TokenAccount>::try_from(asset_token_account_info)?;
+
+        init_if_needed_ata(
+            sellside_escrow_token_account.to_account_info(),
+            owner.to_account_info(),
+            pool.to_account_info(),
+            asset_mint.to_account_info(),
+            associated_token_program.to_account_info(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+        )?;
+        anchor_spl::token_2022::transfer_checked(
+            CpiContext::new(
+                token_program.to_account_info(),
+                anchor_spl::token_2022::TransferChecked {
+                    from: asset_token_account.to_account_info(),
+                    mint: asset_mint.to_account_info(),
+                    to: sellside_escrow_token_account.to_account_info(),
+                    authority: owner.to_account_info(),
+                },
+            ),
+            1,
+            asset_mint.decimals,
+        )?;
+        if asset_token_account.amount == 1 {
+            anchor_spl::token_2022::close_account(CpiContext::new(
+                token_program.to_account_info(),
+                anchor_spl::token_2022::CloseAccount {
+                    account: asset_token_account.to_account_info(),
+                    destination: owner.to_account_info(),
+                    authority: owner.to_account_info(),
+                },
+            ))?;
+        }
+
+        // like the init_if_needed sell_state of deposit_sell
+        let (sell_state_key, sell_state_bump) = Pubkey::find_program_address(
+            &[
+                SELL_STATE_PREFIX.as_bytes(),
+                pool_key.as_ref(),
+                asset_mint.key().as_ref(),
+            ],
+            ctx.program_id,
+        );
+        if sell_state_info.key() != sell_state_key {
+            return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+        }
+        let mut sell_state = if sell_state_info.data_is_empty() {
+            invoke_signed(
+                &anchor_lang::solana_program::system_instruction::create_account(
+                    owner.key,
+                    sell_state_info.key,
+                    rent.minimum_balance(SellState::LEN),
+                    SellState::LEN as u64,
+                    ctx.program_id,
+                ),
+                &[
+                    owner.to_account_info(),
+                    sell_state_info.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+                &[&[
+                    SELL_STATE_PREFIX.as_bytes(),
+                    pool_key.as_ref(),
+                    asset_mint.key().as_ref(),
+                    &[sell_state_bump],
+                ]],
+            )?;
+            SellState::default()
+        } else {
+            Account::<SellState>::try_from(sell_state_info)?.into_inner()
+        };
+        sell_state.pool = pool_key;
+        sell_state.pool_owner = owner.key();
+        sell_state.asset_mint = asset_mint.key();
+        sell_state.cosigner_annotation = pool.cosigner_annotation;
+        sell_state.asset_amount = sell_state
+            .asset_amount
+            .checked_add(1)
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+        sell_state.try_serialize(&mut &mut sell_state_info.try_borrow_mut_data()?[..])?;
+    }
+
+    pool.sellside_asset_amount = pool
+        .sellside_asset_amount
+        .checked_add(args.asset_amount)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    log_pool("post_deposit_sell_batch", pool)?;
+
+    Ok(())
+}
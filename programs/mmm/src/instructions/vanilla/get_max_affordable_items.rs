@@ -0,0 +1,63 @@
+use anchor_lang::{
+    prelude::*, solana_program::log::sol_log_data, AnchorDeserialize, AnchorSerialize,
+};
+
+use crate::{constants::*, errors::MMMErrorCode, state::Pool, util::max_affordable_items};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetMaxAffordableItemsArgs {
+    pub budget: u64,
+    pub maker_fee_bp: i16,
+    pub taker_fee_bp: i16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MaxAffordableItems {
+    pub budget: u64,
+    pub asset_amount: u64,
+}
+
+// GetMaxAffordableItems is a read-only instruction, it logs how many assets
+// a buyer can take from the pool with the given budget, so that sweeps can
+// be sized with a single simulation.
+#[derive(Accounts)]
+#[instruction(args:GetMaxAffordableItemsArgs)]
+pub struct GetMaxAffordableItems<'info> {
+    /// CHECK: we will check the owner field that matches the pool owner
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+        constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    /// CHECK: it's a pda, and the private key is owned by the seeds
+    #[account(
+        seeds = [BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump,
+    )]
+    pub buyside_sol_escrow_account: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<GetMaxAffordableItems>, args: GetMaxAffordableItemsArgs) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let buyside_sol_escrow_account = &ctx.accounts.buyside_sol_escrow_account;
+
+    let asset_amount = max_affordable_items(
+        pool,
+        buyside_sol_escrow_account.lamports(),
+        args.budget,
+        args.maker_fee_bp,
+        args.taker_fee_bp,
+    )?;
+
+    let affordable = MaxAffordableItems {
+        budget: args.budget,
+        asset_amount,
+    };
+    msg!("max_affordable_items");
+    sol_log_data(&[&affordable.try_to_vec()?]);
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use anchor_lang::{
+    prelude::*, solana_program::log::sol_log_data, AnchorDeserialize, AnchorSerialize,
+};
+
+use crate::{
+    constants::*,
+    errors::MMMErrorCode,
+    state::Pool,
+    util::{check_remaining_accounts_for_m2, get_sell_fulfill_pool_price_info, PoolPriceInfo},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetPoolNotionalValueArgs {
+    pub maker_fee_bp: i16,
+    pub taker_fee_bp: i16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolNotionalValue {
+    pub pool: Pubkey,
+    pub buyside_value: u64, // the buyside escrow balance, or the m2 shared escrow balance
+    pub sellside_value: u64, // the fulfill sell quote of the whole sellside inventory
+    pub fees_owed: i64,     // the maker fee of that fulfill sell, negative for a rebate
+    pub notional_value: u64, // buyside_value + sellside_value - fees_owed
+}
+
+// GetPoolNotionalValue is a read-only instruction, it logs what the pool would be worth
+// to its owner if the whole sellside inventory was sold into the pool's own sell curve,
+// so that dashboards can show a single value per pool with one simulation.
+// Remaining accounts, only when the pool is using the shared escrow
+//   0: m2_program
+//   1: shared_escrow_account
+#[derive(Accounts)]
+#[instruction(args:GetPoolNotionalValueArgs)]
+pub struct GetPoolNotionalValue<'info> {
+    /// CHECK: we will check the owner field that matches the pool owner
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+        constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    /// CHECK: it's a pda, and the private key is owned by the seeds
+    #[account(
+        seeds = [BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump,
+    )]
+    pub buyside_sol_escrow_account: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<GetPoolNotionalValue>, args: GetPoolNotionalValueArgs) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let owner = &ctx.accounts.owner;
+    let buyside_sol_escrow_account = &ctx.accounts.buyside_sol_escrow_account;
+
+    let buyside_value = if pool.using_shared_escrow() {
+        check_remaining_accounts_for_m2(ctx.remaining_accounts, &pool.owner)?;
+        ctx.remaining_accounts[1].lamports()
+    } else {
+        buyside_sol_escrow_account.lamports()
+    };
+
+    let (sellside_value, fees_owed) = if pool.sellside_asset_amount == 0 {
+        (0, 0)
+    } else {
+        let PoolPriceInfo {
+            total_price,
+            maker_fee,
+            ..
+        } = get_sell_fulfill_pool_price_info(
+            pool,
+            owner,
+            &buyside_sol_escrow_account.to_account_info(),
+            pool.sellside_asset_amount,
+            args.maker_fee_bp,
+            args.taker_fee_bp,
+            // there is no taker, so no taker fee exemption applies
+            false,
+        )?;
+        (total_price, maker_fee)
+    };
+
+    let notional_value = u64::try_from(
+        i128::from(buyside_value)
+            .checked_add(i128::from(sellside_value))
+            .and_then(|value| value.checked_sub(i128::from(fees_owed)))
+            .ok_or(MMMErrorCode::NumericOverflow)?,
+    )
+    .map_err(|_| MMMErrorCode::NumericOverflow)?;
+
+    let notional = PoolNotionalValue {
+        pool: pool.key(),
+        buyside_value,
+        sellside_value,
+        fees_owed,
+        notional_value,
+    };
+    msg!("pool_notional_value");
+    sol_log_data(&[&notional.try_to_vec()?]);
+
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use anchor_lang::{
+    prelude::*, solana_program::log::sol_log_data, AnchorDeserialize, AnchorSerialize,
+};
+use mpl_token_metadata::accounts::Metadata;
+use open_creator_protocol::state::Policy;
+
+use crate::{
+    constants::*,
+    errors::MMMErrorCode,
+    state::Pool,
+    util::{
+        check_pool_side, get_buy_fulfill_pool_price_info, get_metadata_royalty_bp,
+        get_sell_fulfill_pool_price_info, get_sol_total_price_and_next_price,
+        split_taker_fee_exemption, PoolPriceInfo,
+    },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetPoolQuoteArgs {
+    pub fulfill_buy: bool,
+    pub asset_amount: u64,
+    pub metadata_royalty_bp: u16, // only used when the asset_metadata is not passed
+    pub maker_fee_bp: i16,
+    pub taker_fee_bp: i16,
+    // clamps asset_amount to the largest amount that can be quoted without a NumericOverflow
+    pub saturating: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolQuote {
+    pub total_price: u64,
+    pub next_price: u64,
+    pub lp_fee: u64,
+    pub maker_fee: i64,
+    pub taker_fee: i64,
+    pub referral_fee: u64,
+    pub royalty_bp: u16,   // the effective royalty bp at the quoted total_price
+    pub asset_amount: u64, // less than args.asset_amount when a saturating quote is clamped
+}
+
+// GetPoolQuote is a read-only instruction, it does not mutate any state
+// and only logs the PoolQuote for the fulfill that would have been
+// executed against the pool, so that it can be simulated by integrators.
+// The remaining accounts are optional: asset_metadata, ocp_policy. When they are passed,
+// the royalty bp is resolved like the fulfill would, including the dynamic royalty of
+// the ocp policy at the quoted total_price, instead of using args.metadata_royalty_bp.
+#[derive(Accounts)]
+#[instruction(args:GetPoolQuoteArgs)]
+pub struct GetPoolQuote<'info> {
+    pub payer: Signer<'info>,
+    /// CHECK: we will check the owner field that matches the pool owner
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+        constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    /// CHECK: it's a pda, and the private key is owned by the seeds
+    #[account(
+        seeds = [BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump,
+    )]
+    pub buyside_sol_escrow_account: UncheckedAccount<'info>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetPoolQuote<'info>>,
+    args: GetPoolQuoteArgs,
+) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let owner = &ctx.accounts.owner;
+    let payer = &ctx.accounts.payer;
+    let buyside_sol_escrow_account = &ctx.accounts.buyside_sol_escrow_account;
+    check_pool_side(pool, args.fulfill_buy)?;
+
+    // the taker fee exemption of the payer goes last, as on a fulfill sell
+    let (remaining_accounts, taker_fee_exempt) =
+        split_taker_fee_exemption(&pool.key(), payer.key, ctx.remaining_accounts);
+    // the metadata and the policy are parsed once, the royalty bp depends on the quoted amount
+    let royalty_accounts = match remaining_accounts {
+        [] => None,
+        [asset_metadata] => Some((parse_quote_metadata(asset_metadata)?, None)),
+        [asset_metadata, ocp_policy] => Some((
+            parse_quote_metadata(asset_metadata)?,
+            Some(Account::<Policy>::try_from(ocp_policy)?),
+        )),
+        _ => return Err(MMMErrorCode::InvalidRemainingAccounts.into()),
+    };
+
+    let quote = |asset_amount: u64| -> Result<PoolQuote> {
+        let royalty_bp = match &royalty_accounts {
+            None => args.metadata_royalty_bp,
+            Some((parsed_metadata, ocp_policy)) => {
+                let (total_price, _) =
+                    get_sol_total_price_and_next_price(pool, asset_amount, args.fulfill_buy)?;
+                get_metadata_royalty_bp(total_price, parsed_metadata, ocp_policy.as_ref())
+            }
+        };
+
+        let PoolPriceInfo {
+            total_price,
+            next_price,
+            seller_receives: _,
+            lp_fee,
+            maker_fee,
+            taker_fee,
+            referral_fee,
+            transfer_sol_to: _,
+        } = if args.fulfill_buy {
+            get_buy_fulfill_pool_price_info(
+                pool,
+                &payer.to_account_info(),
+                &buyside_sol_escrow_account.to_account_info(),
+                asset_amount,
+                royalty_bp,
+                args.maker_fee_bp,
+                args.taker_fee_bp,
+            )?
+        } else {
+            get_sell_fulfill_pool_price_info(
+                pool,
+                owner,
+                &buyside_sol_escrow_account.to_account_info(),
+                asset_amount,
+                args.maker_fee_bp,
+                args.taker_fee_bp,
+                taker_fee_exempt,
+            )?
+        };
+
+        Ok(PoolQuote {
+            total_price,
+            next_price,
+            lp_fee,
+            maker_fee,
+            taker_fee,
+            referral_fee,
+            royalty_bp,
+            asset_amount,
+        })
+    };
+
+    let asset_amount = if args.saturating {
+        // binary search for the largest amount that doesn't overflow, the prices only grow
+        // with the amount, other errors are left to the quote below
+        let overflows = |asset_amount: u64| {
+            quote(asset_amount).is_err_and(|e| e == MMMErrorCode::NumericOverflow.into())
+        };
+        let (mut lo, mut hi) = (0, args.asset_amount);
+        while lo < hi {
+            let mid = lo + (hi - lo).div_ceil(2);
+            if overflows(mid) {
+                hi = mid - 1;
+            } else {
+                lo = mid;
+            }
+        }
+        lo
+    } else {
+        args.asset_amount
+    };
+    let quote = quote(asset_amount)?;
+    msg!("pool_quote");
+    sol_log_data(&[&quote.try_to_vec()?]);
+
+    Ok(())
+}
+
+fn parse_quote_metadata(asset_metadata: &AccountInfo) -> Result<Metadata> {
+    if *asset_metadata.owner != mpl_token_metadata::ID {
+        return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+    let parsed_metadata = Metadata::safe_deserialize(&asset_metadata.data.borrow())?;
+    if Metadata::find_pda(&parsed_metadata.mint).0 != asset_metadata.key() {
+        return Err(ErrorCode::ConstraintSeeds.into());
+    }
+    Ok(parsed_metadata)
+}
@@ -0,0 +1,33 @@
+use anchor_lang::{
+    prelude::*, solana_program::log::sol_log_data, AnchorDeserialize, AnchorSerialize,
+};
+
+use crate::state::SellState;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SellStateSummary {
+    pub pool: Pubkey,
+    pub asset_mint: Pubkey,
+    pub asset_amount: u64,
+}
+
+// LogSellState is a read-only instruction, it logs the bookkeeping of a sell state,
+// so that integrators can check their index of the pool inventory against it.
+#[derive(Accounts)]
+pub struct LogSellState<'info> {
+    pub sell_state: Account<'info, SellState>,
+}
+
+pub fn handler(ctx: Context<LogSellState>) -> Result<()> {
+    let sell_state = &ctx.accounts.sell_state;
+
+    let summary = SellStateSummary {
+        pool: sell_state.pool,
+        asset_mint: sell_state.asset_mint,
+        asset_amount: sell_state.asset_amount,
+    };
+    msg!("sell_state");
+    sol_log_data(&[&summary.try_to_vec()?]);
+
+    Ok(())
+}
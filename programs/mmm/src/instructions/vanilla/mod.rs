@@ -1,17 +1,33 @@
 #![allow(missing_docs)]
 
+pub mod check_pool_solvency;
 pub mod close_if_balance_invalid;
 pub mod deposit_sell;
+pub mod deposit_sell_batch;
+pub mod get_max_affordable_items;
+pub mod get_pool_notional_value;
+pub mod get_pool_quote;
+pub mod log_sell_state;
 pub mod sol_deposit_buy;
 pub mod sol_fulfill_buy;
 pub mod sol_fulfill_sell;
+pub mod sol_fulfill_sell_batch;
 pub mod sol_withdraw_buy;
+pub mod validate_mint_against_allowlist;
 pub mod withdraw_sell;
 
+pub use check_pool_solvency::*;
 pub use close_if_balance_invalid::*;
 pub use deposit_sell::*;
+pub use deposit_sell_batch::*;
+pub use get_max_affordable_items::*;
+pub use get_pool_notional_value::*;
+pub use get_pool_quote::*;
+pub use log_sell_state::*;
 pub use sol_deposit_buy::*;
 pub use sol_fulfill_buy::*;
 pub use sol_fulfill_sell::*;
+pub use sol_fulfill_sell_batch::*;
 pub use sol_withdraw_buy::*;
+pub use validate_mint_against_allowlist::*;
 pub use withdraw_sell::*;
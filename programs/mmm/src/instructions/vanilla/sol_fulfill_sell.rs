@@ -3,16 +3,20 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
-use std::convert::TryFrom;
 
 use crate::{
     constants::*,
     errors::MMMErrorCode,
-    instructions::{get_sell_fulfill_pool_price_info, PoolPriceInfo},
+    instructions::{check_price_slippage, get_sell_fulfill_pool_price_info, PoolPriceInfo},
     state::{Pool, SellState},
     util::{
-        check_allowlists_for_mint, get_metadata_royalty_bp, log_pool, pay_creator_fees_in_sol,
-        try_close_pool, try_close_sell_state,
+        add_pool_volume, assert_token_program_matches_mint, check_allowlists_for_mint,
+        check_fill_cooldown, check_min_fill_amount, check_oracle_deviation, check_pool_side,
+        check_referrals, get_enforced_creator_royalty_bp, get_metadata_royalty_bp,
+        get_sell_fill_asset_amount, get_sell_state_rent_recipient, get_taker_royalty_bp,
+        log_fill_summary, log_pool, pay_creator_fees_in_sol, pay_referral_fees, pay_sell_proceeds,
+        split_merkle_proof, split_taker_fee_exemption, split_wsol_accounts, try_close_pool,
+        try_close_sell_state, update_pool_curve, FillSummary,
     },
 };
 
@@ -20,10 +24,13 @@ use crate::{
 pub struct SolFulfillSellArgs {
     pub asset_amount: u64,
     pub max_payment_amount: u64,
+    pub max_price_per_item: Option<u64>, // reject the fill if the pool price moved above this
     pub buyside_creator_royalty_bp: u16,
     pub allowlist_aux: Option<String>, // TODO: use it for future allowlist_aux
     pub maker_fee_bp: i16,             // will be checked by cosigner
     pub taker_fee_bp: i16,             // will be checked by cosigner
+    pub partial_fill_ok: bool, // fill what the pool escrows for the mint when asset_amount is more than that
+    pub royalty_bp_override: Option<u16>, // pay more than the royalty bp of the mint, see get_taker_royalty_bp
 }
 
 // FulfillSell means a buyer wants to buy NFT/SFT from the pool
@@ -39,9 +46,12 @@ pub struct SolFulfillSell<'info> {
     #[account(mut)]
     pub owner: UncheckedAccount<'info>,
     pub cosigner: Signer<'info>,
-    /// CHECK: we will check that the referral matches the pool's referral
+    /// CHECK: we will check that the referral matches the pool's referral, it receives the maker fee
     #[account(mut)]
     pub referral: UncheckedAccount<'info>,
+    /// CHECK: the taker's referral receives the taker fee, it will be checked by the cosigner
+    #[account(mut)]
+    pub taker_referral: UncheckedAccount<'info>,
     #[account(
         mut,
         seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
@@ -114,10 +124,23 @@ pub fn handler<'info>(
     args: SolFulfillSellArgs,
 ) -> Result<()> {
     let token_program = &ctx.accounts.token_program;
+    assert_token_program_matches_mint(
+        &ctx.accounts.asset_mint.to_account_info(),
+        token_program.key,
+    )?;
     let system_program = &ctx.accounts.system_program;
     let owner = &ctx.accounts.owner;
     let referral = &ctx.accounts.referral;
+    let taker_referral = &ctx.accounts.taker_referral;
     let pool = &mut ctx.accounts.pool;
+    check_pool_side(pool, false)?;
+    check_fill_cooldown(pool)?;
+    check_referrals(
+        pool,
+        ctx.accounts.payer.key,
+        ctx.accounts.referral.key,
+        ctx.accounts.taker_referral.key,
+    )?;
     let sell_state = &mut ctx.accounts.sell_state;
 
     let payer = &ctx.accounts.payer;
@@ -134,50 +157,63 @@ pub fn handler<'info>(
         pool.uuid.as_ref(),
         &[ctx.bumps.pool],
     ]];
+    let asset_amount =
+        get_sell_fill_asset_amount(args.asset_amount, args.partial_fill_ok, pool, sell_state);
+    check_min_fill_amount(pool, asset_amount)?;
 
+    let (remaining_accounts, taker_fee_exempt) =
+        split_taker_fee_exemption(&pool.key(), payer.key, ctx.remaining_accounts);
+    let remaining_accounts = check_oracle_deviation(pool, remaining_accounts)?;
+    let (remaining_accounts, wsol_accounts) =
+        split_wsol_accounts(pool, payer.key, remaining_accounts)?;
+    let allowlists = pool.get_allowlists();
+    let (merkle_proof, remaining_accounts) =
+        split_merkle_proof(&allowlists, &asset_mint.key(), remaining_accounts);
     let parsed_metadata = check_allowlists_for_mint(
-        &pool.allowlists,
+        &allowlists,
+        pool.allowlist_match_mode,
         asset_mint,
         payer_asset_metadata,
         Some(asset_master_edition),
-        args.allowlist_aux,
+        pool.get_allowlist_aux(args.allowlist_aux),
+        merkle_proof,
     )?;
 
     let PoolPriceInfo {
         total_price,
         next_price,
+        seller_receives: _,
         lp_fee,
         maker_fee,
         taker_fee,
-        referral_fee,
+        referral_fee: _,
         transfer_sol_to,
     } = get_sell_fulfill_pool_price_info(
         pool,
         owner,
         buyside_sol_escrow_account,
-        args.asset_amount,
+        asset_amount,
         args.maker_fee_bp,
         args.taker_fee_bp,
+        taker_fee_exempt,
     )?;
+    check_price_slippage(total_price, asset_amount, None, args.max_price_per_item)?;
+
+    // a wsol settled fill is paid in lamports out of the unwrapped wsol, the rest is wrapped back
+    let wsol_amount = match &wsol_accounts {
+        Some(wsol_accounts) => wsol_accounts.unwrap(&payer.to_account_info())?,
+        None => 0,
+    };
 
     // TODO: make sure that the lp fee is paid with the correct amount
-    anchor_lang::solana_program::program::invoke(
-        &anchor_lang::solana_program::system_instruction::transfer(
-            payer.key,
-            transfer_sol_to.key,
-            u64::try_from(
-                i64::try_from(total_price)
-                    .map_err(|_| MMMErrorCode::NumericOverflow)?
-                    .checked_sub(maker_fee)
-                    .ok_or(MMMErrorCode::NumericOverflow)?,
-            )
-            .map_err(|_| MMMErrorCode::NumericOverflow)?,
-        ),
-        &[
-            payer.to_account_info(),
-            transfer_sol_to,
-            system_program.to_account_info(),
-        ],
+    pay_sell_proceeds(
+        pool,
+        &payer.to_account_info(),
+        &owner.to_account_info(),
+        transfer_sol_to,
+        total_price,
+        maker_fee,
+        &system_program.to_account_info(),
     )?;
 
     anchor_spl::token_2022::transfer(
@@ -190,10 +226,10 @@ pub fn handler<'info>(
             },
             pool_seeds,
         ),
-        args.asset_amount,
+        asset_amount,
     )?;
     // we can close the sellside_escrow_token_account if no amount left
-    if sellside_escrow_token_account.amount == args.asset_amount {
+    if sellside_escrow_token_account.amount == asset_amount {
         anchor_spl::token_2022::close_account(CpiContext::new_with_signer(
             token_program.to_account_info(),
             anchor_spl::token_2022::CloseAccount {
@@ -218,41 +254,44 @@ pub fn handler<'info>(
         )?;
     }
 
-    if referral_fee > 0 {
-        anchor_lang::solana_program::program::invoke(
-            &anchor_lang::solana_program::system_instruction::transfer(
-                payer.key,
-                referral.key,
-                referral_fee,
-            ),
-            &[
-                payer.to_account_info(),
-                referral.to_account_info(),
-                system_program.to_account_info(),
-            ],
-        )?;
-    }
+    pay_referral_fees(
+        maker_fee,
+        taker_fee,
+        &payer.to_account_info(),
+        &referral.to_account_info(),
+        &taker_referral.to_account_info(),
+        &[],
+        &system_program.to_account_info(),
+    )?;
 
-    pool.spot_price = next_price;
+    add_pool_volume(pool, total_price)?;
+    let spot_price_before = pool.spot_price;
+    update_pool_curve(pool, asset_amount, next_price, false)?;
     pool.sellside_asset_amount = pool
         .sellside_asset_amount
-        .checked_sub(args.asset_amount)
+        .checked_sub(asset_amount)
         .ok_or(MMMErrorCode::NumericOverflow)?;
     pool.lp_fee_earned = pool
         .lp_fee_earned
         .checked_add(lp_fee)
         .ok_or(MMMErrorCode::NumericOverflow)?;
 
-    let royalty_bp = get_metadata_royalty_bp(total_price, &parsed_metadata, None);
+    let royalty_bp = get_taker_royalty_bp(
+        get_metadata_royalty_bp(total_price, &parsed_metadata, None),
+        args.royalty_bp_override,
+    )?;
     let royalty_paid = pay_creator_fees_in_sol(
-        args.buyside_creator_royalty_bp,
+        get_enforced_creator_royalty_bp(pool, args.buyside_creator_royalty_bp),
         total_price,
         &parsed_metadata,
-        ctx.remaining_accounts,
+        remaining_accounts,
         payer.to_account_info(),
         royalty_bp,
         &[&[&[]]],
         system_program.to_account_info(),
+        &pool.extra_royalty_recipients,
+        pool.get_royalty_fallback_account(),
+        pool.require_all_creators_paid,
     )?;
 
     // prevent frontrun by pool config changes
@@ -266,12 +305,28 @@ pub fn handler<'info>(
     if payment_amount > args.max_payment_amount {
         return Err(MMMErrorCode::InvalidRequestedPrice.into());
     }
+    if let Some(wsol_accounts) = &wsol_accounts {
+        wsol_accounts.wrap(
+            wsol_amount
+                .checked_sub(payment_amount)
+                .ok_or(MMMErrorCode::InsufficientWsolBalance)?,
+            &payer.to_account_info(),
+            &[],
+            &payer.to_account_info(),
+            &ctx.accounts.associated_token_program.to_account_info(),
+            &system_program.to_account_info(),
+            &ctx.accounts.rent.to_account_info(),
+        )?;
+    }
 
     sell_state.asset_amount = sell_state
         .asset_amount
-        .checked_sub(args.asset_amount)
+        .checked_sub(asset_amount)
         .ok_or(MMMErrorCode::NumericOverflow)?;
-    try_close_sell_state(sell_state, owner.to_account_info())?;
+    try_close_sell_state(
+        sell_state,
+        get_sell_state_rent_recipient(pool, owner.to_account_info(), payer.to_account_info()),
+    )?;
 
     pool.buyside_payment_amount = buyside_sol_escrow_account.lamports();
     log_pool("post_sol_fulfill_sell", pool)?;
@@ -284,5 +339,17 @@ pub fn handler<'info>(
         total_price,
     );
 
+    log_fill_summary(&FillSummary {
+        asset_mint: asset_mint.key(),
+        asset_amount,
+        total_price,
+        lp_fee,
+        royalty_paid,
+        maker_fee,
+        taker_fee,
+        spot_price_before,
+        spot_price_after: pool.spot_price,
+    })?;
+
     Ok(())
 }
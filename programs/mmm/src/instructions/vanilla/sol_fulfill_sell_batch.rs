@@ -0,0 +1,397 @@
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::{
+    ata::init_if_needed_ata,
+    constants::*,
+    errors::MMMErrorCode,
+    state::{Pool, SellState},
+    util::{
+        add_pool_volume, assert_token_program_matches_mint, assert_valid_fees_bp,
+        check_fill_cooldown, check_min_fill_amount, check_oracle_deviation, check_pool_side,
+        check_price_slippage, check_referrals, get_enforced_creator_royalty_bp, get_fees_bp,
+        get_metadata_royalty_bp, get_sell_state_rent_recipient, get_sol_fee, get_sol_lp_fee,
+        get_sol_total_price_and_next_price, log_fill_summary, log_pool, pay_creator_fees_in_sol,
+        pay_referral_fees, pay_sell_proceeds, split_taker_fee_exemption, try_close_pool,
+        try_close_sell_state, update_pool_curve, AllowlistMatcher, FillSummary,
+    },
+};
+
+// the fixed accounts of each mint in the remaining accounts, they are followed by the
+// creator accounts of the mint when the metadata has creators:
+//   asset_mint, asset_metadata, asset_master_edition, sellside_escrow_token_account,
+//   payer_asset_account, sell_state, ...creators, ...non-empty extra royalty recipients
+const BATCH_MINT_ACCOUNTS_LEN: usize = 6;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SolFulfillSellBatchArgs {
+    pub asset_amount: u64, // number of mints in the remaining accounts, one asset each
+    pub max_payment_amount: u64,
+    pub max_price_per_item: Option<u64>, // reject the fill if the pool price moved above this
+    pub buyside_creator_royalty_bp: u16,
+    pub allowlist_aux: Option<String>, // TODO: use it for future allowlist_aux
+    pub maker_fee_bp: i16,             // will be checked by cosigner
+    pub taker_fee_bp: i16,             // will be checked by cosigner
+}
+
+// FulfillSellBatch is the same as FulfillSell, except that the buyer
+// buys one asset of each of the asset_amount mints in the remaining
+// accounts, and the pool curve moves once for the whole batch.
+#[derive(Accounts)]
+#[instruction(args:SolFulfillSellBatchArgs)]
+pub struct SolFulfillSellBatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: we will check the owner field that matches the pool owner
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    pub cosigner: Signer<'info>,
+    /// CHECK: we will check that the referral matches the pool's referral, it receives the maker fee
+    #[account(mut)]
+    pub referral: UncheckedAccount<'info>,
+    /// CHECK: the taker's referral receives the taker fee, it will be checked by the cosigner
+    #[account(mut)]
+    pub taker_referral: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_PREFIX.as_bytes(), owner.key().as_ref(), pool.uuid.as_ref()],
+        has_one = owner @ MMMErrorCode::InvalidOwner,
+        has_one = referral @ MMMErrorCode::InvalidReferral,
+        has_one = cosigner @ MMMErrorCode::InvalidCosigner,
+        constraint = pool.payment_mint.eq(&Pubkey::default()) @ MMMErrorCode::InvalidPaymentMint,
+        constraint = !pool.settle_in_wsol @ MMMErrorCode::WsolSettlementNotSupported,
+        constraint = pool.expiry == 0 || pool.expiry > Clock::get().unwrap().unix_timestamp @ MMMErrorCode::Expired,
+        constraint = args.buyside_creator_royalty_bp <= 10000 @ MMMErrorCode::InvalidBP,
+        constraint = args.asset_amount > 0 && args.asset_amount <= MAX_FULFILL_SELL_BATCH_SIZE @ MMMErrorCode::InvalidBatchSize,
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    /// CHECK: it's a pda, and the private key is owned by the seeds
+    #[account(
+        mut,
+        seeds = [BUYSIDE_SOL_ESCROW_ACCOUNT_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump,
+    )]
+    pub buyside_sol_escrow_account: AccountInfo<'info>,
+    /// CHECK: will be used for allowlist checks
+    pub allowlist_aux_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SolFulfillSellBatch<'info>>,
+    args: SolFulfillSellBatchArgs,
+) -> Result<()> {
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let associated_token_program = &ctx.accounts.associated_token_program;
+    let rent = &ctx.accounts.rent;
+    let owner = &ctx.accounts.owner;
+    let referral = &ctx.accounts.referral;
+    let taker_referral = &ctx.accounts.taker_referral;
+    let pool = &mut ctx.accounts.pool;
+    check_pool_side(pool, false)?;
+    check_fill_cooldown(pool)?;
+    check_min_fill_amount(pool, args.asset_amount)?;
+    check_referrals(
+        pool,
+        ctx.accounts.payer.key,
+        ctx.accounts.referral.key,
+        ctx.accounts.taker_referral.key,
+    )?;
+    let payer = &ctx.accounts.payer;
+    let buyside_sol_escrow_account = &ctx.accounts.buyside_sol_escrow_account;
+    let pool_seeds: &[&[&[u8]]] = &[&[
+        POOL_PREFIX.as_bytes(),
+        pool.owner.as_ref(),
+        pool.uuid.as_ref(),
+        &[ctx.bumps.pool],
+    ]];
+
+    let (remaining_accounts, taker_fee_exempt) =
+        split_taker_fee_exemption(&pool.key(), payer.key, ctx.remaining_accounts);
+
+    // every step is priced as if the mints were filled one by one,
+    // so the batch pays the same as asset_amount sequential fills
+    let (maker_fee_bp, taker_fee_bp) =
+        get_fees_bp(args.maker_fee_bp, args.taker_fee_bp, taker_fee_exempt);
+    assert_valid_fees_bp(maker_fee_bp, taker_fee_bp)?;
+    let buyside_sol_escrow_balance = buyside_sol_escrow_account.lamports();
+    // the price of each step, and the spot price the step leaves the pool at
+    let mut steps: Vec<(u64, u64)> = Vec::with_capacity(args.asset_amount as usize);
+    let mut total_price: u64 = 0;
+    let mut next_price: u64 = pool.spot_price;
+    for n in 1..=args.asset_amount {
+        let (n_total_price, n_next_price) = get_sol_total_price_and_next_price(pool, n, false)?;
+        steps.push((
+            n_total_price
+                .checked_sub(total_price)
+                .ok_or(MMMErrorCode::NumericOverflow)?,
+            n_next_price,
+        ));
+        total_price = n_total_price;
+        next_price = n_next_price;
+    }
+    check_price_slippage(
+        total_price,
+        args.asset_amount,
+        None,
+        args.max_price_per_item,
+    )?;
+
+    let mut fills: Vec<FillSummary> = Vec::with_capacity(steps.len());
+    let mut spot_price_before = pool.spot_price;
+    for &(step_price, spot_price_after) in steps.iter() {
+        fills.push(FillSummary {
+            asset_mint: Pubkey::default(),
+            asset_amount: 1,
+            total_price: step_price,
+            lp_fee: get_sol_lp_fee(
+                pool,
+                buyside_sol_escrow_balance,
+                step_price,
+                args.asset_amount,
+            )?,
+            royalty_paid: 0,
+            maker_fee: get_sol_fee(step_price, maker_fee_bp)?,
+            taker_fee: get_sol_fee(step_price, taker_fee_bp)?,
+            spot_price_before,
+            spot_price_after,
+        });
+        spot_price_before = spot_price_after;
+    }
+    let lp_fee = fills.iter().try_fold(0u64, |acc, fill| {
+        acc.checked_add(fill.lp_fee)
+            .ok_or(MMMErrorCode::NumericOverflow)
+    })?;
+    let maker_fee = fills.iter().try_fold(0i64, |acc, fill| {
+        acc.checked_add(fill.maker_fee)
+            .ok_or(MMMErrorCode::NumericOverflow)
+    })?;
+    let taker_fee = fills.iter().try_fold(0i64, |acc, fill| {
+        acc.checked_add(fill.taker_fee)
+            .ok_or(MMMErrorCode::NumericOverflow)
+    })?;
+
+    let transfer_sol_to = if pool.reinvest_fulfill_sell {
+        buyside_sol_escrow_account.to_account_info()
+    } else {
+        owner.to_account_info()
+    };
+
+    pay_sell_proceeds(
+        pool,
+        &payer.to_account_info(),
+        &owner.to_account_info(),
+        transfer_sol_to,
+        total_price,
+        maker_fee,
+        &system_program.to_account_info(),
+    )?;
+
+    if lp_fee > 0 {
+        anchor_lang::solana_program::program::invoke(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                payer.key, owner.key, lp_fee,
+            ),
+            &[
+                payer.to_account_info(),
+                owner.to_account_info(),
+                system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    pay_referral_fees(
+        maker_fee,
+        taker_fee,
+        &payer.to_account_info(),
+        &referral.to_account_info(),
+        &taker_referral.to_account_info(),
+        &[],
+        &system_program.to_account_info(),
+    )?;
+
+    let extra_royalty_recipients_len = pool
+        .extra_royalty_recipients
+        .iter()
+        .filter(|r| !r.is_empty())
+        .count();
+    let remaining_accounts = check_oracle_deviation(pool, remaining_accounts)?;
+    let mut offset: usize = 0;
+    let mut royalty_paid: u64 = 0;
+    let allowlist_aux = pool.get_allowlist_aux(args.allowlist_aux);
+    let allowlists = pool.get_allowlists();
+    let allowlist_matcher = AllowlistMatcher::new(&allowlists, pool.allowlist_match_mode);
+    for fill in fills.iter_mut() {
+        let mint_accounts = remaining_accounts
+            .get(offset..offset + BATCH_MINT_ACCOUNTS_LEN)
+            .ok_or(MMMErrorCode::InvalidRemainingAccounts)?;
+        offset += BATCH_MINT_ACCOUNTS_LEN;
+        assert_token_program_matches_mint(&mint_accounts[0], token_program.key)?;
+        let asset_mint = InterfaceAccount::<Mint>::try_from(&mint_accounts[0])?;
+        let asset_metadata = &mint_accounts[1];
+        let asset_master_edition = &mint_accounts[2];
+        let sellside_escrow_token_account_info = &mint_accounts[3];
+        let payer_asset_account = &mint_accounts[4];
+        let sell_state_info = &mint_accounts[5];
+
+        // the remaining accounts are laid out per mint, so there is no room for a
+        // merkle proof and merkle entries don't match in a batch
+        let parsed_metadata = allowlist_matcher.check_mint(
+            &asset_mint,
+            asset_metadata,
+            Some(asset_master_edition),
+            allowlist_aux.clone(),
+            &[],
+        )?;
+
+        if sellside_escrow_token_account_info.key()
+            != get_associated_token_address_with_program_id(
+                &pool.key(),
+                &asset_mint.key(),
+                token_program.key,
+            )
+        {
+            return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+        }
+        let sellside_escrow_token_account =
+            InterfaceAccount::<TokenAccount>::try_from(sellside_escrow_token_account_info)?;
+        let mut sell_state = Account::<SellState>::try_from(sell_state_info)?;
+        if sell_state.pool != pool.key() || sell_state.asset_mint != asset_mint.key() {
+            return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+        }
+
+        init_if_needed_ata(
+            payer_asset_account.to_account_info(),
+            payer.to_account_info(),
+            payer.to_account_info(),
+            asset_mint.to_account_info(),
+            associated_token_program.to_account_info(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+        )?;
+
+        anchor_spl::token_2022::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                anchor_spl::token_2022::TransferChecked {
+                    from: sellside_escrow_token_account.to_account_info(),
+                    mint: asset_mint.to_account_info(),
+                    to: payer_asset_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                pool_seeds,
+            ),
+            1,
+            asset_mint.decimals,
+        )?;
+        // we can close the sellside_escrow_token_account if no amount left
+        if sellside_escrow_token_account.amount == 1 {
+            anchor_spl::token_2022::close_account(CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                anchor_spl::token_2022::CloseAccount {
+                    account: sellside_escrow_token_account.to_account_info(),
+                    destination: owner.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                pool_seeds,
+            ))?;
+        }
+
+        let creator_accounts_len = match &parsed_metadata.creators {
+            Some(creators) => creators.len() + extra_royalty_recipients_len,
+            None => 0,
+        };
+        let creator_accounts = remaining_accounts
+            .get(offset..offset + creator_accounts_len)
+            .ok_or(MMMErrorCode::InvalidRemainingAccounts)?;
+        offset += creator_accounts_len;
+        let royalty_bp = get_metadata_royalty_bp(fill.total_price, &parsed_metadata, None);
+        fill.royalty_paid = pay_creator_fees_in_sol(
+            get_enforced_creator_royalty_bp(pool, args.buyside_creator_royalty_bp),
+            fill.total_price,
+            &parsed_metadata,
+            creator_accounts,
+            payer.to_account_info(),
+            royalty_bp,
+            &[&[&[]]],
+            system_program.to_account_info(),
+            &pool.extra_royalty_recipients,
+            pool.get_royalty_fallback_account(),
+            pool.require_all_creators_paid,
+        )?;
+        fill.asset_mint = asset_mint.key();
+        royalty_paid = royalty_paid
+            .checked_add(fill.royalty_paid)
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+
+        sell_state.asset_amount = sell_state
+            .asset_amount
+            .checked_sub(1)
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+        if sell_state.asset_amount == 0 {
+            try_close_sell_state(
+                &sell_state,
+                get_sell_state_rent_recipient(
+                    pool,
+                    owner.to_account_info(),
+                    payer.to_account_info(),
+                ),
+            )?;
+        } else {
+            sell_state.exit(ctx.program_id)?;
+        }
+    }
+    if offset != remaining_accounts.len() {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    }
+
+    add_pool_volume(pool, total_price)?;
+    update_pool_curve(pool, args.asset_amount, next_price, false)?;
+    pool.sellside_asset_amount = pool
+        .sellside_asset_amount
+        .checked_sub(args.asset_amount)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    pool.lp_fee_earned = pool
+        .lp_fee_earned
+        .checked_add(lp_fee)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+
+    // prevent frontrun by pool config changes
+    let payment_amount = total_price
+        .checked_add(lp_fee)
+        .ok_or(MMMErrorCode::NumericOverflow)?
+        .checked_add(taker_fee as u64)
+        .ok_or(MMMErrorCode::NumericOverflow)?
+        .checked_add(royalty_paid)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    if payment_amount > args.max_payment_amount {
+        return Err(MMMErrorCode::InvalidRequestedPrice.into());
+    }
+
+    pool.buyside_payment_amount = buyside_sol_escrow_account.lamports();
+    log_pool("post_sol_fulfill_sell_batch", pool)?;
+    try_close_pool(pool, owner.to_account_info())?;
+
+    msg!(
+        "{{\"lp_fee\":{},\"royalty_paid\":{},\"total_price\":{}}}",
+        lp_fee,
+        royalty_paid,
+        total_price,
+    );
+
+    for fill in fills.iter() {
+        log_fill_summary(fill)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anchor_lang::{
+    prelude::*, solana_program::log::sol_log_data, AnchorDeserialize, AnchorSerialize,
+};
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::*,
+    errors::MMMErrorCode,
+    state::{Allowlist, ALLOWLIST_MATCH_MODE_INTERSECTION, ALLOWLIST_MATCH_MODE_UNION},
+    util::{check_allowlists, check_allowlists_for_mint_with_kind, split_merkle_proof},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ValidateMintAgainstAllowlistArgs {
+    pub allowlists: [Allowlist; ALLOWLIST_MAX_LEN],
+    pub allowlist_match_mode: Option<u8>,
+    pub allowlist_aux: Option<String>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AllowlistMatch {
+    pub asset_mint: Pubkey,
+    pub kind: u8,
+}
+
+// ValidateMintAgainstAllowlist is a read-only instruction, it runs the allowlist
+// check of the fulfill instructions against an allowlist config that doesn't need
+// to belong to a pool, and logs the kind of the matched entry. It fails like a fill
+// would when the mint doesn't pass.
+#[derive(Accounts)]
+#[instruction(args:ValidateMintAgainstAllowlistArgs)]
+pub struct ValidateMintAgainstAllowlist<'info> {
+    /// CHECK: we will check the metadata in check_allowlists_for_mint()
+    #[account(
+    seeds = [
+        "metadata".as_bytes(),
+        mpl_token_metadata::ID.as_ref(),
+        asset_mint.key().as_ref(),
+    ],
+    bump,
+    seeds::program = mpl_token_metadata::ID,
+    )]
+    pub asset_metadata: UncheckedAccount<'info>,
+    /// CHECK: we will check the master_edtion in check_allowlists_for_mint()
+    pub asset_master_edition: UncheckedAccount<'info>,
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+}
+
+pub fn handler(
+    ctx: Context<ValidateMintAgainstAllowlist>,
+    args: ValidateMintAgainstAllowlistArgs,
+) -> Result<()> {
+    let asset_mint = &ctx.accounts.asset_mint;
+
+    let allowlist_match_mode = args
+        .allowlist_match_mode
+        .unwrap_or(ALLOWLIST_MATCH_MODE_UNION);
+    if allowlist_match_mode > ALLOWLIST_MATCH_MODE_INTERSECTION {
+        return Err(MMMErrorCode::InvalidAllowLists.into());
+    }
+    check_allowlists(&args.allowlists, allowlist_match_mode)?;
+
+    let (merkle_proof, _) =
+        split_merkle_proof(&args.allowlists, &asset_mint.key(), ctx.remaining_accounts);
+    let (_, kind) = check_allowlists_for_mint_with_kind(
+        &args.allowlists,
+        allowlist_match_mode,
+        asset_mint,
+        &ctx.accounts.asset_metadata,
+        Some(&ctx.accounts.asset_master_edition),
+        args.allowlist_aux,
+        merkle_proof,
+    )?;
+
+    let allowlist_match = AllowlistMatch {
+        asset_mint: asset_mint.key(),
+        kind,
+    };
+    msg!("allowlist_match");
+    sol_log_data(&[&allowlist_match.try_to_vec()?]);
+
+    Ok(())
+}
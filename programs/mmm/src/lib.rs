@@ -26,6 +26,14 @@ pub mod mmm {
         instructions::update_pool::handler(ctx, args)
     }
 
+    pub fn reprice_pool(ctx: Context<RepricePool>, args: RepricePoolArgs) -> Result<()> {
+        instructions::reprice_pool::handler(ctx, args)
+    }
+
+    pub fn reset_pool_stats(ctx: Context<ResetPoolStats>, args: ResetPoolStatsArgs) -> Result<()> {
+        instructions::reset_pool_stats::handler(ctx, args)
+    }
+
     pub fn update_allowlists(
         ctx: Context<UpdateAllowlists>,
         args: UpdateAllowlistsArgs,
@@ -37,6 +45,22 @@ pub mod mmm {
         instructions::sol_close_pool::handler(ctx)
     }
 
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        instructions::migrate_pool::handler(ctx)
+    }
+
+    pub fn owner_drain_and_close<'info>(
+        ctx: Context<'_, '_, 'info, 'info, OwnerDrainAndClose<'info>>,
+    ) -> Result<()> {
+        instructions::owner_drain_and_close::handler(ctx)
+    }
+
+    pub fn transfer_pool_ownership<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TransferPoolOwnership<'info>>,
+    ) -> Result<()> {
+        instructions::transfer_pool_ownership::handler(ctx)
+    }
+
     pub fn sol_deposit_buy(ctx: Context<SolDepositBuy>, args: SolDepositBuyArgs) -> Result<()> {
         instructions::sol_deposit_buy::handler(ctx, args)
     }
@@ -59,6 +83,13 @@ pub mod mmm {
         instructions::sol_fulfill_sell::handler(ctx, args)
     }
 
+    pub fn sol_fulfill_sell_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SolFulfillSellBatch<'info>>,
+        args: SolFulfillSellBatchArgs,
+    ) -> Result<()> {
+        instructions::sol_fulfill_sell_batch::handler(ctx, args)
+    }
+
     pub fn withdraw_sell(ctx: Context<WithdrawSell>, args: WithdrawSellArgs) -> Result<()> {
         instructions::withdraw_sell::handler(ctx, args)
     }
@@ -67,6 +98,13 @@ pub mod mmm {
         instructions::deposit_sell::handler(ctx, args)
     }
 
+    pub fn deposit_sell_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositSellBatch<'info>>,
+        args: DepositSellBatchArgs,
+    ) -> Result<()> {
+        instructions::deposit_sell_batch::handler(ctx, args)
+    }
+
     pub fn ocp_deposit_sell(ctx: Context<OcpDepositSell>, args: DepositSellArgs) -> Result<()> {
         instructions::ocp_deposit_sell::handler(ctx, args)
     }
@@ -114,6 +152,45 @@ pub mod mmm {
         instructions::sol_mip1_fulfill_buy::handler(ctx, args)
     }
 
+    pub fn get_pool_quote<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetPoolQuote<'info>>,
+        args: GetPoolQuoteArgs,
+    ) -> Result<()> {
+        instructions::get_pool_quote::handler(ctx, args)
+    }
+
+    pub fn get_max_affordable_items(
+        ctx: Context<GetMaxAffordableItems>,
+        args: GetMaxAffordableItemsArgs,
+    ) -> Result<()> {
+        instructions::get_max_affordable_items::handler(ctx, args)
+    }
+
+    pub fn check_pool_solvency(
+        ctx: Context<CheckPoolSolvency>,
+        args: CheckPoolSolvencyArgs,
+    ) -> Result<()> {
+        instructions::check_pool_solvency::handler(ctx, args)
+    }
+
+    pub fn get_pool_notional_value(
+        ctx: Context<GetPoolNotionalValue>,
+        args: GetPoolNotionalValueArgs,
+    ) -> Result<()> {
+        instructions::get_pool_notional_value::handler(ctx, args)
+    }
+
+    pub fn validate_mint_against_allowlist(
+        ctx: Context<ValidateMintAgainstAllowlist>,
+        args: ValidateMintAgainstAllowlistArgs,
+    ) -> Result<()> {
+        instructions::validate_mint_against_allowlist::handler(ctx, args)
+    }
+
+    pub fn log_sell_state(ctx: Context<LogSellState>) -> Result<()> {
+        instructions::log_sell_state::handler(ctx)
+    }
+
     pub fn close_if_balance_invalid(ctx: Context<CloseIfBalanceInvalid>) -> Result<()> {
         instructions::close_if_balance_invalid::handler(ctx)
     }
@@ -125,6 +202,21 @@ pub mod mmm {
         instructions::set_shared_escrow::handler(ctx, args)
     }
 
+    pub fn reconcile_shared_escrow(ctx: Context<ReconcileSharedEscrow>) -> Result<()> {
+        instructions::reconcile_shared_escrow::handler(ctx)
+    }
+
+    pub fn add_taker_fee_exemption(
+        ctx: Context<AddTakerFeeExemption>,
+        args: AddTakerFeeExemptionArgs,
+    ) -> Result<()> {
+        instructions::add_taker_fee_exemption::handler(ctx, args)
+    }
+
+    pub fn remove_taker_fee_exemption(ctx: Context<RemoveTakerFeeExemption>) -> Result<()> {
+        instructions::remove_taker_fee_exemption::handler(ctx)
+    }
+
     pub fn ext_deposit_sell<'info>(
         ctx: Context<'_, '_, '_, 'info, ExtDepositeSell<'info>>,
         args: DepositSellArgs,
@@ -146,7 +238,10 @@ pub mod mmm {
         instructions::sol_ext_fulfill_buy::handler(ctx, args)
     }
 
-    pub fn ext_withdraw_sell(ctx: Context<ExtWithdrawSell>, args: WithdrawSellArgs) -> Result<()> {
+    pub fn ext_withdraw_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExtWithdrawSell<'info>>,
+        args: WithdrawSellArgs,
+    ) -> Result<()> {
         instructions::ext_withdraw_sell::handler(ctx, args)
     }
 }
@@ -4,6 +4,9 @@ use crate::constants::*;
 
 pub const CURVE_KIND_LINEAR: u8 = 0;
 pub const CURVE_KIND_EXP: u8 = 1;
+pub const CURVE_KIND_LOG: u8 = 2;
+pub const CURVE_KIND_XYK: u8 = 3;
+pub const CURVE_KIND_FLAT: u8 = 4;
 
 pub const ALLOWLIST_KIND_EMPTY: u8 = 0;
 pub const ALLOWLIST_KIND_FVCA: u8 = 1;
@@ -11,9 +14,46 @@ pub const ALLOWLIST_KIND_MINT: u8 = 2;
 pub const ALLOWLIST_KIND_MCC: u8 = 3;
 pub const ALLOWLIST_KIND_METADATA: u8 = 4;
 pub const ALLOWLIST_KIND_GROUP: u8 = 5;
+// DENY_MINT rejects the mint regardless of any other matching allowlist entries
+pub const ALLOWLIST_KIND_DENY_MINT: u8 = 6;
+// MAX_ROYALTY matches mints whose seller_fee_basis_points is at most the bp in the value's low bytes
+pub const ALLOWLIST_KIND_MAX_ROYALTY: u8 = 7;
+// MERKLE matches mints in the merkle tree whose root is the value, the proof is passed
+// in the remaining accounts, see split_merkle_proof
+pub const ALLOWLIST_KIND_MERKLE: u8 = 8;
+// RESERVED is not a valid kind, taker fee exemptions are kept in TakerFeeExemption accounts
+// so that they don't take up allowlist entries
+pub const ALLOWLIST_KIND_RESERVED: u8 = 9;
+// UPDATE_AUTHORITY matches mints whose metadata update_authority is the value, the update
+// authority is unsigned so it is only accepted next to a verifiable entry in intersection mode
+pub const ALLOWLIST_KIND_UPDATE_AUTHORITY: u8 = 10;
 // ANY nft will pass the allowlist check, please make sure to use cosigner to check NFT validity
 pub const ALLOWLIST_KIND_ANY: u8 = u8::MAX;
 
+// union: the mint needs to match one of the allowlist entries
+// intersection: the mint needs to match every non-empty allowlist entry
+pub const ALLOWLIST_MATCH_MODE_UNION: u8 = 0;
+pub const ALLOWLIST_MATCH_MODE_INTERSECTION: u8 = 1;
+
+// rounding of the lp fee, the signed maker and taker fees always truncate to stay symmetric
+pub const LP_FEE_ROUNDING_FLOOR: u8 = 0;
+pub const LP_FEE_ROUNDING_CEIL: u8 = 1;
+pub const LP_FEE_ROUNDING_NEAREST: u8 = 2;
+
+// the fulfill side a pool accepts, the owner can deposit and withdraw on both sides regardless
+pub const POOL_SIDE_TWO_SIDED: u8 = 0;
+pub const POOL_SIDE_BUY_ONLY: u8 = 1; // only fulfill_buy, the pool accumulates
+pub const POOL_SIDE_SELL_ONLY: u8 = 2; // only fulfill_sell, the pool distributes
+
+// the direction of the linear fulfill_buy steps, fulfill_sell steps always move the price up
+pub const CURVE_DIRECTION_NORMAL: u8 = 0; // fulfill_buy steps move the price down
+pub const CURVE_DIRECTION_INVERTED: u8 = 1; // fulfill_buy steps move the price up
+
+// the creator royalty of a vanilla fill, mip1 and ocp fills always pay the full royalty
+pub const ROYALTY_ENFORCEMENT_MODE_OPTIONAL: u8 = 0; // the buyside_creator_royalty_bp of the fill
+pub const ROYALTY_ENFORCEMENT_MODE_ENFORCE: u8 = 1; // the full metadata royalty
+pub const ROYALTY_ENFORCEMENT_MODE_BYPASS: u8 = 2; // no royalty
+
 #[derive(Default, Copy, Clone, AnchorSerialize, AnchorDeserialize)]
 pub struct Allowlist {
     pub kind: u8,
@@ -21,18 +61,31 @@ pub struct Allowlist {
 }
 
 impl Allowlist {
+    pub const LEN: usize = 1 + 32;
+
     // kind == 0: empty
     // kind == 1: first verified creator address (FVCA)
     // kind == 2: single mint, useful for SFT
     // kind == 3: verified MCC
     // kind == 4: metadata
     // kind == 5: group extension
-    // kind == 6,7,... will be supported in the future
+    // kind == 6: deny single mint
+    // kind == 7: max royalty bp, a 0 bp ceiling is the default pubkey
+    // kind == 8: merkle root of mints
+    // kind == 9: reserved, taker fee exemptions are TakerFeeExemption accounts
+    // kind == 10: metadata update authority
+    // kind == 11,12,... will be supported in the future
     // kind == 255: any
     pub fn valid(&self) -> bool {
-        if self.kind > ALLOWLIST_KIND_GROUP && self.kind != ALLOWLIST_KIND_ANY {
+        if (self.kind > ALLOWLIST_KIND_UPDATE_AUTHORITY && self.kind != ALLOWLIST_KIND_ANY)
+            || self.kind == ALLOWLIST_KIND_RESERVED
+        {
             return false;
         }
+        if self.kind == ALLOWLIST_KIND_MAX_ROYALTY {
+            return self.value.to_bytes()[2..].iter().all(|&b| b == 0)
+                && self.max_royalty_bp() <= 10000;
+        }
         if self.kind != 0 && self.kind != ALLOWLIST_KIND_ANY {
             return self.value.ne(&Pubkey::default());
         }
@@ -42,6 +95,57 @@ impl Allowlist {
     pub fn is_empty(&self) -> bool {
         self.kind == ALLOWLIST_KIND_EMPTY
     }
+
+    // deny entries are never a match for a mint
+    pub fn matches_mints(&self) -> bool {
+        self.kind != ALLOWLIST_KIND_EMPTY && self.kind != ALLOWLIST_KIND_DENY_MINT
+    }
+
+    // entries that identify a mint by something that its creator can't forge
+    pub fn is_verifiable(&self) -> bool {
+        matches!(
+            self.kind,
+            ALLOWLIST_KIND_FVCA
+                | ALLOWLIST_KIND_MINT
+                | ALLOWLIST_KIND_MCC
+                | ALLOWLIST_KIND_GROUP
+                | ALLOWLIST_KIND_MERKLE
+        )
+    }
+
+    // the little endian u16 in the first two bytes of the value
+    pub fn max_royalty_bp(&self) -> u16 {
+        let bytes = self.value.to_bytes();
+        u16::from_le_bytes([bytes[0], bytes[1]])
+    }
+}
+
+// lp_fee_bp applies to fills of at least min_asset_amount assets,
+// min_asset_amount == 0 means the tier is unset
+#[derive(Default, Copy, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct LpFeeTier {
+    pub min_asset_amount: u64,
+    pub lp_fee_bp: u16,
+}
+
+impl LpFeeTier {
+    pub fn is_empty(&self) -> bool {
+        self.min_asset_amount == 0
+    }
+}
+
+// bp is the share of the total royalty paid to address before the metadata creators split the rest,
+// a default address means the recipient is unset
+#[derive(Default, Copy, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct RoyaltyRecipient {
+    pub address: Pubkey,
+    pub bp: u16,
+}
+
+impl RoyaltyRecipient {
+    pub fn is_empty(&self) -> bool {
+        self.address == Pubkey::default()
+    }
 }
 
 // seeds = [
@@ -78,11 +182,127 @@ pub struct Pool {
     pub cosigner: Pubkey,
     pub uuid: Pubkey, // randomly generated keypair
     pub payment_mint: Pubkey,
-    pub allowlists: [Allowlist; ALLOWLIST_MAX_LEN],
+    pub allowlists: [Allowlist; POOL_ALLOWLISTS_LEN], // see get_allowlists
     pub buyside_payment_amount: u64,
 
     pub shared_escrow_account: Pubkey, // this points to the shared escrow account PDA (usually M2)
     pub shared_escrow_count: u64, // this means that how many times (count) the shared escrow account can be fulfilled, and it can be mutable
+
+    pub price_floor: u64, // fulfill_buy step prices are pinned to this floor, 0 means no floor
+    pub price_ceiling: u64, // fulfill_sell step prices are capped by this ceiling, 0 means unbounded
+    pub allowlist_match_mode: u8, // see ALLOWLIST_MATCH_MODE_*
+
+    // lp_fee_bp decays linearly to lp_fee_bp_floor between lp_fee_decay_start and lp_fee_decay_end,
+    // lp_fee_decay_end == 0 means no decay
+    pub lp_fee_decay_start: i64,
+    pub lp_fee_decay_end: i64,
+    pub lp_fee_bp_floor: u16,
+
+    pub lp_fee_tiers: [LpFeeTier; LP_FEE_TIERS_MAX_LEN], // all empty means the flat lp_fee_bp
+    pub min_escrow_balance_bp: u16,                      // 0 means MIN_SOL_ESCROW_BALANCE_BP
+
+    // paid after the metadata creators, and the accounts follow the creator accounts
+    pub extra_royalty_recipients: [RoyaltyRecipient; EXTRA_ROYALTY_RECIPIENTS_MAX_LEN],
+
+    pub version: u8, // 0 means the pool predates POOL_VERSION and needs migrate_pool
+
+    pub max_spot_move_bp: u16, // caps the spot price move of a single fill, 0 means unbounded
+
+    pub lp_fee_rounding: u8, // see LP_FEE_ROUNDING_*
+
+    // fills the linear curve must be able to take on the fulfill_buy side without the
+    // price reaching zero, 0 means unchecked
+    pub max_fill_depth: u16,
+
+    // the total_price of all fulfill_buy fills is capped by max_buyside_spend, 0 means unbounded
+    pub max_buyside_spend: u64,
+    pub buyside_spent: u64,
+
+    pub pool_side: u8, // see POOL_SIDE_*
+
+    // when set, fulfill_buy only takes mints of a matching mcc entry from a sized collection
+    pub require_sized_collection: bool,
+
+    // accumulated over every fill, total_volume_sol sums the total_price of each fill
+    pub total_volume_sol: u128,
+    pub fill_count: u64,
+
+    // opts in to check_referrals, so that the owner or the taker can't be its own referral
+    pub reject_self_referral: bool,
+
+    // fills pay and receive wsol on the taker's wsol ata instead of lamports, see split_wsol_accounts
+    pub settle_in_wsol: bool,
+
+    // only takes in master editions without prints, see check_one_of_one
+    pub require_one_of_one: bool,
+
+    // only takes in programmable nfts, see check_programmable
+    pub require_programmable: bool,
+
+    // steps linear fulfill_sell prices instead of curve_delta, 0 means curve_delta
+    pub curve_delta_sell: u64,
+
+    pub royalty_enforcement_mode: u8, // see ROYALTY_ENFORCEMENT_MODE_*
+
+    // caps the total_price of a single fill, 0 means MAX_TOTAL_PRICE
+    pub max_total_price: u64,
+
+    // the asset_amount of a fill can't be below it, owner deposits and withdrawals aside
+    pub min_fill_amount: u64,
+
+    // fills within fill_cooldown_secs of last_fill_ts are rejected, 0 means no cooldown
+    pub fill_cooldown_secs: i64,
+    pub last_fill_ts: i64,
+
+    // the rent of a sell state emptied by a fulfill sell goes to the taker instead of the
+    // owner, see get_sell_state_rent_recipient
+    pub sell_state_rent_to_taker: bool,
+
+    // the utf8 allowlist_aux for metadata allowlists, zero padded, it takes precedence over the
+    // allowlist_aux of the instructions when it's set, see get_allowlist_aux
+    pub allowlist_aux: [u8; ALLOWLIST_AUX_MAX_LEN],
+
+    // with allow_royalty_fallback, the sol royalty share of a recipient that would be left
+    // below rent goes to this account instead when it's passed after the royalty accounts,
+    // see get_royalty_fallback_account
+    pub royalty_fallback_account: Pubkey,
+
+    // on a reinvest_fulfill_sell pool, the bp of the fulfill sell proceeds that goes to the
+    // owner instead of the buyside escrow, see pay_sell_proceeds
+    pub profit_skim_bp: u16,
+
+    // when set, fills take the oracle account off the end of the remaining accounts and are
+    // rejected when the spot_price deviates from its price by more than max_oracle_deviation_bp,
+    // see check_oracle_deviation. Pubkey::default means no oracle
+    pub oracle: Pubkey,
+    pub max_oracle_deviation_bp: u16,
+
+    // when set, a fill is rejected instead of skipping a sol royalty recipient whose share
+    // would leave its account below rent, see pay_creator_shares_in_sol
+    pub require_all_creators_paid: bool,
+
+    // see CURVE_DIRECTION_*, only linear curves can be inverted, see check_curve
+    pub curve_direction: u8,
+
+    // when set, fulfill_buy cross-checks the verified collection of a matching mcc entry
+    // against the collection metadata in the remaining accounts, see check_collection_metadata
+    pub require_collection_metadata: bool,
+
+    // when set, fulfill_buy prices multi-item fills along the curve but doesn't move the
+    // spot_price, so that the bids replenish at the same price, see update_pool_curve
+    pub static_spot: bool,
+
+    // the allowlists past POOL_ALLOWLISTS_LEN, they live at the end of the pool so that the
+    // fields of pools with POOL_ALLOWLISTS_LEN allowlists stay in place, see get_allowlists
+    pub allowlists_ext: [Allowlist; POOL_ALLOWLISTS_EXT_LEN],
+
+    // opts in to paying unpayable royalty shares to the royalty_fallback_account
+    pub allow_royalty_fallback: bool,
+
+    // the spot price a log curve steps away from, it's reset whenever the owner sets the
+    // spot price, and fills work out their step from it, see get_log_curve_step.
+    // 0 on pools that haven't been filled since they got this field
+    pub log_curve_base_price: u64,
 }
 
 impl Pool {
@@ -94,14 +314,119 @@ impl Pool {
         32 * 5 + // Pubkey
         2 + // bool
         32 + // [u8; 32]
-        4 + (1 + 32) * ALLOWLIST_MAX_LEN + // Allowlist
+        4 + Allowlist::LEN * POOL_ALLOWLISTS_LEN + // Allowlist
         32 + // Pubkey
         8 + // u64
-        352; // padding
+        8 + // u64 price_floor
+        8 + // u64 price_ceiling
+        1 + // u8 allowlist_match_mode
+        8 * 2 + // i64 lp_fee_decay_start, lp_fee_decay_end
+        2 + // u16 lp_fee_bp_floor
+        (8 + 2) * LP_FEE_TIERS_MAX_LEN + // LpFeeTier
+        2 + // u16 min_escrow_balance_bp
+        (32 + 2) * EXTRA_ROYALTY_RECIPIENTS_MAX_LEN + // RoyaltyRecipient
+        1 + // u8 version
+        2 + // u16 max_spot_move_bp
+        1 + // u8 lp_fee_rounding
+        2 + // u16 max_fill_depth
+        8 * 2 + // u64 max_buyside_spend, buyside_spent
+        1 + // u8 pool_side
+        1 + // bool require_sized_collection
+        16 + // u128 total_volume_sol
+        8 + // u64 fill_count
+        1 + // bool reject_self_referral
+        1 + // bool settle_in_wsol
+        1 + // bool require_one_of_one
+        1 + // bool require_programmable
+        8 + // u64 curve_delta_sell
+        1 + // u8 royalty_enforcement_mode
+        8 + // u64 max_total_price
+        8 + // u64 min_fill_amount
+        8 * 2 + // i64 fill_cooldown_secs, last_fill_ts
+        1 + // bool sell_state_rent_to_taker
+        ALLOWLIST_AUX_MAX_LEN + // [u8; ALLOWLIST_AUX_MAX_LEN] allowlist_aux
+        32 + // Pubkey royalty_fallback_account
+        2 + // u16 profit_skim_bp
+        32 + // Pubkey oracle
+        2 + // u16 max_oracle_deviation_bp
+        1 + // bool require_all_creators_paid
+        1 + // u8 curve_direction
+        1 + // bool require_collection_metadata
+        1 + // bool static_spot
+        Allowlist::LEN * POOL_ALLOWLISTS_EXT_LEN + // [Allowlist; POOL_ALLOWLISTS_EXT_LEN]
+        1 + // bool allow_royalty_fallback
+        8; // u64 log_curve_base_price
+
+    // the owner is at the same offset in every pool layout
+    pub const OWNER_OFFSET: usize = 8 + 8 + 1 + 8 + 1 + 1 + 8 + 2 + 32 + 2 + 2 + 32 + 8 + 8;
+
+    // the length of pools created before allowlists_ext, every other field is at the same
+    // offset, see migrate_pool
+    pub const LEGACY_LEN: usize = Pool::LEN - Allowlist::LEN * POOL_ALLOWLISTS_EXT_LEN;
 
     pub fn using_shared_escrow(&self) -> bool {
         self.shared_escrow_account != Pubkey::default()
     }
+
+    // the allowlists followed by the allowlists_ext
+    pub fn get_allowlists(&self) -> [Allowlist; ALLOWLIST_MAX_LEN] {
+        let mut allowlists = [Allowlist::default(); ALLOWLIST_MAX_LEN];
+        allowlists[..POOL_ALLOWLISTS_LEN].copy_from_slice(&self.allowlists);
+        allowlists[POOL_ALLOWLISTS_LEN..].copy_from_slice(&self.allowlists_ext);
+        allowlists
+    }
+
+    pub fn set_allowlists(&mut self, allowlists: &[Allowlist; ALLOWLIST_MAX_LEN]) {
+        self.allowlists
+            .copy_from_slice(&allowlists[..POOL_ALLOWLISTS_LEN]);
+        self.allowlists_ext
+            .copy_from_slice(&allowlists[POOL_ALLOWLISTS_LEN..]);
+    }
+
+    // the stored allowlist_aux when it's set, otherwise the one passed to the instruction
+    pub fn get_allowlist_aux(&self, allowlist_aux: Option<String>) -> Option<String> {
+        let len = self
+            .allowlist_aux
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(ALLOWLIST_AUX_MAX_LEN);
+        if len == 0 {
+            return allowlist_aux;
+        }
+        String::from_utf8(self.allowlist_aux[..len].to_vec()).ok()
+    }
+
+    // ext mints are 1 of 1 unless the pool explicitly allowlists the mint by its key,
+    // then every unit of it is priced as one asset on the curve
+    pub fn accepts_mint_supply(&self, mint: &Pubkey, supply: u64) -> bool {
+        supply == 1
+            || self
+                .get_allowlists()
+                .iter()
+                .any(|val| val.kind == ALLOWLIST_KIND_MINT && val.value == *mint)
+    }
+
+    // the royalty_fallback_account when the pool opted in to it
+    pub fn get_royalty_fallback_account(&self) -> Option<&Pubkey> {
+        Some(&self.royalty_fallback_account)
+            .filter(|account| self.allow_royalty_fallback && **account != Pubkey::default())
+    }
+
+    // the log_curve_base_price, or the spot_price on a pool that hasn't got one yet
+    pub fn get_log_curve_base_price(&self) -> u64 {
+        match self.log_curve_base_price {
+            0 => self.spot_price,
+            base_price => base_price,
+        }
+    }
+
+    pub fn min_escrow_balance_bp(&self) -> u16 {
+        if self.min_escrow_balance_bp == 0 {
+            MIN_SOL_ESCROW_BALANCE_BP
+        } else {
+            self.min_escrow_balance_bp
+        }
+    }
 }
 
 // seeds = [
@@ -132,3 +457,25 @@ impl SellState {
         32 + // [u8; 32]
         200; // padding
 }
+
+// seeds = [
+//     TAKER_FEE_EXEMPTION_PREFIX.as_bytes(),
+//     pool.key().as_ref(),
+//     taker.key().as_ref(),
+// ]
+//
+// the taker pays no taker fee on a fulfill sell from the pool when the
+// exemption is passed as the last of the remaining accounts, see
+// split_taker_fee_exemption
+#[account]
+#[derive(Default)]
+pub struct TakerFeeExemption {
+    pub pool: Pubkey,
+    pub taker: Pubkey,
+}
+
+impl TakerFeeExemption {
+    pub const LEN: usize = 8 +
+        32 * 2 + // Pubkey
+        64; // padding
+}
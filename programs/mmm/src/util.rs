@@ -1,32 +1,44 @@
 use crate::{
+    ata::init_if_needed_ata,
     constants::{
-        M2_AUCTION_HOUSE, M2_PREFIX, M2_PROGRAM, MAX_METADATA_CREATOR_ROYALTY_BP,
-        MAX_REFERRAL_FEE_BP, MAX_TOTAL_PRICE, MIN_SOL_ESCROW_BALANCE_BP, POOL_PREFIX,
+        ALLOWLIST_AUX_MAX_LEN, EXP_CURVE_PRECISION, EXTRA_ROYALTY_RECIPIENTS_MAX_LEN,
+        LP_FEE_TIERS_MAX_LEN, M2_AUCTION_HOUSE, M2_PREFIX, M2_PROGRAM, MAX_EXP_CURVE_DELTA,
+        MAX_FILL_ITEMS, MAX_LP_FEE_BP, MAX_METADATA_CREATOR_ROYALTY_BP, MAX_REFERRAL_FEE_BP,
+        MAX_ROYALTY_CREATORS, MAX_TOTAL_PRICE, METADATA_URI_CONTAINS_MARKER,
+        METADATA_URI_SUFFIX_MARKER, ORACLE_PRICE_LEN, POOL_PREFIX, STEEP_EXP_CURVE_CHECK_STEPS,
+        TAKER_FEE_EXEMPTION_PREFIX, TOKEN_METADATA_CREATOR_KEY_PREFIX,
+        TOKEN_METADATA_ROYALTY_BP_KEY, TRANSFER_HOOK_EXTRA_ACCOUNT_METAS_PREFIX,
     },
     errors::MMMErrorCode,
     state::*,
 };
-use anchor_lang::{prelude::*, solana_program::log::sol_log_data};
-use anchor_spl::token_interface::Mint;
+use anchor_lang::{
+    prelude::*,
+    solana_program::{hash::hashv, log::sol_log_data},
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
 use m2_interface::{
     withdraw_by_mmm_ix_with_program_id, WithdrawByMMMArgs, WithdrawByMmmIxArgs, WithdrawByMmmKeys,
 };
 use mpl_token_metadata::{
     accounts::{MasterEdition, Metadata},
-    types::TokenStandard,
+    types::{Creator, TokenStandard},
 };
 use open_creator_protocol::state::Policy;
 use solana_program::program::invoke_signed;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 use spl_token_2022::{
     extension::{
-        group_member_pointer::GroupMemberPointer, metadata_pointer::MetadataPointer,
-        BaseStateWithExtensions, StateWithExtensions,
+        group_member_pointer::GroupMemberPointer,
+        metadata_pointer::MetadataPointer,
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        transfer_hook, BaseStateWithExtensions, StateWithExtensions,
     },
-    state::Mint as Token22Mint,
+    state::{Account as Token22Account, Mint as Token22Mint},
 };
 use spl_token_group_interface::state::TokenGroupMember;
 use spl_token_metadata_interface::state::TokenMetadata;
-use std::convert::TryFrom;
+use std::{convert::TryFrom, str::FromStr};
 
 #[macro_export]
 macro_rules! index_ra {
@@ -43,132 +55,801 @@ fn check_master_edition(master_edition_account_info: &AccountInfo) -> bool {
     version == 2 || version == 6
 }
 
-pub fn check_allowlists(allowlists: &[Allowlist]) -> Result<()> {
-    for allowlist in allowlists.iter() {
+// a require_one_of_one pool only takes in master editions that can't print, max_supply == Some(0).
+// the master_edition was checked in check_allowlists_for_mint, so only its supply is left to
+// check. mints without a master edition, or fulfills without the account, can't be verified.
+pub fn check_one_of_one(pool: &Pool, master_edition: Option<&AccountInfo>) -> Result<()> {
+    if !pool.require_one_of_one {
+        return Ok(());
+    }
+    let Some(master_edition) = master_edition.filter(|m| !m.data_is_empty()) else {
+        return Err(MMMErrorCode::NotOneOfOne.into());
+    };
+    let parsed_master_edition = MasterEdition::from_bytes(&master_edition.data.borrow())
+        .map_err(|_| MMMErrorCode::InvalidMasterEdition)?;
+    if parsed_master_edition.max_supply != Some(0) {
+        return Err(MMMErrorCode::NotOneOfOne.into());
+    }
+    Ok(())
+}
+
+// a require_programmable pool only takes in ProgrammableNonFungible assets. the mip1
+// instructions always assert it, the others can only be verified against the metadata,
+// and mints without a metadata can't be verified.
+pub fn check_programmable(pool: &Pool, parsed_metadata: Option<&Metadata>) -> Result<()> {
+    if !pool.require_programmable {
+        return Ok(());
+    }
+    match parsed_metadata {
+        Some(parsed_metadata) => assert_is_programmable(parsed_metadata),
+        None => Err(MMMErrorCode::InvalidTokenStandard.into()),
+    }
+}
+
+pub fn check_allowlists(allowlists: &[Allowlist], allowlist_match_mode: u8) -> Result<()> {
+    // pools with fewer entries than ALLOWLIST_MAX_LEN leave the trailing ones empty
+    for allowlist in allowlists.iter().filter(|allowlist| !allowlist.is_empty()) {
         if !allowlist.valid() {
             msg!("InvalidAllowLists: invalid entry");
             return Err(MMMErrorCode::InvalidAllowLists.into());
         }
     }
 
+    // the metadata update authority is picked by whoever creates the metadata without
+    // its signature, so it can only narrow down an entry that verifies the mint
+    if allowlists
+        .iter()
+        .any(|val| val.kind == ALLOWLIST_KIND_UPDATE_AUTHORITY)
+        && (allowlist_match_mode != ALLOWLIST_MATCH_MODE_INTERSECTION
+            || !allowlists.iter().any(|val| val.is_verifiable()))
+    {
+        msg!("InvalidAllowLists: update authority needs a verifiable entry to intersect");
+        return Err(MMMErrorCode::InvalidAllowLists.into());
+    }
+
+    Ok(())
+}
+
+fn check_allowlists_deny_mint(allowlists: &[Allowlist], mint: &Pubkey) -> Result<()> {
+    if allowlists
+        .iter()
+        .any(|val| val.kind == ALLOWLIST_KIND_DENY_MINT && val.value == *mint)
+    {
+        msg!("InvalidAllowLists: mint |{}| is denied", mint);
+        return Err(MMMErrorCode::InvalidAllowLists.into());
+    }
+    Ok(())
+}
+
+// allowlist_aux is matched against the metadata uri as a prefix by default,
+// "suffix:<x>" and "contains:<x>" match <x> as a suffix or a substring instead
+fn check_metadata_uri(uri: &str, aux_key: &str) -> Result<()> {
+    // Handle URI padding.
+    let uri = uri.trim();
+    let matched = if let Some(suffix) = aux_key.strip_prefix(METADATA_URI_SUFFIX_MARKER) {
+        uri.ends_with(suffix)
+    } else if let Some(substring) = aux_key.strip_prefix(METADATA_URI_CONTAINS_MARKER) {
+        uri.contains(substring)
+    } else {
+        uri.starts_with(aux_key)
+    };
+    if !matched {
+        msg!(
+            "Failed metadata validation. Expected URI: |{}| but got |{}|",
+            aux_key,
+            uri
+        );
+        return Err(MMMErrorCode::UnexpectedMetadataUri.into());
+    }
     Ok(())
 }
 
 pub fn check_allowlists_for_mint(
     allowlists: &[Allowlist],
+    allowlist_match_mode: u8,
     mint: &InterfaceAccount<Mint>,
     metadata: &AccountInfo,
     master_edition: Option<&AccountInfo>,
     allowlist_aux: Option<String>,
+    merkle_proof: &[AccountInfo],
 ) -> Result<Metadata> {
-    // We need to check the following validation rules
-    // 1. make sure the metadata is correctly derived from the metadata pda with the mint
-    // 2. make sure mint+metadata(e.g. first verified creator address) can match one of the allowlist
-    // 3. note that the allowlist is unioned together, not intersection
-    // 4. skip if the allowlist.is_empty()
-    // 5. verify that nft either does not have master edition or is master edition
-
-    if *metadata.owner != mpl_token_metadata::ID {
-        return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+    AllowlistMatcher::new(allowlists, allowlist_match_mode).check_mint(
+        mint,
+        metadata,
+        master_edition,
+        allowlist_aux,
+        merkle_proof,
+    )
+}
+
+// same as check_allowlists_for_mint, and also returns the kind of the matched allowlist entry,
+// in intersection mode every entry matched and it's the kind of the first one
+pub fn check_allowlists_for_mint_with_kind(
+    allowlists: &[Allowlist],
+    allowlist_match_mode: u8,
+    mint: &InterfaceAccount<Mint>,
+    metadata: &AccountInfo,
+    master_edition: Option<&AccountInfo>,
+    allowlist_aux: Option<String>,
+    merkle_proof: &[AccountInfo],
+) -> Result<(Metadata, u8)> {
+    AllowlistMatcher::new(allowlists, allowlist_match_mode).check_mint_with_kind(
+        mint,
+        metadata,
+        master_edition,
+        allowlist_aux,
+        merkle_proof,
+    )
+}
+
+// AllowlistMatcher holds what the allowlist checks derive from the pool allowlists alone,
+// so that batches build it once and check each of their mints against it
+pub struct AllowlistMatcher<'a> {
+    allowlists: &'a [Allowlist],
+    intersection: bool,
+    has_metadata_entry: bool,
+    // the kind returned in intersection mode
+    first_kind: Option<u8>,
+    // the entries that can match, the cheapest kinds first and the first match wins, so the
+    // matched kind doesn't depend on the order of the entries
+    by_check_cost: Vec<&'a Allowlist>,
+}
+
+impl<'a> AllowlistMatcher<'a> {
+    pub fn new(allowlists: &'a [Allowlist], allowlist_match_mode: u8) -> Self {
+        let first_kind = allowlists
+            .iter()
+            .find(|allowlist_val| allowlist_val.matches_mints())
+            .map(|allowlist_val| allowlist_val.kind);
+        let by_check_cost = (0..=ALLOWLIST_CHECK_COST_METADATA)
+            .flat_map(|cost| {
+                allowlists.iter().filter(move |allowlist_val| {
+                    allowlist_val.matches_mints()
+                        && allowlist_check_cost(allowlist_val.kind) == cost
+                })
+            })
+            .collect();
+        AllowlistMatcher {
+            allowlists,
+            intersection: allowlist_match_mode == ALLOWLIST_MATCH_MODE_INTERSECTION,
+            has_metadata_entry: allowlists
+                .iter()
+                .any(|&val| val.kind == ALLOWLIST_KIND_METADATA),
+            first_kind,
+            by_check_cost,
+        }
     }
-    if Metadata::find_pda(&mint.key()).0 != metadata.key() {
-        return Err(ErrorCode::ConstraintSeeds.into());
+
+    pub fn check_mint(
+        &self,
+        mint: &InterfaceAccount<Mint>,
+        metadata: &AccountInfo,
+        master_edition: Option<&AccountInfo>,
+        allowlist_aux: Option<String>,
+        merkle_proof: &[AccountInfo],
+    ) -> Result<Metadata> {
+        self.check_mint_with_kind(mint, metadata, master_edition, allowlist_aux, merkle_proof)
+            .map(|(parsed_metadata, _)| parsed_metadata)
     }
-    let parsed_metadata = Metadata::safe_deserialize(&metadata.data.borrow())?;
-    if let Some(master_edition) = master_edition {
-        if MasterEdition::find_pda(&mint.key()).0 != master_edition.key() {
+
+    pub fn check_mint_with_kind(
+        &self,
+        mint: &InterfaceAccount<Mint>,
+        metadata: &AccountInfo,
+        master_edition: Option<&AccountInfo>,
+        allowlist_aux: Option<String>,
+        merkle_proof: &[AccountInfo],
+    ) -> Result<(Metadata, u8)> {
+        // We need to check the following validation rules
+        // 1. make sure the metadata is correctly derived from the metadata pda with the mint
+        // 2. make sure mint+metadata(e.g. first verified creator address) can match one of the allowlist
+        // 3. note that the allowlist is unioned together, unless the pool uses intersection mode
+        // 4. skip if the allowlist.is_empty()
+        // 5. verify that nft either does not have master edition or is master edition
+        // 6. reject the mint if it matches any deny entry, regardless of the other entries
+
+        if *metadata.owner != mpl_token_metadata::ID {
+            return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+        }
+        if Metadata::find_pda(&mint.key()).0 != metadata.key() {
             return Err(ErrorCode::ConstraintSeeds.into());
         }
-        if !master_edition.data_is_empty() {
-            if master_edition.owner.ne(&mpl_token_metadata::ID) {
-                return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+        let parsed_metadata = Metadata::safe_deserialize(&metadata.data.borrow())?;
+        if let Some(master_edition) = master_edition {
+            if MasterEdition::find_pda(&mint.key()).0 != master_edition.key() {
+                return Err(ErrorCode::ConstraintSeeds.into());
             }
-            if !check_master_edition(master_edition) {
-                return Err(MMMErrorCode::InvalidMasterEdition.into());
+            if !master_edition.data_is_empty() {
+                if master_edition.owner.ne(&mpl_token_metadata::ID) {
+                    return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+                }
+                if !check_master_edition(master_edition) {
+                    return Err(MMMErrorCode::InvalidMasterEdition.into());
+                }
             }
         }
-    }
 
-    if allowlists
-        .iter()
-        .any(|&val| val.kind == ALLOWLIST_KIND_METADATA)
-    {
-        // If allowlist_aux is not passed in, do not validate URI.
-        if let Some(ref aux_key) = allowlist_aux {
-            // Handle URI padding.
-            if !parsed_metadata.uri.trim().starts_with(aux_key) {
-                msg!(
-                    "Failed metadata validation. Expected URI: |{}| but got |{}|",
-                    *aux_key,
-                    parsed_metadata.uri
-                );
-                return Err(MMMErrorCode::UnexpectedMetadataUri.into());
+        if self.has_metadata_entry {
+            // If allowlist_aux is not passed in, do not validate URI.
+            if let Some(ref aux_key) = allowlist_aux {
+                check_metadata_uri(&parsed_metadata.uri, aux_key)?;
             }
         }
-    }
 
-    for allowlist_val in allowlists.iter() {
-        match allowlist_val.kind {
-            ALLOWLIST_KIND_EMPTY => {}
-            ALLOWLIST_KIND_ANY => {
-                // any is a special case, we don't need to check anything else
-                return Ok(parsed_metadata);
-            }
-            ALLOWLIST_KIND_FVCA => {
-                if let Some(ref creators) = parsed_metadata.creators {
-                    // TODO: can we make sure we only take master_edition here?
-                    if !creators.is_empty()
-                        && creators[0].address == allowlist_val.value
-                        && creators[0].verified
-                    {
-                        return Ok(parsed_metadata);
+        // deny entries are checked before anything else, so that they can't be bypassed
+        check_allowlists_deny_mint(self.allowlists, &mint.key())?;
+
+        let intersection = self.intersection;
+        for allowlist_val in self.by_check_cost.iter() {
+            let matched = match allowlist_val.kind {
+                ALLOWLIST_KIND_ANY => {
+                    // any is a special case, we don't need to check anything else in union mode
+                    if !intersection {
+                        return Ok((parsed_metadata, ALLOWLIST_KIND_ANY));
                     }
+                    true
                 }
-            }
-            ALLOWLIST_KIND_MINT => {
-                if mint.key() == allowlist_val.value {
-                    return Ok(parsed_metadata);
+                ALLOWLIST_KIND_FVCA => {
+                    // TODO: can we make sure we only take master_edition here?
+                    parsed_metadata.creators.as_ref().is_some_and(|creators| {
+                        !creators.is_empty()
+                            && creators[0].address == allowlist_val.value
+                            && creators[0].verified
+                    })
                 }
-            }
-            ALLOWLIST_KIND_MCC => {
-                if let Some(ref collection_data) = parsed_metadata.collection {
-                    if collection_data.key == allowlist_val.value && collection_data.verified {
-                        return Ok(parsed_metadata);
+                ALLOWLIST_KIND_MINT => mint.key() == allowlist_val.value,
+                ALLOWLIST_KIND_MCC => {
+                    parsed_metadata
+                        .collection
+                        .as_ref()
+                        .is_some_and(|collection_data| {
+                            collection_data.key == allowlist_val.value && collection_data.verified
+                        })
+                }
+                ALLOWLIST_KIND_MAX_ROYALTY => {
+                    parsed_metadata.seller_fee_basis_points <= allowlist_val.max_royalty_bp()
+                }
+                ALLOWLIST_KIND_UPDATE_AUTHORITY => {
+                    parsed_metadata.update_authority == allowlist_val.value
+                }
+                ALLOWLIST_KIND_MERKLE => {
+                    get_merkle_root(&mint.key(), merkle_proof) == allowlist_val.value.to_bytes()
+                }
+                // group members are token 2022 mints, see check_allowlists_for_mint_ext, the
+                // entry doesn't apply to a metadata mint and never matches it
+                ALLOWLIST_KIND_GROUP => false,
+                ALLOWLIST_KIND_METADATA => {
+                    // Do not validate URI here, as we already did it above.
+                    // These checks are separate since allowlist values are unioned together,
+                    // in intersection mode the URI check above is the match.
+                    if !intersection {
+                        continue;
                     }
+                    true
                 }
+                _ => {
+                    return Err(MMMErrorCode::InvalidAllowLists.into());
+                }
+            };
+            if matched && !intersection {
+                return Ok((parsed_metadata, allowlist_val.kind));
             }
-            ALLOWLIST_KIND_METADATA => {
-                // Do not validate URI here, as we already did it above.
-                // These checks are separate since allowlist values are unioned together.
-                continue;
-            }
-            _ => {
+            if !matched && intersection {
                 return Err(MMMErrorCode::InvalidAllowLists.into());
             }
         }
+
+        // in union mode we didn't find a match, in intersection mode all entries matched
+        if let (true, Some(kind)) = (intersection, self.first_kind) {
+            return Ok((parsed_metadata, kind));
+        }
+        Err(MMMErrorCode::InvalidAllowLists.into())
     }
+}
 
-    // at the end, we didn't find a match, thus return err
-    Err(MMMErrorCode::InvalidAllowLists.into())
+const ALLOWLIST_CHECK_COST_KEY: u8 = 0;
+const ALLOWLIST_CHECK_COST_FIELD: u8 = 1;
+const ALLOWLIST_CHECK_COST_METADATA: u8 = 2;
+
+// mint and any entries only compare keys, max royalty, update authority and metadata entries
+// read a single metadata field, fvca and mcc entries walk the creators and the collection, and merkle
+// entries hash the whole proof
+fn allowlist_check_cost(kind: u8) -> u8 {
+    match kind {
+        ALLOWLIST_KIND_MAX_ROYALTY | ALLOWLIST_KIND_UPDATE_AUTHORITY | ALLOWLIST_KIND_METADATA => {
+            ALLOWLIST_CHECK_COST_FIELD
+        }
+        ALLOWLIST_KIND_FVCA | ALLOWLIST_KIND_MCC | ALLOWLIST_KIND_MERKLE => {
+            ALLOWLIST_CHECK_COST_METADATA
+        }
+        _ => ALLOWLIST_CHECK_COST_KEY,
+    }
 }
 
-pub fn check_curve(curve_type: u8, curve_delta: u64) -> Result<()> {
-    // So far we only allow linear and exponential curves
+// The leaves of a merkle allowlist are sha256(MERKLE_LEAF_PREFIX || mint) and the nodes
+// are sha256(MERKLE_NODE_PREFIX || a || b) with the two children sorted, so that a proof
+// is only the sibling hashes from the leaf up, without their positions. The prefixes keep
+// a node from being passed off as a leaf.
+const MERKLE_LEAF_PREFIX: &[u8] = &[0];
+const MERKLE_NODE_PREFIX: &[u8] = &[1];
+
+fn get_merkle_leaf(mint: &Pubkey) -> [u8; 32] {
+    hashv(&[MERKLE_LEAF_PREFIX, mint.as_ref()]).to_bytes()
+}
+
+fn hash_merkle_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[MERKLE_NODE_PREFIX, lo, hi]).to_bytes()
+}
+
+// the root that the proof hashes the mint leaf up to, each proof node is the key of an account
+pub fn get_merkle_root(mint: &Pubkey, merkle_proof: &[AccountInfo]) -> [u8; 32] {
+    merkle_proof
+        .iter()
+        .fold(get_merkle_leaf(mint), |node, sibling| {
+            hash_merkle_node(&node, &sibling.key.to_bytes())
+        })
+}
+
+// The merkle proof of a mint goes first in the remaining accounts, before the accounts the
+// instruction already takes there. Its length is found by hashing the mint leaf up with the
+// leading accounts until it reaches the root of one of the merkle entries. Without a merkle
+// entry or a matching proof, no accounts are taken and the merkle entries don't match.
+// Returns the proof and the remaining accounts after it.
+pub fn split_merkle_proof<'a, 'info>(
+    allowlists: &[Allowlist],
+    mint: &Pubkey,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> (&'a [AccountInfo<'info>], &'a [AccountInfo<'info>]) {
+    let roots: Vec<[u8; 32]> = allowlists
+        .iter()
+        .filter(|allowlist_val| allowlist_val.kind == ALLOWLIST_KIND_MERKLE)
+        .map(|allowlist_val| allowlist_val.value.to_bytes())
+        .collect();
+    if roots.is_empty() {
+        return (&[], remaining_accounts);
+    }
+    let mut node = get_merkle_leaf(mint);
+    for proof_len in 0..=remaining_accounts.len() {
+        if roots.contains(&node) {
+            return remaining_accounts.split_at(proof_len);
+        }
+        if let Some(sibling) = remaining_accounts.get(proof_len) {
+            node = hash_merkle_node(&node, &sibling.key.to_bytes());
+        }
+    }
+    (&[], remaining_accounts)
+}
+
+// When the pool requires the collection metadata or a sized collection and the mint is in the
+// verified collection of one of its mcc entries, the collection metadata and master edition
+// are the first two of the remaining accounts. They need to be derived from the collection
+// mint, so that the verified collection is cross-checked against a collection with a master
+// edition rather than trusted as is, and a sized collection also needs collection details.
+// Returns the remaining accounts after them.
+pub fn check_collection_metadata<'a, 'info>(
+    pool: &Pool,
+    parsed_metadata: &Metadata,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<&'a [AccountInfo<'info>]> {
+    let collection = match &parsed_metadata.collection {
+        Some(collection) if collection.verified => collection,
+        _ => return Ok(remaining_accounts),
+    };
+    if !(pool.require_collection_metadata || pool.require_sized_collection)
+        || !pool
+            .get_allowlists()
+            .iter()
+            .any(|val| val.kind == ALLOWLIST_KIND_MCC && val.value == collection.key)
+    {
+        return Ok(remaining_accounts);
+    }
+    let [collection_metadata, collection_master_edition, remaining_accounts @ ..] =
+        remaining_accounts
+    else {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    };
+
+    if *collection_metadata.owner != mpl_token_metadata::ID
+        || *collection_master_edition.owner != mpl_token_metadata::ID
+    {
+        return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+    if Metadata::find_pda(&collection.key).0 != collection_metadata.key()
+        || MasterEdition::find_pda(&collection.key).0 != collection_master_edition.key()
+    {
+        return Err(ErrorCode::ConstraintSeeds.into());
+    }
+    if !check_master_edition(collection_master_edition) {
+        return Err(MMMErrorCode::InvalidMasterEdition.into());
+    }
+    let parsed_collection_metadata =
+        Metadata::safe_deserialize(&collection_metadata.data.borrow())?;
+    if parsed_collection_metadata.mint != collection.key {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    }
+    if pool.require_sized_collection && parsed_collection_metadata.collection_details.is_none() {
+        return Err(MMMErrorCode::UnsizedCollection.into());
+    }
+    Ok(remaining_accounts)
+}
+
+// the cap on the total price of a fill, a max_total_price of 0 means MAX_TOTAL_PRICE
+pub fn get_max_total_price(max_total_price: u64) -> u64 {
+    match max_total_price {
+        0 => MAX_TOTAL_PRICE,
+        max_total_price => max_total_price,
+    }
+}
+
+// max_total_price is the effective cap of the pool, see get_max_total_price
+pub fn check_curve(
+    curve_type: u8,
+    curve_delta: u64,
+    spot_price: u64,
+    max_fill_depth: u16,
+    curve_direction: u8,
+    max_total_price: u64,
+) -> Result<()> {
+    // So far we only allow linear, exponential, logarithmic, constant product and flat curves
     // 0: linear
     // 1: exp
-    if curve_type > 1 {
+    // 2: log
+    // 3: xyk
+    // 4: flat, which ignores curve_delta
+    if curve_type > CURVE_KIND_FLAT {
         return Err(MMMErrorCode::InvalidCurveType.into());
     }
 
-    // If the curve type is exp, then the curve_delta should follow bp format,
-    // which is less than 10000
-    if curve_type == 1 && curve_delta > 10000 {
+    // If the curve type is exp or log, then the curve_delta should follow bp format,
+    // which is less than 10000 for log, exp curves can step up to MAX_EXP_CURVE_DELTA
+    if (curve_type == CURVE_KIND_LOG && curve_delta > 10000)
+        || (curve_type == CURVE_KIND_EXP && curve_delta > MAX_EXP_CURVE_DELTA)
+    {
+        return Err(MMMErrorCode::InvalidCurveDelta.into());
+    }
+
+    // A steep exp curve, i.e. more than 2x per step, needs to keep the fulfill_sell price
+    // after STEEP_EXP_CURVE_CHECK_STEPS steps under max_total_price, so that it can still be
+    // filled a few items deep
+    if curve_type == CURVE_KIND_EXP && curve_delta > 10000 {
+        let mut price = spot_price as u128;
+        for _ in 0..STEEP_EXP_CURVE_CHECK_STEPS {
+            price = price
+                .checked_mul(curve_delta as u128 + 10000)
+                .ok_or(MMMErrorCode::InvalidCurveDelta)?
+                / 10000;
+        }
+        if price > max_total_price as u128 {
+            return Err(MMMErrorCode::InvalidCurveDelta.into());
+        }
+    }
+
+    // Only linear curves can be inverted, i.e. step the fulfill_buy price up by curve_delta
+    if curve_direction > CURVE_DIRECTION_INVERTED
+        || (curve_direction == CURVE_DIRECTION_INVERTED && curve_type != CURVE_KIND_LINEAR)
+    {
+        return Err(MMMErrorCode::InvalidCurveDirection.into());
+    }
+
+    // If the curve type is linear, then max_fill_depth fulfill_buy steps need to keep the
+    // price above zero. An inverted curve moves the price up instead, so it needs to keep
+    // the fulfill_buy price after STEEP_EXP_CURVE_CHECK_STEPS steps under max_total_price
+    if curve_type == CURVE_KIND_LINEAR && curve_direction == CURVE_DIRECTION_INVERTED {
+        let price = (curve_delta as u128)
+            .checked_mul(STEEP_EXP_CURVE_CHECK_STEPS as u128)
+            .and_then(|v| v.checked_add(spot_price as u128))
+            .ok_or(MMMErrorCode::InvalidCurveDelta)?;
+        if price > max_total_price as u128 {
+            return Err(MMMErrorCode::InvalidCurveDelta.into());
+        }
+    } else if curve_type == CURVE_KIND_LINEAR
+        && (spot_price as u128) <= (max_fill_depth as u128) * (curve_delta as u128)
+    {
         return Err(MMMErrorCode::InvalidCurveDelta.into());
     }
 
+    // If the curve type is xyk, then the curve_delta is the virtual token reserve, the
+    // implied invariant k = spot_price * curve_delta^2 needs to fit in u128 and the
+    // price after one buy needs to be non-zero
+    if curve_type == CURVE_KIND_XYK {
+        if curve_delta == 0 {
+            return Err(MMMErrorCode::InvalidCurveDelta.into());
+        }
+        let y = curve_delta as u128;
+        let k = (spot_price as u128)
+            .checked_mul(y)
+            .and_then(|v| v.checked_mul(y))
+            .ok_or(MMMErrorCode::InvalidCurveDelta)?;
+        let y_next = y.checked_add(1).ok_or(MMMErrorCode::InvalidCurveDelta)?;
+        let next_price = y_next
+            .checked_mul(y_next)
+            .map(|d| k / d)
+            .ok_or(MMMErrorCode::InvalidCurveDelta)?;
+        if next_price == 0 {
+            return Err(MMMErrorCode::InvalidCurveDelta.into());
+        }
+    }
+
     Ok(())
 }
 
+// curve_delta_sell only steps the fulfill_sell prices of linear curves, so that the sell side
+// can be spread wider than the buy side. 0 means the sell side follows curve_delta. the
+// fulfill_sell steps move the price up, so there's no fill depth to keep above zero.
+pub fn check_curve_delta_sell(
+    curve_type: u8,
+    curve_delta_sell: u64,
+    spot_price: u64,
+) -> Result<()> {
+    if curve_delta_sell == 0 {
+        return Ok(());
+    }
+    if curve_type != CURVE_KIND_LINEAR {
+        return Err(MMMErrorCode::InvalidCurveDelta.into());
+    }
+    check_curve(
+        curve_type,
+        curve_delta_sell,
+        spot_price,
+        0,
+        CURVE_DIRECTION_NORMAL,
+        MAX_TOTAL_PRICE,
+    )
+}
+
+// the fulfill_buy steps of an inverted linear curve, i.e. p + i * delta for i in 0..n.
+// only the first c steps are under the ceiling, the rest are pinned to it, 0 means unbounded
+fn get_inverted_linear_total_and_next_price(
+    p: u64,
+    delta: u64,
+    n: u64,
+    ceiling: u64,
+) -> Result<(u64, u64)> {
+    let c = match (ceiling, delta) {
+        (0, _) | (_, 0) => n,
+        _ => n.min(
+            ceiling
+                .checked_sub(p)
+                .ok_or(MMMErrorCode::NumericOverflow)?
+                .checked_div(delta)
+                .ok_or(MMMErrorCode::NumericOverflow)?
+                .saturating_add(1),
+        ),
+    };
+    // c*(2*p+(c-1)*delta)/2 + (n-c)*ceiling
+    let total_price = match c {
+        0 => 0,
+        _ => c
+            .checked_mul(
+                p.checked_mul(2)
+                    .ok_or(MMMErrorCode::NumericOverflow)?
+                    .checked_add(
+                        (c - 1)
+                            .checked_mul(delta)
+                            .ok_or(MMMErrorCode::NumericOverflow)?,
+                    )
+                    .ok_or(MMMErrorCode::NumericOverflow)?,
+            )
+            .ok_or(MMMErrorCode::NumericOverflow)?
+            .checked_div(2)
+            .ok_or(MMMErrorCode::NumericOverflow)?,
+    }
+    .checked_add(
+        (n - c)
+            .checked_mul(ceiling)
+            .ok_or(MMMErrorCode::NumericOverflow)?,
+    )
+    .ok_or(MMMErrorCode::NumericOverflow)?;
+    // min(p + n * delta, ceiling)
+    let next_price = p.checked_add(n.checked_mul(delta).ok_or(MMMErrorCode::NumericOverflow)?);
+    let final_price = match ceiling {
+        0 => next_price.ok_or(MMMErrorCode::NumericOverflow)?,
+        _ => next_price.unwrap_or(ceiling).min(ceiling),
+    };
+    Ok((total_price, final_price))
+}
+
+// buying n items from the pool with virtual reserves x = p * y and y = delta, i.e.
+// the pool receives n tokens and pays out x - k / (y + n) = p * y * n / (y + n);
+// for selling n items the pool pays out n tokens and receives
+// k / (y - n) - x = p * y * n / (y - n), rounded up in favor of the pool.
+// the next spot price would be k / (y +- n)^2
+// the first m items are priced on the curve as long as the marginal price after them
+// stays above the floor, the remaining n - m items are pinned to the floor
+fn get_xyk_curve_total_and_next_price_with_floor(
+    p: u64,
+    delta: u64,
+    n: u64,
+    floor: u64,
+) -> Result<(u128, u128)> {
+    let (total_price, final_price) = get_xyk_curve_total_and_next_price(p, delta, n, true)?;
+    if final_price >= floor as u128 {
+        return Ok((total_price, final_price));
+    }
+    let (mut lo, mut hi) = (0, n);
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if get_xyk_curve_total_and_next_price(p, delta, mid, true)?.1 >= floor as u128 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let (total_price, _) = get_xyk_curve_total_and_next_price(p, delta, lo, true)?;
+    Ok((
+        total_price
+            .checked_add((floor as u128) * ((n - lo) as u128))
+            .ok_or(MMMErrorCode::NumericOverflow)?,
+        floor as u128,
+    ))
+}
+
+// the first m items are priced on the curve as long as the marginal price after them
+// stays below the ceiling, the remaining n - m items are pinned to the ceiling
+fn get_xyk_curve_total_and_next_price_with_ceiling(
+    p: u64,
+    delta: u64,
+    n: u64,
+    ceiling: u64,
+) -> Result<(u128, u128)> {
+    if ceiling == 0 {
+        return get_xyk_curve_total_and_next_price(p, delta, n, false);
+    }
+    let below_ceiling = |m: u64| -> bool {
+        matches!(
+            get_xyk_curve_total_and_next_price(p, delta, m, false),
+            Ok((_, next_price)) if next_price <= ceiling as u128
+        )
+    };
+    if below_ceiling(n) {
+        return get_xyk_curve_total_and_next_price(p, delta, n, false);
+    }
+    let (mut lo, mut hi) = (0, n);
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if below_ceiling(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let (total_price, _) = get_xyk_curve_total_and_next_price(p, delta, lo, false)?;
+    Ok((
+        total_price
+            .checked_add((ceiling as u128) * ((n - lo) as u128))
+            .ok_or(MMMErrorCode::NumericOverflow)?,
+        ceiling as u128,
+    ))
+}
+
+fn get_xyk_curve_total_and_next_price(
+    p: u64,
+    delta: u64,
+    n: u64,
+    fulfill_buy: bool,
+) -> Result<(u128, u128)> {
+    let p = p as u128;
+    let y = delta as u128;
+    let n = n as u128;
+    let y_next = if fulfill_buy {
+        y.checked_add(n)
+    } else {
+        y.checked_sub(n).filter(|v| *v > 0)
+    }
+    .ok_or(MMMErrorCode::NumericOverflow)?;
+    let numerator = p
+        .checked_mul(y)
+        .and_then(|v| v.checked_mul(n))
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    let total_price = if fulfill_buy {
+        numerator / y_next
+    } else {
+        numerator
+            .checked_add(y_next - 1)
+            .ok_or(MMMErrorCode::NumericOverflow)?
+            / y_next
+    };
+    let k = p
+        .checked_mul(y)
+        .and_then(|v| v.checked_mul(y))
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    let final_price = k / y_next
+        .checked_mul(y_next)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    if final_price == 0 {
+        return Err(MMMErrorCode::NumericOverflow.into());
+    }
+    Ok((total_price, final_price))
+}
+
+// log2(x) in fixed point, scaled by 10000, x needs to be at least 1
+fn log2_bp(x: u64) -> u128 {
+    const FRAC_BITS: u32 = 16;
+    let int_part = 63 - x.leading_zeros();
+    // normalize x into [1, 2) as a Q32 number, then square it to extract
+    // the fractional bits one at a time
+    let mut y: u128 = (u128::from(x) << 32) >> int_part;
+    let mut frac: u128 = 0;
+    for i in 1..=FRAC_BITS {
+        y = (y * y) >> 32;
+        if y >= 2 << 32 {
+            y >>= 1;
+            frac |= 1 << (FRAC_BITS - i);
+        }
+    }
+    ((((int_part as u128) << FRAC_BITS) | frac) * 10000) >> FRAC_BITS
+}
+
+// price of the log curve at step s away from the base price b, where the multiplier
+// grows with log2(1 + |s|) instead of compounding like the exp curve. fulfill_buy
+// steps s up, and fulfill_sell steps it down
+//   s >= 0: b / (1 + delta * log2(1 + s))
+//   s < 0:  b * (1 + delta * log2(1 - s))
+fn get_log_curve_price(b: u64, delta: u64, s: i64) -> Result<u128> {
+    let multiplier = (delta as u128)
+        .checked_mul(log2_bp(
+            s.unsigned_abs()
+                .checked_add(1)
+                .ok_or(MMMErrorCode::NumericOverflow)?,
+        ))
+        .and_then(|v| v.checked_add(10000 * 10000))
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    let price = if s >= 0 {
+        (b as u128)
+            .checked_mul(10000 * 10000)
+            .and_then(|v| v.checked_div(multiplier))
+    } else {
+        (b as u128)
+            .checked_mul(multiplier)
+            .and_then(|v| v.checked_div(10000 * 10000))
+    };
+    price.ok_or(MMMErrorCode::NumericOverflow.into())
+}
+
+// the step of the log curve with base price b the spot price p is at, i.e. the smallest
+// s with a price at or below p. Fills leave p on the curve, so the next fill carries on
+// from the same step instead of restarting the curve at p
+fn get_log_curve_step(b: u64, delta: u64, p: u64) -> Result<i64> {
+    const MAX_STEP: i64 = 1 << 62;
+    // every step has the base price on a flat curve
+    if delta == 0 {
+        return Ok(0);
+    }
+    let at_or_below =
+        |s: i64| -> Result<bool> { Ok(get_log_curve_price(b, delta, s)? <= p as u128) };
+    // the price goes down as s goes up, so bracket the step between lo and hi first
+    let (mut lo, mut hi) = if at_or_below(0)? {
+        let mut t: i64 = 1;
+        while t < MAX_STEP && at_or_below(-t)? {
+            t *= 2;
+        }
+        (-t, 0)
+    } else {
+        let mut t: i64 = 1;
+        while t < MAX_STEP && !at_or_below(t)? {
+            t *= 2;
+        }
+        (0, t)
+    };
+    // no number of fills gets a pool past MAX_STEP
+    if at_or_below(lo)? {
+        return Ok(lo);
+    }
+    if !at_or_below(hi)? {
+        return Ok(hi);
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if at_or_below(mid)? {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Ok(hi)
+}
+
 pub fn get_buyside_seller_receives(
     total_sol_price: u64,
     lp_fee_bp: u16,
@@ -190,27 +871,136 @@ pub fn get_buyside_seller_receives(
         .ok_or(MMMErrorCode::NumericOverflow.into())
 }
 
-pub fn get_lp_fee_bp(pool: &Pool, buyside_sol_escrow_balance: u64) -> u16 {
+pub fn get_lp_fee_bp(
+    pool: &Pool,
+    buyside_sol_escrow_balance: u64,
+    asset_amount: u64,
+) -> Result<u16> {
     if pool.sellside_asset_amount < 1 {
-        return 0;
+        return Ok(0);
     }
 
     if buyside_sol_escrow_balance < pool.spot_price {
-        return 0;
+        return Ok(0);
+    }
+
+    // the tier with the highest threshold at or below the fill size replaces the flat lp fee
+    if let Some(tier) = pool
+        .lp_fee_tiers
+        .iter()
+        .filter(|tier| !tier.is_empty() && asset_amount >= tier.min_asset_amount)
+        .max_by_key(|tier| tier.min_asset_amount)
+    {
+        return Ok(tier.lp_fee_bp);
     }
 
-    pool.lp_fee_bp
+    if pool.lp_fee_decay_end == 0 || pool.lp_fee_decay_end <= pool.lp_fee_decay_start {
+        return Ok(pool.lp_fee_bp);
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if now <= pool.lp_fee_decay_start {
+        return Ok(pool.lp_fee_bp);
+    }
+    if now >= pool.lp_fee_decay_end {
+        return Ok(pool.lp_fee_bp_floor);
+    }
+
+    // interpolate linearly between lp_fee_bp and lp_fee_bp_floor
+    let elapsed = (now - pool.lp_fee_decay_start) as u128;
+    let window = (pool.lp_fee_decay_end - pool.lp_fee_decay_start) as u128;
+    let decay_range = pool.lp_fee_bp.saturating_sub(pool.lp_fee_bp_floor) as u128;
+    let decayed = decay_range
+        .checked_mul(elapsed)
+        .ok_or(MMMErrorCode::NumericOverflow)?
+        / window;
+    Ok(pool.lp_fee_bp - decayed as u16)
+}
+
+pub fn get_lp_fee_tiers(lp_fee_tiers: &[LpFeeTier]) -> Result<[LpFeeTier; LP_FEE_TIERS_MAX_LEN]> {
+    if lp_fee_tiers.len() > LP_FEE_TIERS_MAX_LEN {
+        return Err(MMMErrorCode::InvalidLpFeeTiers.into());
+    }
+    let mut tiers = [LpFeeTier::default(); LP_FEE_TIERS_MAX_LEN];
+    for (i, tier) in lp_fee_tiers.iter().enumerate() {
+        if tier.lp_fee_bp > MAX_LP_FEE_BP {
+            msg!("InvalidBP: lp fee tier above max lp fee bp");
+            return Err(MMMErrorCode::InvalidBP.into());
+        }
+        tiers[i] = *tier;
+    }
+    Ok(tiers)
+}
+
+// an empty allowlist_aux clears the stored one
+pub fn get_allowlist_aux_bytes(allowlist_aux: &str) -> Result<[u8; ALLOWLIST_AUX_MAX_LEN]> {
+    let bytes = allowlist_aux.as_bytes();
+    if bytes.len() > ALLOWLIST_AUX_MAX_LEN || bytes.contains(&0) {
+        return Err(MMMErrorCode::InvalidAllowlistAux.into());
+    }
+    let mut allowlist_aux = [0; ALLOWLIST_AUX_MAX_LEN];
+    allowlist_aux[..bytes.len()].copy_from_slice(bytes);
+    Ok(allowlist_aux)
+}
+
+pub fn get_extra_royalty_recipients(
+    extra_royalty_recipients: &[RoyaltyRecipient],
+) -> Result<[RoyaltyRecipient; EXTRA_ROYALTY_RECIPIENTS_MAX_LEN]> {
+    if extra_royalty_recipients.len() > EXTRA_ROYALTY_RECIPIENTS_MAX_LEN {
+        return Err(MMMErrorCode::InvalidExtraRoyaltyRecipients.into());
+    }
+    let mut recipients = [RoyaltyRecipient::default(); EXTRA_ROYALTY_RECIPIENTS_MAX_LEN];
+    let mut total_bp: u16 = 0;
+    for (i, recipient) in extra_royalty_recipients.iter().enumerate() {
+        if !recipient.is_empty() {
+            total_bp = total_bp
+                .checked_add(recipient.bp)
+                .ok_or(MMMErrorCode::NumericOverflow)?;
+        }
+        recipients[i] = *recipient;
+    }
+    // the extra recipients can not take more than the total royalty
+    if total_bp > 10000 {
+        msg!("InvalidBP: extra royalty recipients above 10000 bp");
+        return Err(MMMErrorCode::InvalidBP.into());
+    }
+    Ok(recipients)
+}
+
+pub fn check_lp_fee_decay(
+    lp_fee_bp: u16,
+    lp_fee_bp_floor: u16,
+    lp_fee_decay_end: i64,
+) -> Result<()> {
+    if lp_fee_bp_floor > lp_fee_bp {
+        msg!("lp_fee_bp_floor is higher than lp_fee_bp");
+        return Err(MMMErrorCode::InvalidLpFeeDecay.into());
+    }
+    if lp_fee_decay_end != 0 && lp_fee_decay_end <= Clock::get()?.unix_timestamp {
+        msg!("lp_fee_decay_end is not in the future");
+        return Err(MMMErrorCode::InvalidLpFeeDecay.into());
+    }
+    Ok(())
 }
 
 pub fn get_sol_lp_fee(
     pool: &Pool,
     buyside_sol_escrow_balance: u64,
     total_sol_price: u64,
+    asset_amount: u64,
 ) -> Result<u64> {
-    let lp_fee_bp = get_lp_fee_bp(pool, buyside_sol_escrow_balance);
+    let lp_fee_bp = get_lp_fee_bp(pool, buyside_sol_escrow_balance, asset_amount)?;
 
-    Ok(((total_sol_price as u128)
+    let lp_fee = (total_sol_price as u128)
         .checked_mul(lp_fee_bp as u128)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    let rounding_offset = match pool.lp_fee_rounding {
+        LP_FEE_ROUNDING_CEIL => 9999,
+        LP_FEE_ROUNDING_NEAREST => 5000,
+        _ => 0,
+    };
+    Ok((lp_fee
+        .checked_add(rounding_offset)
         .ok_or(MMMErrorCode::NumericOverflow)?
         .checked_div(10000)
         .ok_or(MMMErrorCode::NumericOverflow)?) as u64)
@@ -227,17 +1017,174 @@ pub fn get_sol_fee(total_sol_price: u64, fee_bp: i16) -> Result<i64> {
     .map_err(|_| MMMErrorCode::NumericOverflow.into())
 }
 
+// the maker fee goes to the pool's referral and the taker fee to the taker's referral.
+// a negative maker fee is a rebate that is funded by the taker fee, so it can't exceed it.
+pub fn get_referral_fees(maker_fee: i64, taker_fee: i64) -> Result<(u64, u64)> {
+    let maker_referral_fee = maker_fee.max(0) as u64;
+    let taker_referral_fee = u64::try_from(
+        taker_fee
+            .checked_add(maker_fee.min(0))
+            .ok_or(MMMErrorCode::NumericOverflow)?,
+    )
+    .map_err(|_| MMMErrorCode::MakerRebateExceedsTakerFee)?;
+    Ok((maker_referral_fee, taker_referral_fee))
+}
+
+// the total paid to both referrals, it's maker_fee + taker_fee with the rebate netted out
+fn get_total_referral_fee(maker_fee: i64, taker_fee: i64) -> Result<u64> {
+    let (maker_referral_fee, taker_referral_fee) = get_referral_fees(maker_fee, taker_fee)?;
+    maker_referral_fee
+        .checked_add(taker_referral_fee)
+        .ok_or(MMMErrorCode::NumericOverflow.into())
+}
+
+pub fn pay_referral_fees<'info>(
+    maker_fee: i64,
+    taker_fee: i64,
+    payer: &AccountInfo<'info>,
+    referral: &AccountInfo<'info>,
+    taker_referral: &AccountInfo<'info>,
+    payer_seeds: &[&[&[u8]]],
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let (maker_referral_fee, taker_referral_fee) = get_referral_fees(maker_fee, taker_fee)?;
+    for (recipient, amount) in [
+        (referral, maker_referral_fee),
+        (taker_referral, taker_referral_fee),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        invoke_signed(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                payer.key,
+                recipient.key,
+                amount,
+            ),
+            &[payer.clone(), recipient.clone(), system_program.clone()],
+            payer_seeds,
+        )?;
+    }
+    Ok(())
+}
+
+// a fulfill buy pays the total price and the maker fee out of the buyside escrow, or out of the
+// m2 shared escrow of a shared escrow pool. the lp fee, the taker fee and the royalty are all
+// carved out of the total price, so checking the balance up front fails an underfunded fill
+// with EscrowUnderfunded instead of a lamports error in one of the transfers
+pub fn check_buyside_escrow_funded(available: u64, total_price: u64, maker_fee: i64) -> Result<()> {
+    let required = i128::from(total_price)
+        .checked_add(i128::from(maker_fee))
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    if i128::from(available) < required {
+        msg!(
+            "buyside escrow balance {} is below the required {}",
+            available,
+            required
+        );
+        return Err(MMMErrorCode::EscrowUnderfunded.into());
+    }
+    Ok(())
+}
+
+// the average price per item of the fill must be within [min_price_per_item, max_price_per_item]
+pub fn check_price_slippage(
+    total_price: u64,
+    asset_amount: u64,
+    min_price_per_item: Option<u64>,
+    max_price_per_item: Option<u64>,
+) -> Result<()> {
+    let total_price = total_price as u128;
+    let asset_amount = asset_amount as u128;
+    if let Some(min_price_per_item) = min_price_per_item {
+        if total_price < (min_price_per_item as u128) * asset_amount {
+            msg!(
+                "total_price {} is below the min_price_per_item",
+                total_price
+            );
+            return Err(MMMErrorCode::PriceSlippageExceeded.into());
+        }
+    }
+    if let Some(max_price_per_item) = max_price_per_item {
+        if total_price > (max_price_per_item as u128) * asset_amount {
+            msg!(
+                "total_price {} is above the max_price_per_item",
+                total_price
+            );
+            return Err(MMMErrorCode::PriceSlippageExceeded.into());
+        }
+    }
+    Ok(())
+}
+
 pub fn get_sol_total_price_and_next_price(
     pool: &Pool,
     n: u64,
     fulfill_buy: bool,
 ) -> Result<(u64, u64)> {
+    // exp and log curves price each item in a loop, so a large n would run out of compute
+    // long before the total price reaches MAX_TOTAL_PRICE
+    if matches!(pool.curve_type, CURVE_KIND_EXP | CURVE_KIND_LOG) && n > MAX_FILL_ITEMS {
+        msg!("fill of {} items is above the max of {}", n, MAX_FILL_ITEMS);
+        return Err(MMMErrorCode::FillExceedsMaxItems.into());
+    }
     // the price needs to go down
     let p = pool.spot_price;
-    let delta = pool.curve_delta;
+    // linear fulfill_sell steps can use their own delta, see check_curve_delta_sell
+    let delta = match (fulfill_buy, pool.curve_type, pool.curve_delta_sell) {
+        (false, CURVE_KIND_LINEAR, curve_delta_sell) if curve_delta_sell > 0 => curve_delta_sell,
+        _ => pool.curve_delta,
+    };
+    let floor = pool.price_floor;
+    let ceiling = pool.price_ceiling;
+    // fulfill_sell step prices are capped by the ceiling, 0 means unbounded
+    let clamp_to_ceiling = |price: u128| match ceiling {
+        0 => price,
+        _ => price.min(ceiling as u128),
+    };
     let ret = match fulfill_buy {
         true => {
             match pool.curve_type {
+                CURVE_KIND_LINEAR if pool.curve_direction == CURVE_DIRECTION_INVERTED => {
+                    get_inverted_linear_total_and_next_price(p, delta, n, ceiling)
+                }
+                CURVE_KIND_LINEAR if floor > 0 => {
+                    // only the first c steps are above the floor, the rest are pinned to it
+                    let c = match delta {
+                        0 => n,
+                        _ => n.min(
+                            p.checked_sub(floor)
+                                .ok_or(MMMErrorCode::NumericOverflow)?
+                                .checked_div(delta)
+                                .ok_or(MMMErrorCode::NumericOverflow)?
+                                .saturating_add(1),
+                        ),
+                    };
+                    let total_price = match c {
+                        0 => 0,
+                        _ => c
+                            .checked_mul(
+                                p.checked_mul(2)
+                                    .ok_or(MMMErrorCode::NumericOverflow)?
+                                    .checked_sub((c - 1) * delta)
+                                    .ok_or(MMMErrorCode::NumericOverflow)?,
+                            )
+                            .ok_or(MMMErrorCode::NumericOverflow)?
+                            .checked_div(2)
+                            .ok_or(MMMErrorCode::NumericOverflow)?,
+                    }
+                    .checked_add(
+                        (n - c)
+                            .checked_mul(floor)
+                            .ok_or(MMMErrorCode::NumericOverflow)?,
+                    )
+                    .ok_or(MMMErrorCode::NumericOverflow)?;
+                    // max(p - n * delta, floor)
+                    let final_price = p
+                        .saturating_sub(n.checked_mul(delta).ok_or(MMMErrorCode::NumericOverflow)?)
+                        .max(floor);
+                    Ok((total_price, final_price))
+                }
                 CURVE_KIND_LINEAR => {
                     // n*(2*p-(n-1)*delta)/2
                     let total_price = n
@@ -262,12 +1209,18 @@ pub fn get_sol_total_price_and_next_price(
                     Ok((total_price, final_price))
                 }
                 CURVE_KIND_EXP => {
-                    // for loop to prevent overflow
-                    let mut total_price: u64 = 0;
-                    let mut curr_price: u128 = p as u128;
+                    // for loop to prevent overflow, the prices carry EXP_CURVE_PRECISION and
+                    // are only truncated to lamports at the end, so rounding doesn't compound
+                    let floor = (floor as u128)
+                        .checked_mul(EXP_CURVE_PRECISION)
+                        .ok_or(MMMErrorCode::NumericOverflow)?;
+                    let mut total_price: u128 = 0;
+                    let mut curr_price: u128 = (p as u128)
+                        .checked_mul(EXP_CURVE_PRECISION)
+                        .ok_or(MMMErrorCode::NumericOverflow)?;
                     for _ in 0..n {
                         total_price = total_price
-                            .checked_add(curr_price as u64)
+                            .checked_add(curr_price.max(floor))
                             .ok_or(MMMErrorCode::NumericOverflow)?;
                         curr_price = curr_price
                             .checked_mul(10000)
@@ -279,7 +1232,42 @@ pub fn get_sol_total_price_and_next_price(
                             )
                             .ok_or(MMMErrorCode::NumericOverflow)?;
                     }
-                    Ok((total_price, curr_price as u64))
+                    Ok((
+                        u64::try_from(total_price / EXP_CURVE_PRECISION)
+                            .map_err(|_| MMMErrorCode::NumericOverflow)?,
+                        u64::try_from(curr_price.max(floor) / EXP_CURVE_PRECISION)
+                            .map_err(|_| MMMErrorCode::NumericOverflow)?,
+                    ))
+                }
+                CURVE_KIND_LOG => {
+                    // sum of the prices at steps s..s + n, where s is the step of the spot price
+                    let b = pool.get_log_curve_base_price();
+                    let s = get_log_curve_step(b, delta, p)?;
+                    let mut total_price: u128 = 0;
+                    for i in 0..n as i64 {
+                        total_price = total_price
+                            .checked_add(get_log_curve_price(b, delta, s + i)?.max(floor as u128))
+                            .ok_or(MMMErrorCode::NumericOverflow)?;
+                    }
+                    let final_price =
+                        get_log_curve_price(b, delta, s + n as i64)?.max(floor as u128);
+                    Ok((
+                        u64::try_from(total_price).map_err(|_| MMMErrorCode::NumericOverflow)?,
+                        u64::try_from(final_price).map_err(|_| MMMErrorCode::NumericOverflow)?,
+                    ))
+                }
+                CURVE_KIND_XYK => {
+                    let (total_price, final_price) =
+                        get_xyk_curve_total_and_next_price_with_floor(p, delta, n, floor)?;
+                    Ok((
+                        u64::try_from(total_price).map_err(|_| MMMErrorCode::NumericOverflow)?,
+                        u64::try_from(final_price).map_err(|_| MMMErrorCode::NumericOverflow)?,
+                    ))
+                }
+                CURVE_KIND_FLAT => {
+                    // n * p, and the spot price never moves
+                    let total_price = n.checked_mul(p).ok_or(MMMErrorCode::NumericOverflow)?;
+                    Ok((total_price, p))
                 }
                 _ => Err(MMMErrorCode::InvalidCurveType.into()),
             }
@@ -287,6 +1275,46 @@ pub fn get_sol_total_price_and_next_price(
         false => {
             // for sales, all prices will be one "step" away from the spot price to prevent pool drain
             match pool.curve_type {
+                CURVE_KIND_LINEAR if ceiling > 0 => {
+                    // only the first c steps are below the ceiling, the rest are pinned to it
+                    let c = match delta {
+                        0 => n,
+                        _ => n.min(
+                            ceiling
+                                .checked_sub(p)
+                                .ok_or(MMMErrorCode::NumericOverflow)?
+                                .checked_div(delta)
+                                .ok_or(MMMErrorCode::NumericOverflow)?,
+                        ),
+                    };
+                    // c*(2*p+(c+1)*delta)/2 + (n-c)*ceiling
+                    let total_price = c
+                        .checked_mul(
+                            p.checked_mul(2)
+                                .ok_or(MMMErrorCode::NumericOverflow)?
+                                .checked_add(
+                                    c.checked_add(1)
+                                        .ok_or(MMMErrorCode::NumericOverflow)?
+                                        .checked_mul(delta)
+                                        .ok_or(MMMErrorCode::NumericOverflow)?,
+                                )
+                                .ok_or(MMMErrorCode::NumericOverflow)?,
+                        )
+                        .ok_or(MMMErrorCode::NumericOverflow)?
+                        .checked_div(2)
+                        .ok_or(MMMErrorCode::NumericOverflow)?
+                        .checked_add(
+                            (n - c)
+                                .checked_mul(ceiling)
+                                .ok_or(MMMErrorCode::NumericOverflow)?,
+                        )
+                        .ok_or(MMMErrorCode::NumericOverflow)?;
+                    // min(p + n * delta, ceiling)
+                    let final_price = p
+                        .saturating_add(n.checked_mul(delta).ok_or(MMMErrorCode::NumericOverflow)?)
+                        .min(ceiling);
+                    Ok((total_price, final_price))
+                }
                 CURVE_KIND_LINEAR => {
                     // n*(2*p+(n+1)*delta)/2
                     let total_price = n
@@ -311,8 +1339,14 @@ pub fn get_sol_total_price_and_next_price(
                     Ok((total_price, final_price))
                 }
                 CURVE_KIND_EXP => {
-                    let mut total_price: u64 = 0;
-                    let mut curr_price: u128 = p as u128;
+                    // same precision as the fulfill_buy side, and the ceiling is scaled to match
+                    let ceiling = (ceiling as u128)
+                        .checked_mul(EXP_CURVE_PRECISION)
+                        .ok_or(MMMErrorCode::NumericOverflow)?;
+                    let mut total_price: u128 = 0;
+                    let mut curr_price: u128 = (p as u128)
+                        .checked_mul(EXP_CURVE_PRECISION)
+                        .ok_or(MMMErrorCode::NumericOverflow)?;
                     for _ in 0..n {
                         curr_price = curr_price
                             .checked_mul(
@@ -323,11 +1357,50 @@ pub fn get_sol_total_price_and_next_price(
                             .ok_or(MMMErrorCode::NumericOverflow)?
                             .checked_div(10000)
                             .ok_or(MMMErrorCode::NumericOverflow)?;
+                        if ceiling > 0 {
+                            curr_price = curr_price.min(ceiling);
+                        }
+                        total_price = total_price
+                            .checked_add(curr_price)
+                            .ok_or(MMMErrorCode::NumericOverflow)?;
+                    }
+                    Ok((
+                        u64::try_from(total_price / EXP_CURVE_PRECISION)
+                            .map_err(|_| MMMErrorCode::NumericOverflow)?,
+                        u64::try_from(curr_price / EXP_CURVE_PRECISION)
+                            .map_err(|_| MMMErrorCode::NumericOverflow)?,
+                    ))
+                }
+                CURVE_KIND_LOG => {
+                    // sum of the prices at steps s - 1 down to s - n, where s is the step of
+                    // the spot price
+                    let b = pool.get_log_curve_base_price();
+                    let s = get_log_curve_step(b, delta, p)?;
+                    let mut total_price: u128 = 0;
+                    let mut curr_price: u128 = p as u128;
+                    for i in 1..=n as i64 {
+                        curr_price = clamp_to_ceiling(get_log_curve_price(b, delta, s - i)?);
                         total_price = total_price
-                            .checked_add(curr_price as u64)
+                            .checked_add(curr_price)
                             .ok_or(MMMErrorCode::NumericOverflow)?;
                     }
-                    Ok((total_price, curr_price as u64))
+                    Ok((
+                        u64::try_from(total_price).map_err(|_| MMMErrorCode::NumericOverflow)?,
+                        u64::try_from(curr_price).map_err(|_| MMMErrorCode::NumericOverflow)?,
+                    ))
+                }
+                CURVE_KIND_XYK => {
+                    let (total_price, final_price) =
+                        get_xyk_curve_total_and_next_price_with_ceiling(p, delta, n, ceiling)?;
+                    Ok((
+                        u64::try_from(total_price).map_err(|_| MMMErrorCode::NumericOverflow)?,
+                        u64::try_from(final_price).map_err(|_| MMMErrorCode::NumericOverflow)?,
+                    ))
+                }
+                CURVE_KIND_FLAT => {
+                    // n * p, and the spot price never moves
+                    let total_price = n.checked_mul(p).ok_or(MMMErrorCode::NumericOverflow)?;
+                    Ok((total_price, p))
                 }
                 _ => Err(MMMErrorCode::InvalidCurveType.into()),
             }
@@ -340,7 +1413,7 @@ pub fn get_sol_total_price_and_next_price(
                 return Err(MMMErrorCode::NumericOverflow.into());
             }
 
-            if total_price > MAX_TOTAL_PRICE {
+            if total_price > get_max_total_price(pool.max_total_price) {
                 return Err(MMMErrorCode::NumericOverflow.into());
             }
             Ok((total_price, final_price))
@@ -349,6 +1422,290 @@ pub fn get_sol_total_price_and_next_price(
     }
 }
 
+// moves the pool along its curve after a fill of n items, for xyk curves the virtual
+// token reserve (curve_delta) moves with the fill as well
+pub fn update_pool_curve(
+    pool: &mut Pool,
+    n: u64,
+    next_price: u64,
+    fulfill_buy: bool,
+) -> Result<()> {
+    // a static spot pool keeps its curve where it is after a buy fill
+    if fulfill_buy && pool.static_spot {
+        return Ok(());
+    }
+    // a log curve steps away from the spot price it had before its first fill
+    if pool.curve_type == CURVE_KIND_LOG && pool.log_curve_base_price == 0 {
+        pool.log_curve_base_price = pool.spot_price;
+    }
+    if pool.curve_type == CURVE_KIND_XYK {
+        pool.curve_delta = if fulfill_buy {
+            pool.curve_delta.checked_add(n)
+        } else {
+            pool.curve_delta.checked_sub(n)
+        }
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    }
+    // a single fill can't move the spot price more than max_spot_move_bp, so that a large
+    // taker can't be sandwiched by a fill that walks the curve far in one instruction
+    if pool.max_spot_move_bp != 0 {
+        let moved = u128::from(next_price.abs_diff(pool.spot_price))
+            .checked_mul(10000)
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+        let max_move = u128::from(pool.spot_price)
+            .checked_mul(u128::from(pool.max_spot_move_bp))
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+        if moved > max_move {
+            return Err(MMMErrorCode::SpotPriceMoveExceeded.into());
+        }
+    }
+    pool.spot_price = next_price;
+    Ok(())
+}
+
+// the buyside_creator_royalty_bp a vanilla fill pays, the pool's royalty_enforcement_mode
+// overrides the bp of the pool or of the taker
+pub fn get_enforced_creator_royalty_bp(pool: &Pool, buyside_creator_royalty_bp: u16) -> u16 {
+    match pool.royalty_enforcement_mode {
+        ROYALTY_ENFORCEMENT_MODE_ENFORCE => 10000,
+        ROYALTY_ENFORCEMENT_MODE_BYPASS => 0,
+        _ => buyside_creator_royalty_bp,
+    }
+}
+
+// keeps a pool on a steep curve from being walked by many small fills
+pub fn check_min_fill_amount(pool: &Pool, asset_amount: u64) -> Result<()> {
+    if asset_amount < pool.min_fill_amount {
+        return Err(MMMErrorCode::FillBelowMinFillAmount.into());
+    }
+    Ok(())
+}
+
+// rejects a fill within fill_cooldown_secs of the last fill of the pool, and records the
+// fill otherwise, a batch fill is a single fill
+pub fn check_fill_cooldown(pool: &mut Pool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if pool.fill_cooldown_secs > 0
+        && now < pool.last_fill_ts.saturating_add(pool.fill_cooldown_secs)
+    {
+        return Err(MMMErrorCode::FillCooldownActive.into());
+    }
+    pool.last_fill_ts = now;
+    Ok(())
+}
+
+// buy only pools reject fulfill_sell and sell only pools reject fulfill_buy
+pub fn check_pool_side(pool: &Pool, fulfill_buy: bool) -> Result<()> {
+    let allowed = match pool.pool_side {
+        POOL_SIDE_BUY_ONLY => fulfill_buy,
+        POOL_SIDE_SELL_ONLY => !fulfill_buy,
+        _ => true,
+    };
+    if !allowed {
+        return Err(MMMErrorCode::PoolSideNotAllowed.into());
+    }
+    Ok(())
+}
+
+// when the pool opts in, the maker and taker referrals of a fill can't be the pool owner or
+// the counterparty, so that neither side can recapture its own fees
+pub fn check_referrals(
+    pool: &Pool,
+    payer: &Pubkey,
+    referral: &Pubkey,
+    taker_referral: &Pubkey,
+) -> Result<()> {
+    if !pool.reject_self_referral {
+        return Ok(());
+    }
+    if [referral, taker_referral]
+        .iter()
+        .any(|&key| key == &pool.owner || key == payer)
+    {
+        return Err(MMMErrorCode::SelfReferral.into());
+    }
+    Ok(())
+}
+
+// the accounts of a wsol settled fill at the end of the remaining accounts:
+//   payer_wsol_account, wsol_mint, wsol_token_program
+pub struct WsolAccounts<'a, 'info> {
+    pub payer_wsol_account: &'a AccountInfo<'info>,
+    pub wsol_mint: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+// a pool with an oracle takes the oracle account off the very end of the remaining accounts,
+// after the wsol accounts, and rejects the fill when its spot_price deviates from the oracle
+// price by more than max_oracle_deviation_bp of the oracle price. Returns the remaining
+// accounts before the oracle account.
+pub fn check_oracle_deviation<'a, 'info>(
+    pool: &Pool,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<&'a [AccountInfo<'info>]> {
+    if pool.oracle == Pubkey::default() {
+        return Ok(remaining_accounts);
+    }
+    let [remaining_accounts @ .., oracle] = remaining_accounts else {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    };
+    if oracle.key() != pool.oracle {
+        return Err(MMMErrorCode::InvalidOracle.into());
+    }
+    let oracle_price = match oracle.try_borrow_data()?.get(..ORACLE_PRICE_LEN) {
+        Some(price) => u64::from_le_bytes(price.try_into().unwrap()),
+        None => return Err(MMMErrorCode::InvalidOracle.into()),
+    };
+    if oracle_price == 0 {
+        return Err(MMMErrorCode::InvalidOracle.into());
+    }
+
+    let deviation = (pool.spot_price.abs_diff(oracle_price) as u128)
+        .checked_mul(10000)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    if deviation > (pool.max_oracle_deviation_bp as u128) * (oracle_price as u128) {
+        msg!(
+            "OraclePriceDeviation: spot price |{}| oracle price |{}|",
+            pool.spot_price,
+            oracle_price
+        );
+        return Err(MMMErrorCode::OraclePriceDeviation.into());
+    }
+    Ok(remaining_accounts)
+}
+
+// a settle_in_wsol pool takes the wsol accounts off the end of the remaining accounts,
+// the payer_wsol_account has to be the payer's wsol ata so that it can be reopened
+pub fn split_wsol_accounts<'a, 'info>(
+    pool: &Pool,
+    payer: &Pubkey,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<(&'a [AccountInfo<'info>], Option<WsolAccounts<'a, 'info>>)> {
+    if !pool.settle_in_wsol {
+        return Ok((remaining_accounts, None));
+    }
+    let [remaining_accounts @ .., payer_wsol_account, wsol_mint, token_program] =
+        remaining_accounts
+    else {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    };
+    if wsol_mint.key() != spl_token::native_mint::ID
+        || token_program.key() != spl_token::ID
+        || payer_wsol_account.key()
+            != get_associated_token_address_with_program_id(
+                payer,
+                &spl_token::native_mint::ID,
+                &spl_token::ID,
+            )
+    {
+        return Err(MMMErrorCode::InvalidRemainingAccounts.into());
+    }
+    Ok((
+        remaining_accounts,
+        Some(WsolAccounts {
+            payer_wsol_account,
+            wsol_mint,
+            token_program,
+        }),
+    ))
+}
+
+impl<'a, 'info> WsolAccounts<'a, 'info> {
+    // closes the payer_wsol_account to the payer, so that the fill can be paid in lamports,
+    // and returns the unwrapped amount. what the fill doesn't use is wrapped back with wrap().
+    pub fn unwrap(&self, payer: &AccountInfo<'info>) -> Result<u64> {
+        if self.payer_wsol_account.data_is_empty() {
+            return Ok(0);
+        }
+        let amount =
+            TokenAccount::try_deserialize(&mut &self.payer_wsol_account.data.borrow()[..])?.amount;
+        anchor_spl::token::close_account(CpiContext::new(
+            self.token_program.clone(),
+            anchor_spl::token::CloseAccount {
+                account: self.payer_wsol_account.clone(),
+                destination: payer.clone(),
+                authority: payer.clone(),
+            },
+        ))?;
+        Ok(amount)
+    }
+
+    // moves amount lamports from `from` into the payer_wsol_account and syncs it,
+    // the ata is (re)opened by the payer when needed
+    #[allow(clippy::too_many_arguments)]
+    pub fn wrap(
+        &self,
+        amount: u64,
+        from: &AccountInfo<'info>,
+        from_seeds: &[&[&[u8]]],
+        payer: &AccountInfo<'info>,
+        associated_token_program: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        rent: &AccountInfo<'info>,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        init_if_needed_ata(
+            self.payer_wsol_account.clone(),
+            payer.clone(),
+            payer.clone(),
+            self.wsol_mint.clone(),
+            associated_token_program.clone(),
+            self.token_program.clone(),
+            system_program.clone(),
+            rent.clone(),
+        )?;
+        invoke_signed(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                from.key,
+                self.payer_wsol_account.key,
+                amount,
+            ),
+            &[
+                from.clone(),
+                self.payer_wsol_account.clone(),
+                system_program.clone(),
+            ],
+            from_seeds,
+        )?;
+        anchor_spl::token::sync_native(CpiContext::new(
+            self.token_program.clone(),
+            anchor_spl::token::SyncNative {
+                account: self.payer_wsol_account.clone(),
+            },
+        ))
+    }
+}
+
+// a fulfill_buy adds its total_price to buyside_spent, and fails when that goes over
+// max_buyside_spend, so that an over funded escrow doesn't deploy more than the cap
+pub fn add_buyside_spent(pool: &mut Pool, total_price: u64) -> Result<()> {
+    let buyside_spent = pool
+        .buyside_spent
+        .checked_add(total_price)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    if pool.max_buyside_spend != 0 && buyside_spent > pool.max_buyside_spend {
+        return Err(MMMErrorCode::BuysideSpendExceeded.into());
+    }
+    pool.buyside_spent = buyside_spent;
+    Ok(())
+}
+
+// every fill adds its total_price to total_volume_sol and counts once in fill_count,
+// a batch fill counts once as well
+pub fn add_pool_volume(pool: &mut Pool, total_price: u64) -> Result<()> {
+    pool.total_volume_sol = pool
+        .total_volume_sol
+        .checked_add(u128::from(total_price))
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    pool.fill_count = pool
+        .fill_count
+        .checked_add(1)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    Ok(())
+}
+
 pub fn try_close_pool<'info>(pool: &Account<'info, Pool>, owner: AccountInfo<'info>) -> Result<()> {
     if pool.sellside_asset_amount != 0 {
         return Ok(());
@@ -362,14 +1719,26 @@ pub fn try_close_pool<'info>(pool: &Account<'info, Pool>, owner: AccountInfo<'in
         return Ok(());
     }
 
+    let curr_lamports = pool.to_account_info().lamports();
+    log_account_closed(
+        "pool_closed",
+        &AccountClosed {
+            pool: pool.key(),
+            owner: pool.owner,
+            lamports: curr_lamports,
+        },
+    )?;
+
     pool.to_account_info()
         .data
         .borrow_mut()
         .copy_from_slice(&[0; Pool::LEN]);
 
-    let curr_lamports = pool.to_account_info().lamports();
     **pool.to_account_info().lamports.borrow_mut() = 0;
-    **owner.lamports.borrow_mut() = owner.lamports().checked_add(curr_lamports).unwrap();
+    **owner.lamports.borrow_mut() = owner
+        .lamports()
+        .checked_add(curr_lamports)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
     Ok(())
 }
 
@@ -381,73 +1750,308 @@ pub fn try_close_escrow<'info>(
 ) -> Result<()> {
     // minimum rent needed to sustain a 0 data account
     let min_rent = Rent::get()?.minimum_balance(0);
-    // if the balance is less than a small percentage of the spot price, then close the escrow
+    // if the balance is less than a small percentage of the next buy price, then close the
+    // escrow. fulfill handlers update the curve before calling this, so the price is the one
+    // the next fulfill_buy would pay, with static_spot and the price floor applied. a curve
+    // that can't price another fill falls back to the spot price
     let min_escrow_balance: u64 = if pool.reinvest_fulfill_sell && pool.sellside_asset_amount > 0 {
         // pool balance can increase, so we just use min_rent as default amount
         min_rent
     } else {
+        let next_buy_price = get_sol_total_price_and_next_price(pool, 1, true)
+            .map(|(price, _)| price)
+            .unwrap_or(pool.spot_price);
         // pool balance cannot increase without manual deposit, so we calculate the actual value
-        (u128::from(pool.spot_price))
-            .checked_mul(u128::from(MIN_SOL_ESCROW_BALANCE_BP))
+        (u128::from(next_buy_price))
+            .checked_mul(u128::from(pool.min_escrow_balance_bp()))
             .and_then(|v| v.checked_div(10000))
             .and_then(|v| u64::try_from(v).ok())
             .ok_or(MMMErrorCode::NumericOverflow)?
     };
+    // the boundary is inclusive: a balance at or below the threshold, which is never below
+    // min_rent, is fully closed by moving every lamport out, so the escrow is never left
+    // partially swept below rent-exemption, and a later deposit re-creates it
     let escrow_lamports = escrow.lamports();
     if escrow_lamports == 0 || escrow_lamports > std::cmp::max(min_rent, min_escrow_balance) {
         Ok(())
     } else {
-        anchor_lang::solana_program::program::invoke_signed(
-            &anchor_lang::solana_program::system_instruction::transfer(
-                escrow.key,
-                &pool.key(),
-                escrow_lamports,
-            ),
-            &[
-                escrow.clone(),
-                pool.to_account_info(),
-                system_program.to_account_info(),
-            ],
-            escrow_seeds,
-        )?;
-        Ok(())
+        close_escrow(escrow, pool, system_program, escrow_seeds)
+    }
+}
+
+// close_escrow moves all the lamports of the escrow to the pool, regardless of the balance,
+// and they are returned to the owner once the pool is closed
+pub fn close_escrow<'info>(
+    escrow: &AccountInfo<'info>,
+    pool: &Account<'info, Pool>,
+    system_program: &Program<'info, System>,
+    escrow_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let escrow_lamports = escrow.lamports();
+    if escrow_lamports == 0 {
+        return Ok(());
+    }
+    log_account_closed(
+        "escrow_closed",
+        &AccountClosed {
+            pool: pool.key(),
+            owner: pool.owner,
+            lamports: escrow_lamports,
+        },
+    )?;
+    anchor_lang::solana_program::program::invoke_signed(
+        &anchor_lang::solana_program::system_instruction::transfer(
+            escrow.key,
+            &pool.key(),
+            escrow_lamports,
+        ),
+        &[
+            escrow.clone(),
+            pool.to_account_info(),
+            system_program.to_account_info(),
+        ],
+        escrow_seeds,
+    )?;
+    Ok(())
+}
+
+// the owner funded the deposit that created the sell state, so it gets the rent back unless
+// the pool routes it to the taker of the fill that emptied the sell state
+pub fn get_sell_state_rent_recipient<'info>(
+    pool: &Pool,
+    owner: AccountInfo<'info>,
+    taker: AccountInfo<'info>,
+) -> AccountInfo<'info> {
+    if pool.sell_state_rent_to_taker {
+        taker
+    } else {
+        owner
+    }
+}
+
+pub fn try_close_sell_state<'info>(
+    sell_state: &Account<'info, SellState>,
+    owner: AccountInfo<'info>,
+) -> Result<()> {
+    if sell_state.asset_amount != 0 {
+        return Ok(());
+    }
+
+    let curr_lamports = sell_state.to_account_info().lamports();
+    log_account_closed(
+        "sell_state_closed",
+        &AccountClosed {
+            pool: sell_state.pool,
+            owner: sell_state.pool_owner,
+            lamports: curr_lamports,
+        },
+    )?;
+
+    sell_state
+        .to_account_info()
+        .data
+        .borrow_mut()
+        .copy_from_slice(&[0; SellState::LEN]);
+
+    **sell_state.to_account_info().lamports.borrow_mut() = 0;
+    **owner.lamports.borrow_mut() = owner
+        .lamports()
+        .checked_add(curr_lamports)
+        .ok_or(MMMErrorCode::NumericOverflow)?;
+    Ok(())
+}
+
+// a mint without creators has no one to pay a royalty to, so its royalty bp is 0 and the
+// royalty isn't held back from what the seller receives on a fulfill buy
+pub fn get_metadata_royalty_bp(
+    total_price: u64,
+    parsed_metadata: &Metadata,
+    policy: Option<&Account<'_, Policy>>,
+) -> u16 {
+    match &parsed_metadata.creators {
+        Some(creators) if !creators.is_empty() => {}
+        _ => return 0,
+    }
+    match policy {
+        None => parsed_metadata.seller_fee_basis_points,
+        Some(p) => match &p.dynamic_royalty {
+            None => parsed_metadata.seller_fee_basis_points,
+            Some(dynamic_royalty) => {
+                dynamic_royalty.get_royalty_bp(total_price, parsed_metadata.seller_fee_basis_points)
+            }
+        },
     }
 }
 
-pub fn try_close_sell_state<'info>(
-    sell_state: &Account<'info, SellState>,
-    owner: AccountInfo<'info>,
-) -> Result<()> {
-    if sell_state.asset_amount != 0 {
-        return Ok(());
+// the royalty bp a fulfill sell pays the creators, the taker can override the royalty bp of
+// the mint to tip the creators, but never to pay less than it
+pub fn get_taker_royalty_bp(
+    metadata_royalty_bp: u16,
+    royalty_bp_override: Option<u16>,
+) -> Result<u16> {
+    match royalty_bp_override {
+        None => Ok(metadata_royalty_bp),
+        Some(royalty_bp) if royalty_bp > 10000 => Err(MMMErrorCode::InvalidBP.into()),
+        Some(royalty_bp) if royalty_bp < metadata_royalty_bp => {
+            msg!(
+                "royalty bp override {} is below the royalty bp {}",
+                royalty_bp,
+                metadata_royalty_bp
+            );
+            Err(MMMErrorCode::InvalidRoyaltyBpOverride.into())
+        }
+        Some(royalty_bp) => Ok(royalty_bp),
+    }
+}
+
+// returns the amount paid, which is 0 if the recipient would not be rent exempt afterwards
+fn pay_royalty_in_sol<'info>(
+    amount: u64,
+    recipient: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    payer_seeds: &[&[&[u8]]],
+    system_program: &AccountInfo<'info>,
+    min_rent: u64,
+) -> Result<u64> {
+    if amount == 0
+        || recipient
+            .lamports()
+            .checked_add(amount)
+            .ok_or(MMMErrorCode::NumericOverflow)?
+            <= min_rent
+    {
+        return Ok(0);
+    }
+    anchor_lang::solana_program::program::invoke_signed(
+        &anchor_lang::solana_program::system_instruction::transfer(
+            payer.key,
+            recipient.key,
+            amount,
+        ),
+        &[
+            payer.to_account_info(),
+            recipient.to_account_info(),
+            system_program.to_account_info(),
+        ],
+        payer_seeds,
+    )?;
+    Ok(amount)
+}
+
+// royalty = total_price * (royalty_bp / 10000) * (buyside_creator_royalty_bp / 10000)
+fn get_royalty_amount(
+    total_price: u64,
+    metadata_royalty_bp: u16,
+    buyside_creator_royalty_bp: u16,
+) -> Result<u64> {
+    Ok(((total_price as u128)
+        .checked_mul(metadata_royalty_bp as u128)
+        .ok_or(MMMErrorCode::NumericOverflow)?
+        .checked_div(10000)
+        .ok_or(MMMErrorCode::NumericOverflow)?
+        .checked_mul(buyside_creator_royalty_bp as u128)
+        .ok_or(MMMErrorCode::NumericOverflow)?
+        .checked_div(10000)
+        .ok_or(MMMErrorCode::NumericOverflow)?) as u64)
+}
+
+// splits the royalty between the extra recipients and the metadata creators and calls pay
+// with each share, the expected recipient address and the next recipient account.
+// pay returns the amount actually paid, the last creator gets whatever the others did not.
+fn pay_royalty_shares<'info>(
+    royalty: u64,
+    creators: &[Creator],
+    recipient_accounts: &[AccountInfo<'info>],
+    extra_royalty_recipients: &[RoyaltyRecipient],
+    mut pay: impl FnMut(u64, &Pubkey, &AccountInfo<'info>) -> Result<u64>,
+) -> Result<u64> {
+    // bound the transfer CPIs before paying anyone, so that a fill can't run out of compute halfway
+    if creators.len() > MAX_ROYALTY_CREATORS {
+        return Err(MMMErrorCode::TooManyRoyaltyCreators.into());
+    }
+    // the last creator takes the remainder of the royalty, so malformed shares would
+    // silently shift the royalty onto it
+    if creators.iter().map(|c| c.share as u16).sum::<u16>() != 100 {
+        return Err(MMMErrorCode::InvalidCreatorShares.into());
     }
 
-    sell_state
-        .to_account_info()
-        .data
-        .borrow_mut()
-        .copy_from_slice(&[0; SellState::LEN]);
+    // extra recipients take their bp slice of the royalty, and the metadata creators split the rest
+    let mut extra_royalties: Vec<u64> = Vec::with_capacity(extra_royalty_recipients.len());
+    for recipient in extra_royalty_recipients.iter().filter(|r| !r.is_empty()) {
+        extra_royalties.push(
+            (royalty as u128)
+                .checked_mul(recipient.bp as u128)
+                .ok_or(MMMErrorCode::NumericOverflow)?
+                .checked_div(10000)
+                .ok_or(MMMErrorCode::NumericOverflow)? as u64,
+        );
+    }
+    let creators_royalty = royalty
+        .checked_sub(extra_royalties.iter().sum())
+        .ok_or(MMMErrorCode::NumericOverflow)?;
 
-    let curr_lamports = sell_state.to_account_info().lamports();
-    **sell_state.to_account_info().lamports.borrow_mut() = 0;
-    **owner.lamports.borrow_mut() = owner.lamports().checked_add(curr_lamports).unwrap();
-    Ok(())
-}
+    // the shares are split before paying anyone: the floored shares of the creators before
+    // the last one sum to at most creators_royalty since the shares sum to 100, and the last
+    // creator takes the remainder, so the shares sum to exactly creators_royalty
+    let mut creator_shares: Vec<u64> = Vec::with_capacity(creators.len());
+    for creator in &creators[..creators.len() - 1] {
+        creator_shares.push(
+            (creators_royalty as u128)
+                .checked_mul(creator.share as u128)
+                .ok_or(MMMErrorCode::NumericOverflow)?
+                .checked_div(100)
+                .ok_or(MMMErrorCode::NumericOverflow)? as u64,
+        );
+    }
+    creator_shares.push(
+        creators_royalty
+            .checked_sub(creator_shares.iter().sum())
+            .ok_or(MMMErrorCode::NumericOverflow)?,
+    );
 
-pub fn get_metadata_royalty_bp(
-    total_price: u64,
-    parsed_metadata: &Metadata,
-    policy: Option<&Account<'_, Policy>>,
-) -> u16 {
-    match policy {
-        None => parsed_metadata.seller_fee_basis_points,
-        Some(p) => match &p.dynamic_royalty {
-            None => parsed_metadata.seller_fee_basis_points,
-            Some(dynamic_royalty) => {
-                dynamic_royalty.get_royalty_bp(total_price, parsed_metadata.seller_fee_basis_points)
-            }
-        },
+    let recipient_accounts_iter = &mut recipient_accounts.iter();
+    let mut creators_paid: u64 = 0;
+    // a creator can be skipped by pay (e.g. a sol payment that would leave it below rent),
+    // its share is then rolled into the next creator, and whatever the trailing creators
+    // leave over goes to the last creator that was paid. Every share is paid at most once,
+    // either to its creator or as dust, so creators_paid never exceeds creators_royalty
+    let mut dust: u64 = 0;
+    let mut last_paid_creator: Option<(&Pubkey, &AccountInfo<'info>)> = None;
+    for (creator, creator_share) in creators.iter().zip(creator_shares) {
+        let creator_fee = creator_share
+            .checked_add(dust)
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+        let current_creator_info = next_account_info(recipient_accounts_iter)?;
+        let paid = pay(creator_fee, &creator.address, current_creator_info)?;
+        if paid == 0 {
+            dust = creator_fee;
+        } else {
+            dust = 0;
+            last_paid_creator = Some((&creator.address, current_creator_info));
+        }
+        creators_paid = creators_paid
+            .checked_add(paid)
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+    }
+    if let Some((address, creator_info)) = last_paid_creator.filter(|_| dust > 0) {
+        creators_paid = creators_paid
+            .checked_add(pay(dust, address, creator_info)?)
+            .ok_or(MMMErrorCode::NumericOverflow)?;
+    }
+    let mut total_royalty = creators_paid;
+
+    // extra recipient accounts follow the creator accounts
+    for (recipient, extra_royalty) in extra_royalty_recipients
+        .iter()
+        .filter(|r| !r.is_empty())
+        .zip(extra_royalties)
+    {
+        let recipient_info = next_account_info(recipient_accounts_iter)?;
+        total_royalty = total_royalty
+            .checked_add(pay(extra_royalty, &recipient.address, recipient_info)?)
+            .ok_or(MMMErrorCode::NumericOverflow)?;
     }
+    Ok(total_royalty)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -460,86 +2064,198 @@ pub fn pay_creator_fees_in_sol<'info>(
     metadata_royalty_bp: u16,
     payer_seeds: &[&[&[u8]]],
     system_program: AccountInfo<'info>,
+    extra_royalty_recipients: &[RoyaltyRecipient],
+    royalty_fallback_account: Option<&Pubkey>,
+    require_all_creators_paid: bool,
 ) -> Result<u64> {
     // total royalty paid by the buyer, it's one of the following
     //   - buyside_sol_escrow_account (when fulfill buy)
     //   - payer                      (when fulfill sell)
     // returns the total royalty paid
-    //   royalty = spot_price * (royalty_bp / 10000) * (buyside_creator_royalty_bp / 10000)
-    let royalty = ((total_price as u128)
-        .checked_mul(metadata_royalty_bp as u128)
-        .ok_or(MMMErrorCode::NumericOverflow)?
-        .checked_div(10000)
-        .ok_or(MMMErrorCode::NumericOverflow)?
-        .checked_mul(buyside_creator_royalty_bp as u128)
-        .ok_or(MMMErrorCode::NumericOverflow)?
-        .checked_div(10000)
-        .ok_or(MMMErrorCode::NumericOverflow)?) as u64;
+    let royalty = get_royalty_amount(total_price, metadata_royalty_bp, buyside_creator_royalty_bp)?;
 
     if royalty == 0 {
         return Ok(0);
     }
 
-    let creators = if let Some(creators) = &parsed_metadata.creators {
-        creators
-    } else {
-        return Ok(0);
+    // a mint without creators has no one to pay, even when it has a seller_fee_basis_points,
+    // and the extra recipients only share the royalty of the creators
+    let creators = match &parsed_metadata.creators {
+        Some(creators) if !creators.is_empty() => creators,
+        _ => return Ok(0),
     };
 
+    pay_creator_shares_in_sol(
+        royalty,
+        parsed_metadata.seller_fee_basis_points,
+        creators,
+        creator_accounts,
+        payer,
+        payer_seeds,
+        system_program,
+        extra_royalty_recipients,
+        royalty_fallback_account,
+        require_all_creators_paid,
+    )
+}
+
+// same as pay_creator_fees_in_sol, but for a token-2022 mint whose royalty bp and creators
+// were read from its TokenMetadata by get_metadata_royalty_bp_ext. the royalty is priced at
+// royalty_bp, which is the metadata_royalty_bp unless the taker overrode it
+#[allow(clippy::too_many_arguments)]
+pub fn pay_creator_fees_in_sol_ext<'info>(
+    buyside_creator_royalty_bp: u16,
+    total_price: u64,
+    creators: &[Creator],
+    creator_accounts: &[AccountInfo<'info>],
+    payer: AccountInfo<'info>,
+    metadata_royalty_bp: u16,
+    royalty_bp: u16,
+    payer_seeds: &[&[&[u8]]],
+    system_program: AccountInfo<'info>,
+    extra_royalty_recipients: &[RoyaltyRecipient],
+    royalty_fallback_account: Option<&Pubkey>,
+    require_all_creators_paid: bool,
+) -> Result<u64> {
+    let royalty = get_royalty_amount(total_price, royalty_bp, buyside_creator_royalty_bp)?;
+
+    if royalty == 0 || creators.is_empty() {
+        return Ok(0);
+    }
+
+    pay_creator_shares_in_sol(
+        royalty,
+        metadata_royalty_bp,
+        creators,
+        creator_accounts,
+        payer,
+        payer_seeds,
+        system_program,
+        extra_royalty_recipients,
+        royalty_fallback_account,
+        require_all_creators_paid,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn pay_creator_shares_in_sol<'info>(
+    royalty: u64,
+    seller_fee_basis_points: u16,
+    creators: &[Creator],
+    creator_accounts: &[AccountInfo<'info>],
+    payer: AccountInfo<'info>,
+    payer_seeds: &[&[&[u8]]],
+    system_program: AccountInfo<'info>,
+    extra_royalty_recipients: &[RoyaltyRecipient],
+    royalty_fallback_account: Option<&Pubkey>,
+    require_all_creators_paid: bool,
+) -> Result<u64> {
     if payer.lamports() < royalty {
         return Err(MMMErrorCode::NotEnoughBalance.into());
     }
 
     // hardcoded the max threshold for InvalidMetadataCreatorRoyalty
-    if parsed_metadata.seller_fee_basis_points > MAX_METADATA_CREATOR_ROYALTY_BP {
+    if seller_fee_basis_points > MAX_METADATA_CREATOR_ROYALTY_BP {
         return Err(MMMErrorCode::InvalidMetadataCreatorRoyalty.into());
     }
     let min_rent = Rent::get()?.minimum_balance(0);
-    let mut total_royalty: u64 = 0;
-
-    let creator_accounts_iter = &mut creator_accounts.iter();
-    for (index, creator) in creators.iter().enumerate() {
-        let creator_fee = if index == creators.len() - 1 {
-            royalty
-                .checked_sub(total_royalty)
-                .ok_or(MMMErrorCode::NumericOverflow)?
-        } else {
-            (royalty as u128)
-                .checked_mul(creator.share as u128)
-                .ok_or(MMMErrorCode::NumericOverflow)?
-                .checked_div(100)
-                .ok_or(MMMErrorCode::NumericOverflow)? as u64
-        };
-        let current_creator_info = next_account_info(creator_accounts_iter)?;
-        if creator.address.ne(current_creator_info.key) {
-            return Err(MMMErrorCode::InvalidCreatorAddress.into());
-        }
-        let current_creator_lamports = current_creator_info.lamports();
-        if creator_fee > 0
-            && current_creator_lamports
-                .checked_add(creator_fee)
-                .ok_or(MMMErrorCode::NumericOverflow)?
-                > min_rent
-        {
-            anchor_lang::solana_program::program::invoke_signed(
-                &anchor_lang::solana_program::system_instruction::transfer(
-                    payer.key,
-                    current_creator_info.key,
-                    creator_fee,
-                ),
-                &[
-                    payer.to_account_info(),
-                    current_creator_info.to_account_info(),
-                    system_program.to_account_info(),
-                ],
+    // the fallback account is looked up by its key, the taker passes it after the royalty accounts
+    let royalty_fallback_info = royalty_fallback_account
+        .and_then(|fallback| creator_accounts.iter().find(|info| info.key == fallback));
+
+    pay_royalty_shares(
+        royalty,
+        creators,
+        creator_accounts,
+        extra_royalty_recipients,
+        |amount, address, recipient| {
+            if address.ne(recipient.key) {
+                return Err(MMMErrorCode::InvalidCreatorAddress.into());
+            }
+            let mut paid = pay_royalty_in_sol(
+                amount,
+                recipient,
+                &payer,
                 payer_seeds,
+                &system_program,
+                min_rent,
             )?;
-            total_royalty = total_royalty
-                .checked_add(creator_fee)
-                .ok_or(MMMErrorCode::NumericOverflow)?;
-        }
-    }
-    Ok(total_royalty)
+            // only a share that would leave its recipient below rent goes to the fallback account
+            if let Some(fallback_info) = royalty_fallback_info.filter(|_| amount > 0 && paid == 0) {
+                paid = pay_royalty_in_sol(
+                    amount,
+                    fallback_info,
+                    &payer,
+                    payer_seeds,
+                    &system_program,
+                    min_rent,
+                )?;
+            }
+            // a skipped share would otherwise be rolled into another recipient as dust
+            if require_all_creators_paid && amount > 0 && paid == 0 {
+                return Err(MMMErrorCode::CreatorNotPaid.into());
+            }
+            Ok(paid)
+        },
+    )
+}
+
+// FillSummary is logged at the end of every fulfill, so that indexers can
+// reconstruct the fee breakdown without diffing pool snapshots
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FillSummary {
+    pub asset_mint: Pubkey,
+    pub asset_amount: u64, // the filled amount, it can be less than requested on a partial fill
+    pub total_price: u64,
+    pub lp_fee: u64,
+    pub royalty_paid: u64,
+    pub maker_fee: i64,
+    pub taker_fee: i64,
+    pub spot_price_before: u64,
+    pub spot_price_after: u64, // the spot_price the fill left the pool at
+}
+
+pub fn log_fill_summary(summary: &FillSummary) -> Result<()> {
+    msg!("fill_summary");
+    sol_log_data(&[&summary.try_to_vec()?]);
+    Ok(())
+}
+
+// AccountClosed is logged right before a pool, escrow or sell state is closed
+// automatically, so that indexers can tell it apart from a manual close
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccountClosed {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+}
+
+pub fn log_account_closed(prefix: &str, closed: &AccountClosed) -> Result<()> {
+    msg!(prefix);
+    sol_log_data(&[&closed.try_to_vec()?]);
+    Ok(())
+}
+
+// PoolEvent is logged as "pool_created" by create_pool and as "pool_updated" by update_pool.
+// The action tag tells them apart without the log prefix, and the full pool state follows
+// in the same log data, serialized like the pool account, so indexers don't have to
+// infer the action from a pool snapshot.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolEvent {
+    pub action: u8, // see POOL_EVENT_ACTION_*
+    pub pool: Pubkey,
+}
+
+pub fn log_pool_event(prefix: &str, action: u8, pool: &Account<'_, Pool>) -> Result<()> {
+    let event = PoolEvent {
+        action,
+        pool: pool.key(),
+    };
+    let mut state = Vec::with_capacity(Pool::LEN);
+    pool.try_serialize(&mut state)?;
+    msg!(prefix);
+    sol_log_data(&[&event.try_to_vec()?, &state]);
+    Ok(())
 }
 
 pub fn log_pool(prefix: &str, pool: &Pool) -> Result<()> {
@@ -556,6 +2272,60 @@ pub fn assert_is_programmable(parsed_metadata: &Metadata) -> Result<()> {
     }
 }
 
+// a taker with a TakerFeeExemption for the pool passes it as the very last of the remaining
+// accounts of a fulfill sell, after the oracle account. It is only taken off when it is the
+// exemption pda of the pool and the taker, so the other remaining accounts are unchanged without
+// one. Returns the remaining accounts before it and whether the taker is exempt.
+pub fn split_taker_fee_exemption<'a, 'info>(
+    pool: &Pubkey,
+    taker: &Pubkey,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> (&'a [AccountInfo<'info>], bool) {
+    let [rest @ .., exemption] = remaining_accounts else {
+        return (remaining_accounts, false);
+    };
+    let (expected, _) = Pubkey::find_program_address(
+        &[
+            TAKER_FEE_EXEMPTION_PREFIX.as_bytes(),
+            pool.as_ref(),
+            taker.as_ref(),
+        ],
+        &crate::ID,
+    );
+    // a closed exemption keeps its address, the owner and the data tell that it still exists
+    if *exemption.key != expected || *exemption.owner != crate::ID || exemption.data_is_empty() {
+        return (remaining_accounts, false);
+    }
+    (rest, true)
+}
+
+// an exempt taker pays no taker fee, and the maker fee is clamped to 0 so that a maker rebate
+// can't be paid out of the waived taker fee. Returns the maker and taker fee bps to charge.
+pub fn get_fees_bp(maker_fee_bp: i16, taker_fee_bp: i16, taker_fee_exempt: bool) -> (i16, i16) {
+    if taker_fee_exempt {
+        return (maker_fee_bp.max(0), 0);
+    }
+    (maker_fee_bp, taker_fee_bp)
+}
+
+// the vanilla and the ext handlers take either token program, so the mint needs to be owned
+// by the spl token or the token 2022 program, and the token program of the instruction needs
+// to be that same program. a mismatch then fails here instead of in a transfer cpi
+pub fn assert_token_program_matches_mint(mint: &AccountInfo, token_program: &Pubkey) -> Result<()> {
+    if (*mint.owner != spl_token::ID && *mint.owner != spl_token_2022::ID)
+        || mint.owner != token_program
+    {
+        msg!(
+            "mint |{}| is owned by |{}|, not the token program |{}|",
+            mint.key,
+            mint.owner,
+            token_program
+        );
+        return Err(MMMErrorCode::InvalidTokenProgram.into());
+    }
+    Ok(())
+}
+
 pub fn assert_valid_fees_bp(maker_fee_bp: i16, taker_fee_bp: i16) -> Result<()> {
     let bound = MAX_REFERRAL_FEE_BP;
     if !(0..=bound).contains(&taker_fee_bp) {
@@ -567,7 +2337,10 @@ pub fn assert_valid_fees_bp(maker_fee_bp: i16, taker_fee_bp: i16) -> Result<()>
     }
 
     let sum = maker_fee_bp + taker_fee_bp;
-    if !(0..=bound).contains(&sum) {
+    if sum < 0 {
+        return Err(MMMErrorCode::MakerRebateExceedsTakerFee.into());
+    }
+    if sum > bound {
         return Err(MMMErrorCode::InvalidMakerOrTakerFeeBP.into());
     }
 
@@ -585,6 +2358,12 @@ pub fn withdraw_m2<'info>(
     wallet: Pubkey,
     amount: u64,
 ) -> Result<()> {
+    // the pool is only entitled to what the shared escrow holds, so a caller asking for more
+    // is rejected here instead of relying on M2 to catch it
+    if amount > m2_buyer_escrow.lamports() {
+        return Err(MMMErrorCode::InsufficientSharedEscrowBalance.into());
+    }
+
     let pool_seeds: &[&[&[u8]]] = &[&[
         POOL_PREFIX.as_bytes(),
         pool.owner.as_ref(),
@@ -622,6 +2401,25 @@ pub fn withdraw_m2<'info>(
         pool_seeds,
     )?;
 
+    log_shared_escrow_withdraw(&SharedEscrowWithdraw {
+        pool: pool.key(),
+        destination: to.key(),
+        amount,
+    })
+}
+
+// SharedEscrowWithdraw is logged after every withdraw from the M2 shared escrow, so that
+// indexers can follow the shared escrow balance without diffing M2 accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SharedEscrowWithdraw {
+    pub pool: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+pub fn log_shared_escrow_withdraw(withdraw: &SharedEscrowWithdraw) -> Result<()> {
+    msg!("shared_escrow_withdraw");
+    sol_log_data(&[&withdraw.try_to_vec()?]);
     Ok(())
 }
 
@@ -657,11 +2455,56 @@ pub fn check_remaining_accounts_for_m2(
     Ok(())
 }
 
+// the token-2022 counterpart of get_metadata_royalty_bp, it returns the royalty bp and the
+// creators in the royalty config of the additional_metadata. a missing or unparsable royalty
+// bp is 0, and creator entries that don't parse are skipped like their royalty would be
+pub fn get_metadata_royalty_bp_ext(metadata: &TokenMetadata) -> (u16, Vec<Creator>) {
+    let royalty_bp = metadata
+        .additional_metadata
+        .iter()
+        .find(|(k, _)| k == TOKEN_METADATA_ROYALTY_BP_KEY)
+        .and_then(|(_, v)| v.trim().parse::<u16>().ok())
+        .unwrap_or(0);
+    let creators = metadata
+        .additional_metadata
+        .iter()
+        .filter_map(|(k, v)| {
+            Some(Creator {
+                address: Pubkey::from_str(k.strip_prefix(TOKEN_METADATA_CREATOR_KEY_PREFIX)?)
+                    .ok()?,
+                verified: false,
+                share: v.trim().parse::<u8>().ok()?,
+            })
+        })
+        .collect();
+    (royalty_bp, creators)
+}
+
+// the number of royalty recipient accounts of an ext fill, they come before the transfer hook
+// accounts. the extra recipients follow the creators and are only paid alongside them
+pub fn get_ext_royalty_accounts_len(
+    creators: &[Creator],
+    extra_royalty_recipients: &[RoyaltyRecipient],
+) -> usize {
+    if creators.is_empty() {
+        return 0;
+    }
+    creators.len()
+        + extra_royalty_recipients
+            .iter()
+            .filter(|r| !r.is_empty())
+            .count()
+}
+
+// returns the parsed metadata, and the transfer fee that the destination account
+// will have withheld when transfer_amount is transferred
 pub fn check_allowlists_for_mint_ext(
     allowlists: &[Allowlist],
+    allowlist_match_mode: u8,
     token_mint: &AccountInfo,
     allowlist_aux: Option<String>,
-) -> Result<TokenMetadata> {
+    transfer_amount: u64,
+) -> Result<(TokenMetadata, u64)> {
     if token_mint.owner != &spl_token_2022::ID || token_mint.data_is_empty() {
         return Err(MMMErrorCode::InvalidTokenMint.into());
     }
@@ -680,6 +2523,7 @@ pub fn check_allowlists_for_mint_ext(
     let parsed_metadata = mint_deserialized
         .get_variable_len_extension::<TokenMetadata>()
         .unwrap();
+    let transfer_fee = get_transfer_fee(&mint_deserialized, transfer_amount)?;
 
     if allowlists
         .iter()
@@ -687,15 +2531,7 @@ pub fn check_allowlists_for_mint_ext(
     {
         // If allowlist_aux is not passed in, do not validate URI.
         if let Some(ref aux_key) = allowlist_aux {
-            // Handle URI padding.
-            if !parsed_metadata.uri.trim().starts_with(aux_key) {
-                msg!(
-                    "Failed metadata validation. Expected URI: |{}| but got |{}|",
-                    *aux_key,
-                    parsed_metadata.uri
-                );
-                return Err(MMMErrorCode::UnexpectedMetadataUri.into());
-            }
+            check_metadata_uri(&parsed_metadata.uri, aux_key)?;
         }
     }
 
@@ -707,48 +2543,124 @@ pub fn check_allowlists_for_mint_ext(
         }
     }
 
+    // deny entries are checked before anything else, so that they can't be bypassed
+    check_allowlists_deny_mint(allowlists, token_mint.key)?;
+
+    let intersection = allowlist_match_mode == ALLOWLIST_MATCH_MODE_INTERSECTION;
+    let mut has_entries = false;
     for allowlist_val in allowlists.iter() {
-        match allowlist_val.kind {
-            ALLOWLIST_KIND_EMPTY => {}
+        let matched = match allowlist_val.kind {
+            ALLOWLIST_KIND_EMPTY | ALLOWLIST_KIND_DENY_MINT => continue,
             ALLOWLIST_KIND_ANY => {
-                // any is a special case, we don't need to check anything else
-                return Ok(parsed_metadata);
+                // any is a special case, we don't need to check anything else in union mode
+                if !intersection {
+                    return Ok((parsed_metadata, transfer_fee));
+                }
+                true
             }
             ALLOWLIST_KIND_FVCA => {
+                // additional metadata is written by whoever holds the metadata update authority,
+                // so a creator or collection key stored there can't be verified
                 return Err(MMMErrorCode::InvalidAllowLists.into());
             }
-            ALLOWLIST_KIND_MINT => {
-                if token_mint.key() == allowlist_val.value {
-                    return Ok(parsed_metadata);
-                }
-            }
+            ALLOWLIST_KIND_MINT => token_mint.key() == allowlist_val.value,
             ALLOWLIST_KIND_MCC => {
                 return Err(MMMErrorCode::InvalidAllowLists.into());
             }
+            ALLOWLIST_KIND_UPDATE_AUTHORITY => {
+                Option::<Pubkey>::from(parsed_metadata.update_authority)
+                    == Some(allowlist_val.value)
+            }
             ALLOWLIST_KIND_GROUP => {
                 let group_address = assert_valid_group(&mint_deserialized, token_mint)?;
                 if group_address != Some(allowlist_val.value) {
                     msg!("group address |{}| is not allowed", group_address.unwrap());
                     return Err(MMMErrorCode::InvalidAllowLists.into());
                 }
-                return Ok(parsed_metadata);
+                true
             }
             ALLOWLIST_KIND_METADATA => {
                 // Do not validate URI here, as we already did it above.
                 // Group is validated in a separate function.
-                // These checks are separate since allowlist values are unioned together.
-                continue;
+                // These checks are separate since allowlist values are unioned together,
+                // in intersection mode the URI check above is the match.
+                if !intersection {
+                    continue;
+                }
+                true
             }
             _ => {
                 return Err(MMMErrorCode::InvalidAllowLists.into());
             }
+        };
+        has_entries = true;
+
+        if matched && !intersection {
+            return Ok((parsed_metadata, transfer_fee));
+        }
+        if !matched && intersection {
+            return Err(MMMErrorCode::InvalidAllowLists.into());
         }
     }
 
-    // at the end, we didn't find a match, thus return err
+    // in union mode we didn't find a match, in intersection mode all entries matched
+    if intersection && has_entries {
+        return Ok((parsed_metadata, transfer_fee));
+    }
     Err(MMMErrorCode::InvalidAllowLists.into())
 }
 
+fn get_transfer_fee(
+    mint_deserialized: &StateWithExtensions<'_, Token22Mint>,
+    amount: u64,
+) -> Result<u64> {
+    match mint_deserialized.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => Ok(transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(MMMErrorCode::NumericOverflow)?),
+        Err(_) => Ok(0),
+    }
+}
+
+// token accounts with withheld transfer fees can't be closed until the fees are harvested
+pub fn has_withheld_transfer_fee(token_account: &AccountInfo) -> Result<bool> {
+    let borrowed_data = token_account.data.borrow();
+    let account_deserialized = StateWithExtensions::<Token22Account>::unpack(&borrowed_data)?;
+    match account_deserialized.get_extension::<TransferFeeAmount>() {
+        Ok(transfer_fee_amount) => Ok(u64::from(transfer_fee_amount.withheld_amount) > 0),
+        Err(_) => Ok(false),
+    }
+}
+
+// mints with a transfer hook can only be transferred when the hook program and its
+// extra account metas are passed along, fail early instead of inside the transfer cpi
+pub fn check_transfer_hook_accounts(
+    token_mint: &AccountInfo,
+    additional_accounts: &[AccountInfo],
+) -> Result<()> {
+    let borrowed_data = token_mint.data.borrow();
+    let mint_deserialized = StateWithExtensions::<Token22Mint>::unpack(&borrowed_data)?;
+    let hook_program_id = match transfer_hook::get_program_id(&mint_deserialized) {
+        Some(program_id) => program_id,
+        None => return Ok(()),
+    };
+    let (extra_account_metas, _) = Pubkey::find_program_address(
+        &[
+            TRANSFER_HOOK_EXTRA_ACCOUNT_METAS_PREFIX.as_bytes(),
+            token_mint.key.as_ref(),
+        ],
+        &hook_program_id,
+    );
+    for required in [hook_program_id, extra_account_metas] {
+        if !additional_accounts.iter().any(|acc| *acc.key == required) {
+            msg!("missing transfer hook account |{}|", required);
+            return Err(MMMErrorCode::MissingTransferHookAccounts.into());
+        }
+    }
+
+    Ok(())
+}
+
 pub fn assert_valid_group(
     mint_deserialized: &StateWithExtensions<'_, Token22Mint>,
     token_mint: &AccountInfo,
@@ -764,9 +2676,26 @@ pub fn assert_valid_group(
     Err(MMMErrorCode::InvalidTokenMemberExtension.into())
 }
 
+// the asset amount a fulfill sell actually fills, when partial fills are ok an over-request
+// is clamped to what the pool escrows for the mint instead of failing the fill
+pub fn get_sell_fill_asset_amount(
+    requested_asset_amount: u64,
+    partial_fill_ok: bool,
+    pool: &Pool,
+    sell_state: &SellState,
+) -> u64 {
+    if !partial_fill_ok {
+        return requested_asset_amount;
+    }
+    requested_asset_amount
+        .min(pool.sellside_asset_amount)
+        .min(sell_state.asset_amount)
+}
+
 pub struct PoolPriceInfo<'info> {
     pub total_price: u64,
     pub next_price: u64,
+    pub seller_receives: u64, // the fee base, it's the total_price minus the buyside fees on fulfill buy
     pub lp_fee: u64,
     pub maker_fee: i64,
     pub taker_fee: i64,
@@ -774,6 +2703,53 @@ pub struct PoolPriceInfo<'info> {
     pub transfer_sol_to: AccountInfo<'info>,
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn get_buy_fulfill_pool_price_info<'info>(
+    pool: &Pool,
+    payer: &AccountInfo<'info>,
+    buyside_sol_escrow_account: &AccountInfo<'info>,
+    asset_amount: u64,
+    metadata_royalty_bp: u16,
+    maker_fee_bp: i16,
+    taker_fee_bp: i16,
+) -> Result<PoolPriceInfo<'info>> {
+    let (total_price, next_price) = get_sol_total_price_and_next_price(pool, asset_amount, true)?;
+    // TODO: update lp_fee_bp when shared escrow for both side is enabled
+    let seller_receives = {
+        let lp_fee_bp = get_lp_fee_bp(pool, buyside_sol_escrow_account.lamports(), asset_amount)?;
+        get_buyside_seller_receives(
+            total_price,
+            lp_fee_bp,
+            metadata_royalty_bp,
+            get_enforced_creator_royalty_bp(pool, pool.buyside_creator_royalty_bp),
+        )
+    }?;
+
+    // TODO: update lp_fee when shared escrow for both side is enabled
+    let lp_fee = get_sol_lp_fee(
+        pool,
+        buyside_sol_escrow_account.lamports(),
+        seller_receives,
+        asset_amount,
+    )?;
+
+    assert_valid_fees_bp(maker_fee_bp, taker_fee_bp)?;
+    let maker_fee = get_sol_fee(seller_receives, maker_fee_bp)?;
+    let taker_fee = get_sol_fee(seller_receives, taker_fee_bp)?;
+    let referral_fee = get_total_referral_fee(maker_fee, taker_fee)?;
+
+    Ok(PoolPriceInfo {
+        total_price,
+        next_price,
+        seller_receives,
+        lp_fee,
+        maker_fee,
+        taker_fee,
+        referral_fee,
+        transfer_sol_to: payer.to_account_info(),
+    })
+}
+
 pub fn get_sell_fulfill_pool_price_info<'info>(
     pool: &Pool,
     owner: &UncheckedAccount<'info>,
@@ -781,19 +2757,21 @@ pub fn get_sell_fulfill_pool_price_info<'info>(
     asset_amount: u64,
     maker_fee_bp: i16,
     taker_fee_bp: i16,
+    taker_fee_exempt: bool,
 ) -> Result<PoolPriceInfo<'info>> {
     let (total_price, next_price) = get_sol_total_price_and_next_price(pool, asset_amount, false)?;
-    let lp_fee = get_sol_lp_fee(pool, buyside_sol_escrow_account.lamports(), total_price)?;
+    let lp_fee = get_sol_lp_fee(
+        pool,
+        buyside_sol_escrow_account.lamports(),
+        total_price,
+        asset_amount,
+    )?;
 
+    let (maker_fee_bp, taker_fee_bp) = get_fees_bp(maker_fee_bp, taker_fee_bp, taker_fee_exempt);
     assert_valid_fees_bp(maker_fee_bp, taker_fee_bp)?;
     let maker_fee = get_sol_fee(total_price, maker_fee_bp)?;
     let taker_fee = get_sol_fee(total_price, taker_fee_bp)?;
-    let referral_fee = u64::try_from(
-        maker_fee
-            .checked_add(taker_fee)
-            .ok_or(MMMErrorCode::NumericOverflow)?,
-    )
-    .map_err(|_| MMMErrorCode::NumericOverflow)?;
+    let referral_fee = get_total_referral_fee(maker_fee, taker_fee)?;
 
     let transfer_sol_to = if pool.reinvest_fulfill_sell {
         buyside_sol_escrow_account.to_account_info()
@@ -804,6 +2782,7 @@ pub fn get_sell_fulfill_pool_price_info<'info>(
     Ok(PoolPriceInfo {
         total_price,
         next_price,
+        seller_receives: total_price,
         lp_fee,
         maker_fee,
         taker_fee,
@@ -811,3 +2790,89 @@ pub fn get_sell_fulfill_pool_price_info<'info>(
         transfer_sol_to,
     })
 }
+
+// pays the proceeds of a fulfill sell, the total_price net of the maker fee, from the payer to
+// transfer_sol_to. on a reinvest_fulfill_sell pool, profit_skim_bp of the proceeds goes to the
+// owner as realized profit and only the rest is reinvested into the buyside escrow
+#[allow(clippy::too_many_arguments)]
+pub fn pay_sell_proceeds<'info>(
+    pool: &Pool,
+    payer: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    transfer_sol_to: AccountInfo<'info>,
+    total_price: u64,
+    maker_fee: i64,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let proceeds = u64::try_from(
+        i64::try_from(total_price)
+            .map_err(|_| MMMErrorCode::NumericOverflow)?
+            .checked_sub(maker_fee)
+            .ok_or(MMMErrorCode::NumericOverflow)?,
+    )
+    .map_err(|_| MMMErrorCode::NumericOverflow)?;
+    let profit_skim = if pool.reinvest_fulfill_sell {
+        (proceeds as u128)
+            .checked_mul(pool.profit_skim_bp as u128)
+            .ok_or(MMMErrorCode::NumericOverflow)?
+            .checked_div(10000)
+            .ok_or(MMMErrorCode::NumericOverflow)? as u64
+    } else {
+        0
+    };
+
+    anchor_lang::solana_program::program::invoke(
+        &anchor_lang::solana_program::system_instruction::transfer(
+            payer.key,
+            transfer_sol_to.key,
+            proceeds
+                .checked_sub(profit_skim)
+                .ok_or(MMMErrorCode::NumericOverflow)?,
+        ),
+        &[payer.clone(), transfer_sol_to, system_program.clone()],
+    )?;
+    if profit_skim > 0 {
+        anchor_lang::solana_program::program::invoke(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                payer.key,
+                owner.key,
+                profit_skim,
+            ),
+            &[payer.clone(), owner.clone(), system_program.clone()],
+        )?;
+    }
+    Ok(())
+}
+
+// the largest asset_amount a buyer can take from the pool with budget, including the lp fee
+// and the taker fee. royalties depend on the mint and are not included.
+pub fn max_affordable_items(
+    pool: &Pool,
+    buyside_sol_escrow_balance: u64,
+    budget: u64,
+    maker_fee_bp: i16,
+    taker_fee_bp: i16,
+) -> Result<u64> {
+    assert_valid_fees_bp(maker_fee_bp, taker_fee_bp)?;
+    // a fill that overflows is never affordable
+    let fully_loaded_cost = |n: u64| -> Option<u64> {
+        let (total_price, _) = get_sol_total_price_and_next_price(pool, n, false).ok()?;
+        let lp_fee = get_sol_lp_fee(pool, buyside_sol_escrow_balance, total_price, n).ok()?;
+        let taker_fee = get_sol_fee(total_price, taker_fee_bp).ok()?;
+        total_price
+            .checked_add(lp_fee)?
+            .checked_add(u64::try_from(taker_fee).ok()?)
+    };
+
+    // binary search for the last n within budget, the cost only grows with n
+    let (mut lo, mut hi) = (0, pool.sellside_asset_amount);
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        match fully_loaded_cost(mid) {
+            Some(cost) if cost <= budget => lo = mid,
+            _ => hi = mid - 1,
+        }
+    }
+
+    Ok(lo)
+}
@@ -1,12 +1,18 @@
+import { PublicKey } from '@solana/web3.js';
+
 export const PREFIXES = {
   POOL: 'mmm_pool',
   BUYSIDE_SOL_ESCROW: 'mmm_buyside_sol_escrow_account',
   SELL_STATE: 'mmm_sell_state',
+  TAKER_FEE_EXEMPTION: 'mmm_taker_fee_exemption',
 };
 
 export enum CurveKind {
   linear = 0,
   exp = 1,
+  log = 2,
+  xyk = 3,
+  flat = 4,
 }
 
 export enum AllowlistKind {
@@ -16,5 +22,58 @@ export enum AllowlistKind {
   mcc = 3,
   metadata = 4,
   group = 5,
+  denyMint = 6,
+  maxRoyalty = 7,
+  merkle = 8,
+  updateAuthority = 10,
   any = 255,
 }
+
+// the value of a maxRoyalty allowlist, the bp ceiling is little endian in the low bytes
+export const getMaxRoyaltyAllowlistValue = (bp: number): PublicKey => {
+  const value = Buffer.alloc(32);
+  value.writeUInt16LE(bp);
+  return new PublicKey(value);
+};
+
+export enum AllowlistMatchMode {
+  union = 0,
+  intersection = 1,
+}
+
+// the direction of the linear fulfill_buy steps, only linear curves can be inverted
+export enum CurveDirection {
+  normal = 0,
+  inverted = 1,
+}
+
+// the fulfill side a pool accepts, deposits and withdrawals are allowed on both sides
+export enum PoolSide {
+  twoSided = 0,
+  buyOnly = 1,
+  sellOnly = 2,
+}
+
+// the creator royalty of a vanilla fill, mip1 and ocp fills always pay the full royalty
+export enum RoyaltyEnforcementMode {
+  optional = 0,
+  enforce = 1,
+  bypass = 2,
+}
+
+export enum PoolEventAction {
+  created = 0,
+  updated = 1,
+}
+
+export enum LpFeeRounding {
+  floor = 0,
+  ceil = 1,
+  nearest = 2,
+}
+
+// allowlist_aux markers for metadata allowlists, unmarked values match as a URI prefix
+export const METADATA_URI_MARKERS = {
+  SUFFIX: 'suffix:',
+  CONTAINS: 'contains:',
+};
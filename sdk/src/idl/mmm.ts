@@ -63,6 +63,52 @@ export type Mmm = {
         }
       ]
     },
+    {
+      "name": "repricePool",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "RepricePoolArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "resetPoolStats",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "ResetPoolStatsArgs"
+          }
+        }
+      ]
+    },
     {
       "name": "updateAllowlists",
       "accounts": [
@@ -122,6 +168,134 @@ export type Mmm = {
       ],
       "args": []
     },
+    {
+      "name": "migratePool",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "ownerDrainAndClose",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "transferPoolOwnership",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "newOwner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "newPool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "newBuysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
     {
       "name": "solDepositBuy",
       "accounts": [
@@ -221,6 +395,11 @@ export type Mmm = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "pool",
           "isMut": true,
@@ -324,6 +503,11 @@ export type Mmm = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "pool",
           "isMut": true,
@@ -400,35 +584,35 @@ export type Mmm = {
       ]
     },
     {
-      "name": "withdrawSell",
+      "name": "solFulfillSellBatch",
       "accounts": [
         {
-          "name": "owner",
+          "name": "payer",
           "isMut": true,
           "isSigner": true
         },
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "cosigner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "pool",
+          "name": "referral",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetTokenAccount",
+          "name": "takerReferral",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "sellsideEscrowTokenAccount",
+          "name": "pool",
           "isMut": true,
           "isSigner": false
         },
@@ -442,11 +626,6 @@ export type Mmm = {
           "isMut": false,
           "isSigner": false
         },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
         {
           "name": "systemProgram",
           "isMut": false,
@@ -472,13 +651,13 @@ export type Mmm = {
         {
           "name": "args",
           "type": {
-            "defined": "WithdrawSellArgs"
+            "defined": "SolFulfillSellBatchArgs"
           }
         }
       ]
     },
     {
-      "name": "depositSell",
+      "name": "withdrawSell",
       "accounts": [
         {
           "name": "owner",
@@ -495,16 +674,6 @@ export type Mmm = {
           "isMut": true,
           "isSigner": false
         },
-        {
-          "name": "assetMetadata",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMasterEdition",
-          "isMut": false,
-          "isSigner": false
-        },
         {
           "name": "assetMint",
           "isMut": false,
@@ -521,7 +690,7 @@ export type Mmm = {
           "isSigner": false
         },
         {
-          "name": "sellState",
+          "name": "buysideSolEscrowAccount",
           "isMut": true,
           "isSigner": false
         },
@@ -530,6 +699,11 @@ export type Mmm = {
           "isMut": false,
           "isSigner": false
         },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "systemProgram",
           "isMut": false,
@@ -555,13 +729,13 @@ export type Mmm = {
         {
           "name": "args",
           "type": {
-            "defined": "DepositSellArgs"
+            "defined": "WithdrawSellArgs"
           }
         }
       ]
     },
     {
-      "name": "ocpDepositSell",
+      "name": "depositSell",
       "accounts": [
         {
           "name": "owner",
@@ -583,6 +757,11 @@ export type Mmm = {
           "isMut": false,
           "isSigner": false
         },
+        {
+          "name": "assetMasterEdition",
+          "isMut": false,
+          "isSigner": false
+        },
         {
           "name": "assetMint",
           "isMut": false,
@@ -608,36 +787,6 @@ export type Mmm = {
           "isMut": false,
           "isSigner": false
         },
-        {
-          "name": "ocpMintState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ocpPolicy",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpFreezeAuthority",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "cmtProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "instructions",
-          "isMut": false,
-          "isSigner": false
-        },
         {
           "name": "systemProgram",
           "isMut": false,
@@ -669,10 +818,171 @@ export type Mmm = {
       ]
     },
     {
-      "name": "solOcpFulfillBuy",
+      "name": "depositSellBatch",
       "accounts": [
         {
-          "name": "payer",
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "DepositSellBatchArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "ocpDepositSell",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMetadata",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpMintState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ocpPolicy",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpFreezeAuthority",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "cmtProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "instructions",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "DepositSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solOcpFulfillBuy",
+      "accounts": [
+        {
+          "name": "payer",
           "isMut": true,
           "isSigner": true
         },
@@ -691,6 +1001,11 @@ export type Mmm = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "pool",
           "isMut": true,
@@ -819,6 +1134,11 @@ export type Mmm = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "pool",
           "isMut": true,
@@ -1286,6 +1606,11 @@ export type Mmm = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "pool",
           "isMut": true,
@@ -1414,6 +1739,11 @@ export type Mmm = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "pool",
           "isMut": true,
@@ -1539,56 +1869,53 @@ export type Mmm = {
       ]
     },
     {
-      "name": "closeIfBalanceInvalid",
+      "name": "getPoolQuote",
       "accounts": [
         {
-          "name": "authority",
+          "name": "payer",
           "isMut": false,
           "isSigner": true
         },
         {
           "name": "owner",
-          "isMut": true,
+          "isMut": false,
           "isSigner": false
         },
         {
           "name": "pool",
-          "isMut": true,
+          "isMut": false,
           "isSigner": false
         },
         {
           "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": []
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "GetPoolQuoteArgs"
+          }
+        }
+      ]
     },
     {
-      "name": "setSharedEscrow",
+      "name": "getMaxAffordableItems",
       "accounts": [
         {
           "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false
         },
         {
           "name": "pool",
-          "isMut": true,
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "sharedEscrowAccount",
+          "name": "buysideSolEscrowAccount",
           "isMut": false,
           "isSigner": false
         }
@@ -1597,61 +1924,54 @@ export type Mmm = {
         {
           "name": "args",
           "type": {
-            "defined": "SetSharedEscrowArgs"
+            "defined": "GetMaxAffordableItemsArgs"
           }
         }
       ]
     },
     {
-      "name": "extDepositSell",
+      "name": "checkPoolSolvency",
       "accounts": [
         {
           "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
           "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "assetMint",
+          "name": "pool",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "assetTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
+          "name": "buysideSolEscrowAccount",
+          "isMut": false,
           "isSigner": false
-        },
+        }
+      ],
+      "args": [
         {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
+          "name": "args",
+          "type": {
+            "defined": "CheckPoolSolvencyArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "getPoolNotionalValue",
+      "accounts": [
         {
-          "name": "systemProgram",
+          "name": "owner",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "tokenProgram",
+          "name": "pool",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "associatedTokenProgram",
+          "name": "buysideSolEscrowAccount",
           "isMut": false,
           "isSigner": false
         }
@@ -1660,13 +1980,259 @@ export type Mmm = {
         {
           "name": "args",
           "type": {
-            "defined": "DepositSellArgs"
+            "defined": "GetPoolNotionalValueArgs"
           }
         }
       ]
     },
     {
-      "name": "solExtFulfillSell",
+      "name": "validateMintAgainstAllowlist",
+      "accounts": [
+        {
+          "name": "assetMetadata",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMasterEdition",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "ValidateMintAgainstAllowlistArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "logSellState",
+      "accounts": [
+        {
+          "name": "sellState",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "closeIfBalanceInvalid",
+      "accounts": [
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "setSharedEscrow",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sharedEscrowAccount",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "SetSharedEscrowArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "reconcileSharedEscrow",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "addTakerFeeExemption",
+      "accounts": [
+        {
+          "name": "cosigner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "takerFeeExemption",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "AddTakerFeeExemptionArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "removeTakerFeeExemption",
+      "accounts": [
+        {
+          "name": "cosigner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "takerFeeExemption",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "extDepositSell",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "DepositSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solExtFulfillSell",
       "accounts": [
         {
           "name": "payer",
@@ -1688,6 +2254,11 @@ export type Mmm = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "pool",
           "isMut": true,
@@ -1766,6 +2337,11 @@ export type Mmm = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "pool",
           "isMut": true,
@@ -2011,84 +2587,143 @@ export type Mmm = {
           {
             "name": "sharedEscrowCount",
             "type": "u64"
-          }
-        ]
-      }
-    },
-    {
-      "name": "sellState",
-      "type": {
-        "kind": "struct",
-        "fields": [
+          },
           {
-            "name": "pool",
-            "type": "publicKey"
+            "name": "priceFloor",
+            "type": "u64"
           },
           {
-            "name": "poolOwner",
-            "type": "publicKey"
+            "name": "priceCeiling",
+            "type": "u64"
           },
           {
-            "name": "assetMint",
-            "type": "publicKey"
+            "name": "allowlistMatchMode",
+            "type": "u8"
           },
           {
-            "name": "assetAmount",
-            "type": "u64"
+            "name": "lpFeeDecayStart",
+            "type": "i64"
           },
           {
-            "name": "cosignerAnnotation",
+            "name": "lpFeeDecayEnd",
+            "type": "i64"
+          },
+          {
+            "name": "lpFeeBpFloor",
+            "type": "u16"
+          },
+          {
+            "name": "lpFeeTiers",
             "type": {
               "array": [
-                "u8",
-                32
+                {
+                  "defined": "LpFeeTier"
+                },
+                4
               ]
             }
-          }
-        ]
-      }
-    }
-  ],
-  "types": [
-    {
-      "name": "CreatePoolArgs",
-      "type": {
-        "kind": "struct",
-        "fields": [
+          },
           {
-            "name": "spotPrice",
+            "name": "minEscrowBalanceBp",
+            "type": "u16"
+          },
+          {
+            "name": "extraRoyaltyRecipients",
+            "type": {
+              "array": [
+                {
+                  "defined": "RoyaltyRecipient"
+                },
+                2
+              ]
+            }
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "maxSpotMoveBp",
+            "type": "u16"
+          },
+          {
+            "name": "lpFeeRounding",
+            "type": "u8"
+          },
+          {
+            "name": "maxFillDepth",
+            "type": "u16"
+          },
+          {
+            "name": "maxBuysideSpend",
             "type": "u64"
           },
           {
-            "name": "curveType",
+            "name": "buysideSpent",
+            "type": "u64"
+          },
+          {
+            "name": "poolSide",
             "type": "u8"
           },
           {
-            "name": "curveDelta",
+            "name": "requireSizedCollection",
+            "type": "bool"
+          },
+          {
+            "name": "totalVolumeSol",
+            "type": "u128"
+          },
+          {
+            "name": "fillCount",
             "type": "u64"
           },
           {
-            "name": "reinvestFulfillBuy",
+            "name": "rejectSelfReferral",
             "type": "bool"
           },
           {
-            "name": "reinvestFulfillSell",
+            "name": "settleInWsol",
             "type": "bool"
           },
           {
-            "name": "expiry",
+            "name": "requireOneOfOne",
+            "type": "bool"
+          },
+          {
+            "name": "requireProgrammable",
+            "type": "bool"
+          },
+          {
+            "name": "curveDeltaSell",
+            "type": "u64"
+          },
+          {
+            "name": "royaltyEnforcementMode",
+            "type": "u8"
+          },
+          {
+            "name": "maxTotalPrice",
+            "type": "u64"
+          },
+          {
+            "name": "minFillAmount",
+            "type": "u64"
+          },
+          {
+            "name": "fillCooldownSecs",
             "type": "i64"
           },
           {
-            "name": "lpFeeBp",
-            "type": "u16"
+            "name": "lastFillTs",
+            "type": "i64"
           },
           {
-            "name": "referral",
-            "type": "publicKey"
+            "name": "sellStateRentToTaker",
+            "type": "bool"
           },
           {
-            "name": "cosignerAnnotation",
+            "name": "allowlistAux",
             "type": {
               "array": [
                 "u8",
@@ -2097,64 +2732,124 @@ export type Mmm = {
             }
           },
           {
-            "name": "buysideCreatorRoyaltyBp",
+            "name": "royaltyFallbackAccount",
+            "type": "publicKey"
+          },
+          {
+            "name": "profitSkimBp",
             "type": "u16"
           },
           {
-            "name": "uuid",
+            "name": "oracle",
             "type": "publicKey"
           },
           {
-            "name": "paymentMint",
-            "type": "publicKey"
+            "name": "maxOracleDeviationBp",
+            "type": "u16"
           },
           {
-            "name": "allowlists",
+            "name": "requireAllCreatorsPaid",
+            "type": "bool"
+          },
+          {
+            "name": "curveDirection",
+            "type": "u8"
+          },
+          {
+            "name": "requireCollectionMetadata",
+            "type": "bool"
+          },
+          {
+            "name": "staticSpot",
+            "type": "bool"
+          },
+          {
+            "name": "allowlistsExt",
             "type": {
               "array": [
                 {
                   "defined": "Allowlist"
                 },
-                6
+                2
               ]
             }
+          },
+          {
+            "name": "allowRoyaltyFallback",
+            "type": "bool"
+          },
+          {
+            "name": "logCurveBasePrice",
+            "type": "u64"
           }
         ]
       }
     },
     {
-      "name": "SetSharedEscrowArgs",
+      "name": "sellState",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "sharedEscrowCount",
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "poolOwner",
+            "type": "publicKey"
+          },
+          {
+            "name": "assetMint",
+            "type": "publicKey"
+          },
+          {
+            "name": "assetAmount",
             "type": "u64"
+          },
+          {
+            "name": "cosignerAnnotation",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
           }
         ]
       }
     },
     {
-      "name": "UpdateAllowlistsArgs",
+      "name": "takerFeeExemption",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "allowlists",
-            "type": {
-              "array": [
-                {
-                  "defined": "Allowlist"
-                },
-                6
-              ]
-            }
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "taker",
+            "type": "publicKey"
+          }
+        ]
+      }
+    }
+  ],
+  "types": [
+    {
+      "name": "AddTakerFeeExemptionArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "taker",
+            "type": "publicKey"
           }
         ]
       }
     },
     {
-      "name": "UpdatePoolArgs",
+      "name": "CreatePoolArgs",
       "type": {
         "kind": "struct",
         "fields": [
@@ -2202,112 +2897,152 @@ export type Mmm = {
           {
             "name": "buysideCreatorRoyaltyBp",
             "type": "u16"
-          }
-        ]
-      }
-    },
-    {
-      "name": "SolMip1FulfillSellArgs",
-      "type": {
-        "kind": "struct",
-        "fields": [
+          },
           {
-            "name": "assetAmount",
-            "type": "u64"
+            "name": "priceFloor",
+            "type": {
+              "option": "u64"
+            }
           },
           {
-            "name": "maxPaymentAmount",
-            "type": "u64"
+            "name": "priceCeiling",
+            "type": {
+              "option": "u64"
+            }
           },
           {
-            "name": "allowlistAux",
+            "name": "lpFeeDecayEnd",
             "type": {
-              "option": "string"
+              "option": "i64"
             }
           },
           {
-            "name": "makerFeeBp",
-            "type": "i16"
+            "name": "lpFeeBpFloor",
+            "type": {
+              "option": "u16"
+            }
           },
           {
-            "name": "takerFeeBp",
-            "type": "i16"
-          }
-        ]
-      }
-    },
-    {
-      "name": "SolOcpFulfillSellArgs",
-      "type": {
-        "kind": "struct",
-        "fields": [
+            "name": "lpFeeTiers",
+            "type": {
+              "option": {
+                "vec": {
+                  "defined": "LpFeeTier"
+                }
+              }
+            }
+          },
           {
-            "name": "assetAmount",
-            "type": "u64"
+            "name": "minEscrowBalanceBp",
+            "type": {
+              "option": "u16"
+            }
           },
           {
-            "name": "maxPaymentAmount",
-            "type": "u64"
+            "name": "extraRoyaltyRecipients",
+            "type": {
+              "option": {
+                "vec": {
+                  "defined": "RoyaltyRecipient"
+                }
+              }
+            }
           },
           {
-            "name": "allowlistAux",
+            "name": "maxSpotMoveBp",
             "type": {
-              "option": "string"
+              "option": "u16"
             }
           },
           {
-            "name": "makerFeeBp",
-            "type": "i16"
+            "name": "lpFeeRounding",
+            "type": {
+              "option": "u8"
+            }
           },
           {
-            "name": "takerFeeBp",
-            "type": "i16"
-          }
-        ]
-      }
-    },
-    {
-      "name": "DepositSellArgs",
-      "type": {
-        "kind": "struct",
-        "fields": [
+            "name": "maxFillDepth",
+            "type": {
+              "option": "u16"
+            }
+          },
           {
-            "name": "assetAmount",
-            "type": "u64"
+            "name": "maxBuysideSpend",
+            "type": {
+              "option": "u64"
+            }
           },
           {
-            "name": "allowlistAux",
+            "name": "poolSide",
             "type": {
-              "option": "string"
+              "option": "u8"
             }
-          }
-        ]
-      }
-    },
-    {
-      "name": "SolDepositBuyArgs",
-      "type": {
-        "kind": "struct",
-        "fields": [
+          },
           {
-            "name": "paymentAmount",
-            "type": "u64"
-          }
-        ]
-      }
-    },
-    {
-      "name": "SolFulfillBuyArgs",
-      "type": {
-        "kind": "struct",
-        "fields": [
+            "name": "requireSizedCollection",
+            "type": {
+              "option": "bool"
+            }
+          },
           {
-            "name": "assetAmount",
-            "type": "u64"
+            "name": "rejectSelfReferral",
+            "type": {
+              "option": "bool"
+            }
           },
           {
-            "name": "minPaymentAmount",
-            "type": "u64"
+            "name": "settleInWsol",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "requireOneOfOne",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "requireProgrammable",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "curveDeltaSell",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "royaltyEnforcementMode",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "maxTotalPrice",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "minFillAmount",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "fillCooldownSecs",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "sellStateRentToTaker",
+            "type": {
+              "option": "bool"
+            }
           },
           {
             "name": "allowlistAux",
@@ -2316,1297 +3051,1499 @@ export type Mmm = {
             }
           },
           {
-            "name": "makerFeeBp",
-            "type": "i16"
+            "name": "royaltyFallbackAccount",
+            "type": {
+              "option": "publicKey"
+            }
           },
           {
-            "name": "takerFeeBp",
-            "type": "i16"
+            "name": "profitSkimBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "oracle",
+            "type": {
+              "option": "publicKey"
+            }
+          },
+          {
+            "name": "maxOracleDeviationBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "requireAllCreatorsPaid",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "curveDirection",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "requireCollectionMetadata",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "staticSpot",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "allowRoyaltyFallback",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "uuid",
+            "type": "publicKey"
+          },
+          {
+            "name": "paymentMint",
+            "type": "publicKey"
+          },
+          {
+            "name": "allowlists",
+            "type": {
+              "array": [
+                {
+                  "defined": "Allowlist"
+                },
+                8
+              ]
+            }
+          },
+          {
+            "name": "allowlistMatchMode",
+            "type": {
+              "option": "u8"
+            }
           }
         ]
       }
     },
     {
-      "name": "SolFulfillSellArgs",
+      "name": "RepricePoolArgs",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "assetAmount",
+            "name": "spotPrice",
             "type": "u64"
           },
           {
-            "name": "maxPaymentAmount",
-            "type": "u64"
+            "name": "curveDelta",
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "ResetPoolStatsArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "totalVolumeSol",
+            "type": "bool"
           },
           {
-            "name": "buysideCreatorRoyaltyBp",
-            "type": "u16"
+            "name": "fillCount",
+            "type": "bool"
           },
           {
-            "name": "allowlistAux",
-            "type": {
-              "option": "string"
-            }
+            "name": "lpFeeEarned",
+            "type": "bool"
+          }
+        ]
+      }
+    },
+    {
+      "name": "StatsReset",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "pool",
+            "type": "publicKey"
           },
           {
-            "name": "makerFeeBp",
-            "type": "i16"
+            "name": "totalVolumeSol",
+            "type": "u128"
           },
           {
-            "name": "takerFeeBp",
-            "type": "i16"
+            "name": "fillCount",
+            "type": "u64"
+          },
+          {
+            "name": "lpFeeEarned",
+            "type": "u64"
           }
         ]
       }
     },
     {
-      "name": "SolWithdrawBuyArgs",
+      "name": "SetSharedEscrowArgs",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "paymentAmount",
+            "name": "sharedEscrowCount",
             "type": "u64"
           }
         ]
       }
     },
     {
-      "name": "WithdrawSellArgs",
+      "name": "UpdateAllowlistsArgs",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "assetAmount",
-            "type": "u64"
+            "name": "allowlists",
+            "type": {
+              "array": [
+                {
+                  "defined": "Allowlist"
+                },
+                8
+              ]
+            }
           },
           {
-            "name": "allowlistAux",
+            "name": "allowlistMatchMode",
             "type": {
-              "option": "string"
+              "option": "u8"
             }
           }
         ]
       }
     },
     {
-      "name": "Allowlist",
+      "name": "UpdatePoolArgs",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "kind",
+            "name": "spotPrice",
+            "type": "u64"
+          },
+          {
+            "name": "curveType",
             "type": "u8"
           },
           {
-            "name": "value",
+            "name": "curveDelta",
+            "type": "u64"
+          },
+          {
+            "name": "reinvestFulfillBuy",
+            "type": "bool"
+          },
+          {
+            "name": "reinvestFulfillSell",
+            "type": "bool"
+          },
+          {
+            "name": "expiry",
+            "type": "i64"
+          },
+          {
+            "name": "lpFeeBp",
+            "type": "u16"
+          },
+          {
+            "name": "referral",
             "type": "publicKey"
-          }
-        ]
-      }
-    }
-  ],
-  "errors": [
-    {
-      "code": 6000,
-      "name": "InvalidLPFee",
-      "msg": "lp fee bp must be between 0 and 10000"
-    },
-    {
-      "code": 6001,
-      "name": "InvalidAllowLists",
-      "msg": "invalid allowlists"
-    },
-    {
-      "code": 6002,
-      "name": "InvalidBP",
-      "msg": "invalid bp"
-    },
-    {
-      "code": 6003,
-      "name": "InvalidCurveType",
-      "msg": "invalid curve type"
-    },
-    {
-      "code": 6004,
-      "name": "InvalidCurveDelta",
-      "msg": "invalid curve delta"
-    },
-    {
-      "code": 6005,
-      "name": "InvalidCosigner",
-      "msg": "invalid cosigner"
-    },
-    {
-      "code": 6006,
-      "name": "InvalidPaymentMint",
-      "msg": "invalid payment mint"
-    },
-    {
-      "code": 6007,
-      "name": "InvalidOwner",
-      "msg": "invalid owner"
-    },
-    {
-      "code": 6008,
-      "name": "NumericOverflow",
-      "msg": "numeric overflow"
-    },
-    {
-      "code": 6009,
-      "name": "InvalidRequestedPrice",
-      "msg": "invalid requested price"
-    },
-    {
-      "code": 6010,
-      "name": "NotEmptyEscrowAccount",
-      "msg": "not empty escrow account"
+          },
+          {
+            "name": "cosignerAnnotation",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "buysideCreatorRoyaltyBp",
+            "type": "u16"
+          },
+          {
+            "name": "priceFloor",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "priceCeiling",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "lpFeeDecayEnd",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "lpFeeBpFloor",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "lpFeeTiers",
+            "type": {
+              "option": {
+                "vec": {
+                  "defined": "LpFeeTier"
+                }
+              }
+            }
+          },
+          {
+            "name": "minEscrowBalanceBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "extraRoyaltyRecipients",
+            "type": {
+              "option": {
+                "vec": {
+                  "defined": "RoyaltyRecipient"
+                }
+              }
+            }
+          },
+          {
+            "name": "maxSpotMoveBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "lpFeeRounding",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "maxFillDepth",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "maxBuysideSpend",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "poolSide",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "requireSizedCollection",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "rejectSelfReferral",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "settleInWsol",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "requireOneOfOne",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "requireProgrammable",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "curveDeltaSell",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "royaltyEnforcementMode",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "maxTotalPrice",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "minFillAmount",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "fillCooldownSecs",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "sellStateRentToTaker",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          },
+          {
+            "name": "royaltyFallbackAccount",
+            "type": {
+              "option": "publicKey"
+            }
+          },
+          {
+            "name": "profitSkimBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "oracle",
+            "type": {
+              "option": "publicKey"
+            }
+          },
+          {
+            "name": "maxOracleDeviationBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "requireAllCreatorsPaid",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "curveDirection",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "requireCollectionMetadata",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "staticSpot",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "allowRoyaltyFallback",
+            "type": {
+              "option": "bool"
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6011,
-      "name": "NotEmptySellsideAssetAmount",
-      "msg": "not empty sell side asset amount"
+      "name": "SolMip1FulfillSellArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "maxPaymentAmount",
+            "type": "u64"
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
+          }
+        ]
+      }
     },
     {
-      "code": 6012,
-      "name": "InvalidReferral",
-      "msg": "invalid referral"
+      "name": "SolOcpFulfillSellArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "maxPaymentAmount",
+            "type": "u64"
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
+          }
+        ]
+      }
     },
     {
-      "code": 6013,
-      "name": "InvalidMasterEdition",
-      "msg": "invalid master edition"
+      "name": "CheckPoolSolvencyArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "fulfillBuy",
+            "type": "bool"
+          },
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
+          }
+        ]
+      }
     },
     {
-      "code": 6014,
-      "name": "Expired",
-      "msg": "expired"
-    },
-    {
-      "code": 6015,
-      "name": "InvalidCreatorAddress",
-      "msg": "invalid creator address"
-    },
-    {
-      "code": 6016,
-      "name": "NotEnoughBalance",
-      "msg": "not enough balance"
-    },
-    {
-      "code": 6017,
-      "name": "InvalidTokenOwner",
-      "msg": "invalid token owner"
-    },
-    {
-      "code": 6018,
-      "name": "PubkeyMismatch",
-      "msg": "pubkey mismatch"
-    },
-    {
-      "code": 6019,
-      "name": "UninitializedAccount",
-      "msg": "uninitialized account"
-    },
-    {
-      "code": 6020,
-      "name": "InvalidSpotPrice",
-      "msg": "invalid spot_price"
+      "name": "PoolSolvency",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "fulfillBuy",
+            "type": "bool"
+          },
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "required",
+            "type": "u64"
+          },
+          {
+            "name": "available",
+            "type": "u64"
+          },
+          {
+            "name": "solvent",
+            "type": "bool"
+          }
+        ]
+      }
     },
     {
-      "code": 6021,
-      "name": "InvalidMetadataCreatorRoyalty",
-      "msg": "invalid metadata creator royalty"
+      "name": "DepositSellBatchArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6022,
-      "name": "InvalidMakerOrTakerFeeBP",
-      "msg": "invalid maker or taker bp"
+      "name": "DepositSellArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6023,
-      "name": "InvalidOcpAssetParams",
-      "msg": "Invalid OCP mint supply constraints"
+      "name": "GetMaxAffordableItemsArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "budget",
+            "type": "u64"
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
+          }
+        ]
+      }
     },
     {
-      "code": 6024,
-      "name": "InvalidAccountState",
-      "msg": "Invalid account state"
+      "name": "MaxAffordableItems",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "budget",
+            "type": "u64"
+          },
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6025,
-      "name": "InvalidTokenMint",
-      "msg": "Invalid token mint"
+      "name": "GetPoolNotionalValueArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
+          }
+        ]
+      }
     },
     {
-      "code": 6026,
-      "name": "InvalidMip1AssetParams",
-      "msg": "Invalid MIP1 mint supply constraints"
+      "name": "PoolNotionalValue",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "buysideValue",
+            "type": "u64"
+          },
+          {
+            "name": "sellsideValue",
+            "type": "u64"
+          },
+          {
+            "name": "feesOwed",
+            "type": "i64"
+          },
+          {
+            "name": "notionalValue",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6027,
-      "name": "InvalidTokenStandard",
-      "msg": "Invalid token standard"
+      "name": "GetPoolQuoteArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "fulfillBuy",
+            "type": "bool"
+          },
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "metadataRoyaltyBp",
+            "type": "u16"
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "saturating",
+            "type": "bool"
+          }
+        ]
+      }
     },
     {
-      "code": 6028,
-      "name": "UnexpectedMetadataUri",
-      "msg": "Unexpected metadata uri"
+      "name": "PoolQuote",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "totalPrice",
+            "type": "u64"
+          },
+          {
+            "name": "nextPrice",
+            "type": "u64"
+          },
+          {
+            "name": "lpFee",
+            "type": "u64"
+          },
+          {
+            "name": "makerFee",
+            "type": "i64"
+          },
+          {
+            "name": "takerFee",
+            "type": "i64"
+          },
+          {
+            "name": "referralFee",
+            "type": "u64"
+          },
+          {
+            "name": "royaltyBp",
+            "type": "u16"
+          },
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6029,
-      "name": "InvalidRemainingAccounts",
-      "msg": "Invalid remaining accounts"
+      "name": "SellStateSummary",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "assetMint",
+            "type": "publicKey"
+          },
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6030,
-      "name": "InvalidTokenMetadataExtension",
-      "msg": "Invalid token metadata extensions"
+      "name": "SolDepositBuyArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "paymentAmount",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6031,
-      "name": "InvalidTokenMemberExtension",
-      "msg": "Invalid token member extensions"
-    }
-  ]
-};
-
-export const IDL: Mmm = {
-  "version": "0.2.0",
-  "name": "mmm",
-  "instructions": [
-    {
-      "name": "createPool",
-      "accounts": [
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "CreatePoolArgs"
+      "name": "SolFulfillBuyArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "minPaymentAmount",
+            "type": "u64"
+          },
+          {
+            "name": "minPricePerItem",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "updatePool",
-      "accounts": [
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "UpdatePoolArgs"
+      "name": "SolFulfillSellBatchArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "maxPaymentAmount",
+            "type": "u64"
+          },
+          {
+            "name": "maxPricePerItem",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "buysideCreatorRoyaltyBp",
+            "type": "u16"
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "updateAllowlists",
-      "accounts": [
-        {
-          "name": "cosigner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "UpdateAllowlistsArgs"
+      "name": "SolFulfillSellArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "maxPaymentAmount",
+            "type": "u64"
+          },
+          {
+            "name": "maxPricePerItem",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "buysideCreatorRoyaltyBp",
+            "type": "u16"
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "partialFillOk",
+            "type": "bool"
+          },
+          {
+            "name": "royaltyBpOverride",
+            "type": {
+              "option": "u16"
+            }
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "solClosePool",
-      "accounts": [
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": []
+      "name": "SolWithdrawBuyArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "paymentAmount",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "name": "solDepositBuy",
-      "accounts": [
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "SolDepositBuyArgs"
+      "name": "ValidateMintAgainstAllowlistArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "allowlists",
+            "type": {
+              "array": [
+                {
+                  "defined": "Allowlist"
+                },
+                8
+              ]
+            }
+          },
+          {
+            "name": "allowlistMatchMode",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "solWithdrawBuy",
-      "accounts": [
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "SolWithdrawBuyArgs"
+      "name": "AllowlistMatch",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetMint",
+            "type": "publicKey"
+          },
+          {
+            "name": "kind",
+            "type": "u8"
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "solFulfillBuy",
-      "accounts": [
-        {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "referral",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMetadata",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMasterEdition",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "payerAssetAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ownerTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "associatedTokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "rent",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "SolFulfillBuyArgs"
+      "name": "WithdrawSellArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "solFulfillSell",
-      "accounts": [
-        {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "referral",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMetadata",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMasterEdition",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "payerAssetAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "associatedTokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "rent",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "SolFulfillSellArgs"
+      "name": "Allowlist",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "kind",
+            "type": "u8"
+          },
+          {
+            "name": "value",
+            "type": "publicKey"
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "withdrawSell",
-      "accounts": [
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "associatedTokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "rent",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "WithdrawSellArgs"
+      "name": "LpFeeTier",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "minAssetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "lpFeeBp",
+            "type": "u16"
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "depositSell",
-      "accounts": [
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMetadata",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMasterEdition",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "associatedTokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "rent",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "DepositSellArgs"
+      "name": "RoyaltyRecipient",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "address",
+            "type": "publicKey"
+          },
+          {
+            "name": "bp",
+            "type": "u16"
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "ocpDepositSell",
-      "accounts": [
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMetadata",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpMintState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ocpPolicy",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpFreezeAuthority",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "cmtProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "instructions",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "associatedTokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "rent",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "DepositSellArgs"
+      "name": "FillSummary",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetMint",
+            "type": "publicKey"
+          },
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "totalPrice",
+            "type": "u64"
+          },
+          {
+            "name": "lpFee",
+            "type": "u64"
+          },
+          {
+            "name": "royaltyPaid",
+            "type": "u64"
+          },
+          {
+            "name": "makerFee",
+            "type": "i64"
+          },
+          {
+            "name": "takerFee",
+            "type": "i64"
+          },
+          {
+            "name": "spotPriceBefore",
+            "type": "u64"
+          },
+          {
+            "name": "spotPriceAfter",
+            "type": "u64"
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "solOcpFulfillBuy",
-      "accounts": [
-        {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "referral",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMetadata",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "payerAssetAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ownerTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ocpMintState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ocpPolicy",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpFreezeAuthority",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "cmtProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "instructions",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "associatedTokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "rent",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "SolFulfillBuyArgs"
+      "name": "AccountClosed",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "owner",
+            "type": "publicKey"
+          },
+          {
+            "name": "lamports",
+            "type": "u64"
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "solOcpFulfillSell",
-      "accounts": [
-        {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "referral",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMetadata",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "payerAssetAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ocpMintState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ocpPolicy",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpFreezeAuthority",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "cmtProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "instructions",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "associatedTokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "rent",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "SolOcpFulfillSellArgs"
+      "name": "PoolEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "action",
+            "type": "u8"
+          },
+          {
+            "name": "pool",
+            "type": "publicKey"
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "ocpWithdrawSell",
-      "accounts": [
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetMetadata",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ocpMintState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ocpPolicy",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpFreezeAuthority",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "ocpProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "cmtProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "instructions",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "associatedTokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "rent",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "WithdrawSellArgs"
+      "name": "SharedEscrowWithdraw",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "destination",
+            "type": "publicKey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
           }
-        }
-      ]
-    },
+        ]
+      }
+    }
+  ],
+  "errors": [
     {
-      "name": "mip1DepositSell",
+      "code": 6000,
+      "name": "InvalidLPFee",
+      "msg": "lp fee bp must be between 0 and 10000"
+    },
+    {
+      "code": 6001,
+      "name": "InvalidAllowLists",
+      "msg": "invalid allowlists"
+    },
+    {
+      "code": 6002,
+      "name": "InvalidBP",
+      "msg": "invalid bp"
+    },
+    {
+      "code": 6003,
+      "name": "InvalidCurveType",
+      "msg": "invalid curve type"
+    },
+    {
+      "code": 6004,
+      "name": "InvalidCurveDelta",
+      "msg": "invalid curve delta"
+    },
+    {
+      "code": 6005,
+      "name": "InvalidCosigner",
+      "msg": "invalid cosigner"
+    },
+    {
+      "code": 6006,
+      "name": "InvalidPaymentMint",
+      "msg": "invalid payment mint"
+    },
+    {
+      "code": 6007,
+      "name": "InvalidOwner",
+      "msg": "invalid owner"
+    },
+    {
+      "code": 6008,
+      "name": "NumericOverflow",
+      "msg": "numeric overflow"
+    },
+    {
+      "code": 6009,
+      "name": "InvalidRequestedPrice",
+      "msg": "invalid requested price"
+    },
+    {
+      "code": 6010,
+      "name": "NotEmptyEscrowAccount",
+      "msg": "not empty escrow account"
+    },
+    {
+      "code": 6011,
+      "name": "NotEmptySellsideAssetAmount",
+      "msg": "not empty sell side asset amount"
+    },
+    {
+      "code": 6012,
+      "name": "InvalidReferral",
+      "msg": "invalid referral"
+    },
+    {
+      "code": 6013,
+      "name": "InvalidMasterEdition",
+      "msg": "invalid master edition"
+    },
+    {
+      "code": 6014,
+      "name": "Expired",
+      "msg": "expired"
+    },
+    {
+      "code": 6015,
+      "name": "InvalidCreatorAddress",
+      "msg": "invalid creator address"
+    },
+    {
+      "code": 6016,
+      "name": "NotEnoughBalance",
+      "msg": "not enough balance"
+    },
+    {
+      "code": 6017,
+      "name": "InvalidTokenOwner",
+      "msg": "invalid token owner"
+    },
+    {
+      "code": 6018,
+      "name": "PubkeyMismatch",
+      "msg": "pubkey mismatch"
+    },
+    {
+      "code": 6019,
+      "name": "UninitializedAccount",
+      "msg": "uninitialized account"
+    },
+    {
+      "code": 6020,
+      "name": "InvalidSpotPrice",
+      "msg": "invalid spot_price"
+    },
+    {
+      "code": 6021,
+      "name": "InvalidMetadataCreatorRoyalty",
+      "msg": "invalid metadata creator royalty"
+    },
+    {
+      "code": 6022,
+      "name": "InvalidMakerOrTakerFeeBP",
+      "msg": "invalid maker or taker bp"
+    },
+    {
+      "code": 6023,
+      "name": "InvalidOcpAssetParams",
+      "msg": "Invalid OCP mint supply constraints"
+    },
+    {
+      "code": 6024,
+      "name": "InvalidAccountState",
+      "msg": "Invalid account state"
+    },
+    {
+      "code": 6025,
+      "name": "InvalidTokenMint",
+      "msg": "Invalid token mint"
+    },
+    {
+      "code": 6026,
+      "name": "InvalidMip1AssetParams",
+      "msg": "Invalid MIP1 mint supply constraints"
+    },
+    {
+      "code": 6027,
+      "name": "InvalidTokenStandard",
+      "msg": "Invalid token standard"
+    },
+    {
+      "code": 6028,
+      "name": "UnexpectedMetadataUri",
+      "msg": "Unexpected metadata uri"
+    },
+    {
+      "code": 6029,
+      "name": "InvalidRemainingAccounts",
+      "msg": "Invalid remaining accounts"
+    },
+    {
+      "code": 6030,
+      "name": "InvalidTokenMetadataExtension",
+      "msg": "Invalid token metadata extensions"
+    },
+    {
+      "code": 6031,
+      "name": "InvalidTokenMemberExtension",
+      "msg": "Invalid token member extensions"
+    },
+    {
+      "code": 6032,
+      "name": "InvalidPriceFloor",
+      "msg": "invalid price_floor"
+    },
+    {
+      "code": 6033,
+      "name": "InvalidPriceCeiling",
+      "msg": "invalid price_ceiling"
+    },
+    {
+      "code": 6034,
+      "name": "InvalidExpiry",
+      "msg": "expiry must be in the future"
+    },
+    {
+      "code": 6035,
+      "name": "InvalidLpFeeDecay",
+      "msg": "invalid lp fee decay"
+    },
+    {
+      "code": 6036,
+      "name": "InvalidLpFeeTiers",
+      "msg": "invalid lp fee tiers"
+    },
+    {
+      "code": 6037,
+      "name": "InvalidExtraRoyaltyRecipients",
+      "msg": "invalid extra royalty recipients"
+    },
+    {
+      "code": 6038,
+      "name": "PriceSlippageExceeded",
+      "msg": "price slippage exceeded"
+    },
+    {
+      "code": 6039,
+      "name": "InvalidBatchSize",
+      "msg": "invalid batch size"
+    },
+    {
+      "code": 6040,
+      "name": "TransferFeeExceedsAmount",
+      "msg": "transfer fee exceeds the transferred amount"
+    },
+    {
+      "code": 6041,
+      "name": "MissingTransferHookAccounts",
+      "msg": "missing transfer hook accounts"
+    },
+    {
+      "code": 6042,
+      "name": "PoolAlreadyMigrated",
+      "msg": "pool already migrated"
+    },
+    {
+      "code": 6043,
+      "name": "TooManyRoyaltyCreators",
+      "msg": "too many royalty creators"
+    },
+    {
+      "code": 6044,
+      "name": "SpotPriceMoveExceeded",
+      "msg": "spot price move exceeds max_spot_move_bp"
+    },
+    {
+      "code": 6045,
+      "name": "InvalidLpFeeRounding",
+      "msg": "invalid lp fee rounding"
+    },
+    {
+      "code": 6046,
+      "name": "BuysideSpendExceeded",
+      "msg": "buyside spend exceeds max_buyside_spend"
+    },
+    {
+      "code": 6047,
+      "name": "InvalidPoolSide",
+      "msg": "invalid pool side"
+    },
+    {
+      "code": 6048,
+      "name": "PoolSideNotAllowed",
+      "msg": "the pool does not fulfill this side"
+    },
+    {
+      "code": 6049,
+      "name": "UnsizedCollection",
+      "msg": "collection is not a sized collection"
+    },
+    {
+      "code": 6050,
+      "name": "SelfReferral",
+      "msg": "referral can not be the pool owner or the counterparty"
+    },
+    {
+      "code": 6051,
+      "name": "MakerRebateExceedsTakerFee",
+      "msg": "maker rebate can not exceed the taker fee"
+    },
+    {
+      "code": 6052,
+      "name": "InsufficientWsolBalance",
+      "msg": "wsol balance is below the payment amount"
+    },
+    {
+      "code": 6053,
+      "name": "WsolSettlementNotSupported",
+      "msg": "wsol settlement is not supported by this fulfill"
+    },
+    {
+      "code": 6054,
+      "name": "NotOneOfOne",
+      "msg": "master edition allows prints, the pool only takes 1/1s"
+    },
+    {
+      "code": 6055,
+      "name": "InsufficientSharedEscrowBalance",
+      "msg": "shared escrow balance is below the withdraw amount"
+    },
+    {
+      "code": 6056,
+      "name": "InvalidRoyaltyEnforcementMode",
+      "msg": "invalid royalty enforcement mode"
+    },
+    {
+      "code": 6057,
+      "name": "InvalidMaxTotalPrice",
+      "msg": "max total price is below the spot price"
+    },
+    {
+      "code": 6058,
+      "name": "FillBelowMinFillAmount",
+      "msg": "fill asset amount is below the pool min_fill_amount"
+    },
+    {
+      "code": 6059,
+      "name": "InvalidFillCooldown",
+      "msg": "invalid fill cooldown"
+    },
+    {
+      "code": 6060,
+      "name": "FillCooldownActive",
+      "msg": "the pool was filled within its fill cooldown"
+    },
+    {
+      "code": 6061,
+      "name": "InvalidAllowlistAux",
+      "msg": "invalid allowlist aux"
+    },
+    {
+      "code": 6062,
+      "name": "InvalidCreatorShares",
+      "msg": "creator shares must sum to 100"
+    },
+    {
+      "code": 6063,
+      "name": "InvalidOracle",
+      "msg": "invalid oracle account"
+    },
+    {
+      "code": 6064,
+      "name": "OraclePriceDeviation",
+      "msg": "spot price deviates from the oracle price"
+    },
+    {
+      "code": 6065,
+      "name": "CreatorNotPaid",
+      "msg": "a royalty share is below the rent of its recipient account"
+    },
+    {
+      "code": 6066,
+      "name": "InvalidCurveDirection",
+      "msg": "invalid curve direction"
+    },
+    {
+      "code": 6067,
+      "name": "EscrowUnderfunded",
+      "msg": "buyside escrow balance is below the total price of the fill"
+    },
+    {
+      "code": 6068,
+      "name": "InvalidTokenProgram",
+      "msg": "token program does not match the mint"
+    },
+    {
+      "code": 6069,
+      "name": "FillExceedsMaxItems",
+      "msg": "fill asset amount is above the max items of a single fill"
+    },
+    {
+      "code": 6070,
+      "name": "InvalidRoyaltyBpOverride",
+      "msg": "royalty bp override is below the royalty bp of the mint"
+    },
+    {
+      "code": 6071,
+      "name": "InvalidPoolLayout",
+      "msg": "pool account layout can not be migrated"
+    }
+  ]
+};
+
+export const IDL: Mmm = {
+  "version": "0.2.0",
+  "name": "mmm",
+  "instructions": [
+    {
+      "name": "createPool",
       "accounts": [
         {
           "name": "owner",
@@ -3624,68 +4561,195 @@ export const IDL: Mmm = {
           "isSigner": false
         },
         {
-          "name": "assetMetadata",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "CreatePoolArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "updatePool",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
         },
         {
-          "name": "assetMasterEdition",
+          "name": "cosigner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "assetTokenAccount",
+          "name": "pool",
           "isMut": true,
           "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "UpdatePoolArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "repricePool",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "sellsideEscrowTokenAccount",
+          "name": "pool",
           "isMut": true,
           "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "RepricePoolArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "resetPoolStats",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "sellState",
+          "name": "pool",
           "isMut": true,
           "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "ResetPoolStatsArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "updateAllowlists",
+      "accounts": [
+        {
+          "name": "cosigner",
+          "isMut": true,
+          "isSigner": true
         },
         {
-          "name": "allowlistAuxAccount",
+          "name": "owner",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "ownerTokenRecord",
+          "name": "pool",
           "isMut": true,
           "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "UpdateAllowlistsArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solClosePool",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
         },
         {
-          "name": "destinationTokenRecord",
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "authorizationRules",
+          "name": "buysideSolEscrowAccount",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "tokenMetadataProgram",
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "migratePool",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "authorizationRulesProgram",
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "ownerDrainAndClose",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
         },
         {
-          "name": "instructions",
+          "name": "cosigner",
           "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
           "isSigner": false
         },
         {
@@ -3709,17 +4773,10 @@ export const IDL: Mmm = {
           "isSigner": false
         }
       ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "DepositSellArgs"
-          }
-        }
-      ]
+      "args": []
     },
     {
-      "name": "mip1WithdrawSell",
+      "name": "transferPoolOwnership",
       "accounts": [
         {
           "name": "owner",
@@ -3727,102 +4784,121 @@ export const IDL: Mmm = {
           "isSigner": true
         },
         {
-          "name": "cosigner",
+          "name": "newOwner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
+          "name": "cosigner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "assetMasterEdition",
-          "isMut": false,
+          "name": "pool",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "assetMetadata",
+          "name": "newPool",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "assetTokenAccount",
+          "name": "buysideSolEscrowAccount",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "sellsideEscrowTokenAccount",
+          "name": "newBuysideSolEscrowAccount",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
+          "name": "systemProgram",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "allowlistAuxAccount",
+          "name": "tokenProgram",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "sellState",
-          "isMut": true,
+          "name": "associatedTokenProgram",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "ownerTokenRecord",
-          "isMut": true,
+          "name": "rent",
+          "isMut": false,
           "isSigner": false
-        },
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "solDepositBuy",
+      "accounts": [
         {
-          "name": "destinationTokenRecord",
+          "name": "owner",
           "isMut": true,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "authorizationRules",
+          "name": "cosigner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "tokenMetadataProgram",
-          "isMut": false,
+          "name": "pool",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "authorizationRulesProgram",
-          "isMut": false,
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "instructions",
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "SolDepositBuyArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solWithdrawBuy",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
         },
         {
-          "name": "systemProgram",
+          "name": "cosigner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
+          "name": "pool",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "associatedTokenProgram",
-          "isMut": false,
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "rent",
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
         }
@@ -3831,13 +4907,13 @@ export const IDL: Mmm = {
         {
           "name": "args",
           "type": {
-            "defined": "WithdrawSellArgs"
+            "defined": "SolWithdrawBuyArgs"
           }
         }
       ]
     },
     {
-      "name": "solMip1FulfillSell",
+      "name": "solFulfillBuy",
       "accounts": [
         {
           "name": "payer",
@@ -3860,22 +4936,22 @@ export const IDL: Mmm = {
           "isSigner": false
         },
         {
-          "name": "pool",
+          "name": "takerReferral",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "buysideSolEscrowAccount",
+          "name": "pool",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "assetMetadata",
+          "name": "buysideSolEscrowAccount",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "assetMint",
+          "name": "assetMetadata",
           "isMut": false,
           "isSigner": false
         },
@@ -3885,53 +4961,33 @@ export const IDL: Mmm = {
           "isSigner": false
         },
         {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "payerAssetAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
+          "name": "assetMint",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "sellState",
+          "name": "payerAssetAccount",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "ownerTokenRecord",
+          "name": "sellsideEscrowTokenAccount",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "destinationTokenRecord",
+          "name": "ownerTokenAccount",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "authorizationRules",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenMetadataProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "authorizationRulesProgram",
+          "name": "allowlistAuxAccount",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "instructions",
-          "isMut": false,
+          "name": "sellState",
+          "isMut": true,
           "isSigner": false
         },
         {
@@ -3959,13 +5015,13 @@ export const IDL: Mmm = {
         {
           "name": "args",
           "type": {
-            "defined": "SolMip1FulfillSellArgs"
+            "defined": "SolFulfillBuyArgs"
           }
         }
       ]
     },
     {
-      "name": "solMip1FulfillBuy",
+      "name": "solFulfillSell",
       "accounts": [
         {
           "name": "payer",
@@ -3988,22 +5044,22 @@ export const IDL: Mmm = {
           "isSigner": false
         },
         {
-          "name": "pool",
+          "name": "takerReferral",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "buysideSolEscrowAccount",
+          "name": "pool",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "assetMetadata",
+          "name": "buysideSolEscrowAccount",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "assetMint",
+          "name": "assetMetadata",
           "isMut": false,
           "isSigner": false
         },
@@ -4013,8 +5069,8 @@ export const IDL: Mmm = {
           "isSigner": false
         },
         {
-          "name": "payerAssetAccount",
-          "isMut": true,
+          "name": "assetMint",
+          "isMut": false,
           "isSigner": false
         },
         {
@@ -4023,7 +5079,7 @@ export const IDL: Mmm = {
           "isSigner": false
         },
         {
-          "name": "ownerTokenAccount",
+          "name": "payerAssetAccount",
           "isMut": true,
           "isSigner": false
         },
@@ -4038,46 +5094,75 @@ export const IDL: Mmm = {
           "isSigner": false
         },
         {
-          "name": "tokenOwnerTokenRecord",
-          "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "This is the token record for the seller"
-          ]
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "poolTokenRecord",
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "SolFulfillSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solFulfillSellBatch",
+      "accounts": [
+        {
+          "name": "payer",
           "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "This is the token record for the pool - will always be required"
-          ]
+          "isSigner": true
         },
         {
-          "name": "poolOwnerTokenRecord",
+          "name": "owner",
           "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "This is the token record for the pool owner - will be required if reinvest = true"
-          ]
+          "isSigner": false
         },
         {
-          "name": "tokenMetadataProgram",
+          "name": "cosigner",
           "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "referral",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "authorizationRulesProgram",
-          "isMut": false,
+          "name": "takerReferral",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "authorizationRules",
-          "isMut": false,
+          "name": "pool",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "instructions",
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
           "isMut": false,
           "isSigner": false
         },
@@ -4106,26 +5191,41 @@ export const IDL: Mmm = {
         {
           "name": "args",
           "type": {
-            "defined": "SolFulfillBuyArgs"
+            "defined": "SolFulfillSellBatchArgs"
           }
         }
       ]
     },
     {
-      "name": "closeIfBalanceInvalid",
+      "name": "withdrawSell",
       "accounts": [
         {
-          "name": "authority",
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "owner",
+          "name": "pool",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "pool",
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
           "isMut": true,
           "isSigner": false
         },
@@ -4135,33 +5235,32 @@ export const IDL: Mmm = {
           "isSigner": false
         },
         {
-          "name": "systemProgram",
+          "name": "allowlistAuxAccount",
           "isMut": false,
           "isSigner": false
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "setSharedEscrow",
-      "accounts": [
+        },
         {
-          "name": "owner",
+          "name": "sellState",
           "isMut": true,
-          "isSigner": true
+          "isSigner": false
         },
         {
-          "name": "cosigner",
+          "name": "systemProgram",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false
         },
         {
-          "name": "pool",
-          "isMut": true,
+          "name": "tokenProgram",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "sharedEscrowAccount",
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
           "isMut": false,
           "isSigner": false
         }
@@ -4170,13 +5269,13 @@ export const IDL: Mmm = {
         {
           "name": "args",
           "type": {
-            "defined": "SetSharedEscrowArgs"
+            "defined": "WithdrawSellArgs"
           }
         }
       ]
     },
     {
-      "name": "extDepositSell",
+      "name": "depositSell",
       "accounts": [
         {
           "name": "owner",
@@ -4193,6 +5292,16 @@ export const IDL: Mmm = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "assetMetadata",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMasterEdition",
+          "isMut": false,
+          "isSigner": false
+        },
         {
           "name": "assetMint",
           "isMut": false,
@@ -4213,6 +5322,11 @@ export const IDL: Mmm = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
         {
           "name": "systemProgram",
           "isMut": false,
@@ -4227,6 +5341,11 @@ export const IDL: Mmm = {
           "name": "associatedTokenProgram",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
@@ -4239,58 +5358,28 @@ export const IDL: Mmm = {
       ]
     },
     {
-      "name": "solExtFulfillSell",
+      "name": "depositSellBatch",
       "accounts": [
-        {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
-        },
         {
           "name": "owner",
           "isMut": true,
-          "isSigner": false
+          "isSigner": true
         },
         {
           "name": "cosigner",
           "isMut": false,
           "isSigner": true
         },
-        {
-          "name": "referral",
-          "isMut": true,
-          "isSigner": false
-        },
         {
           "name": "pool",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
+          "name": "allowlistAuxAccount",
           "isMut": false,
           "isSigner": false
         },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "payerAssetAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
         {
           "name": "systemProgram",
           "isMut": false,
@@ -4305,187 +5394,2385 @@ export const IDL: Mmm = {
           "name": "associatedTokenProgram",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
           "name": "args",
           "type": {
-            "defined": "SolFulfillSellArgs"
+            "defined": "DepositSellBatchArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "ocpDepositSell",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMetadata",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpMintState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ocpPolicy",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpFreezeAuthority",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "cmtProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "instructions",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "DepositSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solOcpFulfillBuy",
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "referral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMetadata",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "payerAssetAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ownerTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ocpMintState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ocpPolicy",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpFreezeAuthority",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "cmtProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "instructions",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "SolFulfillBuyArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solOcpFulfillSell",
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "referral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMetadata",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "payerAssetAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ocpMintState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ocpPolicy",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpFreezeAuthority",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "cmtProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "instructions",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "SolOcpFulfillSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "ocpWithdrawSell",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMetadata",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ocpMintState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ocpPolicy",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpFreezeAuthority",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ocpProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "cmtProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "instructions",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "WithdrawSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "mip1DepositSell",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMetadata",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMasterEdition",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "ownerTokenRecord",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "destinationTokenRecord",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "authorizationRules",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenMetadataProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "authorizationRulesProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "instructions",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "DepositSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "mip1WithdrawSell",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMasterEdition",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMetadata",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ownerTokenRecord",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "destinationTokenRecord",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "authorizationRules",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenMetadataProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "authorizationRulesProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "instructions",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "WithdrawSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solMip1FulfillSell",
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "referral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMetadata",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMasterEdition",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "payerAssetAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ownerTokenRecord",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "destinationTokenRecord",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "authorizationRules",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenMetadataProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "authorizationRulesProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "instructions",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "SolMip1FulfillSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solMip1FulfillBuy",
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "referral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMetadata",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMasterEdition",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "payerAssetAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ownerTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenOwnerTokenRecord",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "This is the token record for the seller"
+          ]
+        },
+        {
+          "name": "poolTokenRecord",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "This is the token record for the pool - will always be required"
+          ]
+        },
+        {
+          "name": "poolOwnerTokenRecord",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "This is the token record for the pool owner - will be required if reinvest = true"
+          ]
+        },
+        {
+          "name": "tokenMetadataProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "authorizationRulesProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "authorizationRules",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "instructions",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "SolFulfillBuyArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "getPoolQuote",
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "GetPoolQuoteArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "getMaxAffordableItems",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "GetMaxAffordableItemsArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "checkPoolSolvency",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "CheckPoolSolvencyArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "getPoolNotionalValue",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "GetPoolNotionalValueArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "validateMintAgainstAllowlist",
+      "accounts": [
+        {
+          "name": "assetMetadata",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMasterEdition",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "ValidateMintAgainstAllowlistArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "logSellState",
+      "accounts": [
+        {
+          "name": "sellState",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "closeIfBalanceInvalid",
+      "accounts": [
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "setSharedEscrow",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sharedEscrowAccount",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "SetSharedEscrowArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "reconcileSharedEscrow",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "addTakerFeeExemption",
+      "accounts": [
+        {
+          "name": "cosigner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "takerFeeExemption",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "AddTakerFeeExemptionArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "removeTakerFeeExemption",
+      "accounts": [
+        {
+          "name": "cosigner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "takerFeeExemption",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "extDepositSell",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "DepositSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solExtFulfillSell",
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "referral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "payerAssetAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "SolFulfillSellArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "solExtFulfillBuy",
+      "accounts": [
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "referral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "takerReferral",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "payerAssetAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "ownerTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "SolFulfillBuyArgs"
+          }
+        }
+      ]
+    },
+    {
+      "name": "extWithdrawSell",
+      "accounts": [
+        {
+          "name": "owner",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "cosigner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "pool",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "assetMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "assetTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "sellsideEscrowTokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "buysideSolEscrowAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "allowlistAuxAccount",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "sellState",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "associatedTokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "args",
+          "type": {
+            "defined": "WithdrawSellArgs"
+          }
+        }
+      ]
+    }
+  ],
+  "accounts": [
+    {
+      "name": "pool",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "spotPrice",
+            "type": "u64"
+          },
+          {
+            "name": "curveType",
+            "type": "u8"
+          },
+          {
+            "name": "curveDelta",
+            "type": "u64"
+          },
+          {
+            "name": "reinvestFulfillBuy",
+            "type": "bool"
+          },
+          {
+            "name": "reinvestFulfillSell",
+            "type": "bool"
+          },
+          {
+            "name": "expiry",
+            "type": "i64"
+          },
+          {
+            "name": "lpFeeBp",
+            "type": "u16"
+          },
+          {
+            "name": "referral",
+            "type": "publicKey"
+          },
+          {
+            "name": "referralBp",
+            "type": "u16"
+          },
+          {
+            "name": "buysideCreatorRoyaltyBp",
+            "type": "u16"
+          },
+          {
+            "name": "cosignerAnnotation",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "sellsideAssetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "lpFeeEarned",
+            "type": "u64"
+          },
+          {
+            "name": "owner",
+            "type": "publicKey"
+          },
+          {
+            "name": "cosigner",
+            "type": "publicKey"
+          },
+          {
+            "name": "uuid",
+            "type": "publicKey"
+          },
+          {
+            "name": "paymentMint",
+            "type": "publicKey"
+          },
+          {
+            "name": "allowlists",
+            "type": {
+              "array": [
+                {
+                  "defined": "Allowlist"
+                },
+                6
+              ]
+            }
+          },
+          {
+            "name": "buysidePaymentAmount",
+            "type": "u64"
+          },
+          {
+            "name": "sharedEscrowAccount",
+            "type": "publicKey"
+          },
+          {
+            "name": "sharedEscrowCount",
+            "type": "u64"
+          },
+          {
+            "name": "priceFloor",
+            "type": "u64"
+          },
+          {
+            "name": "priceCeiling",
+            "type": "u64"
+          },
+          {
+            "name": "allowlistMatchMode",
+            "type": "u8"
+          },
+          {
+            "name": "lpFeeDecayStart",
+            "type": "i64"
+          },
+          {
+            "name": "lpFeeDecayEnd",
+            "type": "i64"
+          },
+          {
+            "name": "lpFeeBpFloor",
+            "type": "u16"
+          },
+          {
+            "name": "lpFeeTiers",
+            "type": {
+              "array": [
+                {
+                  "defined": "LpFeeTier"
+                },
+                4
+              ]
+            }
+          },
+          {
+            "name": "minEscrowBalanceBp",
+            "type": "u16"
+          },
+          {
+            "name": "extraRoyaltyRecipients",
+            "type": {
+              "array": [
+                {
+                  "defined": "RoyaltyRecipient"
+                },
+                2
+              ]
+            }
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "maxSpotMoveBp",
+            "type": "u16"
+          },
+          {
+            "name": "lpFeeRounding",
+            "type": "u8"
+          },
+          {
+            "name": "maxFillDepth",
+            "type": "u16"
+          },
+          {
+            "name": "maxBuysideSpend",
+            "type": "u64"
+          },
+          {
+            "name": "buysideSpent",
+            "type": "u64"
+          },
+          {
+            "name": "poolSide",
+            "type": "u8"
+          },
+          {
+            "name": "requireSizedCollection",
+            "type": "bool"
+          },
+          {
+            "name": "totalVolumeSol",
+            "type": "u128"
+          },
+          {
+            "name": "fillCount",
+            "type": "u64"
+          },
+          {
+            "name": "rejectSelfReferral",
+            "type": "bool"
+          },
+          {
+            "name": "settleInWsol",
+            "type": "bool"
+          },
+          {
+            "name": "requireOneOfOne",
+            "type": "bool"
+          },
+          {
+            "name": "requireProgrammable",
+            "type": "bool"
+          },
+          {
+            "name": "curveDeltaSell",
+            "type": "u64"
+          },
+          {
+            "name": "royaltyEnforcementMode",
+            "type": "u8"
+          },
+          {
+            "name": "maxTotalPrice",
+            "type": "u64"
+          },
+          {
+            "name": "minFillAmount",
+            "type": "u64"
+          },
+          {
+            "name": "fillCooldownSecs",
+            "type": "i64"
+          },
+          {
+            "name": "lastFillTs",
+            "type": "i64"
+          },
+          {
+            "name": "sellStateRentToTaker",
+            "type": "bool"
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "royaltyFallbackAccount",
+            "type": "publicKey"
+          },
+          {
+            "name": "profitSkimBp",
+            "type": "u16"
+          },
+          {
+            "name": "oracle",
+            "type": "publicKey"
+          },
+          {
+            "name": "maxOracleDeviationBp",
+            "type": "u16"
+          },
+          {
+            "name": "requireAllCreatorsPaid",
+            "type": "bool"
+          },
+          {
+            "name": "curveDirection",
+            "type": "u8"
+          },
+          {
+            "name": "requireCollectionMetadata",
+            "type": "bool"
+          },
+          {
+            "name": "staticSpot",
+            "type": "bool"
+          },
+          {
+            "name": "allowlistsExt",
+            "type": {
+              "array": [
+                {
+                  "defined": "Allowlist"
+                },
+                2
+              ]
+            }
+          },
+          {
+            "name": "allowRoyaltyFallback",
+            "type": "bool"
+          },
+          {
+            "name": "logCurveBasePrice",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "sellState",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "poolOwner",
+            "type": "publicKey"
+          },
+          {
+            "name": "assetMint",
+            "type": "publicKey"
+          },
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "cosignerAnnotation",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "takerFeeExemption",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "taker",
+            "type": "publicKey"
+          }
+        ]
+      }
+    }
+  ],
+  "types": [
+    {
+      "name": "AddTakerFeeExemptionArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "taker",
+            "type": "publicKey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "CreatePoolArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "spotPrice",
+            "type": "u64"
+          },
+          {
+            "name": "curveType",
+            "type": "u8"
+          },
+          {
+            "name": "curveDelta",
+            "type": "u64"
+          },
+          {
+            "name": "reinvestFulfillBuy",
+            "type": "bool"
+          },
+          {
+            "name": "reinvestFulfillSell",
+            "type": "bool"
+          },
+          {
+            "name": "expiry",
+            "type": "i64"
+          },
+          {
+            "name": "lpFeeBp",
+            "type": "u16"
+          },
+          {
+            "name": "referral",
+            "type": "publicKey"
+          },
+          {
+            "name": "cosignerAnnotation",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "buysideCreatorRoyaltyBp",
+            "type": "u16"
+          },
+          {
+            "name": "priceFloor",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "priceCeiling",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "lpFeeDecayEnd",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "lpFeeBpFloor",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "lpFeeTiers",
+            "type": {
+              "option": {
+                "vec": {
+                  "defined": "LpFeeTier"
+                }
+              }
+            }
+          },
+          {
+            "name": "minEscrowBalanceBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "extraRoyaltyRecipients",
+            "type": {
+              "option": {
+                "vec": {
+                  "defined": "RoyaltyRecipient"
+                }
+              }
+            }
+          },
+          {
+            "name": "maxSpotMoveBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "lpFeeRounding",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "maxFillDepth",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "maxBuysideSpend",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "poolSide",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "requireSizedCollection",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "rejectSelfReferral",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "settleInWsol",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "requireOneOfOne",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "requireProgrammable",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "curveDeltaSell",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "royaltyEnforcementMode",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "maxTotalPrice",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "minFillAmount",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "fillCooldownSecs",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "sellStateRentToTaker",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          },
+          {
+            "name": "royaltyFallbackAccount",
+            "type": {
+              "option": "publicKey"
+            }
+          },
+          {
+            "name": "profitSkimBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "oracle",
+            "type": {
+              "option": "publicKey"
+            }
+          },
+          {
+            "name": "maxOracleDeviationBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "requireAllCreatorsPaid",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "curveDirection",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "requireCollectionMetadata",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "staticSpot",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "allowRoyaltyFallback",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "uuid",
+            "type": "publicKey"
+          },
+          {
+            "name": "paymentMint",
+            "type": "publicKey"
+          },
+          {
+            "name": "allowlists",
+            "type": {
+              "array": [
+                {
+                  "defined": "Allowlist"
+                },
+                8
+              ]
+            }
+          },
+          {
+            "name": "allowlistMatchMode",
+            "type": {
+              "option": "u8"
+            }
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "solExtFulfillBuy",
-      "accounts": [
-        {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "referral",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "payerAssetAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "ownerTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "associatedTokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "rent",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "SolFulfillBuyArgs"
+      "name": "RepricePoolArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "spotPrice",
+            "type": "u64"
+          },
+          {
+            "name": "curveDelta",
+            "type": {
+              "option": "u64"
+            }
           }
-        }
-      ]
+        ]
+      }
     },
     {
-      "name": "extWithdrawSell",
-      "accounts": [
-        {
-          "name": "owner",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "cosigner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "pool",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "assetMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "assetTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "sellsideEscrowTokenAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "buysideSolEscrowAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "allowlistAuxAccount",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "sellState",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "associatedTokenProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "args",
-          "type": {
-            "defined": "WithdrawSellArgs"
+      "name": "ResetPoolStatsArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "totalVolumeSol",
+            "type": "bool"
+          },
+          {
+            "name": "fillCount",
+            "type": "bool"
+          },
+          {
+            "name": "lpFeeEarned",
+            "type": "bool"
+          }
+        ]
+      }
+    },
+    {
+      "name": "StatsReset",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "totalVolumeSol",
+            "type": "u128"
+          },
+          {
+            "name": "fillCount",
+            "type": "u64"
+          },
+          {
+            "name": "lpFeeEarned",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "SetSharedEscrowArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "sharedEscrowCount",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "UpdateAllowlistsArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "allowlists",
+            "type": {
+              "array": [
+                {
+                  "defined": "Allowlist"
+                },
+                8
+              ]
+            }
+          },
+          {
+            "name": "allowlistMatchMode",
+            "type": {
+              "option": "u8"
+            }
           }
-        }
-      ]
-    }
-  ],
-  "accounts": [
+        ]
+      }
+    },
     {
-      "name": "pool",
+      "name": "UpdatePoolArgs",
       "type": {
         "kind": "struct",
         "fields": [
@@ -4518,78 +7805,433 @@ export const IDL: Mmm = {
             "type": "u16"
           },
           {
-            "name": "referral",
-            "type": "publicKey"
+            "name": "referral",
+            "type": "publicKey"
+          },
+          {
+            "name": "cosignerAnnotation",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "buysideCreatorRoyaltyBp",
+            "type": "u16"
+          },
+          {
+            "name": "priceFloor",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "priceCeiling",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "lpFeeDecayEnd",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "lpFeeBpFloor",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "lpFeeTiers",
+            "type": {
+              "option": {
+                "vec": {
+                  "defined": "LpFeeTier"
+                }
+              }
+            }
+          },
+          {
+            "name": "minEscrowBalanceBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "extraRoyaltyRecipients",
+            "type": {
+              "option": {
+                "vec": {
+                  "defined": "RoyaltyRecipient"
+                }
+              }
+            }
+          },
+          {
+            "name": "maxSpotMoveBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "lpFeeRounding",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "maxFillDepth",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "maxBuysideSpend",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "poolSide",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "requireSizedCollection",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "rejectSelfReferral",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "settleInWsol",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "requireOneOfOne",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "requireProgrammable",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "curveDeltaSell",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "royaltyEnforcementMode",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "maxTotalPrice",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "minFillAmount",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "fillCooldownSecs",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "sellStateRentToTaker",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          },
+          {
+            "name": "royaltyFallbackAccount",
+            "type": {
+              "option": "publicKey"
+            }
+          },
+          {
+            "name": "profitSkimBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "oracle",
+            "type": {
+              "option": "publicKey"
+            }
+          },
+          {
+            "name": "maxOracleDeviationBp",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "requireAllCreatorsPaid",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "curveDirection",
+            "type": {
+              "option": "u8"
+            }
+          },
+          {
+            "name": "requireCollectionMetadata",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "staticSpot",
+            "type": {
+              "option": "bool"
+            }
+          },
+          {
+            "name": "allowRoyaltyFallback",
+            "type": {
+              "option": "bool"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "SolMip1FulfillSellArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "maxPaymentAmount",
+            "type": "u64"
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "SolOcpFulfillSellArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "maxPaymentAmount",
+            "type": "u64"
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "CheckPoolSolvencyArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "fulfillBuy",
+            "type": "bool"
+          },
+          {
+            "name": "assetAmount",
+            "type": "u64"
+          },
+          {
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "PoolSolvency",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "fulfillBuy",
+            "type": "bool"
+          },
+          {
+            "name": "assetAmount",
+            "type": "u64"
           },
           {
-            "name": "referralBp",
-            "type": "u16"
+            "name": "required",
+            "type": "u64"
           },
           {
-            "name": "buysideCreatorRoyaltyBp",
-            "type": "u16"
+            "name": "available",
+            "type": "u64"
           },
           {
-            "name": "cosignerAnnotation",
-            "type": {
-              "array": [
-                "u8",
-                32
-              ]
-            }
-          },
+            "name": "solvent",
+            "type": "bool"
+          }
+        ]
+      }
+    },
+    {
+      "name": "DepositSellBatchArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
           {
-            "name": "sellsideAssetAmount",
+            "name": "assetAmount",
             "type": "u64"
           },
           {
-            "name": "lpFeeEarned",
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "DepositSellArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "assetAmount",
             "type": "u64"
           },
           {
-            "name": "owner",
-            "type": "publicKey"
-          },
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "GetMaxAffordableItemsArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
           {
-            "name": "cosigner",
-            "type": "publicKey"
+            "name": "budget",
+            "type": "u64"
           },
           {
-            "name": "uuid",
-            "type": "publicKey"
+            "name": "makerFeeBp",
+            "type": "i16"
           },
           {
-            "name": "paymentMint",
-            "type": "publicKey"
-          },
+            "name": "takerFeeBp",
+            "type": "i16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "MaxAffordableItems",
+      "type": {
+        "kind": "struct",
+        "fields": [
           {
-            "name": "allowlists",
-            "type": {
-              "array": [
-                {
-                  "defined": "Allowlist"
-                },
-                6
-              ]
-            }
+            "name": "budget",
+            "type": "u64"
           },
           {
-            "name": "buysidePaymentAmount",
+            "name": "assetAmount",
             "type": "u64"
-          },
+          }
+        ]
+      }
+    },
+    {
+      "name": "GetPoolNotionalValueArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
           {
-            "name": "sharedEscrowAccount",
-            "type": "publicKey"
+            "name": "makerFeeBp",
+            "type": "i16"
           },
           {
-            "name": "sharedEscrowCount",
-            "type": "u64"
+            "name": "takerFeeBp",
+            "type": "i16"
           }
         ]
       }
     },
     {
-      "name": "sellState",
+      "name": "PoolNotionalValue",
       "type": {
         "kind": "struct",
         "fields": [
@@ -4598,189 +8240,166 @@ export const IDL: Mmm = {
             "type": "publicKey"
           },
           {
-            "name": "poolOwner",
-            "type": "publicKey"
+            "name": "buysideValue",
+            "type": "u64"
           },
           {
-            "name": "assetMint",
-            "type": "publicKey"
+            "name": "sellsideValue",
+            "type": "u64"
           },
           {
-            "name": "assetAmount",
-            "type": "u64"
+            "name": "feesOwed",
+            "type": "i64"
           },
           {
-            "name": "cosignerAnnotation",
-            "type": {
-              "array": [
-                "u8",
-                32
-              ]
-            }
+            "name": "notionalValue",
+            "type": "u64"
           }
         ]
       }
-    }
-  ],
-  "types": [
+    },
     {
-      "name": "CreatePoolArgs",
+      "name": "GetPoolQuoteArgs",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "spotPrice",
+            "name": "fulfillBuy",
+            "type": "bool"
+          },
+          {
+            "name": "assetAmount",
             "type": "u64"
           },
           {
-            "name": "curveType",
-            "type": "u8"
+            "name": "metadataRoyaltyBp",
+            "type": "u16"
           },
           {
-            "name": "curveDelta",
-            "type": "u64"
+            "name": "makerFeeBp",
+            "type": "i16"
           },
           {
-            "name": "reinvestFulfillBuy",
-            "type": "bool"
+            "name": "takerFeeBp",
+            "type": "i16"
           },
           {
-            "name": "reinvestFulfillSell",
+            "name": "saturating",
             "type": "bool"
-          },
+          }
+        ]
+      }
+    },
+    {
+      "name": "PoolQuote",
+      "type": {
+        "kind": "struct",
+        "fields": [
           {
-            "name": "expiry",
-            "type": "i64"
+            "name": "totalPrice",
+            "type": "u64"
           },
           {
-            "name": "lpFeeBp",
-            "type": "u16"
+            "name": "nextPrice",
+            "type": "u64"
           },
           {
-            "name": "referral",
-            "type": "publicKey"
+            "name": "lpFee",
+            "type": "u64"
           },
           {
-            "name": "cosignerAnnotation",
-            "type": {
-              "array": [
-                "u8",
-                32
-              ]
-            }
+            "name": "makerFee",
+            "type": "i64"
           },
           {
-            "name": "buysideCreatorRoyaltyBp",
-            "type": "u16"
+            "name": "takerFee",
+            "type": "i64"
           },
           {
-            "name": "uuid",
-            "type": "publicKey"
+            "name": "referralFee",
+            "type": "u64"
           },
           {
-            "name": "paymentMint",
-            "type": "publicKey"
+            "name": "royaltyBp",
+            "type": "u16"
           },
           {
-            "name": "allowlists",
-            "type": {
-              "array": [
-                {
-                  "defined": "Allowlist"
-                },
-                6
-              ]
-            }
+            "name": "assetAmount",
+            "type": "u64"
           }
         ]
       }
     },
     {
-      "name": "SetSharedEscrowArgs",
+      "name": "SellStateSummary",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "sharedEscrowCount",
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "assetMint",
+            "type": "publicKey"
+          },
+          {
+            "name": "assetAmount",
             "type": "u64"
           }
         ]
       }
     },
     {
-      "name": "UpdateAllowlistsArgs",
+      "name": "SolDepositBuyArgs",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "allowlists",
-            "type": {
-              "array": [
-                {
-                  "defined": "Allowlist"
-                },
-                6
-              ]
-            }
+            "name": "paymentAmount",
+            "type": "u64"
           }
         ]
       }
     },
     {
-      "name": "UpdatePoolArgs",
+      "name": "SolFulfillBuyArgs",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "spotPrice",
+            "name": "assetAmount",
             "type": "u64"
           },
           {
-            "name": "curveType",
-            "type": "u8"
-          },
-          {
-            "name": "curveDelta",
+            "name": "minPaymentAmount",
             "type": "u64"
           },
           {
-            "name": "reinvestFulfillBuy",
-            "type": "bool"
-          },
-          {
-            "name": "reinvestFulfillSell",
-            "type": "bool"
-          },
-          {
-            "name": "expiry",
-            "type": "i64"
-          },
-          {
-            "name": "lpFeeBp",
-            "type": "u16"
-          },
-          {
-            "name": "referral",
-            "type": "publicKey"
-          },
-          {
-            "name": "cosignerAnnotation",
+            "name": "minPricePerItem",
             "type": {
-              "array": [
-                "u8",
-                32
-              ]
+              "option": "u64"
+            }
+          },
+          {
+            "name": "allowlistAux",
+            "type": {
+              "option": "string"
             }
           },
           {
-            "name": "buysideCreatorRoyaltyBp",
-            "type": "u16"
+            "name": "makerFeeBp",
+            "type": "i16"
+          },
+          {
+            "name": "takerFeeBp",
+            "type": "i16"
           }
         ]
       }
     },
     {
-      "name": "SolMip1FulfillSellArgs",
+      "name": "SolFulfillSellBatchArgs",
       "type": {
         "kind": "struct",
         "fields": [
@@ -4792,6 +8411,16 @@ export const IDL: Mmm = {
             "name": "maxPaymentAmount",
             "type": "u64"
           },
+          {
+            "name": "maxPricePerItem",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "buysideCreatorRoyaltyBp",
+            "type": "u16"
+          },
           {
             "name": "allowlistAux",
             "type": {
@@ -4810,7 +8439,7 @@ export const IDL: Mmm = {
       }
     },
     {
-      "name": "SolOcpFulfillSellArgs",
+      "name": "SolFulfillSellArgs",
       "type": {
         "kind": "struct",
         "fields": [
@@ -4822,6 +8451,16 @@ export const IDL: Mmm = {
             "name": "maxPaymentAmount",
             "type": "u64"
           },
+          {
+            "name": "maxPricePerItem",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "buysideCreatorRoyaltyBp",
+            "type": "u16"
+          },
           {
             "name": "allowlistAux",
             "type": {
@@ -4835,18 +8474,53 @@ export const IDL: Mmm = {
           {
             "name": "takerFeeBp",
             "type": "i16"
+          },
+          {
+            "name": "partialFillOk",
+            "type": "bool"
+          },
+          {
+            "name": "royaltyBpOverride",
+            "type": {
+              "option": "u16"
+            }
           }
         ]
       }
     },
     {
-      "name": "DepositSellArgs",
+      "name": "SolWithdrawBuyArgs",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "assetAmount",
+            "name": "paymentAmount",
             "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "ValidateMintAgainstAllowlistArgs",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "allowlists",
+            "type": {
+              "array": [
+                {
+                  "defined": "Allowlist"
+                },
+                8
+              ]
+            }
+          },
+          {
+            "name": "allowlistMatchMode",
+            "type": {
+              "option": "u8"
+            }
           },
           {
             "name": "allowlistAux",
@@ -4858,19 +8532,23 @@ export const IDL: Mmm = {
       }
     },
     {
-      "name": "SolDepositBuyArgs",
+      "name": "AllowlistMatch",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "paymentAmount",
-            "type": "u64"
+            "name": "assetMint",
+            "type": "publicKey"
+          },
+          {
+            "name": "kind",
+            "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "SolFulfillBuyArgs",
+      "name": "WithdrawSellArgs",
       "type": {
         "kind": "struct",
         "fields": [
@@ -4878,103 +8556,159 @@ export const IDL: Mmm = {
             "name": "assetAmount",
             "type": "u64"
           },
-          {
-            "name": "minPaymentAmount",
-            "type": "u64"
-          },
           {
             "name": "allowlistAux",
             "type": {
               "option": "string"
             }
+          }
+        ]
+      }
+    },
+    {
+      "name": "Allowlist",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "kind",
+            "type": "u8"
           },
           {
-            "name": "makerFeeBp",
-            "type": "i16"
+            "name": "value",
+            "type": "publicKey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "LpFeeTier",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "minAssetAmount",
+            "type": "u64"
           },
           {
-            "name": "takerFeeBp",
-            "type": "i16"
+            "name": "lpFeeBp",
+            "type": "u16"
           }
         ]
       }
     },
     {
-      "name": "SolFulfillSellArgs",
+      "name": "RoyaltyRecipient",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "address",
+            "type": "publicKey"
+          },
+          {
+            "name": "bp",
+            "type": "u16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "FillSummary",
       "type": {
         "kind": "struct",
         "fields": [
+          {
+            "name": "assetMint",
+            "type": "publicKey"
+          },
           {
             "name": "assetAmount",
             "type": "u64"
           },
           {
-            "name": "maxPaymentAmount",
+            "name": "totalPrice",
             "type": "u64"
           },
           {
-            "name": "buysideCreatorRoyaltyBp",
-            "type": "u16"
+            "name": "lpFee",
+            "type": "u64"
           },
           {
-            "name": "allowlistAux",
-            "type": {
-              "option": "string"
-            }
+            "name": "royaltyPaid",
+            "type": "u64"
           },
           {
-            "name": "makerFeeBp",
-            "type": "i16"
+            "name": "makerFee",
+            "type": "i64"
           },
           {
-            "name": "takerFeeBp",
-            "type": "i16"
+            "name": "takerFee",
+            "type": "i64"
+          },
+          {
+            "name": "spotPriceBefore",
+            "type": "u64"
+          },
+          {
+            "name": "spotPriceAfter",
+            "type": "u64"
           }
         ]
       }
     },
     {
-      "name": "SolWithdrawBuyArgs",
+      "name": "AccountClosed",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "paymentAmount",
+            "name": "pool",
+            "type": "publicKey"
+          },
+          {
+            "name": "owner",
+            "type": "publicKey"
+          },
+          {
+            "name": "lamports",
             "type": "u64"
           }
         ]
       }
     },
     {
-      "name": "WithdrawSellArgs",
+      "name": "PoolEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "assetAmount",
-            "type": "u64"
+            "name": "action",
+            "type": "u8"
           },
           {
-            "name": "allowlistAux",
-            "type": {
-              "option": "string"
-            }
+            "name": "pool",
+            "type": "publicKey"
           }
         ]
       }
     },
     {
-      "name": "Allowlist",
+      "name": "SharedEscrowWithdraw",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "kind",
-            "type": "u8"
+            "name": "pool",
+            "type": "publicKey"
           },
           {
-            "name": "value",
+            "name": "destination",
             "type": "publicKey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
           }
         ]
       }
@@ -5140,6 +8874,206 @@ export const IDL: Mmm = {
       "code": 6031,
       "name": "InvalidTokenMemberExtension",
       "msg": "Invalid token member extensions"
+    },
+    {
+      "code": 6032,
+      "name": "InvalidPriceFloor",
+      "msg": "invalid price_floor"
+    },
+    {
+      "code": 6033,
+      "name": "InvalidPriceCeiling",
+      "msg": "invalid price_ceiling"
+    },
+    {
+      "code": 6034,
+      "name": "InvalidExpiry",
+      "msg": "expiry must be in the future"
+    },
+    {
+      "code": 6035,
+      "name": "InvalidLpFeeDecay",
+      "msg": "invalid lp fee decay"
+    },
+    {
+      "code": 6036,
+      "name": "InvalidLpFeeTiers",
+      "msg": "invalid lp fee tiers"
+    },
+    {
+      "code": 6037,
+      "name": "InvalidExtraRoyaltyRecipients",
+      "msg": "invalid extra royalty recipients"
+    },
+    {
+      "code": 6038,
+      "name": "PriceSlippageExceeded",
+      "msg": "price slippage exceeded"
+    },
+    {
+      "code": 6039,
+      "name": "InvalidBatchSize",
+      "msg": "invalid batch size"
+    },
+    {
+      "code": 6040,
+      "name": "TransferFeeExceedsAmount",
+      "msg": "transfer fee exceeds the transferred amount"
+    },
+    {
+      "code": 6041,
+      "name": "MissingTransferHookAccounts",
+      "msg": "missing transfer hook accounts"
+    },
+    {
+      "code": 6042,
+      "name": "PoolAlreadyMigrated",
+      "msg": "pool already migrated"
+    },
+    {
+      "code": 6043,
+      "name": "TooManyRoyaltyCreators",
+      "msg": "too many royalty creators"
+    },
+    {
+      "code": 6044,
+      "name": "SpotPriceMoveExceeded",
+      "msg": "spot price move exceeds max_spot_move_bp"
+    },
+    {
+      "code": 6045,
+      "name": "InvalidLpFeeRounding",
+      "msg": "invalid lp fee rounding"
+    },
+    {
+      "code": 6046,
+      "name": "BuysideSpendExceeded",
+      "msg": "buyside spend exceeds max_buyside_spend"
+    },
+    {
+      "code": 6047,
+      "name": "InvalidPoolSide",
+      "msg": "invalid pool side"
+    },
+    {
+      "code": 6048,
+      "name": "PoolSideNotAllowed",
+      "msg": "the pool does not fulfill this side"
+    },
+    {
+      "code": 6049,
+      "name": "UnsizedCollection",
+      "msg": "collection is not a sized collection"
+    },
+    {
+      "code": 6050,
+      "name": "SelfReferral",
+      "msg": "referral can not be the pool owner or the counterparty"
+    },
+    {
+      "code": 6051,
+      "name": "MakerRebateExceedsTakerFee",
+      "msg": "maker rebate can not exceed the taker fee"
+    },
+    {
+      "code": 6052,
+      "name": "InsufficientWsolBalance",
+      "msg": "wsol balance is below the payment amount"
+    },
+    {
+      "code": 6053,
+      "name": "WsolSettlementNotSupported",
+      "msg": "wsol settlement is not supported by this fulfill"
+    },
+    {
+      "code": 6054,
+      "name": "NotOneOfOne",
+      "msg": "master edition allows prints, the pool only takes 1/1s"
+    },
+    {
+      "code": 6055,
+      "name": "InsufficientSharedEscrowBalance",
+      "msg": "shared escrow balance is below the withdraw amount"
+    },
+    {
+      "code": 6056,
+      "name": "InvalidRoyaltyEnforcementMode",
+      "msg": "invalid royalty enforcement mode"
+    },
+    {
+      "code": 6057,
+      "name": "InvalidMaxTotalPrice",
+      "msg": "max total price is below the spot price"
+    },
+    {
+      "code": 6058,
+      "name": "FillBelowMinFillAmount",
+      "msg": "fill asset amount is below the pool min_fill_amount"
+    },
+    {
+      "code": 6059,
+      "name": "InvalidFillCooldown",
+      "msg": "invalid fill cooldown"
+    },
+    {
+      "code": 6060,
+      "name": "FillCooldownActive",
+      "msg": "the pool was filled within its fill cooldown"
+    },
+    {
+      "code": 6061,
+      "name": "InvalidAllowlistAux",
+      "msg": "invalid allowlist aux"
+    },
+    {
+      "code": 6062,
+      "name": "InvalidCreatorShares",
+      "msg": "creator shares must sum to 100"
+    },
+    {
+      "code": 6063,
+      "name": "InvalidOracle",
+      "msg": "invalid oracle account"
+    },
+    {
+      "code": 6064,
+      "name": "OraclePriceDeviation",
+      "msg": "spot price deviates from the oracle price"
+    },
+    {
+      "code": 6065,
+      "name": "CreatorNotPaid",
+      "msg": "a royalty share is below the rent of its recipient account"
+    },
+    {
+      "code": 6066,
+      "name": "InvalidCurveDirection",
+      "msg": "invalid curve direction"
+    },
+    {
+      "code": 6067,
+      "name": "EscrowUnderfunded",
+      "msg": "buyside escrow balance is below the total price of the fill"
+    },
+    {
+      "code": 6068,
+      "name": "InvalidTokenProgram",
+      "msg": "token program does not match the mint"
+    },
+    {
+      "code": 6069,
+      "name": "FillExceedsMaxItems",
+      "msg": "fill asset amount is above the max items of a single fill"
+    },
+    {
+      "code": 6070,
+      "name": "InvalidRoyaltyBpOverride",
+      "msg": "royalty bp override is below the royalty bp of the mint"
+    },
+    {
+      "code": 6071,
+      "name": "InvalidPoolLayout",
+      "msg": "pool account layout can not be migrated"
     }
   ]
 };
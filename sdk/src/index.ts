@@ -4,3 +4,4 @@ export * from './pda';
 export * from './metadataProvider';
 export * from './mmmClient';
 export * from './price';
+export * from './merkle';
@@ -0,0 +1,71 @@
+import { AccountMeta, PublicKey } from '@solana/web3.js';
+import { createHash } from 'crypto';
+
+// leaves are sha256(0x00 || mint) and nodes are sha256(0x01 || a || b) with the
+// two children sorted, this needs to match get_merkle_root in the program
+const MERKLE_LEAF_PREFIX = Buffer.from([0]);
+const MERKLE_NODE_PREFIX = Buffer.from([1]);
+
+const sha256 = (...data: Buffer[]): Buffer =>
+  createHash('sha256').update(Buffer.concat(data)).digest();
+
+const getMerkleLeaf = (mint: PublicKey): Buffer =>
+  sha256(MERKLE_LEAF_PREFIX, mint.toBuffer());
+
+const hashMerkleNode = (a: Buffer, b: Buffer): Buffer =>
+  Buffer.compare(a, b) <= 0
+    ? sha256(MERKLE_NODE_PREFIX, a, b)
+    : sha256(MERKLE_NODE_PREFIX, b, a);
+
+// the layers of the tree from the leaves up to the root, an odd node at the end
+// of a layer moves up unchanged
+const getMerkleLayers = (mints: PublicKey[]): Buffer[][] => {
+  if (mints.length === 0) {
+    throw new Error('a merkle tree needs at least one mint');
+  }
+  const layers = [mints.map(getMerkleLeaf)];
+  while (layers[layers.length - 1].length > 1) {
+    const layer = layers[layers.length - 1];
+    const nextLayer: Buffer[] = [];
+    for (let i = 0; i < layer.length; i += 2) {
+      nextLayer.push(
+        i + 1 < layer.length
+          ? hashMerkleNode(layer[i], layer[i + 1])
+          : layer[i],
+      );
+    }
+    layers.push(nextLayer);
+  }
+  return layers;
+};
+
+// the value of a merkle allowlist
+export const getMerkleAllowlistValue = (mints: PublicKey[]): PublicKey => {
+  const layers = getMerkleLayers(mints);
+  return new PublicKey(layers[layers.length - 1][0]);
+};
+
+// the proof of mint goes first in the remaining accounts of the instruction,
+// each node is passed as the key of an account
+export const getMerkleProof = (
+  mints: PublicKey[],
+  mint: PublicKey,
+): AccountMeta[] => {
+  let index = mints.findIndex((m) => m.equals(mint));
+  if (index < 0) {
+    throw new Error(`mint ${mint.toBase58()} is not in the merkle tree`);
+  }
+  const proof: AccountMeta[] = [];
+  for (const layer of getMerkleLayers(mints).slice(0, -1)) {
+    const sibling = index ^ 1;
+    if (sibling < layer.length) {
+      proof.push({
+        pubkey: new PublicKey(layer[sibling]),
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    index >>= 1;
+  }
+  return proof;
+};
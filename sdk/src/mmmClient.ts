@@ -47,6 +47,12 @@ export const getEmptyAllowLists = (num: number) => {
   return new Array(num).fill(emptyAllowList);
 };
 
+// the allowlists of a fetched pool, the entries past the first 6 are kept in allowlistsExt
+export const getPoolAllowlists = <T>(pool: {
+  allowlists: T[];
+  allowlistsExt: T[];
+}): T[] => [...pool.allowlists, ...pool.allowlistsExt];
+
 export const MMMProgramID = new PublicKey(
   'mmm3XBJg5gk8XJxEKBvdgptZz6SgK4tXvn36sodowMc',
 );
@@ -298,6 +304,7 @@ export class MMMClient {
         assetMint,
         assetMetadata,
         referral: this.poolData.referral,
+        takerReferral: this.poolData.referral,
         cosigner: this.poolData.cosigner,
         payerAssetAccount: assetTokenAccount,
         ownerTokenAccount,
@@ -341,6 +348,7 @@ export class MMMClient {
           assetMetadata,
           assetMasterEdition,
           referral: this.poolData.referral,
+          takerReferral: this.poolData.referral,
           cosigner: this.poolData.cosigner,
           payerAssetAccount: assetTokenAccount,
           ownerTokenAccount,
@@ -366,6 +374,7 @@ export class MMMClient {
           assetMasterEdition,
           assetMetadata,
           referral: this.poolData.referral,
+          takerReferral: this.poolData.referral,
           cosigner: this.poolData.cosigner,
           payerAssetAccount: assetTokenAccount,
           ownerTokenAccount,
@@ -457,6 +466,7 @@ export class MMMClient {
           assetMint,
           assetMetadata,
           referral: this.poolData.referral,
+          takerReferral: this.poolData.referral,
           cosigner: this.poolData.cosigner,
           payerAssetAccount,
           sellState,
@@ -493,6 +503,7 @@ export class MMMClient {
             assetMasterEdition,
             assetMetadata,
             referral: this.poolData.referral,
+            takerReferral: this.poolData.referral,
             cosigner: this.poolData.cosigner,
             payerAssetAccount,
             sellState,
@@ -520,6 +531,7 @@ export class MMMClient {
           assetMasterEdition,
           assetMetadata,
           referral: this.poolData.referral,
+          takerReferral: this.poolData.referral,
           cosigner: this.poolData.cosigner,
           payerAssetAccount,
           sellState,
@@ -15,6 +15,14 @@ export const getMMMPoolPDA = (
   return { key, bump };
 };
 
+// a single-collection pool can be created with its collection mint as the
+// uuid, its address is then derived from the owner and the collection alone
+export const getMMMCollectionPoolPDA = (
+  programId: PublicKey,
+  owner: PublicKey,
+  collectionMint: PublicKey,
+) => getMMMPoolPDA(programId, owner, collectionMint);
+
 export const getMMMSellStatePDA = (
   programId: PublicKey,
   pool: PublicKey,
@@ -27,6 +35,22 @@ export const getMMMSellStatePDA = (
   return { key, bump };
 };
 
+export const getMMMTakerFeeExemptionPDA = (
+  programId: PublicKey,
+  pool: PublicKey,
+  taker: PublicKey,
+) => {
+  const [key, bump] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(PREFIXES.TAKER_FEE_EXEMPTION),
+      pool.toBuffer(),
+      taker.toBuffer(),
+    ],
+    programId,
+  );
+  return { key, bump };
+};
+
 export const getMMMBuysideSolEscrowPDA = (
   programId: PublicKey,
   pool: PublicKey,
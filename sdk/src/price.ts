@@ -1,4 +1,6 @@
-import { BN } from '@project-serum/anchor';
+import { BN, BorshCoder, IdlAccounts } from '@project-serum/anchor';
+import { PublicKey } from '@solana/web3.js';
+import { IDL, Mmm } from './idl/mmm';
 
 export interface GetFulfillBuyPricesParams {
   totalPriceLamports: number;
@@ -47,3 +49,183 @@ export const getSolFulfillBuyPrices = (args: GetFulfillBuyPricesParams) => {
     makerFeePaid,
   };
 };
+
+export interface PoolQuote {
+  totalPrice: BN;
+  nextPrice: BN;
+  lpFee: BN;
+  makerFee: BN;
+  takerFee: BN;
+  referralFee: BN;
+  royaltyBp: number;
+  assetAmount: BN;
+}
+
+// parses the borsh encoded data logged right after the `prefix` log line
+const getLoggedTypeFromLogs = <T>(
+  logs: string[],
+  prefix: string,
+  typeName: string,
+): T | null => {
+  const idx = logs.indexOf(`Program log: ${prefix}`);
+  if (idx < 0 || !logs[idx + 1]?.startsWith('Program data: ')) {
+    return null;
+  }
+  const data = Buffer.from(
+    logs[idx + 1].slice('Program data: '.length),
+    'base64',
+  );
+  return new BorshCoder(IDL).types.decode(typeName, data);
+};
+
+// parses the PoolQuote logged by a (simulated) getPoolQuote instruction
+export const getPoolQuoteFromLogs = (logs: string[]): PoolQuote | null =>
+  getLoggedTypeFromLogs(logs, 'pool_quote', 'PoolQuote');
+
+export interface MaxAffordableItems {
+  budget: BN;
+  assetAmount: BN;
+}
+
+// parses the MaxAffordableItems logged by a (simulated) getMaxAffordableItems instruction
+export const getMaxAffordableItemsFromLogs = (
+  logs: string[],
+): MaxAffordableItems | null =>
+  getLoggedTypeFromLogs(logs, 'max_affordable_items', 'MaxAffordableItems');
+
+export interface PoolSolvency {
+  fulfillBuy: boolean;
+  assetAmount: BN;
+  required: BN;
+  available: BN;
+  solvent: boolean;
+}
+
+// parses the PoolSolvency logged by a (simulated) checkPoolSolvency instruction
+export const getPoolSolvencyFromLogs = (logs: string[]): PoolSolvency | null =>
+  getLoggedTypeFromLogs(logs, 'pool_solvency', 'PoolSolvency');
+
+export interface PoolNotionalValue {
+  pool: PublicKey;
+  buysideValue: BN;
+  sellsideValue: BN;
+  feesOwed: BN;
+  notionalValue: BN;
+}
+
+// parses the PoolNotionalValue logged by a (simulated) getPoolNotionalValue
+// instruction
+export const getPoolNotionalValueFromLogs = (
+  logs: string[],
+): PoolNotionalValue | null =>
+  getLoggedTypeFromLogs(logs, 'pool_notional_value', 'PoolNotionalValue');
+
+export interface AllowlistMatch {
+  assetMint: PublicKey;
+  kind: number;
+}
+
+// parses the AllowlistMatch logged by a (simulated) validateMintAgainstAllowlist instruction
+export const getAllowlistMatchFromLogs = (
+  logs: string[],
+): AllowlistMatch | null =>
+  getLoggedTypeFromLogs(logs, 'allowlist_match', 'AllowlistMatch');
+
+export interface SellStateSummary {
+  pool: PublicKey;
+  assetMint: PublicKey;
+  assetAmount: BN;
+}
+
+// parses the SellStateSummary logged by a (simulated) logSellState instruction
+export const getSellStateSummaryFromLogs = (
+  logs: string[],
+): SellStateSummary | null =>
+  getLoggedTypeFromLogs(logs, 'sell_state', 'SellStateSummary');
+
+export interface FillSummary {
+  assetMint: PublicKey;
+  assetAmount: BN;
+  totalPrice: BN;
+  lpFee: BN;
+  royaltyPaid: BN;
+  makerFee: BN;
+  takerFee: BN;
+  spotPriceBefore: BN;
+  spotPriceAfter: BN; // the spot price the fill left the pool at
+}
+
+// parses the FillSummary logged at the end of every fulfill instruction
+export const getFillSummaryFromLogs = (logs: string[]): FillSummary | null =>
+  getLoggedTypeFromLogs(logs, 'fill_summary', 'FillSummary');
+
+export type AccountClosedKind =
+  | 'pool_closed'
+  | 'escrow_closed'
+  | 'sell_state_closed';
+
+export interface AccountClosed {
+  pool: PublicKey;
+  owner: PublicKey;
+  lamports: BN;
+}
+
+// parses the AccountClosed logged right before a pool, escrow or sell state is
+// closed automatically
+export const getAccountClosedFromLogs = (
+  logs: string[],
+  kind: AccountClosedKind,
+): AccountClosed | null => getLoggedTypeFromLogs(logs, kind, 'AccountClosed');
+
+export interface SharedEscrowWithdraw {
+  pool: PublicKey;
+  destination: PublicKey;
+  amount: BN;
+}
+
+// parses the SharedEscrowWithdraw logged after an M2 shared escrow withdraw
+export const getSharedEscrowWithdrawFromLogs = (
+  logs: string[],
+): SharedEscrowWithdraw | null =>
+  getLoggedTypeFromLogs(logs, 'shared_escrow_withdraw', 'SharedEscrowWithdraw');
+
+export interface StatsReset {
+  pool: PublicKey;
+  totalVolumeSol: BN;
+  fillCount: BN;
+  lpFeeEarned: BN;
+}
+
+// parses the StatsReset logged by resetPoolStats, it holds the counters from
+// before the reset
+export const getStatsResetFromLogs = (logs: string[]): StatsReset | null =>
+  getLoggedTypeFromLogs(logs, 'stats_reset', 'StatsReset');
+
+export type PoolEventKind = 'pool_created' | 'pool_updated';
+
+export interface PoolEvent {
+  action: number; // see PoolEventAction
+  pool: PublicKey;
+  state: IdlAccounts<Mmm>['pool'];
+}
+
+// parses the PoolEvent logged by createPool and updatePool, the pool state is
+// logged right after the event in the same data line
+export const getPoolEventFromLogs = (
+  logs: string[],
+  kind: PoolEventKind,
+): PoolEvent | null => {
+  const idx = logs.indexOf(`Program log: ${kind}`);
+  if (idx < 0 || !logs[idx + 1]?.startsWith('Program data: ')) {
+    return null;
+  }
+  const [event, state] = logs[idx + 1]
+    .slice('Program data: '.length)
+    .split(' ')
+    .map((data) => Buffer.from(data, 'base64'));
+  const coder = new BorshCoder(IDL);
+  return {
+    ...coder.types.decode('PoolEvent', event),
+    state: coder.accounts.decode('Pool', state),
+  };
+};
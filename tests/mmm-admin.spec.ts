@@ -13,9 +13,17 @@ import {
   CurveKind,
   AllowlistKind,
   getMMMPoolPDA,
+  getPoolEventFromLogs,
   MMMProgramID,
+  PoolEventAction,
+  getPoolAllowlists,
 } from '../sdk/src';
-import { airdrop, getEmptyAllowLists } from './utils';
+import {
+  airdrop,
+  createPool,
+  getEmptyAllowLists,
+  getTransactionLogs,
+} from './utils';
 
 describe('mmm-admin', () => {
   const { connection } = anchor.AnchorProvider.env();
@@ -29,6 +37,7 @@ describe('mmm-admin', () => {
     provider,
   ) as anchor.Program<Mmm>;
   const cosigner = Keypair.generate();
+  const expiry = new anchor.BN(Math.floor(Date.now() / 1000) + 24 * 60 * 60);
 
   beforeEach(async () => {
     await airdrop(connection, wallet.publicKey, 50);
@@ -47,7 +56,7 @@ describe('mmm-admin', () => {
         { kind: AllowlistKind.fvca, value: referral.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       await program.methods
@@ -57,7 +66,7 @@ describe('mmm-admin', () => {
           curveDelta: new anchor.BN(0),
           reinvestFulfillBuy: true,
           reinvestFulfillSell: true,
-          expiry: new anchor.BN(42),
+          expiry,
           lpFeeBp: 200,
           referral: referral.publicKey,
           cosignerAnnotation: new Array(32).fill(0),
@@ -66,6 +75,40 @@ describe('mmm-admin', () => {
           uuid: uuid.publicKey,
           paymentMint: PublicKey.default,
           allowlists,
+          priceFloor: null,
+          priceCeiling: null,
+          allowlistMatchMode: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
         })
         .accountsStrict({
           owner: wallet.publicKey,
@@ -82,7 +125,7 @@ describe('mmm-admin', () => {
       assert.equal(poolAccountInfo.curveDelta.toNumber(), 0);
       assert.isTrue(poolAccountInfo.reinvestFulfillBuy);
       assert.isTrue(poolAccountInfo.reinvestFulfillSell);
-      assert.equal(poolAccountInfo.expiry.toNumber(), 42);
+      assert.equal(poolAccountInfo.expiry.toNumber(), expiry.toNumber());
       assert.equal(poolAccountInfo.lpFeeBp, 200);
       assert.equal(
         poolAccountInfo.referral.toBase58(),
@@ -99,7 +142,40 @@ describe('mmm-admin', () => {
       assert.deepEqual(poolAccountInfo.cosigner, cosigner.publicKey);
       assert.deepEqual(poolAccountInfo.uuid, uuid.publicKey);
       assert.deepEqual(poolAccountInfo.paymentMint, PublicKey.default);
-      assert.deepEqual(poolAccountInfo.allowlists, allowlists);
+      assert.deepEqual(getPoolAllowlists(poolAccountInfo), allowlists);
+    });
+
+    it('holds as many allowlists as the max count', async () => {
+      const allowlists = Array.from({ length: 8 }, () => ({
+        kind: AllowlistKind.mint,
+        value: Keypair.generate().publicKey,
+      }));
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        allowlists,
+      });
+
+      const poolAccountInfo = await program.account.pool.fetch(poolKey);
+      assert.deepEqual(getPoolAllowlists(poolAccountInfo), allowlists);
+    });
+
+    it('can not create a pool with more allowlists than the max count', async () => {
+      const allowlists = Array.from({ length: 9 }, () => ({
+        kind: AllowlistKind.mint,
+        value: Keypair.generate().publicKey,
+      }));
+      try {
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          allowlists,
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        // the extra entry runs into the args after the fixed size allowlists
+        assert.include(`${e}`, 'InstructionDidNotDeserialize');
+      }
     });
   });
 
@@ -116,7 +192,7 @@ describe('mmm-admin', () => {
         { kind: AllowlistKind.fvca, value: referral.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       await program.methods
@@ -126,7 +202,7 @@ describe('mmm-admin', () => {
           curveDelta: new anchor.BN(0),
           reinvestFulfillBuy: true,
           reinvestFulfillSell: true,
-          expiry: new anchor.BN(42),
+          expiry,
           lpFeeBp: 200,
           referral: referral.publicKey,
           cosignerAnnotation: new Array(32).fill(0),
@@ -135,6 +211,40 @@ describe('mmm-admin', () => {
           uuid: uuid.publicKey,
           paymentMint: PublicKey.default,
           allowlists,
+          priceFloor: null,
+          priceCeiling: null,
+          allowlistMatchMode: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
         })
         .accountsStrict({
           owner: wallet.publicKey,
@@ -157,6 +267,39 @@ describe('mmm-admin', () => {
           referral: PublicKey.default,
           cosignerAnnotation: new Array(32).fill(0).map((_, index) => index),
           buysideCreatorRoyaltyBp: 0,
+          priceFloor: null,
+          priceCeiling: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
         })
         .accountsStrict({
           owner: wallet.publicKey,
@@ -186,7 +329,7 @@ describe('mmm-admin', () => {
       assert.deepEqual(poolAccountInfo.cosigner, cosigner.publicKey);
       assert.deepEqual(poolAccountInfo.uuid, uuid.publicKey);
       assert.deepEqual(poolAccountInfo.paymentMint, PublicKey.default);
-      assert.deepEqual(poolAccountInfo.allowlists, allowlists);
+      assert.deepEqual(getPoolAllowlists(poolAccountInfo), allowlists);
     });
   });
 
@@ -213,14 +356,14 @@ describe('mmm-admin', () => {
         { kind: AllowlistKind.fvca, value: fvca.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       const newAllowlists = [
         { kind: AllowlistKind.fvca, value: newFcva.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       const referral = Keypair.generate();
@@ -239,7 +382,7 @@ describe('mmm-admin', () => {
           curveDelta: new anchor.BN(0),
           reinvestFulfillBuy: true,
           reinvestFulfillSell: true,
-          expiry: new anchor.BN(42),
+          expiry,
           lpFeeBp: 200,
           referral: referral.publicKey,
           cosignerAnnotation: new Array(32).fill(0),
@@ -248,6 +391,40 @@ describe('mmm-admin', () => {
           uuid: uuid.publicKey,
           paymentMint: PublicKey.default,
           allowlists,
+          priceFloor: null,
+          priceCeiling: null,
+          allowlistMatchMode: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
         })
         .accountsStrict({
           owner: wallet.publicKey,
@@ -260,6 +437,7 @@ describe('mmm-admin', () => {
       await program.methods
         .updateAllowlists({
           allowlists: newAllowlists,
+          allowlistMatchMode: null,
         })
         .accountsStrict({
           cosigner: cosigner.publicKey,
@@ -276,7 +454,7 @@ describe('mmm-admin', () => {
       assert.equal(poolAccountInfo.curveDelta.toNumber(), 0);
       assert.isTrue(poolAccountInfo.reinvestFulfillBuy);
       assert.isTrue(poolAccountInfo.reinvestFulfillSell);
-      assert.equal(poolAccountInfo.expiry.toNumber(), 42);
+      assert.equal(poolAccountInfo.expiry.toNumber(), expiry.toNumber());
       assert.equal(poolAccountInfo.lpFeeBp, 200);
       assert.equal(
         poolAccountInfo.referral.toBase58(),
@@ -295,7 +473,7 @@ describe('mmm-admin', () => {
       assert.deepEqual(poolAccountInfo.paymentMint, PublicKey.default);
 
       // ...except for the allowlists.
-      assert.deepEqual(poolAccountInfo.allowlists, newAllowlists);
+      assert.deepEqual(getPoolAllowlists(poolAccountInfo), newAllowlists);
     });
 
     it('invalid authority cannot update', async () => {
@@ -308,14 +486,14 @@ describe('mmm-admin', () => {
         { kind: AllowlistKind.fvca, value: fvca.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       const newAllowlists = [
         { kind: AllowlistKind.fvca, value: newFcva.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       const referral = Keypair.generate();
@@ -334,7 +512,7 @@ describe('mmm-admin', () => {
           curveDelta: new anchor.BN(0),
           reinvestFulfillBuy: true,
           reinvestFulfillSell: true,
-          expiry: new anchor.BN(42),
+          expiry,
           lpFeeBp: 200,
           referral: referral.publicKey,
           cosignerAnnotation: new Array(32).fill(0),
@@ -343,6 +521,40 @@ describe('mmm-admin', () => {
           uuid: uuid.publicKey,
           paymentMint: PublicKey.default,
           allowlists,
+          priceFloor: null,
+          priceCeiling: null,
+          allowlistMatchMode: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
         })
         .accountsStrict({
           owner: wallet.publicKey,
@@ -357,6 +569,7 @@ describe('mmm-admin', () => {
         await program.methods
           .updateAllowlists({
             allowlists: newAllowlists,
+            allowlistMatchMode: null,
           })
           .accountsStrict({
             cosigner: inavlidAuthorityKeypair.publicKey,
@@ -376,7 +589,7 @@ describe('mmm-admin', () => {
         assert.strictEqual(err.error.errorCode.number, 6005);
 
         const poolAccountInfo = await program.account.pool.fetch(poolKey);
-        assert.deepEqual(poolAccountInfo.allowlists, allowlists);
+        assert.deepEqual(getPoolAllowlists(poolAccountInfo), allowlists);
       }
     });
 
@@ -388,14 +601,14 @@ describe('mmm-admin', () => {
         { kind: AllowlistKind.fvca, value: fvca.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       const newAllowlists = [
         { kind: AllowlistKind.fvca, value: newFcva.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       const referral = Keypair.generate();
@@ -414,7 +627,7 @@ describe('mmm-admin', () => {
           curveDelta: new anchor.BN(0),
           reinvestFulfillBuy: true,
           reinvestFulfillSell: true,
-          expiry: new anchor.BN(42),
+          expiry,
           lpFeeBp: 200,
           referral: referral.publicKey,
           cosignerAnnotation: new Array(32).fill(0),
@@ -423,6 +636,40 @@ describe('mmm-admin', () => {
           uuid: uuid.publicKey,
           paymentMint: PublicKey.default,
           allowlists,
+          priceFloor: null,
+          priceCeiling: null,
+          allowlistMatchMode: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
         })
         .accountsStrict({
           owner: wallet.publicKey,
@@ -437,6 +684,7 @@ describe('mmm-admin', () => {
         await program.methods
           .updateAllowlists({
             allowlists: newAllowlists,
+            allowlistMatchMode: null,
           })
           .accountsStrict({
             cosigner: wallet.publicKey,
@@ -455,7 +703,7 @@ describe('mmm-admin', () => {
         assert.strictEqual(err.error.errorCode.number, 6005);
 
         const poolAccountInfo = await program.account.pool.fetch(poolKey);
-        assert.deepEqual(poolAccountInfo.allowlists, allowlists);
+        assert.deepEqual(getPoolAllowlists(poolAccountInfo), allowlists);
       }
     });
 
@@ -467,14 +715,14 @@ describe('mmm-admin', () => {
         { kind: AllowlistKind.fvca, value: fvca.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       const newAllowlists = [
         { kind: AllowlistKind.fvca, value: newFcva.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       const referral = Keypair.generate();
@@ -493,7 +741,7 @@ describe('mmm-admin', () => {
           curveDelta: new anchor.BN(0),
           reinvestFulfillBuy: true,
           reinvestFulfillSell: true,
-          expiry: new anchor.BN(42),
+          expiry,
           lpFeeBp: 200,
           referral: referral.publicKey,
           cosignerAnnotation: new Array(32).fill(0),
@@ -502,6 +750,40 @@ describe('mmm-admin', () => {
           uuid: uuid.publicKey,
           paymentMint: PublicKey.default,
           allowlists,
+          priceFloor: null,
+          priceCeiling: null,
+          allowlistMatchMode: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
         })
         .accountsStrict({
           owner: wallet.publicKey,
@@ -518,6 +800,7 @@ describe('mmm-admin', () => {
       await program.methods
         .updateAllowlists({
           allowlists: newAllowlists,
+          allowlistMatchMode: null,
         })
         .accountsStrict({
           cosigner: wallet.publicKey,
@@ -527,7 +810,7 @@ describe('mmm-admin', () => {
         .rpc();
 
       poolAccountInfo = await program.account.pool.fetch(poolKey);
-      assert.deepEqual(poolAccountInfo.allowlists, newAllowlists);
+      assert.deepEqual(getPoolAllowlists(poolAccountInfo), newAllowlists);
     });
 
     it('cosigner cannot update pool they are not owner on', async () => {
@@ -543,14 +826,14 @@ describe('mmm-admin', () => {
         { kind: AllowlistKind.fvca, value: fvca.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       const newAllowlists = [
         { kind: AllowlistKind.fvca, value: newFcva.publicKey },
         { kind: AllowlistKind.mint, value: cosigner.publicKey },
         { kind: AllowlistKind.mcc, value: wallet.publicKey },
-        ...getEmptyAllowLists(3),
+        ...getEmptyAllowLists(5),
       ];
 
       const referral = Keypair.generate();
@@ -578,7 +861,7 @@ describe('mmm-admin', () => {
           curveDelta: new anchor.BN(0),
           reinvestFulfillBuy: true,
           reinvestFulfillSell: true,
-          expiry: new anchor.BN(42),
+          expiry,
           lpFeeBp: 200,
           referral: referral.publicKey,
           cosignerAnnotation: new Array(32).fill(0),
@@ -587,6 +870,40 @@ describe('mmm-admin', () => {
           uuid: uuid.publicKey,
           paymentMint: PublicKey.default,
           allowlists,
+          priceFloor: null,
+          priceCeiling: null,
+          allowlistMatchMode: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
         })
         .accountsStrict({
           owner: wallet.publicKey,
@@ -605,7 +922,7 @@ describe('mmm-admin', () => {
           curveDelta: new anchor.BN(0),
           reinvestFulfillBuy: true,
           reinvestFulfillSell: true,
-          expiry: new anchor.BN(42),
+          expiry,
           lpFeeBp: 200,
           referral: referral2.publicKey,
           cosignerAnnotation: new Array(32).fill(0),
@@ -614,6 +931,40 @@ describe('mmm-admin', () => {
           uuid: uuid2.publicKey,
           paymentMint: PublicKey.default,
           allowlists,
+          priceFloor: null,
+          priceCeiling: null,
+          allowlistMatchMode: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
         })
         .accountsStrict({
           owner: owner2.publicKey,
@@ -628,6 +979,7 @@ describe('mmm-admin', () => {
         await program.methods
           .updateAllowlists({
             allowlists: newAllowlists,
+            allowlistMatchMode: null,
           })
           .accountsStrict({
             cosigner: cosigner.publicKey,
@@ -649,7 +1001,234 @@ describe('mmm-admin', () => {
         assert.strictEqual(err.error.errorCode.number, 6005);
 
         const poolAccountInfo = await program.account.pool.fetch(poolKey);
-        assert.deepEqual(poolAccountInfo.allowlists, allowlists);
+        assert.deepEqual(getPoolAllowlists(poolAccountInfo), allowlists);
+      }
+    });
+  });
+
+  describe('Pool events', () => {
+    it('logs pool_created on create and pool_updated on update', async () => {
+      const referral = Keypair.generate();
+      const uuid = Keypair.generate();
+      const { key: poolKey } = getMMMPoolPDA(
+        program.programId,
+        wallet.publicKey,
+        uuid.publicKey,
+      );
+
+      const createTxId = await program.methods
+        .createPool({
+          spotPrice: new anchor.BN(1 * LAMPORTS_PER_SOL),
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(0),
+          reinvestFulfillBuy: true,
+          reinvestFulfillSell: true,
+          expiry,
+          lpFeeBp: 200,
+          referral: referral.publicKey,
+          cosignerAnnotation: new Array(32).fill(0),
+          buysideCreatorRoyaltyBp: 0,
+
+          uuid: uuid.publicKey,
+          paymentMint: PublicKey.default,
+          allowlists: [
+            { kind: AllowlistKind.fvca, value: referral.publicKey },
+            ...getEmptyAllowLists(7),
+          ],
+          priceFloor: null,
+          priceCeiling: null,
+          allowlistMatchMode: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
+        })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([cosigner])
+        .rpc();
+
+      const createLogs = await getTransactionLogs(connection, createTxId);
+      assert.isNull(getPoolEventFromLogs(createLogs, 'pool_updated'));
+      const created = getPoolEventFromLogs(createLogs, 'pool_created');
+      assert.isNotNull(created);
+      assert.equal(created!.action, PoolEventAction.created);
+      assert.equal(created!.pool.toBase58(), poolKey.toBase58());
+      assert.equal(created!.state.spotPrice.toNumber(), 1 * LAMPORTS_PER_SOL);
+      assert.equal(created!.state.lpFeeBp, 200);
+      assert.equal(
+        created!.state.owner.toBase58(),
+        wallet.publicKey.toBase58(),
+      );
+
+      const updateTxId = await program.methods
+        .updatePool({
+          spotPrice: new anchor.BN(2 * LAMPORTS_PER_SOL),
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(0),
+          reinvestFulfillBuy: true,
+          reinvestFulfillSell: true,
+          expiry,
+          lpFeeBp: 150,
+          referral: referral.publicKey,
+          cosignerAnnotation: new Array(32).fill(0),
+          buysideCreatorRoyaltyBp: 0,
+          priceFloor: null,
+          priceCeiling: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
+        })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolKey,
+        })
+        .signers([cosigner])
+        .rpc();
+
+      const updateLogs = await getTransactionLogs(connection, updateTxId);
+      assert.isNull(getPoolEventFromLogs(updateLogs, 'pool_created'));
+      const updated = getPoolEventFromLogs(updateLogs, 'pool_updated');
+      assert.isNotNull(updated);
+      assert.equal(updated!.action, PoolEventAction.updated);
+      assert.equal(updated!.pool.toBase58(), poolKey.toBase58());
+      assert.equal(updated!.state.spotPrice.toNumber(), 2 * LAMPORTS_PER_SOL);
+      assert.equal(updated!.state.lpFeeBp, 150);
+    });
+  });
+
+  describe('Can reprice sol mmm', () => {
+    const repricePool = (
+      poolKey: PublicKey,
+      spotPrice: anchor.BN,
+      curveDelta: anchor.BN | null = null,
+    ) =>
+      program.methods
+        .repricePool({ spotPrice, curveDelta })
+        .accountsStrict({ owner: wallet.publicKey, pool: poolKey })
+        .rpc();
+
+    it('updates the spot price and curve delta', async () => {
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+      });
+      const poolBefore = await program.account.pool.fetch(poolKey);
+
+      // signed by the owner alone
+      await repricePool(poolKey, new anchor.BN(2 * LAMPORTS_PER_SOL));
+      let pool = await program.account.pool.fetch(poolKey);
+      assert.equal(pool.spotPrice.toNumber(), 2 * LAMPORTS_PER_SOL);
+      assert.equal(pool.curveDelta.toNumber(), LAMPORTS_PER_SOL / 10);
+      assert.equal(pool.lpFeeBp, poolBefore.lpFeeBp);
+      assert.equal(pool.expiry.toNumber(), poolBefore.expiry.toNumber());
+      assert.equal(pool.referral.toBase58(), poolBefore.referral.toBase58());
+
+      await repricePool(
+        poolKey,
+        new anchor.BN(3 * LAMPORTS_PER_SOL),
+        new anchor.BN(LAMPORTS_PER_SOL / 5),
+      );
+      pool = await program.account.pool.fetch(poolKey);
+      assert.equal(pool.spotPrice.toNumber(), 3 * LAMPORTS_PER_SOL);
+      assert.equal(pool.curveDelta.toNumber(), LAMPORTS_PER_SOL / 5);
+    });
+
+    it('rejects a spot price below the price floor', async () => {
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(2 * LAMPORTS_PER_SOL),
+        priceFloor: new anchor.BN(LAMPORTS_PER_SOL),
+      });
+      try {
+        await repricePool(poolKey, new anchor.BN(LAMPORTS_PER_SOL / 2));
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidPriceFloor');
+      }
+      const pool = await program.account.pool.fetch(poolKey);
+      assert.equal(pool.spotPrice.toNumber(), 2 * LAMPORTS_PER_SOL);
+    });
+
+    it('can not be repriced by someone else than the owner', async () => {
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+      });
+      try {
+        await program.methods
+          .repricePool({
+            spotPrice: new anchor.BN(2 * LAMPORTS_PER_SOL),
+            curveDelta: null,
+          })
+          .accountsStrict({ owner: cosigner.publicKey, pool: poolKey })
+          .signers([cosigner])
+          .rpc();
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'ConstraintSeeds');
       }
     });
   });
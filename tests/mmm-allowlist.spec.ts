@@ -0,0 +1,845 @@
+import * as anchor from '@project-serum/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { AccountMeta, Keypair, PublicKey } from '@solana/web3.js';
+import { assert } from 'chai';
+import {
+  Mmm,
+  AllowlistKind,
+  AllowlistMatchMode,
+  CurveKind,
+  getAllowlistMatchFromLogs,
+  getMaxRoyaltyAllowlistValue,
+  getMerkleAllowlistValue,
+  getMerkleProof,
+  IDL,
+  MMMProgramID,
+  getPoolAllowlists,
+} from '../sdk/src';
+import {
+  airdrop,
+  assertFailedTx,
+  assertTx,
+  createPoolWithExampleDepositsUmi,
+  getEmptyAllowLists,
+  PoolData,
+  sellExtraNftToPool,
+} from './utils';
+import { toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
+import { MPL_TOKEN_METADATA_PROGRAM_ID } from '@metaplex-foundation/mpl-token-metadata';
+
+describe('mmm-allowlist', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const cosigner = Keypair.generate();
+
+  beforeEach(async () => {
+    await airdrop(connection, wallet.publicKey, 50);
+  });
+
+  const createBuysidePool = async (
+    kinds: AllowlistKind[],
+    seller: PublicKey,
+    poolArgs: {
+      allowlistMatchMode?: AllowlistMatchMode;
+      requireSizedCollection?: boolean;
+      requireCollectionMetadata?: boolean;
+    } = {},
+    sellerFeeBasisPoints?: number,
+    sizedCollection = false,
+  ) =>
+    createPoolWithExampleDepositsUmi(
+      program,
+      kinds,
+      {
+        owner: wallet.publicKey,
+        cosigner,
+        curveType: CurveKind.linear,
+        curveDelta: new anchor.BN(0),
+        expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+        ...poolArgs,
+      },
+      'buy',
+      TOKEN_PROGRAM_ID,
+      seller,
+      sellerFeeBasisPoints,
+      sizedCollection,
+    );
+
+  // the collection metadata and master edition go first in the remaining
+  // accounts
+  const getCollectionAccounts = (collectionMint: PublicKey): AccountMeta[] => {
+    const metadataProgramId = toWeb3JsPublicKey(MPL_TOKEN_METADATA_PROGRAM_ID);
+    const seeds = [
+      Buffer.from('metadata'),
+      metadataProgramId.toBuffer(),
+      collectionMint.toBuffer(),
+    ];
+    const [metadata] = PublicKey.findProgramAddressSync(
+      seeds,
+      metadataProgramId,
+    );
+    const [masterEdition] = PublicKey.findProgramAddressSync(
+      [...seeds, Buffer.from('edition')],
+      metadataProgramId,
+    );
+    return [
+      { pubkey: metadata, isSigner: false, isWritable: false },
+      { pubkey: masterEdition, isSigner: false, isWritable: false },
+    ];
+  };
+
+  const sellExtraNft = (poolData: PoolData, seller: Keypair) =>
+    sellExtraNftToPool(program, poolData, wallet.publicKey, cosigner, seller);
+
+  describe('allowlist match mode', () => {
+    // the collection is verified, but the first creator is not the
+    // collection mint, so only the mcc entry matches
+    it('accepts a mint matching only mcc in union mode', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createBuysidePool(
+          [AllowlistKind.mcc, AllowlistKind.fvca],
+          seller.publicKey,
+        ),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.equal(poolAccount.allowlistMatchMode, AllowlistMatchMode.union);
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertTx(txId, confirmedTx);
+    });
+
+    it('rejects a mint matching only mcc in intersection mode', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createBuysidePool(
+          [AllowlistKind.mcc, AllowlistKind.fvca],
+          seller.publicKey,
+          { allowlistMatchMode: AllowlistMatchMode.intersection },
+        ),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.equal(
+        poolAccount.allowlistMatchMode,
+        AllowlistMatchMode.intersection,
+      );
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertFailedTx(txId, confirmedTx);
+    });
+
+    it('accepts a mint matching every entry in intersection mode', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createBuysidePool([AllowlistKind.mcc], seller.publicKey, {
+          allowlistMatchMode: AllowlistMatchMode.intersection,
+        }),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertTx(txId, confirmedTx);
+    });
+  });
+
+  describe('update allowlists', () => {
+    const updateAllowlists = (
+      poolData: PoolData,
+      kind: AllowlistKind,
+      value: PublicKey,
+    ) =>
+      program.methods
+        .updateAllowlists({
+          allowlists: [{ kind, value }, ...getEmptyAllowLists(7)],
+          allowlistMatchMode: null,
+        })
+        .accountsStrict({
+          cosigner: cosigner.publicKey,
+          owner: wallet.publicKey,
+          pool: poolData.poolKey,
+        })
+        .signers([cosigner])
+        .rpc();
+
+    it('validates fills against the replaced allowlists', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createBuysidePool([AllowlistKind.mcc], seller.publicKey),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+
+      // the mcc entry is gone, and the fvca entry doesn't match the creator
+      await updateAllowlists(
+        poolData,
+        AllowlistKind.fvca,
+        Keypair.generate().publicKey,
+      );
+      {
+        const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+        assertFailedTx(txId, confirmedTx);
+      }
+
+      await updateAllowlists(
+        poolData,
+        AllowlistKind.fvca,
+        poolData.nftCreator.publicKey,
+      );
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.equal(poolAccount.allowlists[0].kind, AllowlistKind.fvca);
+      assert.isTrue(
+        getPoolAllowlists(poolAccount)
+          .slice(1)
+          .every((a) => a.kind === AllowlistKind.empty),
+      );
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertTx(txId, confirmedTx);
+    });
+
+    it('can not replace the allowlists with an invalid entry', async () => {
+      const poolData = await createBuysidePool(
+        [AllowlistKind.mcc],
+        wallet.publicKey,
+      );
+      try {
+        await updateAllowlists(poolData, AllowlistKind.fvca, PublicKey.default);
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidAllowLists');
+      }
+    });
+  });
+
+  describe('deny mint', () => {
+    it('rejects a denied mint even if it matches mcc', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createBuysidePool([AllowlistKind.mcc], seller.publicKey),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      const denied = {
+        kind: AllowlistKind.denyMint,
+        value: toWeb3JsPublicKey(poolData.extraNft.mintAddress),
+      };
+      await program.methods
+        .updateAllowlists({
+          allowlists: [
+            denied,
+            ...getPoolAllowlists(poolAccount).filter(
+              (a) => a.kind !== AllowlistKind.empty,
+            ),
+            ...getEmptyAllowLists(6),
+          ],
+          allowlistMatchMode: null,
+        })
+        .accountsStrict({
+          cosigner: cosigner.publicKey,
+          owner: wallet.publicKey,
+          pool: poolData.poolKey,
+        })
+        .signers([cosigner])
+        .rpc();
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertFailedTx(txId, confirmedTx);
+    });
+
+    it('accepts a mint that is not denied', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createBuysidePool([AllowlistKind.mcc], seller.publicKey),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      await program.methods
+        .updateAllowlists({
+          allowlists: [
+            {
+              kind: AllowlistKind.denyMint,
+              value: toWeb3JsPublicKey(poolData.nft.mintAddress),
+            },
+            ...getPoolAllowlists(poolAccount).filter(
+              (a) => a.kind !== AllowlistKind.empty,
+            ),
+            ...getEmptyAllowLists(6),
+          ],
+          allowlistMatchMode: null,
+        })
+        .accountsStrict({
+          cosigner: cosigner.publicKey,
+          owner: wallet.publicKey,
+          pool: poolData.poolKey,
+        })
+        .signers([cosigner])
+        .rpc();
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertTx(txId, confirmedTx);
+    });
+  });
+
+  describe('max royalty', () => {
+    // the example nfts are minted with a 500bp royalty
+    const createMaxRoyaltyPool = async (
+      seller: Keypair,
+      maxRoyaltyBp: number,
+    ) => {
+      const [poolData] = await Promise.all([
+        createBuysidePool([AllowlistKind.mcc], seller.publicKey, {}, 500),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      await program.methods
+        .updateAllowlists({
+          allowlists: [
+            {
+              kind: AllowlistKind.maxRoyalty,
+              value: getMaxRoyaltyAllowlistValue(maxRoyaltyBp),
+            },
+            ...getEmptyAllowLists(7),
+          ],
+          allowlistMatchMode: null,
+        })
+        .accountsStrict({
+          cosigner: cosigner.publicKey,
+          owner: wallet.publicKey,
+          pool: poolData.poolKey,
+        })
+        .signers([cosigner])
+        .rpc();
+      return poolData;
+    };
+
+    it('rejects a mint above the royalty ceiling', async () => {
+      const seller = Keypair.generate();
+      const poolData = await createMaxRoyaltyPool(seller, 300);
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertFailedTx(txId, confirmedTx);
+    });
+
+    it('accepts a mint under the royalty ceiling', async () => {
+      const seller = Keypair.generate();
+      const poolData = await createMaxRoyaltyPool(seller, 1000);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.equal(poolAccount.allowlists[0].kind, AllowlistKind.maxRoyalty);
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertTx(txId, confirmedTx);
+    });
+
+    it('can not set a royalty ceiling above 10000bp', async () => {
+      const seller = Keypair.generate();
+      try {
+        await createMaxRoyaltyPool(seller, 10001);
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidAllowLists');
+      }
+    });
+  });
+
+  describe('update authority', () => {
+    // the update authority entry narrows down the mcc entry of the pool
+    const createUpdateAuthorityPool = async (
+      seller: Keypair,
+      updateAuthority: (poolData: PoolData) => Promise<PublicKey>,
+    ) => {
+      const [poolData] = await Promise.all([
+        createBuysidePool([AllowlistKind.mcc], seller.publicKey, {
+          allowlistMatchMode: AllowlistMatchMode.intersection,
+        }),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      await updateAllowlists(poolData, [
+        {
+          kind: AllowlistKind.updateAuthority,
+          value: await updateAuthority(poolData),
+        },
+        poolAccount.allowlists[0],
+      ]);
+      return poolData;
+    };
+
+    const updateAllowlists = (
+      poolData: PoolData,
+      allowlists: { kind: number; value: PublicKey }[],
+      allowlistMatchMode: AllowlistMatchMode | null = null,
+    ) =>
+      program.methods
+        .updateAllowlists({
+          allowlists: [
+            ...allowlists,
+            ...getEmptyAllowLists(8 - allowlists.length),
+          ],
+          allowlistMatchMode,
+        })
+        .accountsStrict({
+          cosigner: cosigner.publicKey,
+          owner: wallet.publicKey,
+          pool: poolData.poolKey,
+        })
+        .signers([cosigner])
+        .rpc();
+
+    // the update authority follows the key byte of the metadata account
+    const getUpdateAuthority = async (poolData: PoolData) => {
+      const metadata = await connection.getAccountInfo(
+        toWeb3JsPublicKey(poolData.extraNft.metadataAddress),
+      );
+      return new PublicKey(metadata!.data.subarray(1, 33));
+    };
+    const getOtherAuthority = async () => Keypair.generate().publicKey;
+
+    it('accepts a mint with the update authority and the mcc', async () => {
+      const seller = Keypair.generate();
+      const poolData = await createUpdateAuthorityPool(
+        seller,
+        getUpdateAuthority,
+      );
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.equal(
+        poolAccount.allowlists[0].kind,
+        AllowlistKind.updateAuthority,
+      );
+      assert.equal(poolAccount.allowlists[1].kind, AllowlistKind.mcc);
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertTx(txId, confirmedTx);
+    });
+
+    it('rejects a mint with another update authority', async () => {
+      const seller = Keypair.generate();
+      const poolData = await createUpdateAuthorityPool(
+        seller,
+        getOtherAuthority,
+      );
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertFailedTx(txId, confirmedTx);
+    });
+
+    it('rejects update authority entries without a verifiable entry', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createBuysidePool([AllowlistKind.mcc], seller.publicKey),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      const updateAuthority = {
+        kind: AllowlistKind.updateAuthority,
+        value: await getUpdateAuthority(poolData),
+      };
+
+      // anyone can create metadata with this update authority, so it can't be
+      // the only entry that matches a mint
+      for (const [allowlists, allowlistMatchMode] of [
+        [[updateAuthority], AllowlistMatchMode.intersection],
+        [
+          [updateAuthority, poolAccount.allowlists[0]],
+          AllowlistMatchMode.union,
+        ],
+      ] as const) {
+        try {
+          await updateAllowlists(poolData, [...allowlists], allowlistMatchMode);
+          assert.fail('should have failed');
+        } catch (err) {
+          assert.include(`${err}`, 'InvalidAllowLists');
+        }
+      }
+    });
+  });
+
+  describe('merkle', () => {
+    // a tree of the extra nft and a few other mints, the proof of the extra
+    // nft has a node on every level
+    const createMerklePool = async (seller: Keypair, inTree = true) => {
+      const [poolData] = await Promise.all([
+        createBuysidePool([AllowlistKind.mcc], seller.publicKey),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const extraNftMint = toWeb3JsPublicKey(poolData.extraNft.mintAddress);
+      const mints = [
+        ...(inTree ? [extraNftMint] : []),
+        ...Array.from({ length: 4 }, () => Keypair.generate().publicKey),
+      ];
+      await program.methods
+        .updateAllowlists({
+          allowlists: [
+            {
+              kind: AllowlistKind.merkle,
+              value: getMerkleAllowlistValue(mints),
+            },
+            ...getEmptyAllowLists(7),
+          ],
+          allowlistMatchMode: null,
+        })
+        .accountsStrict({
+          cosigner: cosigner.publicKey,
+          owner: wallet.publicKey,
+          pool: poolData.poolKey,
+        })
+        .signers([cosigner])
+        .rpc();
+      return { poolData, mints };
+    };
+
+    const sellExtraNftWithProof = (
+      poolData: PoolData,
+      seller: Keypair,
+      proof: AccountMeta[],
+    ) =>
+      sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+        proof,
+      );
+
+    it('accepts a mint with a valid proof', async () => {
+      const seller = Keypair.generate();
+      const { poolData, mints } = await createMerklePool(seller);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.equal(poolAccount.allowlists[0].kind, AllowlistKind.merkle);
+
+      const proof = getMerkleProof(mints, mints[0]);
+      assert.equal(proof.length, 3);
+      const { txId, confirmedTx } = await sellExtraNftWithProof(
+        poolData,
+        seller,
+        proof,
+      );
+      assertTx(txId, confirmedTx);
+    });
+
+    it('rejects a mint with a tampered proof', async () => {
+      const seller = Keypair.generate();
+      const { poolData, mints } = await createMerklePool(seller);
+
+      const proof = getMerkleProof(mints, mints[0]);
+      proof[proof.length - 1] = {
+        ...proof[proof.length - 1],
+        pubkey: Keypair.generate().publicKey,
+      };
+      const { txId, confirmedTx } = await sellExtraNftWithProof(
+        poolData,
+        seller,
+        proof,
+      );
+      assertFailedTx(txId, confirmedTx);
+    });
+
+    it('rejects a mint that is not in the tree', async () => {
+      const seller = Keypair.generate();
+      const { poolData, mints } = await createMerklePool(seller, false);
+
+      // the proof of another mint doesn't hash the extra nft up to the root
+      const { txId, confirmedTx } = await sellExtraNftWithProof(
+        poolData,
+        seller,
+        getMerkleProof(mints, mints[0]),
+      );
+      assertFailedTx(txId, confirmedTx);
+    });
+  });
+
+  describe('sized collection', () => {
+    const createSizedCollectionPool = async (
+      seller: Keypair,
+      sizedCollection: boolean,
+    ) => {
+      const [poolData] = await Promise.all([
+        createBuysidePool(
+          [AllowlistKind.mcc],
+          seller.publicKey,
+          { requireSizedCollection: true },
+          undefined,
+          sizedCollection,
+        ),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.isTrue(poolAccount.requireSizedCollection);
+      return { poolData, collectionMint: poolAccount.allowlists[0].value };
+    };
+
+    it('accepts a mint in a sized collection', async () => {
+      const seller = Keypair.generate();
+      const { poolData, collectionMint } = await createSizedCollectionPool(
+        seller,
+        true,
+      );
+
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+        getCollectionAccounts(collectionMint),
+      );
+      assertTx(txId, confirmedTx);
+    });
+
+    it('rejects a mint in an unsized collection', async () => {
+      const seller = Keypair.generate();
+      const { poolData, collectionMint } = await createSizedCollectionPool(
+        seller,
+        false,
+      );
+
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+        getCollectionAccounts(collectionMint),
+      );
+      assertFailedTx(txId, confirmedTx);
+    });
+
+    it('rejects a mint without the collection accounts', async () => {
+      const seller = Keypair.generate();
+      const { poolData } = await createSizedCollectionPool(seller, true);
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertFailedTx(txId, confirmedTx);
+    });
+  });
+
+  describe('collection metadata', () => {
+    const createCollectionMetadataPool = async (seller: Keypair) => {
+      const [poolData] = await Promise.all([
+        createBuysidePool([AllowlistKind.mcc], seller.publicKey, {
+          requireCollectionMetadata: true,
+        }),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.isTrue(poolAccount.requireCollectionMetadata);
+      return { poolData, collectionMint: poolAccount.allowlists[0].value };
+    };
+
+    it('accepts a mint with the metadata of its verified collection', async () => {
+      // an unsized collection is enough without require_sized_collection
+      const seller = Keypair.generate();
+      const { poolData, collectionMint } = await createCollectionMetadataPool(
+        seller,
+      );
+
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+        getCollectionAccounts(collectionMint),
+      );
+      assertTx(txId, confirmedTx);
+    });
+
+    it('rejects the metadata of an unrelated collection mint', async () => {
+      const seller = Keypair.generate();
+      const [{ poolData }, { collectionMint: unrelatedCollectionMint }] =
+        await Promise.all([
+          createCollectionMetadataPool(seller),
+          createCollectionMetadataPool(Keypair.generate()),
+        ]);
+
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+        getCollectionAccounts(unrelatedCollectionMint),
+      );
+      assertFailedTx(txId, confirmedTx);
+    });
+
+    it('rejects a mint without the collection accounts', async () => {
+      const seller = Keypair.generate();
+      const { poolData } = await createCollectionMetadataPool(seller);
+
+      const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+      assertFailedTx(txId, confirmedTx);
+    });
+  });
+
+  describe('validate_mint_against_allowlist', () => {
+    // the extra nft is in a verified collection, with a verified first creator and a 100bp royalty
+    let poolData: PoolData;
+    let collectionMint: PublicKey;
+    before(async () => {
+      await airdrop(connection, wallet.publicKey, 50);
+      poolData = await createBuysidePool(
+        [AllowlistKind.mcc],
+        Keypair.generate().publicKey,
+      );
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      collectionMint = poolAccount.allowlists[0].value;
+    });
+
+    const validate = (
+      allowlists: { kind: AllowlistKind; value: PublicKey }[],
+      allowlistMatchMode: AllowlistMatchMode | null = null,
+      allowlistAux: string | null = null,
+    ) =>
+      program.methods
+        .validateMintAgainstAllowlist({
+          allowlists: [
+            ...allowlists,
+            ...getEmptyAllowLists(8 - allowlists.length),
+          ],
+          allowlistMatchMode,
+          allowlistAux,
+        })
+        .accountsStrict({
+          assetMetadata: toWeb3JsPublicKey(poolData.extraNft.metadataAddress),
+          assetMasterEdition: toWeb3JsPublicKey(
+            poolData.extraNft.masterEditionAddress,
+          ),
+          assetMint: toWeb3JsPublicKey(poolData.extraNft.mintAddress),
+        })
+        .simulate();
+
+    const assertMatchedKind = async (
+      simulation: ReturnType<typeof validate>,
+      kind: AllowlistKind,
+    ) => {
+      const { raw } = await simulation;
+      const allowlistMatch = getAllowlistMatchFromLogs([...raw]);
+      assert.isNotNull(allowlistMatch);
+      assert.equal(allowlistMatch!.kind, kind);
+      assert.equal(
+        allowlistMatch!.assetMint.toBase58(),
+        toWeb3JsPublicKey(poolData.extraNft.mintAddress).toBase58(),
+      );
+    };
+
+    it('logs the matched fvca entry', async () => {
+      await assertMatchedKind(
+        validate([
+          { kind: AllowlistKind.mint, value: poolData.poolKey },
+          { kind: AllowlistKind.fvca, value: poolData.nftCreator.publicKey },
+        ]),
+        AllowlistKind.fvca,
+      );
+    });
+
+    it('logs the matched mint entry', async () => {
+      await assertMatchedKind(
+        validate([
+          {
+            kind: AllowlistKind.mint,
+            value: toWeb3JsPublicKey(poolData.extraNft.mintAddress),
+          },
+        ]),
+        AllowlistKind.mint,
+      );
+    });
+
+    it('logs the matched mcc entry', async () => {
+      await assertMatchedKind(
+        validate([{ kind: AllowlistKind.mcc, value: collectionMint }]),
+        AllowlistKind.mcc,
+      );
+    });
+
+    it('logs the matched max royalty entry', async () => {
+      await assertMatchedKind(
+        validate([
+          {
+            kind: AllowlistKind.maxRoyalty,
+            value: getMaxRoyaltyAllowlistValue(100),
+          },
+        ]),
+        AllowlistKind.maxRoyalty,
+      );
+    });
+
+    it('logs the any entry', async () => {
+      await assertMatchedKind(
+        validate([{ kind: AllowlistKind.any, value: PublicKey.default }]),
+        AllowlistKind.any,
+      );
+    });
+
+    it('matches the mint entry first regardless of the entry order', async () => {
+      // every entry matches, and the mint entry is the cheapest to check
+      const mintEntry = {
+        kind: AllowlistKind.mint,
+        value: toWeb3JsPublicKey(poolData.extraNft.mintAddress),
+      };
+      const fvcaEntry = {
+        kind: AllowlistKind.fvca,
+        value: poolData.nftCreator.publicKey,
+      };
+      const mccEntry = { kind: AllowlistKind.mcc, value: collectionMint };
+      for (const allowlists of [
+        [fvcaEntry, mccEntry, mintEntry],
+        [mccEntry, fvcaEntry, mintEntry],
+        [mintEntry, fvcaEntry, mccEntry],
+        [fvcaEntry, mintEntry, mccEntry],
+      ]) {
+        await assertMatchedKind(validate(allowlists), AllowlistKind.mint);
+      }
+    });
+
+    it('logs the first entry in intersection mode', async () => {
+      await assertMatchedKind(
+        validate(
+          [
+            {
+              kind: AllowlistKind.metadata,
+              value: toWeb3JsPublicKey(poolData.extraNft.metadataAddress),
+            },
+            { kind: AllowlistKind.mcc, value: collectionMint },
+          ],
+          AllowlistMatchMode.intersection,
+          'nft://',
+        ),
+        AllowlistKind.metadata,
+      );
+    });
+
+    it('fails for a denied mint or a mint that matches no entry', async () => {
+      for (const allowlists of [
+        [
+          {
+            kind: AllowlistKind.denyMint,
+            value: toWeb3JsPublicKey(poolData.extraNft.mintAddress),
+          },
+          { kind: AllowlistKind.mcc, value: collectionMint },
+        ],
+        [{ kind: AllowlistKind.fvca, value: collectionMint }],
+        [
+          {
+            kind: AllowlistKind.maxRoyalty,
+            value: getMaxRoyaltyAllowlistValue(99),
+          },
+        ],
+      ]) {
+        try {
+          await validate(allowlists);
+          assert.fail('should have failed');
+        } catch (e) {
+          assert.include(`${e}`, 'InvalidAllowLists');
+        }
+      }
+    });
+  });
+});
@@ -22,6 +22,7 @@ import {
   getMMMSellStatePDA,
   IDL,
   MMMProgramID,
+  getPoolAllowlists,
 } from '../sdk/src';
 import {
   airdrop,
@@ -78,9 +79,9 @@ describe('mmm-any-allowlist', () => {
         ]);
 
         const poolAccount = await program.account.pool.fetch(poolData.poolKey);
-        assert.deepEqual(poolAccount.allowlists, [
+        assert.deepEqual(getPoolAllowlists(poolAccount), [
           { kind: AllowlistKind.any, value: PublicKey.default },
-          ...getEmptyAllowLists(5),
+          ...getEmptyAllowLists(7),
         ]);
 
         const ownerExtraNftAtaAddress = await getAssociatedTokenAddress(
@@ -101,12 +102,14 @@ describe('mmm-any-allowlist', () => {
             allowlistAux: null,
             takerFeeBp: 100,
             makerFeeBp: 0,
+            minPricePerItem: null,
           })
           .accountsStrict({
             payer: seller.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: toWeb3JsPublicKey(poolData.extraNft.metadataAddress),
@@ -176,12 +179,16 @@ describe('mmm-any-allowlist', () => {
             allowlistAux: null,
             makerFeeBp: 100,
             takerFeeBp: 100,
+            maxPricePerItem: null,
+            partialFillOk: false,
+            royaltyBpOverride: null,
           })
           .accountsStrict({
             payer: buyer.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: toWeb3JsPublicKey(poolData.nft.metadataAddress),
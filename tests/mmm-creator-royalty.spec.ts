@@ -9,6 +9,7 @@ import {
 import {
   Keypair,
   LAMPORTS_PER_SOL,
+  PublicKey,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
 } from '@solana/web3.js';
@@ -21,10 +22,13 @@ import {
   IDL,
   MMMProgramID,
   getSolFulfillBuyPrices,
+  RoyaltyEnforcementMode,
 } from '../sdk/src';
 import {
   airdrop,
+  assertFailedTx,
   assertTx,
+  createPool,
   createPoolWithExampleDeposits,
   createPoolWithExampleDepositsUmi,
   getMetaplexInstance,
@@ -34,8 +38,16 @@ import {
   SIGNATURE_FEE_LAMPORTS,
   getTokenAccount2022,
   IMMUTABLE_OWNER_EXTENSION_LAMPORTS,
+  sellExtraNftToPool,
+  umiMintNfts,
 } from './utils';
-import { toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
+import {
+  fromWeb3JsPublicKey,
+  toWeb3JsPublicKey,
+} from '@metaplex-foundation/umi-web3js-adapters';
+import { generateSigner } from '@metaplex-foundation/umi';
+import { createUmi } from '@metaplex-foundation/umi-bundle-tests';
+import { mplTokenMetadata } from '@metaplex-foundation/mpl-token-metadata';
 
 describe('mmm-creator-royalty', () => {
   const TOKEN_PROGRAM_IDS = [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID];
@@ -134,12 +146,14 @@ describe('mmm-creator-royalty', () => {
               allowlistAux: null,
               takerFeeBp: 100,
               makerFeeBp: 0,
+              minPricePerItem: null,
             })
             .accountsStrict({
               payer: seller.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: toWeb3JsPublicKey(
@@ -261,12 +275,16 @@ describe('mmm-creator-royalty', () => {
               allowlistAux: '',
               takerFeeBp: 100,
               makerFeeBp: 100,
+              maxPricePerItem: null,
+              partialFillOk: false,
+              royaltyBpOverride: null,
             })
             .accountsStrict({
               payer: buyer.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: toWeb3JsPublicKey(poolData.nft.metadataAddress),
@@ -359,4 +377,707 @@ describe('mmm-creator-royalty', () => {
       });
     });
   });
+
+  describe('pool buyside creator royalty bp', () => {
+    [10000, 0].forEach((buysideCreatorRoyaltyBp) => {
+      it(`pays ${buysideCreatorRoyaltyBp} bp of the royalty on a buy fill`, async () => {
+        const seller = Keypair.generate();
+        const [poolData] = await Promise.all([
+          createPoolWithExampleDepositsUmi(
+            program,
+            [AllowlistKind.mint],
+            {
+              owner: wallet.publicKey,
+              cosigner,
+              curveType: CurveKind.linear,
+              curveDelta: new anchor.BN(0),
+              expiry: new anchor.BN(0),
+              lpFeeBp: 0,
+              buysideCreatorRoyaltyBp,
+            },
+            'buy',
+            TOKEN_PROGRAM_ID,
+            seller.publicKey,
+          ),
+          airdrop(connection, seller.publicKey, 10),
+        ]);
+        const initCreatorBalance = await connection.getBalance(
+          poolData.nftCreator.publicKey,
+        );
+
+        const { txId, confirmedTx } = await sellExtraNftToPool(
+          program,
+          poolData,
+          wallet.publicKey,
+          cosigner,
+          seller,
+          [
+            {
+              pubkey: poolData.nftCreator.publicKey,
+              isSigner: false,
+              isWritable: true,
+            },
+          ],
+        );
+        assertTx(txId, confirmedTx);
+
+        const { royaltyPaid } = getSolFulfillBuyPrices({
+          totalPriceLamports: LAMPORTS_PER_SOL,
+          lpFeeBp: 0,
+          takerFeeBp: 0,
+          metadataRoyaltyBp: 100,
+          buysideCreatorRoyaltyBp,
+          makerFeeBp: 0,
+        });
+        assert.equal(
+          royaltyPaid.toNumber(),
+          buysideCreatorRoyaltyBp === 0 ? 0 : LAMPORTS_PER_SOL / 100,
+        );
+        assert.equal(
+          await connection.getBalance(poolData.nftCreator.publicKey),
+          initCreatorBalance + royaltyPaid.toNumber(),
+        );
+      });
+    });
+
+    it('can not commit to more than the full royalty', async () => {
+      try {
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          buysideCreatorRoyaltyBp: 10001,
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidBP');
+      }
+    });
+  });
+
+  describe('royalty enforcement mode', () => {
+    [
+      { mode: RoyaltyEnforcementMode.optional, royaltyBp: 5000 },
+      { mode: RoyaltyEnforcementMode.enforce, royaltyBp: 10000 },
+      { mode: RoyaltyEnforcementMode.bypass, royaltyBp: 0 },
+    ].forEach(({ mode, royaltyBp }) => {
+      it(`pays ${royaltyBp} bp of the royalty in mode ${mode}`, async () => {
+        const seller = Keypair.generate();
+        const [poolData] = await Promise.all([
+          createPoolWithExampleDepositsUmi(
+            program,
+            [AllowlistKind.mint],
+            {
+              owner: wallet.publicKey,
+              cosigner,
+              curveType: CurveKind.linear,
+              curveDelta: new anchor.BN(0),
+              expiry: new anchor.BN(0),
+              lpFeeBp: 0,
+              buysideCreatorRoyaltyBp: 5000,
+              royaltyEnforcementMode: mode,
+            },
+            'buy',
+            TOKEN_PROGRAM_ID,
+            seller.publicKey,
+          ),
+          airdrop(connection, seller.publicKey, 10),
+        ]);
+        const initCreatorBalance = await connection.getBalance(
+          poolData.nftCreator.publicKey,
+        );
+
+        const { txId, confirmedTx } = await sellExtraNftToPool(
+          program,
+          poolData,
+          wallet.publicKey,
+          cosigner,
+          seller,
+          [
+            {
+              pubkey: poolData.nftCreator.publicKey,
+              isSigner: false,
+              isWritable: true,
+            },
+          ],
+        );
+        assertTx(txId, confirmedTx);
+
+        const { royaltyPaid } = getSolFulfillBuyPrices({
+          totalPriceLamports: LAMPORTS_PER_SOL,
+          lpFeeBp: 0,
+          takerFeeBp: 0,
+          metadataRoyaltyBp: 100,
+          buysideCreatorRoyaltyBp: royaltyBp,
+          makerFeeBp: 0,
+        });
+        assert.equal(
+          await connection.getBalance(poolData.nftCreator.publicKey),
+          initCreatorBalance + royaltyPaid.toNumber(),
+        );
+      });
+    });
+
+    it('can not be set to an unknown mode', async () => {
+      try {
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          royaltyEnforcementMode: RoyaltyEnforcementMode.bypass + 1,
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidRoyaltyEnforcementMode');
+      }
+    });
+  });
+
+  describe('royalty bp override', () => {
+    // buys the nft out of a sellside pool at 1 SOL, the example nfts carry a
+    // 100bp royalty
+    const buyNftWithOverride = async (royaltyBpOverride: number) => {
+      const buyer = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mint],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            lpFeeBp: 0,
+          },
+          'sell',
+          TOKEN_PROGRAM_ID,
+          buyer.publicKey,
+        ),
+        airdrop(connection, buyer.publicKey, 10),
+      ]);
+      const initCreatorBalance = await connection.getBalance(
+        poolData.nftCreator.publicKey,
+      );
+      const nftMint = toWeb3JsPublicKey(poolData.nft.mintAddress);
+      const tx = await program.methods
+        .solFulfillSell({
+          assetAmount: new anchor.BN(1),
+          maxPaymentAmount: new anchor.BN(2 * LAMPORTS_PER_SOL),
+          buysideCreatorRoyaltyBp: 10000,
+          allowlistAux: '',
+          takerFeeBp: 0,
+          makerFeeBp: 0,
+          maxPricePerItem: null,
+          partialFillOk: false,
+          royaltyBpOverride,
+        })
+        .accountsStrict({
+          payer: buyer.publicKey,
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
+          pool: poolData.poolKey,
+          buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+          assetMetadata: toWeb3JsPublicKey(poolData.nft.metadataAddress),
+          assetMasterEdition: toWeb3JsPublicKey(
+            poolData.nft.masterEditionAddress,
+          ),
+          assetMint: nftMint,
+          sellsideEscrowTokenAccount: poolData.poolAtaNft,
+          payerAssetAccount: await getAssociatedTokenAddress(
+            nftMint,
+            buyer.publicKey,
+            true,
+            TOKEN_PROGRAM_ID,
+          ),
+          allowlistAuxAccount: SystemProgram.programId,
+          sellState: getMMMSellStatePDA(
+            program.programId,
+            poolData.poolKey,
+            nftMint,
+          ).key,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .remainingAccounts([
+          {
+            pubkey: poolData.nftCreator.publicKey,
+            isSigner: false,
+            isWritable: true,
+          },
+        ])
+        .transaction();
+
+      const blockhashData = await connection.getLatestBlockhash();
+      tx.feePayer = buyer.publicKey;
+      tx.recentBlockhash = blockhashData.blockhash;
+      tx.partialSign(cosigner, buyer);
+      const txId = await connection.sendRawTransaction(tx.serialize(), {
+        skipPreflight: true,
+      });
+      const confirmedTx = await connection.confirmTransaction(
+        {
+          signature: txId,
+          blockhash: blockhashData.blockhash,
+          lastValidBlockHeight: blockhashData.lastValidBlockHeight,
+        },
+        'processed',
+      );
+      const creatorPaid =
+        (await connection.getBalance(poolData.nftCreator.publicKey)) -
+        initCreatorBalance;
+      return { txId, confirmedTx, creatorPaid };
+    };
+
+    it('pays the creators a royalty above the metadata royalty', async () => {
+      const { txId, confirmedTx, creatorPaid } = await buyNftWithOverride(500);
+      assertTx(txId, confirmedTx);
+      assert.equal(creatorPaid, LAMPORTS_PER_SOL * 0.05);
+    });
+
+    it('rejects a royalty below the metadata royalty', async () => {
+      const { txId, confirmedTx, creatorPaid } = await buyNftWithOverride(50);
+      assertFailedTx(txId, confirmedTx);
+      // 0x17b6 InvalidRoyaltyBpOverride
+      assert.include(JSON.stringify(confirmedTx.value.err), '"Custom":6070');
+      assert.equal(creatorPaid, 0);
+    });
+  });
+
+  describe('extra royalty recipients', () => {
+    it('pays the extra recipient its bp slice and the creators the rest', async () => {
+      const seller = Keypair.generate();
+      const extraRecipient = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mint],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            lpFeeBp: 0,
+            buysideCreatorRoyaltyBp: 10000,
+            extraRoyaltyRecipients: [
+              { address: extraRecipient.publicKey, bp: 2000 },
+            ],
+          },
+          'buy',
+          TOKEN_PROGRAM_ID,
+          seller.publicKey,
+        ),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const initCreatorBalance = await connection.getBalance(
+        poolData.nftCreator.publicKey,
+      );
+
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+        [
+          {
+            pubkey: poolData.nftCreator.publicKey,
+            isSigner: false,
+            isWritable: true,
+          },
+          {
+            pubkey: extraRecipient.publicKey,
+            isSigner: false,
+            isWritable: true,
+          },
+        ],
+      );
+      assertTx(txId, confirmedTx);
+
+      const { royaltyPaid } = getSolFulfillBuyPrices({
+        totalPriceLamports: LAMPORTS_PER_SOL,
+        lpFeeBp: 0,
+        takerFeeBp: 0,
+        metadataRoyaltyBp: 100,
+        buysideCreatorRoyaltyBp: 10000,
+        makerFeeBp: 0,
+      });
+      const expectedExtraRoyalty = royaltyPaid.muln(2000).divn(10000);
+      const [creatorBalance, extraRecipientBalance] = await Promise.all([
+        connection.getBalance(poolData.nftCreator.publicKey),
+        connection.getBalance(extraRecipient.publicKey),
+      ]);
+      assert.equal(extraRecipientBalance, expectedExtraRoyalty.toNumber());
+      assert.equal(
+        creatorBalance,
+        initCreatorBalance + royaltyPaid.sub(expectedExtraRoyalty).toNumber(),
+      );
+    });
+
+    it('can not configure more than the total royalty', async () => {
+      try {
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          extraRoyaltyRecipients: [
+            { address: Keypair.generate().publicKey, bp: 6000 },
+            { address: Keypair.generate().publicKey, bp: 5000 },
+          ],
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidBP');
+      }
+    });
+  });
+
+  describe('creator dust', () => {
+    // sells an nft split between fresh creators into a buyside pool, a 5%
+    // share of the 0.01 SOL royalty is below the rent of an empty account.
+    // A fallback account is passed after the creator accounts
+    const sellSplitNftToPool = async (
+      shares: number[],
+      requireAllCreatorsPaid = false,
+      fallback: { allowRoyaltyFallback: boolean } | null = null,
+    ) => {
+      const umi = (await createUmi('http://127.0.0.1:8899')).use(
+        mplTokenMetadata(),
+      );
+      const seller = Keypair.generate();
+      const fallbackAccount = Keypair.generate();
+      const creators = shares.map(() => generateSigner(umi));
+      const [poolData, [splitNft]] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.any],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            lpFeeBp: 0,
+            buysideCreatorRoyaltyBp: 10000,
+            requireAllCreatorsPaid,
+            royaltyFallbackAccount: fallback
+              ? fallbackAccount.publicKey
+              : PublicKey.default,
+            allowRoyaltyFallback: fallback?.allowRoyaltyFallback ?? false,
+          },
+          'buy',
+          TOKEN_PROGRAM_ID,
+          seller.publicKey,
+        ),
+        umiMintNfts(
+          umi,
+          {
+            numNfts: 1,
+            verifyCollection: false,
+            creators: creators.map((creator, index) => ({
+              address: creator.publicKey,
+              share: shares[index],
+              verified: false,
+            })),
+            recipient: fromWeb3JsPublicKey(seller.publicKey),
+          },
+          TOKEN_PROGRAM_ID,
+        ),
+        airdrop(connection, seller.publicKey, 10),
+        airdrop(connection, fallbackAccount.publicKey, 1),
+      ]);
+      const initFallbackBalance = await connection.getBalance(
+        fallbackAccount.publicKey,
+      );
+
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        {
+          ...poolData,
+          extraNft: splitNft,
+          poolAtaExtraNft: await getAssociatedTokenAddress(
+            toWeb3JsPublicKey(splitNft.mintAddress),
+            poolData.poolKey,
+            true,
+            TOKEN_PROGRAM_ID,
+          ),
+        },
+        wallet.publicKey,
+        cosigner,
+        seller,
+        [
+          ...creators.map((creator) => ({
+            pubkey: toWeb3JsPublicKey(creator.publicKey),
+            isSigner: false,
+            isWritable: true,
+          })),
+          ...(fallback
+            ? [
+                {
+                  pubkey: fallbackAccount.publicKey,
+                  isSigner: false,
+                  isWritable: true,
+                },
+              ]
+            : []),
+        ],
+      );
+
+      const { royaltyPaid } = getSolFulfillBuyPrices({
+        totalPriceLamports: LAMPORTS_PER_SOL,
+        lpFeeBp: 0,
+        takerFeeBp: 0,
+        metadataRoyaltyBp: 100,
+        buysideCreatorRoyaltyBp: 10000,
+        makerFeeBp: 0,
+      });
+      const creatorBalances = await Promise.all(
+        creators.map((creator) =>
+          connection.getBalance(toWeb3JsPublicKey(creator.publicKey)),
+        ),
+      );
+      const fallbackPaid =
+        (await connection.getBalance(fallbackAccount.publicKey)) -
+        initFallbackBalance;
+      return { txId, confirmedTx, royaltyPaid, creatorBalances, fallbackPaid };
+    };
+
+    it('rolls a skipped share into the next creator', async () => {
+      const { txId, confirmedTx, royaltyPaid, creatorBalances } =
+        await sellSplitNftToPool([5, 95]);
+      assertTx(txId, confirmedTx);
+      assert.equal(creatorBalances[0], 0);
+      assert.equal(creatorBalances[1], royaltyPaid.toNumber());
+    });
+
+    it('pays a skipped last share to the last paid creator', async () => {
+      const { txId, confirmedTx, royaltyPaid, creatorBalances } =
+        await sellSplitNftToPool([95, 5]);
+      assertTx(txId, confirmedTx);
+      assert.equal(creatorBalances[0], royaltyPaid.toNumber());
+      assert.equal(creatorBalances[1], 0);
+    });
+
+    it('never pays more than the royalty with skipped shares around', async () => {
+      // every 1% share is below the rent, so they are skipped before and after
+      // the only creator that can be paid, which gets the royalty exactly
+      const { txId, confirmedTx, royaltyPaid, creatorBalances } =
+        await sellSplitNftToPool([1, 1, 96, 1, 1]);
+      assertTx(txId, confirmedTx);
+      assert.deepEqual(creatorBalances, [0, 0, royaltyPaid.toNumber(), 0, 0]);
+    });
+
+    it('rejects a skipped share when every creator must be paid', async () => {
+      const { txId, confirmedTx, creatorBalances } = await sellSplitNftToPool(
+        [5, 95],
+        true,
+      );
+      assertFailedTx(txId, confirmedTx);
+      // 0x17b1 CreatorNotPaid
+      assert.include(JSON.stringify(confirmedTx.value.err), '"Custom":6065');
+      assert.deepEqual(creatorBalances, [0, 0]);
+    });
+
+    it('pays every creator above the rent when they must be paid', async () => {
+      const { txId, confirmedTx, royaltyPaid, creatorBalances } =
+        await sellSplitNftToPool([50, 50], true);
+      assertTx(txId, confirmedTx);
+      assert.deepEqual(creatorBalances, [
+        royaltyPaid.toNumber() / 2,
+        royaltyPaid.toNumber() / 2,
+      ]);
+    });
+
+    it('pays a share below the rent to the fallback account', async () => {
+      const { txId, confirmedTx, royaltyPaid, creatorBalances, fallbackPaid } =
+        await sellSplitNftToPool([5, 95], false, {
+          allowRoyaltyFallback: true,
+        });
+      assertTx(txId, confirmedTx);
+      const skippedShare = Math.floor((royaltyPaid.toNumber() * 5) / 100);
+      assert.deepEqual(creatorBalances, [
+        0,
+        royaltyPaid.toNumber() - skippedShare,
+      ]);
+      assert.equal(fallbackPaid, skippedShare);
+    });
+
+    it('counts a share paid to the fallback account as paid', async () => {
+      const { txId, confirmedTx, royaltyPaid, creatorBalances, fallbackPaid } =
+        await sellSplitNftToPool([5, 95], true, {
+          allowRoyaltyFallback: true,
+        });
+      assertTx(txId, confirmedTx);
+      const skippedShare = Math.floor((royaltyPaid.toNumber() * 5) / 100);
+      assert.deepEqual(creatorBalances, [
+        0,
+        royaltyPaid.toNumber() - skippedShare,
+      ]);
+      assert.equal(fallbackPaid, skippedShare);
+    });
+
+    it('rolls a share below the rent over without the fallback flag', async () => {
+      const { txId, confirmedTx, royaltyPaid, creatorBalances, fallbackPaid } =
+        await sellSplitNftToPool([5, 95], false, {
+          allowRoyaltyFallback: false,
+        });
+      assertTx(txId, confirmedTx);
+      assert.deepEqual(creatorBalances, [0, royaltyPaid.toNumber()]);
+      assert.equal(fallbackPaid, 0);
+    });
+
+    it('does not pay the fallback account for creators above the rent', async () => {
+      const { txId, confirmedTx, royaltyPaid, creatorBalances, fallbackPaid } =
+        await sellSplitNftToPool([50, 50], false, {
+          allowRoyaltyFallback: true,
+        });
+      assertTx(txId, confirmedTx);
+      assert.deepEqual(creatorBalances, [
+        royaltyPaid.toNumber() / 2,
+        royaltyPaid.toNumber() / 2,
+      ]);
+      assert.equal(fallbackPaid, 0);
+    });
+  });
+
+  describe('mint without creators', () => {
+    it('pays the royalty bp of a mint without creators to the seller', async () => {
+      const umi = (await createUmi('http://127.0.0.1:8899')).use(
+        mplTokenMetadata(),
+      );
+      const seller = Keypair.generate();
+      const [poolData, [uncreditedNft]] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.any],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            lpFeeBp: 0,
+            buysideCreatorRoyaltyBp: 10000,
+          },
+          'buy',
+          TOKEN_PROGRAM_ID,
+          seller.publicKey,
+        ),
+        umiMintNfts(
+          umi,
+          {
+            numNfts: 1,
+            verifyCollection: false,
+            creators: null,
+            sellerFeeBasisPoints: 500,
+            recipient: fromWeb3JsPublicKey(seller.publicKey),
+          },
+          TOKEN_PROGRAM_ID,
+        ),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const initEscrowBalance = await connection.getBalance(
+        poolData.poolPaymentEscrow,
+      );
+
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        {
+          ...poolData,
+          extraNft: uncreditedNft,
+          poolAtaExtraNft: await getAssociatedTokenAddress(
+            toWeb3JsPublicKey(uncreditedNft.mintAddress),
+            poolData.poolKey,
+            true,
+            TOKEN_PROGRAM_ID,
+          ),
+        },
+        wallet.publicKey,
+        cosigner,
+        seller,
+      );
+      assertTx(txId, confirmedTx);
+
+      // the seller receives the full price, nothing is left in the escrow
+      assert.equal(
+        await connection.getBalance(poolData.poolPaymentEscrow),
+        initEscrowBalance - LAMPORTS_PER_SOL,
+      );
+    });
+  });
+
+  describe('royalty fallback account', () => {
+    // sells the extra nft into a buyside pool, passing the fallback account
+    // in place of the creator account
+    const sellExtraNftWithFallback = async (allowRoyaltyFallback: boolean) => {
+      const seller = Keypair.generate();
+      const fallback = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mint],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            lpFeeBp: 0,
+            buysideCreatorRoyaltyBp: 10000,
+            royaltyFallbackAccount: fallback.publicKey,
+            allowRoyaltyFallback,
+          },
+          'buy',
+          TOKEN_PROGRAM_ID,
+          seller.publicKey,
+        ),
+        airdrop(connection, seller.publicKey, 10),
+        airdrop(connection, fallback.publicKey, 1),
+      ]);
+      const [initCreatorBalance, initFallbackBalance] = await Promise.all([
+        connection.getBalance(poolData.nftCreator.publicKey),
+        connection.getBalance(fallback.publicKey),
+      ]);
+
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+        [{ pubkey: fallback.publicKey, isSigner: false, isWritable: true }],
+      );
+      const [creatorBalance, fallbackBalance] = await Promise.all([
+        connection.getBalance(poolData.nftCreator.publicKey),
+        connection.getBalance(fallback.publicKey),
+      ]);
+      return {
+        txId,
+        confirmedTx,
+        creatorPaid: creatorBalance - initCreatorBalance,
+        fallbackPaid: fallbackBalance - initFallbackBalance,
+      };
+    };
+
+    it('does not replace a valid creator with the fallback account', async () => {
+      const { txId, confirmedTx, creatorPaid, fallbackPaid } =
+        await sellExtraNftWithFallback(true);
+      assertFailedTx(txId, confirmedTx);
+      // 0x177f InvalidCreatorAddress
+      assert.include(JSON.stringify(confirmedTx.value.err), '"Custom":6015');
+      assert.equal(creatorPaid, 0);
+      assert.equal(fallbackPaid, 0);
+    });
+
+    it('requires the creator account without the fallback flag', async () => {
+      const { txId, confirmedTx, creatorPaid, fallbackPaid } =
+        await sellExtraNftWithFallback(false);
+      assertFailedTx(txId, confirmedTx);
+      assert.equal(creatorPaid, 0);
+      assert.equal(fallbackPaid, 0);
+    });
+  });
 });
@@ -1,16 +1,21 @@
 import * as anchor from '@project-serum/anchor';
 import {
   getAssociatedTokenAddress,
+  getAssociatedTokenAddressSync,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
   TOKEN_2022_PROGRAM_ID,
 } from '@solana/spl-token';
 import {
+  AccountMeta,
+  ComputeBudgetProgram,
   Keypair,
   LAMPORTS_PER_SOL,
   PublicKey,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
+  TransactionMessage,
+  VersionedTransaction,
 } from '@solana/web3.js';
 import {
   Program as UmiProgram,
@@ -26,16 +31,21 @@ import {
   IDL,
   MMMProgramID,
   CurveKind,
+  AllowlistMatchMode,
+  getMaxRoyaltyAllowlistValue,
 } from '../sdk/src';
 import {
   PoolData,
   airdrop,
+  createLookupTable,
   createPool,
   createPoolWithExampleDepositsUmi,
+  createTestMintAndTokenT22VanillaExt,
   getEmptyAllowLists,
   getMetadataURI,
   getPoolRent,
   getTokenAccount2022,
+  Nft,
   sendAndAssertTx,
   umiMintCollection,
   umiMintNfts,
@@ -203,12 +213,14 @@ describe('mmm-deposit', () => {
                 allowlistAux: '',
                 makerFeeBp: 0,
                 takerFeeBp: 0,
+                minPricePerItem: null,
               })
               .accountsStrict({
                 payer: seller.publicKey,
                 owner: wallet.publicKey,
                 cosigner: cosigner.publicKey,
                 referral: poolData.referral.publicKey,
+                takerReferral: poolData.referral.publicKey,
                 pool: poolData.poolKey,
                 buysideSolEscrowAccount: poolData.poolPaymentEscrow,
                 assetMetadata: poolData.extraNft.metadataAddress,
@@ -448,7 +460,7 @@ describe('mmm-deposit', () => {
             kind: AllowlistKind.fvca,
             value: toWeb3JsPublicKey(creator.publicKey),
           },
-          ...getEmptyAllowLists(5),
+          ...getEmptyAllowLists(7),
         ];
 
         const [{ poolKey }, nfts, sfts] = await Promise.all([
@@ -668,7 +680,7 @@ describe('mmm-deposit', () => {
                 kind: AllowlistKind.mcc,
                 value: toWeb3JsPublicKey(collection.mintAddress),
               },
-              ...getEmptyAllowLists(5),
+              ...getEmptyAllowLists(7),
             ],
           }),
           umiMintNfts(
@@ -813,6 +825,120 @@ describe('mmm-deposit', () => {
         );
       });
 
+      it('skips group allowlists for metadata mints when depositing items', async () => {
+        const umi = (await createUmi('http://127.0.0.1:8899')).use(
+          mplTokenMetadata(),
+        );
+
+        const creator = generateSigner(umi);
+
+        const token2022Program: UmiProgram = {
+          name: 'splToken2022',
+          publicKey: publicKey('TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb'),
+          getErrorFromCode: () => null,
+          getErrorFromName: () => null,
+          isOnCluster: () => true,
+        };
+
+        umi.programs.add(token2022Program);
+
+        const { collection } = await umiMintCollection(
+          umi,
+          {
+            numNfts: 0,
+            legacy: true,
+            verifyCollection: false,
+          },
+          tokenProgramId,
+        );
+
+        const group = Keypair.generate().publicKey;
+        const [{ poolKey }, { poolKey: groupOnlyPoolKey }, nfts] =
+          await Promise.all([
+            createPool(program, {
+              owner: wallet.publicKey,
+              cosigner,
+              allowlists: [
+                { kind: AllowlistKind.group, value: group },
+                {
+                  kind: AllowlistKind.mcc,
+                  value: toWeb3JsPublicKey(collection.mintAddress),
+                },
+                ...getEmptyAllowLists(6),
+              ],
+            }),
+            createPool(program, {
+              owner: wallet.publicKey,
+              cosigner,
+              allowlists: [
+                { kind: AllowlistKind.group, value: group },
+                ...getEmptyAllowLists(7),
+              ],
+            }),
+            umiMintNfts(
+              umi,
+              {
+                numNfts: 1,
+                verifyCollection: true,
+                collectionAddress: collection.mintAddress,
+                creatorSigner: creator,
+                creators: [
+                  { address: creator.publicKey, share: 100, verified: false },
+                ],
+                recipient: fromWeb3JsPublicKey(wallet.publicKey),
+              },
+              tokenProgramId,
+            ),
+          ]);
+
+        const mintAddress = nfts[0].mintAddress;
+        const depositNft = async (pool: PublicKey) =>
+          program.methods
+            .depositSell({ assetAmount: new anchor.BN(1), allowlistAux: '' })
+            .accountsStrict({
+              owner: wallet.publicKey,
+              cosigner: cosigner.publicKey,
+              pool,
+              assetMetadata: findMetadataPda(umi, { mint: mintAddress })[0],
+              assetMasterEdition: findMasterEditionPda(umi, {
+                mint: mintAddress,
+              })[0],
+              assetMint: mintAddress,
+              assetTokenAccount: nfts[0].tokenAddress!,
+              sellsideEscrowTokenAccount: await getAssociatedTokenAddress(
+                toWeb3JsPublicKey(mintAddress),
+                pool,
+                true,
+                tokenProgramId,
+              ),
+              allowlistAuxAccount: SystemProgram.programId,
+              sellState: getMMMSellStatePDA(
+                program.programId,
+                pool,
+                toWeb3JsPublicKey(mintAddress),
+              ).key,
+              systemProgram: SystemProgram.programId,
+              tokenProgram: tokenProgramId,
+              associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+              rent: SYSVAR_RENT_PUBKEY,
+            })
+            .signers([cosigner])
+            .rpc();
+
+        // the group entry alone never matches a metadata mint
+        try {
+          await depositNft(groupOnlyPoolKey);
+          assert.fail('should have failed');
+        } catch (e) {
+          assert.include(`${e}`, 'InvalidAllowLists');
+        }
+
+        // and next to a matching mcc entry it's skipped
+        await depositNft(poolKey);
+        const poolAccountInfo = await program.account.pool.fetch(poolKey);
+        assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 1);
+      });
+
       it('correctly verifies metadata+mcc allowlists when depositing items', async () => {
         const umi = (await createUmi('http://127.0.0.1:8899')).use(
           mplTokenMetadata(),
@@ -853,7 +979,7 @@ describe('mmm-deposit', () => {
                 kind: AllowlistKind.metadata,
                 value: toWeb3JsPublicKey(collection.mintAddress),
               },
-              ...getEmptyAllowLists(4),
+              ...getEmptyAllowLists(6),
             ],
           }),
           umiMintNfts(
@@ -1014,7 +1140,7 @@ describe('mmm-deposit', () => {
               kind: AllowlistKind.mint,
               value: toWeb3JsPublicKey(mintAddress2),
             },
-            ...getEmptyAllowLists(4),
+            ...getEmptyAllowLists(6),
           ],
         });
 
@@ -1125,4 +1251,445 @@ describe('mmm-deposit', () => {
       });
     });
   });
+
+  describe('deposit_sell_batch', () => {
+    const createBatchPool = async (numNfts: number) => {
+      const umi = (await createUmi('http://127.0.0.1:8899')).use(
+        mplTokenMetadata(),
+      );
+      const creator = generateSigner(umi);
+      const [{ poolKey }, nfts] = await Promise.all([
+        createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          allowlists: [
+            {
+              kind: AllowlistKind.fvca,
+              value: toWeb3JsPublicKey(creator.publicKey),
+            },
+            ...getEmptyAllowLists(7),
+          ],
+        }),
+        umiMintNfts(
+          umi,
+          {
+            numNfts,
+            verifyCollection: false,
+            creatorSigner: creator,
+            creators: [
+              { address: creator.publicKey, share: 100, verified: false },
+            ],
+            recipient: fromWeb3JsPublicKey(wallet.publicKey),
+          },
+          TOKEN_PROGRAM_ID,
+        ),
+      ]);
+      const mints = nfts.map((nft) => {
+        const assetMint = toWeb3JsPublicKey(nft.mintAddress);
+        return {
+          assetMint,
+          assetMetadata: toWeb3JsPublicKey(nft.metadataAddress),
+          assetMasterEdition: toWeb3JsPublicKey(nft.masterEditionAddress),
+          assetTokenAccount: toWeb3JsPublicKey(nft.tokenAddress),
+          sellsideEscrowTokenAccount: getAssociatedTokenAddressSync(
+            assetMint,
+            poolKey,
+            true,
+            TOKEN_PROGRAM_ID,
+          ),
+          sellState: getMMMSellStatePDA(program.programId, poolKey, assetMint)
+            .key,
+        };
+      });
+      return { poolKey, mints };
+    };
+
+    const depositSellBatch = (
+      poolKey: PublicKey,
+      assetAmount: number,
+      remainingAccounts: AccountMeta[],
+    ) =>
+      program.methods
+        .depositSellBatch({
+          assetAmount: new anchor.BN(assetAmount),
+          allowlistAux: '',
+        })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolKey,
+          allowlistAuxAccount: SystemProgram.programId,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .remainingAccounts(remainingAccounts);
+
+    it('deposits 5 mints and creates their sell states', async () => {
+      const { poolKey, mints } = await createBatchPool(5);
+      const remainingAccounts: AccountMeta[] = mints.flatMap((mint) => [
+        { pubkey: mint.assetMint, isSigner: false, isWritable: false },
+        { pubkey: mint.assetMetadata, isSigner: false, isWritable: false },
+        { pubkey: mint.assetMasterEdition, isSigner: false, isWritable: false },
+        { pubkey: mint.assetTokenAccount, isSigner: false, isWritable: true },
+        {
+          pubkey: mint.sellsideEscrowTokenAccount,
+          isSigner: false,
+          isWritable: true,
+        },
+        { pubkey: mint.sellState, isSigner: false, isWritable: true },
+      ]);
+      const lookupTable = await createLookupTable(
+        connection,
+        wallet.payer,
+        remainingAccounts.map((account) => account.pubkey),
+      );
+
+      const blockhashData = await connection.getLatestBlockhash();
+      const tx = new VersionedTransaction(
+        new TransactionMessage({
+          payerKey: wallet.publicKey,
+          recentBlockhash: blockhashData.blockhash,
+          instructions: [
+            ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+            await depositSellBatch(
+              poolKey,
+              mints.length,
+              remainingAccounts,
+            ).instruction(),
+          ],
+        }).compileToV0Message([lookupTable]),
+      );
+      tx.sign([wallet.payer, cosigner]);
+      await sendAndAssertTx(connection, tx, blockhashData, false);
+
+      const poolAccountInfo = await program.account.pool.fetch(poolKey);
+      assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 5);
+      for (const mint of mints) {
+        const escrow = await getTokenAccount2022(
+          connection,
+          mint.sellsideEscrowTokenAccount,
+          TOKEN_PROGRAM_ID,
+        );
+        assert.equal(Number(escrow.amount), 1);
+        assert.equal(escrow.owner.toBase58(), poolKey.toBase58());
+        // the emptied owner token account is closed
+        assert.equal(await connection.getBalance(mint.assetTokenAccount), 0);
+
+        const sellState = await program.account.sellState.fetch(mint.sellState);
+        assert.equal(sellState.pool.toBase58(), poolKey.toBase58());
+        assert.equal(
+          sellState.poolOwner.toBase58(),
+          wallet.publicKey.toBase58(),
+        );
+        assert.equal(sellState.assetMint.toBase58(), mint.assetMint.toBase58());
+        assert.equal(sellState.assetAmount.toNumber(), 1);
+      }
+    });
+
+    it('checks each mint of the batch against a mixed allowlist', async () => {
+      const umi = (await createUmi('http://127.0.0.1:8899')).use(
+        mplTokenMetadata(),
+      );
+      const creator = generateSigner(umi);
+      const otherCreator = generateSigner(umi);
+      const mintNft = (creatorSigner: typeof creator) =>
+        umiMintNfts(
+          umi,
+          {
+            numNfts: 1,
+            verifyCollection: false,
+            creatorSigner,
+            creators: [
+              {
+                address: creatorSigner.publicKey,
+                share: 100,
+                verified: false,
+              },
+            ],
+            recipient: fromWeb3JsPublicKey(wallet.publicKey),
+          },
+          TOKEN_PROGRAM_ID,
+        ).then(([nft]) => nft);
+      const [fvcaNft, mintNft1, deniedNft] = await Promise.all([
+        mintNft(creator),
+        mintNft(otherCreator),
+        mintNft(otherCreator),
+      ]);
+      const createMixedPool = (allowlistMatchMode: AllowlistMatchMode) =>
+        createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          allowlists: [
+            { kind: AllowlistKind.empty, value: PublicKey.default },
+            { kind: AllowlistKind.metadata, value: PublicKey.default },
+            {
+              kind: AllowlistKind.fvca,
+              value: toWeb3JsPublicKey(creator.publicKey),
+            },
+            {
+              kind: AllowlistKind.mint,
+              value: toWeb3JsPublicKey(mintNft1.mintAddress),
+            },
+            {
+              kind: AllowlistKind.denyMint,
+              value: toWeb3JsPublicKey(deniedNft.mintAddress),
+            },
+            {
+              kind: AllowlistKind.maxRoyalty,
+              value: getMaxRoyaltyAllowlistValue(0),
+            },
+            ...getEmptyAllowLists(2),
+          ],
+          allowlistMatchMode,
+        });
+      const getBatchAccounts = (poolKey: PublicKey, nfts: Nft[]) =>
+        nfts.flatMap((nft): AccountMeta[] => {
+          const assetMint = toWeb3JsPublicKey(nft.mintAddress);
+          return [
+            { pubkey: assetMint, isSigner: false, isWritable: false },
+            {
+              pubkey: toWeb3JsPublicKey(nft.metadataAddress),
+              isSigner: false,
+              isWritable: false,
+            },
+            {
+              pubkey: toWeb3JsPublicKey(nft.masterEditionAddress),
+              isSigner: false,
+              isWritable: false,
+            },
+            {
+              pubkey: toWeb3JsPublicKey(nft.tokenAddress),
+              isSigner: false,
+              isWritable: true,
+            },
+            {
+              pubkey: getAssociatedTokenAddressSync(
+                assetMint,
+                poolKey,
+                true,
+                TOKEN_PROGRAM_ID,
+              ),
+              isSigner: false,
+              isWritable: true,
+            },
+            {
+              pubkey: getMMMSellStatePDA(program.programId, poolKey, assetMint)
+                .key,
+              isSigner: false,
+              isWritable: true,
+            },
+          ];
+        });
+      const deposit = (poolKey: PublicKey, nfts: Nft[]) =>
+        depositSellBatch(poolKey, nfts.length, getBatchAccounts(poolKey, nfts))
+          .preInstructions([
+            ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+          ])
+          .signers([cosigner])
+          .rpc();
+      const [{ poolKey }, { poolKey: intersectionPoolKey }] =
+        await Promise.all([
+          createMixedPool(AllowlistMatchMode.union),
+          createMixedPool(AllowlistMatchMode.intersection),
+        ]);
+
+      // a denied mint fails the whole batch, whatever it's next to
+      try {
+        await deposit(poolKey, [fvcaNft, deniedNft]);
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidAllowLists');
+      }
+      // neither mint matches the fvca and mint entries at once
+      try {
+        await deposit(intersectionPoolKey, [fvcaNft]);
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidAllowLists');
+      }
+      await deposit(poolKey, [fvcaNft, mintNft1]);
+
+      const poolAccountInfo = await program.account.pool.fetch(poolKey);
+      assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 2);
+    });
+
+    it('can not deposit more than the max batch size', async () => {
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+      });
+      try {
+        await depositSellBatch(poolKey, 6, []).signers([cosigner]).rpc();
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidBatchSize');
+      }
+    });
+
+    it('can not deposit a different amount than the mints passed', async () => {
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+      });
+      try {
+        await depositSellBatch(poolKey, 2, []).signers([cosigner]).rpc();
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidRemainingAccounts');
+      }
+    });
+
+    it('can not deposit a mint of another token program', async () => {
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+      });
+      const { mint, recipientTokenAccount } =
+        await createTestMintAndTokenT22VanillaExt(connection, wallet.payer);
+      // the batch passes the legacy token program, the mint is a token 2022 one
+      const remainingAccounts: AccountMeta[] = [
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: recipientTokenAccount, isSigner: false, isWritable: true },
+        {
+          pubkey: getAssociatedTokenAddressSync(
+            mint,
+            poolKey,
+            true,
+            TOKEN_2022_PROGRAM_ID,
+          ),
+          isSigner: false,
+          isWritable: true,
+        },
+        {
+          pubkey: getMMMSellStatePDA(program.programId, poolKey, mint).key,
+          isSigner: false,
+          isWritable: true,
+        },
+      ];
+      try {
+        await depositSellBatch(poolKey, 1, remainingAccounts)
+          .signers([cosigner])
+          .rpc();
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidTokenProgram');
+      }
+    });
+  });
+
+  describe('require_one_of_one', () => {
+    const depositNft = async (poolKey: PublicKey, nft: Nft) => {
+      const assetMint = toWeb3JsPublicKey(nft.mintAddress);
+      await program.methods
+        .depositSell({ assetAmount: new anchor.BN(1), allowlistAux: '' })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolKey,
+          assetMetadata: toWeb3JsPublicKey(nft.metadataAddress),
+          assetMasterEdition: toWeb3JsPublicKey(nft.masterEditionAddress),
+          assetMint,
+          assetTokenAccount: toWeb3JsPublicKey(nft.tokenAddress),
+          sellsideEscrowTokenAccount: getAssociatedTokenAddressSync(
+            assetMint,
+            poolKey,
+            true,
+            TOKEN_PROGRAM_ID,
+          ),
+          allowlistAuxAccount: SystemProgram.programId,
+          sellState: getMMMSellStatePDA(program.programId, poolKey, assetMint)
+            .key,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([cosigner])
+        .rpc();
+    };
+
+    it('only takes master editions without prints', async () => {
+      const umi = (await createUmi('http://127.0.0.1:8899')).use(
+        mplTokenMetadata(),
+      );
+      const creator = generateSigner(umi);
+      const mintConfig = {
+        numNfts: 1,
+        verifyCollection: false,
+        creatorSigner: creator,
+        creators: [{ address: creator.publicKey, share: 100, verified: false }],
+        recipient: fromWeb3JsPublicKey(wallet.publicKey),
+      };
+      const [{ poolKey }, [uniqueNft], [printableNft]] = await Promise.all([
+        createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          allowlists: [
+            {
+              kind: AllowlistKind.fvca,
+              value: toWeb3JsPublicKey(creator.publicKey),
+            },
+            ...getEmptyAllowLists(7),
+          ],
+          requireOneOfOne: true,
+        }),
+        umiMintNfts(umi, mintConfig, TOKEN_PROGRAM_ID),
+        umiMintNfts(umi, { ...mintConfig, printSupply: 10 }, TOKEN_PROGRAM_ID),
+      ]);
+
+      try {
+        await depositNft(poolKey, printableNft);
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'NotOneOfOne');
+      }
+
+      await depositNft(poolKey, uniqueNft);
+      const poolAccountInfo = await program.account.pool.fetch(poolKey);
+      assert.isTrue(poolAccountInfo.requireOneOfOne);
+      assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 1);
+    });
+
+    it('takes printable master editions when the flag is off', async () => {
+      const umi = (await createUmi('http://127.0.0.1:8899')).use(
+        mplTokenMetadata(),
+      );
+      const creator = generateSigner(umi);
+      const [{ poolKey }, [printableNft]] = await Promise.all([
+        createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          allowlists: [
+            {
+              kind: AllowlistKind.fvca,
+              value: toWeb3JsPublicKey(creator.publicKey),
+            },
+            ...getEmptyAllowLists(7),
+          ],
+        }),
+        umiMintNfts(
+          umi,
+          {
+            numNfts: 1,
+            verifyCollection: false,
+            creatorSigner: creator,
+            creators: [
+              { address: creator.publicKey, share: 100, verified: false },
+            ],
+            recipient: fromWeb3JsPublicKey(wallet.publicKey),
+            printSupply: 10,
+          },
+          TOKEN_PROGRAM_ID,
+        ),
+      ]);
+
+      await depositNft(poolKey, printableNft);
+      const poolAccountInfo = await program.account.pool.fetch(poolKey);
+      assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 1);
+    });
+  });
 });
@@ -0,0 +1,280 @@
+import * as anchor from '@project-serum/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair, LAMPORTS_PER_SOL, SystemProgram } from '@solana/web3.js';
+import { assert } from 'chai';
+import {
+  Mmm,
+  AllowlistKind,
+  CurveKind,
+  getAccountClosedFromLogs,
+  IDL,
+  MMMProgramID,
+} from '../sdk/src';
+import {
+  airdrop,
+  assertFailedTx,
+  assertTx,
+  createPoolWithExampleDepositsUmi,
+  getTransactionLogs,
+  sellExtraNftToPool,
+} from './utils';
+
+describe('mmm-escrow-close', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const cosigner = Keypair.generate();
+
+  beforeEach(async () => {
+    await airdrop(connection, wallet.publicKey, 50);
+  });
+
+  // creates a buyside pool at 1 SOL that drops to 0.5 SOL after one buy fill,
+  // with the escrow funding one fill plus the remaining escrow
+  const createPoolWithRemainingEscrow = async (
+    remainingEscrow: number,
+    poolArgs: {
+      minEscrowBalanceBp?: number;
+      reinvestFulfillBuy?: boolean;
+      staticSpot?: boolean;
+    } = {},
+  ) => {
+    const seller = Keypair.generate();
+    const [poolData] = await Promise.all([
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mcc],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 2),
+          reinvestFulfillBuy: false,
+          reinvestFulfillSell: false,
+          lpFeeBp: 0,
+          buysideCreatorRoyaltyBp: 0,
+          expiry: new anchor.BN(0),
+          ...poolArgs,
+        },
+        'buy',
+        TOKEN_PROGRAM_ID,
+        seller.publicKey,
+      ),
+      airdrop(connection, seller.publicKey, 10),
+    ]);
+
+    // leave exactly one fill at the spot price plus the remaining escrow
+    const escrowBalance = await connection.getBalance(
+      poolData.poolPaymentEscrow,
+    );
+    await program.methods
+      .solWithdrawBuy({
+        paymentAmount: new anchor.BN(
+          escrowBalance - LAMPORTS_PER_SOL - remainingEscrow,
+        ),
+      })
+      .accountsStrict({
+        owner: wallet.publicKey,
+        cosigner: cosigner.publicKey,
+        pool: poolData.poolKey,
+        buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([cosigner])
+      .rpc();
+
+    return { poolData, seller };
+  };
+
+  // sells one nft into the pool of createPoolWithRemainingEscrow and returns
+  // the escrow balance and the fill logs
+  const fillWithRemainingEscrow = async (
+    remainingEscrow: number,
+    poolArgs: {
+      minEscrowBalanceBp?: number;
+      reinvestFulfillBuy?: boolean;
+      staticSpot?: boolean;
+    } = {},
+  ) => {
+    const { poolData, seller } = await createPoolWithRemainingEscrow(
+      remainingEscrow,
+      poolArgs,
+    );
+    const { txId, confirmedTx } = await sellExtraNftToPool(
+      program,
+      poolData,
+      wallet.publicKey,
+      cosigner,
+      seller,
+    );
+    assertTx(txId, confirmedTx);
+
+    return {
+      poolData,
+      escrowBalance: await connection.getBalance(poolData.poolPaymentEscrow),
+      logs: await getTransactionLogs(connection, txId),
+    };
+  };
+
+  it('keeps the escrow after the spot price just decreased on a buy fill', async () => {
+    // 0.008 SOL is below 1% of the pre fill spot price (0.01 SOL), but above
+    // 1% of the post fill spot price (0.005 SOL)
+    const remainingEscrow = (LAMPORTS_PER_SOL / 1000) * 8;
+    const { poolData, escrowBalance, logs } = await fillWithRemainingEscrow(
+      remainingEscrow,
+    );
+    assert.equal(escrowBalance, remainingEscrow);
+    assert.isNull(getAccountClosedFromLogs(logs, 'escrow_closed'));
+    assert.isNull(getAccountClosedFromLogs(logs, 'pool_closed'));
+
+    const poolAccountInfo = await program.account.pool.fetch(poolData.poolKey);
+    assert.equal(poolAccountInfo.spotPrice.toNumber(), LAMPORTS_PER_SOL / 2);
+    assert.equal(
+      poolAccountInfo.buysidePaymentAmount.toNumber(),
+      remainingEscrow,
+    );
+  });
+
+  it('prices the threshold at the unchanged spot price with static_spot', async () => {
+    // the same 0.008 SOL is kept above, but the next buy fill of a static spot
+    // pool still pays 1 SOL, so it is below 1% of that price
+    const remainingEscrow = (LAMPORTS_PER_SOL / 1000) * 8;
+    const { escrowBalance, logs } = await fillWithRemainingEscrow(
+      remainingEscrow,
+      { staticSpot: true },
+    );
+    assert.equal(escrowBalance, 0);
+    const escrowClosed = getAccountClosedFromLogs(logs, 'escrow_closed');
+    assert.isNotNull(escrowClosed);
+    assert.equal(escrowClosed!.lamports.toNumber(), remainingEscrow);
+  });
+
+  it('sweeps the escrow when it is below the post fill threshold', async () => {
+    const remainingEscrow = (LAMPORTS_PER_SOL / 1000) * 4;
+    const { poolData, escrowBalance, logs } = await fillWithRemainingEscrow(
+      remainingEscrow,
+    );
+    assert.equal(escrowBalance, 0);
+    const escrowClosed = getAccountClosedFromLogs(logs, 'escrow_closed');
+    assert.isNotNull(escrowClosed);
+    assert.equal(escrowClosed!.pool.toBase58(), poolData.poolKey.toBase58());
+    assert.equal(escrowClosed!.owner.toBase58(), wallet.publicKey.toBase58());
+    assert.equal(escrowClosed!.lamports.toNumber(), remainingEscrow);
+
+    // the pool has no assets and no escrow left, so it is closed
+    assert.isNull(await connection.getAccountInfo(poolData.poolKey));
+    const poolClosed = getAccountClosedFromLogs(logs, 'pool_closed');
+    assert.isNotNull(poolClosed);
+    assert.equal(poolClosed!.pool.toBase58(), poolData.poolKey.toBase58());
+    assert.isAbove(poolClosed!.lamports.toNumber(), remainingEscrow);
+  });
+
+  it('uses the configured min escrow balance bp', async () => {
+    // 0.1% of the post fill spot price is below the min rent, so only the
+    // min rent is kept as the threshold
+    const remainingEscrow = (LAMPORTS_PER_SOL / 1000) * 4;
+    const { poolData, escrowBalance } = await fillWithRemainingEscrow(
+      remainingEscrow,
+      { minEscrowBalanceBp: 10 },
+    );
+    assert.equal(escrowBalance, remainingEscrow);
+
+    const poolAccountInfo = await program.account.pool.fetch(poolData.poolKey);
+    assert.equal(poolAccountInfo.minEscrowBalanceBp, 10);
+  });
+
+  it('closes the escrow holding exactly the min rent', async () => {
+    // 0.01% of the post fill spot price is below the min rent, so the min rent
+    // is the threshold, and a balance equal to it is fully closed
+    const minRent = await connection.getMinimumBalanceForRentExemption(0);
+    const { escrowBalance, logs } = await fillWithRemainingEscrow(minRent, {
+      minEscrowBalanceBp: 1,
+    });
+    assert.equal(escrowBalance, 0);
+    const escrowClosed = getAccountClosedFromLogs(logs, 'escrow_closed');
+    assert.isNotNull(escrowClosed);
+    assert.equal(escrowClosed!.lamports.toNumber(), minRent);
+  });
+
+  it('keeps the escrow holding one lamport above the min rent', async () => {
+    const minRent = await connection.getMinimumBalanceForRentExemption(0);
+    const { poolData, escrowBalance, logs } = await fillWithRemainingEscrow(
+      minRent + 1,
+      { minEscrowBalanceBp: 1 },
+    );
+    assert.equal(escrowBalance, minRent + 1);
+    assert.isNull(getAccountClosedFromLogs(logs, 'escrow_closed'));
+
+    const poolAccountInfo = await program.account.pool.fetch(poolData.poolKey);
+    assert.equal(poolAccountInfo.buysidePaymentAmount.toNumber(), minRent + 1);
+  });
+
+  it('fills from an escrow holding exactly the total price', async () => {
+    const { escrowBalance } = await fillWithRemainingEscrow(0);
+    assert.equal(escrowBalance, 0);
+  });
+
+  it('rejects a fill from an escrow one lamport short of the total price', async () => {
+    const { poolData, seller } = await createPoolWithRemainingEscrow(-1);
+    const { txId, confirmedTx } = await sellExtraNftToPool(
+      program,
+      poolData,
+      wallet.publicKey,
+      cosigner,
+      seller,
+    );
+    assertFailedTx(txId, confirmedTx);
+    // 0x17b3 EscrowUnderfunded
+    assert.include(JSON.stringify(confirmedTx.value.err), '"Custom":6067');
+    assert.equal(
+      await connection.getBalance(poolData.poolPaymentEscrow),
+      LAMPORTS_PER_SOL - 1,
+    );
+  });
+
+  it('sweeps the same escrow balance differently per configured bp', async () => {
+    // 0.008 SOL is above 1% but below 2% of the post fill spot price
+    const remainingEscrow = (LAMPORTS_PER_SOL / 1000) * 8;
+    const [defaultBp, higherBp] = await Promise.all([
+      fillWithRemainingEscrow(remainingEscrow),
+      fillWithRemainingEscrow(remainingEscrow, { minEscrowBalanceBp: 200 }),
+    ]);
+    assert.equal(defaultBp.escrowBalance, remainingEscrow);
+    assert.isNull(getAccountClosedFromLogs(defaultBp.logs, 'escrow_closed'));
+    assert.equal(higherBp.escrowBalance, 0);
+    assert.isNotNull(getAccountClosedFromLogs(higherBp.logs, 'escrow_closed'));
+  });
+
+  it('logs the sell state close only when the nft is not reinvested', async () => {
+    const remainingEscrow = (LAMPORTS_PER_SOL / 1000) * 8;
+    {
+      const { poolData, logs } = await fillWithRemainingEscrow(
+        remainingEscrow,
+      );
+      const sellStateClosed = getAccountClosedFromLogs(
+        logs,
+        'sell_state_closed',
+      );
+      assert.isNotNull(sellStateClosed);
+      assert.equal(
+        sellStateClosed!.pool.toBase58(),
+        poolData.poolKey.toBase58(),
+      );
+      assert.isAbove(sellStateClosed!.lamports.toNumber(), 0);
+    }
+    {
+      const { logs } = await fillWithRemainingEscrow(remainingEscrow, {
+        reinvestFulfillBuy: true,
+      });
+      assert.isNull(getAccountClosedFromLogs(logs, 'sell_state_closed'));
+    }
+  });
+});
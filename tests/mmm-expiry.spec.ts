@@ -0,0 +1,173 @@
+import * as anchor from '@project-serum/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair, LAMPORTS_PER_SOL, SystemProgram } from '@solana/web3.js';
+import { assert } from 'chai';
+import { Mmm, AllowlistKind, CurveKind, IDL, MMMProgramID } from '../sdk/src';
+import {
+  airdrop,
+  assertFailedTx,
+  assertTx,
+  createPool,
+  createPoolWithExampleDepositsUmi,
+  getChainTime,
+  PoolData,
+  sellExtraNftToPool,
+  waitForChainTime,
+} from './utils';
+
+describe('mmm-expiry', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const cosigner = Keypair.generate();
+
+  beforeEach(async () => {
+    await airdrop(connection, wallet.publicKey, 50);
+  });
+
+  const createBuysidePool = async (expiry: number, seller: Keypair) => {
+    const [poolData] = await Promise.all([
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mcc],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(0),
+          expiry: new anchor.BN(expiry),
+        },
+        'buy',
+        TOKEN_PROGRAM_ID,
+        seller.publicKey,
+      ),
+      airdrop(connection, seller.publicKey, 10),
+    ]);
+    return poolData;
+  };
+
+  const sellExtraNft = (poolData: PoolData, seller: Keypair) =>
+    sellExtraNftToPool(program, poolData, wallet.publicKey, cosigner, seller);
+
+  it('can fill right before expiry', async () => {
+    const seller = Keypair.generate();
+    const poolData = await createBuysidePool(
+      (await getChainTime(connection)) + 30,
+      seller,
+    );
+
+    const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+    assertTx(txId, confirmedTx);
+  });
+
+  it('can not fill right after expiry, but can still withdraw', async () => {
+    const seller = Keypair.generate();
+    const expiry = (await getChainTime(connection)) + 5;
+    const poolData = await createBuysidePool(expiry, seller);
+    await waitForChainTime(connection, expiry);
+
+    const { txId, confirmedTx } = await sellExtraNft(poolData, seller);
+    assertFailedTx(txId, confirmedTx);
+
+    // the owner can still reclaim the escrowed sol
+    await program.methods
+      .solWithdrawBuy({
+        paymentAmount: new anchor.BN(LAMPORTS_PER_SOL),
+      })
+      .accountsStrict({
+        owner: wallet.publicKey,
+        cosigner: cosigner.publicKey,
+        pool: poolData.poolKey,
+        buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([cosigner])
+      .rpc();
+  });
+
+  it('can not create a pool with an expiry in the past', async () => {
+    try {
+      await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        expiry: new anchor.BN((await getChainTime(connection)) - 1),
+      });
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidExpiry');
+    }
+  });
+
+  it('can not update a pool to an expiry in the past', async () => {
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner,
+      expiry: new anchor.BN((await getChainTime(connection)) + 1000),
+    });
+    try {
+      await program.methods
+        .updatePool({
+          spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(0),
+          reinvestFulfillBuy: false,
+          reinvestFulfillSell: false,
+          expiry: new anchor.BN((await getChainTime(connection)) - 1),
+          lpFeeBp: 0,
+          referral: Keypair.generate().publicKey,
+          cosignerAnnotation: new Array(32).fill(0),
+          buysideCreatorRoyaltyBp: 0,
+          priceFloor: null,
+          priceCeiling: null,
+          lpFeeDecayEnd: null,
+          lpFeeBpFloor: null,
+          lpFeeTiers: null,
+          minEscrowBalanceBp: null,
+          extraRoyaltyRecipients: null,
+          maxSpotMoveBp: null,
+          lpFeeRounding: null,
+          maxFillDepth: null,
+          maxBuysideSpend: null,
+          poolSide: null,
+          requireSizedCollection: null,
+          rejectSelfReferral: null,
+          settleInWsol: null,
+          requireOneOfOne: null,
+          requireProgrammable: null,
+          curveDeltaSell: null,
+          royaltyEnforcementMode: null,
+          maxTotalPrice: null,
+          minFillAmount: null,
+          fillCooldownSecs: null,
+          sellStateRentToTaker: null,
+          allowlistAux: null,
+          royaltyFallbackAccount: null,
+          profitSkimBp: null,
+          oracle: null,
+          maxOracleDeviationBp: null,
+          requireAllCreatorsPaid: null,
+          curveDirection: null,
+          requireCollectionMetadata: null,
+          staticSpot: null,
+          allowRoyaltyFallback: null,
+        })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolKey,
+        })
+        .signers([cosigner])
+        .rpc();
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidExpiry');
+    }
+  });
+});
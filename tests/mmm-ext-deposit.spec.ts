@@ -3,15 +3,23 @@ import {
   getAssociatedTokenAddress,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_2022_PROGRAM_ID,
+  createUpdateAuthorityInstruction,
 } from '@solana/spl-token';
-import { Keypair, PublicKey, SystemProgram } from '@solana/web3.js';
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from '@solana/web3.js';
 import { assert, expect } from 'chai';
 import {
   Mmm,
   AllowlistKind,
   getMMMSellStatePDA,
   IDL,
+  METADATA_URI_MARKERS,
   MMMProgramID,
+  AllowlistMatchMode,
 } from '../sdk/src';
 import {
   airdrop,
@@ -203,7 +211,7 @@ describe('mmm-ext-deposit', () => {
               kind: AllowlistKind.any,
               value: PublicKey.default,
             },
-            ...getEmptyAllowLists(5),
+            ...getEmptyAllowLists(7),
           ],
         },
       );
@@ -442,7 +450,7 @@ describe('mmm-ext-deposit', () => {
             kind: AllowlistKind.group,
             value: groupAddress,
           },
-          ...getEmptyAllowLists(4),
+          ...getEmptyAllowLists(6),
         ],
       });
 
@@ -514,7 +522,7 @@ describe('mmm-ext-deposit', () => {
             kind: AllowlistKind.group,
             value: mint, // unexpected group address
           },
-          ...getEmptyAllowLists(4),
+          ...getEmptyAllowLists(6),
         ],
       });
 
@@ -560,6 +568,666 @@ describe('mmm-ext-deposit', () => {
         assertProgramError(err, 'invalid allowlists');
       }
     });
+
+    it('books the amount net of the transfer fee', async () => {
+      const { poolData, groupAddress } =
+        await createPoolWithExampleT22ExtDeposits(
+          program,
+          connection,
+          wallet.payer,
+          'none',
+          {
+            owner: wallet.publicKey,
+            cosigner,
+          },
+        );
+      // 10% of 10 assets is withheld by the transfer
+      const { mint, recipientTokenAccount } =
+        await createTestMintAndTokenT22VanillaExt(
+          connection,
+          wallet.payer,
+          undefined,
+          groupAddress,
+          undefined,
+          [],
+          'example.com',
+          10,
+          1000,
+        );
+      const poolAta = await getAssociatedTokenAddress(
+        mint,
+        poolData.poolKey,
+        true,
+        TOKEN_2022_PROGRAM_ID,
+      );
+      const { key: sellState } = getMMMSellStatePDA(
+        program.programId,
+        poolData.poolKey,
+        mint,
+      );
+      await program.methods
+        .extDepositSell({
+          assetAmount: new anchor.BN(10),
+          allowlistAux: '',
+        })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolData.poolKey,
+          assetMint: mint,
+          assetTokenAccount: recipientTokenAccount,
+          sellsideEscrowTokenAccount: poolAta,
+          sellState,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([cosigner])
+        .rpc({ skipPreflight: true });
+
+      const [escrow, poolAccountInfo, sellStateAccountInfo] =
+        await Promise.all([
+          getTokenAccount2022(connection, poolAta, TOKEN_2022_PROGRAM_ID),
+          program.account.pool.fetch(poolData.poolKey),
+          program.account.sellState.fetch(sellState),
+        ]);
+      assert.equal(Number(escrow.amount), 9);
+      assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 9);
+      assert.equal(sellStateAccountInfo.assetAmount.toNumber(), 9);
+    });
+
+    it('does not require transfer hook accounts for a hookless mint', async () => {
+      const { poolData, groupAddress } =
+        await createPoolWithExampleT22ExtDeposits(
+          program,
+          connection,
+          wallet.payer,
+          'none',
+          {
+            owner: wallet.publicKey,
+            cosigner,
+          },
+        );
+      const { mint, recipientTokenAccount } =
+        await createTestMintAndTokenT22VanillaExt(
+          connection,
+          wallet.payer,
+          undefined,
+          groupAddress,
+        );
+      const poolAta = await getAssociatedTokenAddress(
+        mint,
+        poolData.poolKey,
+        true,
+        TOKEN_2022_PROGRAM_ID,
+      );
+      const { key: sellState } = getMMMSellStatePDA(
+        program.programId,
+        poolData.poolKey,
+        mint,
+      );
+      await program.methods
+        .extDepositSell({
+          assetAmount: new anchor.BN(1),
+          allowlistAux: '',
+        })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolData.poolKey,
+          assetMint: mint,
+          assetTokenAccount: recipientTokenAccount,
+          sellsideEscrowTokenAccount: poolAta,
+          sellState,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([])
+        .signers([cosigner])
+        .rpc({ skipPreflight: true });
+
+      const poolAccountInfo = await program.account.pool.fetch(
+        poolData.poolKey,
+      );
+      assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 1);
+    });
+
+    it('requires the transfer hook accounts for a mint with a hook', async () => {
+      const { poolData, groupAddress } =
+        await createPoolWithExampleT22ExtDeposits(
+          program,
+          connection,
+          wallet.payer,
+          'none',
+          {
+            owner: wallet.publicKey,
+            cosigner,
+          },
+        );
+      // the hook program is never invoked, the deposit fails before the transfer
+      const { mint, recipientTokenAccount } =
+        await createTestMintAndTokenT22VanillaExt(
+          connection,
+          wallet.payer,
+          undefined,
+          groupAddress,
+          undefined,
+          [],
+          'example.com',
+          1,
+          undefined,
+          Keypair.generate().publicKey,
+        );
+      const poolAta = await getAssociatedTokenAddress(
+        mint,
+        poolData.poolKey,
+        true,
+        TOKEN_2022_PROGRAM_ID,
+      );
+      const { key: sellState } = getMMMSellStatePDA(
+        program.programId,
+        poolData.poolKey,
+        mint,
+      );
+      try {
+        await program.methods
+          .extDepositSell({
+            assetAmount: new anchor.BN(1),
+            allowlistAux: '',
+          })
+          .accountsStrict({
+            owner: wallet.publicKey,
+            cosigner: cosigner.publicKey,
+            pool: poolData.poolKey,
+            assetMint: mint,
+            assetTokenAccount: recipientTokenAccount,
+            sellsideEscrowTokenAccount: poolAta,
+            sellState,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          })
+          .signers([cosigner])
+          .rpc();
+        assert.fail('should have failed');
+      } catch (err) {
+        assertProgramError(err, 'missing transfer hook accounts');
+      }
+    });
+
+    it('rejects spoofed mcc and fvca additional metadata', async () => {
+      // anyone can mint a token-2022 nft carrying a collection or creator key in its
+      // additional metadata, so fvca and mcc allowlists never match ext mints
+      const collection = Keypair.generate().publicKey;
+      const { mint, recipientTokenAccount } =
+        await createTestMintAndTokenT22VanillaExt(
+          connection,
+          wallet.payer,
+          undefined,
+          undefined,
+          undefined,
+          [
+            ['mcc', collection.toBase58()],
+            ['fvca', collection.toBase58()],
+          ],
+        );
+
+      for (const kind of [AllowlistKind.mcc, AllowlistKind.fvca]) {
+        const poolData = await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          allowlists: [{ kind, value: collection }, ...getEmptyAllowLists(7)],
+        });
+        const sellsideEscrowTokenAccount = await getAssociatedTokenAddress(
+          mint,
+          poolData.poolKey,
+          true,
+          TOKEN_2022_PROGRAM_ID,
+        );
+        const { key: sellState } = getMMMSellStatePDA(
+          program.programId,
+          poolData.poolKey,
+          mint,
+        );
+        try {
+          await program.methods
+            .extDepositSell({
+              assetAmount: new anchor.BN(1),
+              allowlistAux: '',
+            })
+            .accountsStrict({
+              owner: wallet.publicKey,
+              cosigner: cosigner.publicKey,
+              pool: poolData.poolKey,
+              assetMint: mint,
+              assetTokenAccount: recipientTokenAccount,
+              sellsideEscrowTokenAccount,
+              sellState,
+              systemProgram: SystemProgram.programId,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+              associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            })
+            .signers([cosigner])
+            .rpc({ skipPreflight: true });
+          assert.fail('should have failed');
+        } catch (err) {
+          assertProgramError(err, 'invalid allowlists');
+        }
+      }
+    });
+
+    it('rejects a spoofed update authority', async () => {
+      const allowlistedAuthority = Keypair.generate().publicKey;
+      const { groupAddress } = await createTestGroupMintExt(
+        connection,
+        wallet.payer,
+      );
+      const { groupAddress: spoofedGroupAddress } =
+        await createTestGroupMintExt(connection, wallet.payer);
+      const { mint, recipientTokenAccount } =
+        await createTestMintAndTokenT22VanillaExt(
+          connection,
+          wallet.payer,
+          undefined,
+          spoofedGroupAddress,
+        );
+      // the current update authority hands the metadata to the allowlisted key,
+      // which never signs anything
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          createUpdateAuthorityInstruction({
+            programId: TOKEN_2022_PROGRAM_ID,
+            metadata: mint,
+            oldAuthority: wallet.publicKey,
+            newAuthority: allowlistedAuthority,
+          }),
+        ),
+      );
+
+      // the update authority alone can't be the allowlist of a pool
+      try {
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          allowlists: [
+            {
+              kind: AllowlistKind.updateAuthority,
+              value: allowlistedAuthority,
+            },
+            ...getEmptyAllowLists(7),
+          ],
+        });
+        assert.fail('should have failed');
+      } catch (err) {
+        assert.include(`${err}`, 'InvalidAllowLists');
+      }
+
+      const poolData = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        allowlists: [
+          {
+            kind: AllowlistKind.updateAuthority,
+            value: allowlistedAuthority,
+          },
+          { kind: AllowlistKind.group, value: groupAddress },
+          ...getEmptyAllowLists(6),
+        ],
+        allowlistMatchMode: AllowlistMatchMode.intersection,
+      });
+      const poolAta = await getAssociatedTokenAddress(
+        mint,
+        poolData.poolKey,
+        true,
+        TOKEN_2022_PROGRAM_ID,
+      );
+      const { key: sellState } = getMMMSellStatePDA(
+        program.programId,
+        poolData.poolKey,
+        mint,
+      );
+      try {
+        await program.methods
+          .extDepositSell({
+            assetAmount: new anchor.BN(1),
+            allowlistAux: '',
+          })
+          .accountsStrict({
+            owner: wallet.publicKey,
+            cosigner: cosigner.publicKey,
+            pool: poolData.poolKey,
+            assetMint: mint,
+            assetTokenAccount: recipientTokenAccount,
+            sellsideEscrowTokenAccount: poolAta,
+            sellState,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          })
+          .signers([cosigner])
+          .rpc({ skipPreflight: true });
+        assert.fail('should have failed');
+      } catch (err) {
+        assertProgramError(err, 'invalid allowlists');
+      }
+    });
+
+    it('rejects a supply above 1 unless the mint is allowlisted', async () => {
+      const { groupAddress } = await createTestGroupMintExt(
+        connection,
+        wallet.payer,
+      );
+      const { mint, recipientTokenAccount } =
+        await createTestMintAndTokenT22VanillaExt(
+          connection,
+          wallet.payer,
+          undefined,
+          groupAddress,
+          undefined,
+          [],
+          'example.com',
+          5,
+        );
+      const poolData = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        allowlists: [
+          { kind: AllowlistKind.group, value: groupAddress },
+          ...getEmptyAllowLists(7),
+        ],
+      });
+      const poolAta = await getAssociatedTokenAddress(
+        mint,
+        poolData.poolKey,
+        true,
+        TOKEN_2022_PROGRAM_ID,
+      );
+      const { key: sellState } = getMMMSellStatePDA(
+        program.programId,
+        poolData.poolKey,
+        mint,
+      );
+      try {
+        await program.methods
+          .extDepositSell({
+            assetAmount: new anchor.BN(1),
+            allowlistAux: '',
+          })
+          .accountsStrict({
+            owner: wallet.publicKey,
+            cosigner: cosigner.publicKey,
+            pool: poolData.poolKey,
+            assetMint: mint,
+            assetTokenAccount: recipientTokenAccount,
+            sellsideEscrowTokenAccount: poolAta,
+            sellState,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          })
+          .signers([cosigner])
+          .rpc({ skipPreflight: true });
+        assert.fail('should have failed');
+      } catch (err) {
+        assertProgramError(err, 'Invalid token mint');
+      }
+    });
+
+    it('matches a mint entry in the allowlists_ext', async () => {
+      const { mint, recipientTokenAccount } =
+        await createTestMintAndTokenT22VanillaExt(connection, wallet.payer);
+      // the last of the 8 entries is kept in allowlistsExt
+      const poolData = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        allowlists: [
+          ...getEmptyAllowLists(7),
+          { kind: AllowlistKind.mint, value: mint },
+        ],
+      });
+      const poolAccountInfo = await program.account.pool.fetch(
+        poolData.poolKey,
+      );
+      assert.equal(poolAccountInfo.allowlistsExt[1].kind, AllowlistKind.mint);
+      const poolAta = await getAssociatedTokenAddress(
+        mint,
+        poolData.poolKey,
+        true,
+        TOKEN_2022_PROGRAM_ID,
+      );
+      const { key: sellState } = getMMMSellStatePDA(
+        program.programId,
+        poolData.poolKey,
+        mint,
+      );
+
+      await program.methods
+        .extDepositSell({
+          assetAmount: new anchor.BN(1),
+          allowlistAux: '',
+        })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolData.poolKey,
+          assetMint: mint,
+          assetTokenAccount: recipientTokenAccount,
+          sellsideEscrowTokenAccount: poolAta,
+          sellState,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([cosigner])
+        .rpc({ skipPreflight: true });
+
+      const nftEscrow = await getTokenAccount2022(
+        connection,
+        poolAta,
+        TOKEN_2022_PROGRAM_ID,
+      );
+      assert.equal(Number(nftEscrow.amount), 1);
+    });
+
+    describe('metadata uri match modes', () => {
+      const cases: { uri: string; aux: string; ok: boolean }[] = [
+        { uri: 'example.com', aux: 'example', ok: true },
+        { uri: 'example.com', aux: 'com', ok: false },
+        {
+          uri: 'example.com',
+          aux: `${METADATA_URI_MARKERS.SUFFIX}.com`,
+          ok: true,
+        },
+        {
+          uri: 'example.com',
+          aux: `${METADATA_URI_MARKERS.SUFFIX}example`,
+          ok: false,
+        },
+        {
+          uri: 'example.com',
+          aux: `${METADATA_URI_MARKERS.CONTAINS}mple.c`,
+          ok: true,
+        },
+        {
+          uri: 'example.com',
+          aux: `${METADATA_URI_MARKERS.CONTAINS}exam.com`,
+          ok: false,
+        },
+        // the uri is trimmed before matching
+        { uri: '  example.com  ', aux: 'example.com', ok: true },
+        {
+          uri: '  example.com  ',
+          aux: `${METADATA_URI_MARKERS.SUFFIX}example.com`,
+          ok: true,
+        },
+      ];
+
+      cases.forEach(({ uri, aux, ok }) => {
+        it(`${ok ? 'accepts' : 'rejects'} |${uri}| with |${aux}|`, async () => {
+          const { groupAddress } = await createTestGroupMintExt(
+            connection,
+            wallet.payer,
+          );
+          const { mint, recipientTokenAccount } =
+            await createTestMintAndTokenT22VanillaExt(
+              connection,
+              wallet.payer,
+              undefined,
+              groupAddress,
+              undefined,
+              [],
+              uri,
+            );
+          const poolData = await createPool(program, {
+            owner: wallet.publicKey,
+            cosigner,
+            allowlists: [
+              { kind: AllowlistKind.metadata, value: mint },
+              { kind: AllowlistKind.group, value: groupAddress },
+              ...getEmptyAllowLists(6),
+            ],
+          });
+          const poolAta = await getAssociatedTokenAddress(
+            mint,
+            poolData.poolKey,
+            true,
+            TOKEN_2022_PROGRAM_ID,
+          );
+          const { key: sellState } = getMMMSellStatePDA(
+            program.programId,
+            poolData.poolKey,
+            mint,
+          );
+
+          try {
+            await program.methods
+              .extDepositSell({
+                assetAmount: new anchor.BN(1),
+                allowlistAux: aux,
+              })
+              .accountsStrict({
+                owner: wallet.publicKey,
+                cosigner: cosigner.publicKey,
+                pool: poolData.poolKey,
+                assetMint: mint,
+                assetTokenAccount: recipientTokenAccount,
+                sellsideEscrowTokenAccount: poolAta,
+                sellState,
+                systemProgram: SystemProgram.programId,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+              })
+              .signers([cosigner])
+              .rpc({ skipPreflight: true });
+            assert.isTrue(ok, 'should have failed');
+          } catch (err) {
+            if (ok) {
+              throw err;
+            }
+            assertProgramError(err, 'Unexpected metadata uri');
+          }
+        });
+      });
+    });
+
+    describe('pool allowlist_aux', () => {
+      const depositWithAux = async (
+        uri: string,
+        poolAux: string,
+        aux: string | null,
+      ) => {
+        const { groupAddress } = await createTestGroupMintExt(
+          connection,
+          wallet.payer,
+        );
+        const { mint, recipientTokenAccount } =
+          await createTestMintAndTokenT22VanillaExt(
+            connection,
+            wallet.payer,
+            undefined,
+            groupAddress,
+            undefined,
+            [],
+            uri,
+          );
+        const poolData = await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          allowlists: [
+            { kind: AllowlistKind.metadata, value: mint },
+            { kind: AllowlistKind.group, value: groupAddress },
+            ...getEmptyAllowLists(6),
+          ],
+          allowlistAux: poolAux,
+        });
+        const poolAta = await getAssociatedTokenAddress(
+          mint,
+          poolData.poolKey,
+          true,
+          TOKEN_2022_PROGRAM_ID,
+        );
+        const { key: sellState } = getMMMSellStatePDA(
+          program.programId,
+          poolData.poolKey,
+          mint,
+        );
+
+        await program.methods
+          .extDepositSell({
+            assetAmount: new anchor.BN(1),
+            allowlistAux: aux,
+          })
+          .accountsStrict({
+            owner: wallet.publicKey,
+            cosigner: cosigner.publicKey,
+            pool: poolData.poolKey,
+            assetMint: mint,
+            assetTokenAccount: recipientTokenAccount,
+            sellsideEscrowTokenAccount: poolAta,
+            sellState,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          })
+          .signers([cosigner])
+          .rpc({ skipPreflight: true });
+        return poolData;
+      };
+
+      it('enforces the stored aux when none is passed', async () => {
+        const poolData = await depositWithAux('example.com', 'example', null);
+        const poolAccountInfo = await program.account.pool.fetch(
+          poolData.poolKey,
+        );
+        assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 1);
+
+        try {
+          await depositWithAux('other.com', 'example', null);
+          assert.fail('should have failed');
+        } catch (err) {
+          assertProgramError(err, 'Unexpected metadata uri');
+        }
+      });
+
+      it('prefers the stored aux over the passed one', async () => {
+        try {
+          await depositWithAux('other.com', 'example', 'other');
+          assert.fail('should have failed');
+        } catch (err) {
+          assertProgramError(err, 'Unexpected metadata uri');
+        }
+      });
+
+      it('can not store an aux longer than 32 bytes', async () => {
+        try {
+          await depositWithAux('example.com', 'example.com/'.repeat(3), null);
+          assert.fail('should have failed');
+        } catch (err) {
+          assert.include(`${err}`, 'InvalidAllowlistAux');
+        }
+      });
+    });
   });
 });
 
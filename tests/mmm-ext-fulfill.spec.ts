@@ -5,6 +5,7 @@ import {
   getAssociatedTokenAddress,
 } from '@solana/spl-token';
 import {
+  AccountMeta,
   Keypair,
   LAMPORTS_PER_SOL,
   PublicKey,
@@ -62,21 +63,28 @@ describe('mmm-ext-fulfill', () => {
     buyerNftAtaAddress: PublicKey,
     takerFeeBp: number,
     makerFeeBp: number,
+    assetAmount: number = 1,
+    buysideCreatorRoyaltyBp: number = 0,
+    remainingAccounts: AccountMeta[] = [],
   ) {
     const tx = await program.methods
       .solExtFulfillSell({
-        assetAmount: new anchor.BN(1),
+        assetAmount: new anchor.BN(assetAmount),
         maxPaymentAmount: new anchor.BN(maxPaymentAmount),
-        buysideCreatorRoyaltyBp: 0,
+        buysideCreatorRoyaltyBp,
         allowlistAux: '',
         takerFeeBp,
         makerFeeBp,
+        maxPricePerItem: null,
+        partialFillOk: false,
+        royaltyBpOverride: null,
       })
       .accountsStrict({
         payer: buyer.publicKey,
         owner: wallet.publicKey,
         cosigner: cosigner.publicKey,
         referral: referral.publicKey,
+        takerReferral: referral.publicKey,
         pool: poolKey,
         buysideSolEscrowAccount: solEscrowKey,
         assetMint: mint,
@@ -87,6 +95,7 @@ describe('mmm-ext-fulfill', () => {
         tokenProgram: TOKEN_2022_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       })
+      .remainingAccounts(remainingAccounts)
       .transaction();
 
     const blockhashData = await connection.getLatestBlockhash();
@@ -215,6 +224,98 @@ describe('mmm-ext-fulfill', () => {
         1.02 * LAMPORTS_PER_SOL,
       );
     });
+
+    it('Sellside only with multiple units of one mint', async () => {
+      const { mint, poolData, poolAta, sellState, solEscrowKey } =
+        await createPoolWithExampleT22ExtDeposits(
+          program,
+          connection,
+          wallet.payer,
+          'sell', // side
+          {
+            owner: wallet.publicKey,
+            cosigner: cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10), // 0.1 SOL
+            expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+            lpFeeBp: 0,
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+          },
+          false, // sharedEscrow
+          undefined, // sharedEscrowCount
+          10, // assetAmount
+        );
+
+      {
+        const [poolAccountInfo, sellStateAccountInfo] = await Promise.all([
+          program.account.pool.fetch(poolData.poolKey),
+          program.account.sellState.fetch(sellState),
+        ]);
+        assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 10);
+        assert.equal(sellStateAccountInfo.assetAmount.toNumber(), 10);
+      }
+
+      const buyerNftAtaAddress = await getAssociatedTokenAddress(
+        mint,
+        buyer.publicKey,
+        true,
+        TOKEN_2022_PROGRAM_ID,
+      );
+      const initBuyerBalance = await connection.getBalance(buyer.publicKey);
+
+      // 1.1 + 1.2 + 1.3, the curve moves once per unit
+      const expectedTotalPrice = 3.6 * LAMPORTS_PER_SOL;
+      await executeFulfillSell(
+        expectedTotalPrice,
+        poolData.referral,
+        poolData.poolKey,
+        mint,
+        sellState,
+        solEscrowKey,
+        poolAta,
+        buyerNftAtaAddress,
+        0, // takerFeeBp
+        0, // makerFeeBp
+        3, // assetAmount
+      );
+
+      const tokenAccountRent =
+        (await getTokenAccountRent(connection)) +
+        IMMUTABLE_OWNER_EXTENSION_LAMPORTS;
+      const [
+        buyerBalance,
+        buyerAta,
+        poolEscrowAta,
+        poolAccountInfo,
+        sellStateAccountInfo,
+      ] = await Promise.all([
+        connection.getBalance(buyer.publicKey),
+        getTokenAccount2022(
+          connection,
+          buyerNftAtaAddress,
+          TOKEN_2022_PROGRAM_ID,
+        ),
+        getTokenAccount2022(connection, poolAta, TOKEN_2022_PROGRAM_ID),
+        program.account.pool.fetch(poolData.poolKey),
+        program.account.sellState.fetch(sellState),
+      ]);
+      assert.equal(
+        buyerBalance,
+        initBuyerBalance -
+          expectedTotalPrice -
+          SIGNATURE_FEE_LAMPORTS * 2 -
+          tokenAccountRent,
+      );
+      assert.equal(Number(buyerAta.amount), 3);
+      assert.equal(Number(poolEscrowAta.amount), 7);
+      assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 7);
+      assert.equal(sellStateAccountInfo.assetAmount.toNumber(), 7);
+      assert.equal(
+        poolAccountInfo.spotPrice.toNumber(),
+        1.3 * LAMPORTS_PER_SOL,
+      );
+    });
   });
 
   describe('ext_fulfill_buy', () => {
@@ -290,12 +391,14 @@ describe('mmm-ext-fulfill', () => {
           allowlistAux: '',
           takerFeeBp: 0,
           makerFeeBp: 150,
+          minPricePerItem: null,
         })
         .accountsStrict({
           payer: seller.publicKey,
           owner: wallet.publicKey,
           cosigner: cosigner.publicKey,
           referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
           pool: poolData.poolKey,
           buysideSolEscrowAccount: solEscrowKey,
           assetMint: extraMint,
@@ -445,12 +548,14 @@ describe('mmm-ext-fulfill', () => {
           allowlistAux: '',
           takerFeeBp: 0,
           makerFeeBp: 150,
+          minPricePerItem: null,
         })
         .accountsStrict({
           payer: seller.publicKey,
           owner: wallet.publicKey,
           cosigner: cosigner.publicKey,
           referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
           pool: poolData.poolKey,
           buysideSolEscrowAccount: solEscrowKey,
           assetMint: extraMint,
@@ -608,12 +713,14 @@ describe('mmm-ext-fulfill', () => {
           allowlistAux: '',
           takerFeeBp: 0,
           makerFeeBp: 150,
+          minPricePerItem: null,
         })
         .accountsStrict({
           payer: seller.publicKey,
           owner: wallet.publicKey,
           cosigner: cosigner.publicKey,
           referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
           pool: poolData.poolKey,
           buysideSolEscrowAccount: solEscrowKey,
           assetMint: extraMint,
@@ -753,12 +860,14 @@ describe('mmm-ext-fulfill', () => {
           allowlistAux: null,
           takerFeeBp: 100,
           makerFeeBp: 0,
+          minPricePerItem: null,
         })
         .accountsStrict({
           payer: seller.publicKey,
           owner: wallet.publicKey,
           cosigner: cosigner.publicKey,
           referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
           pool: poolData.poolKey,
           buysideSolEscrowAccount: solEscrowKey,
           assetMint: extraMint,
@@ -844,12 +953,16 @@ describe('mmm-ext-fulfill', () => {
             allowlistAux: '',
             takerFeeBp: 100,
             makerFeeBp: 100,
+            maxPricePerItem: null,
+            partialFillOk: false,
+            royaltyBpOverride: null,
           })
           .accountsStrict({
             payer: buyer.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: solEscrowKey,
             assetMint: mint,
@@ -925,4 +1038,361 @@ describe('mmm-ext-fulfill', () => {
       );
     });
   });
+
+  describe('token metadata royalty', () => {
+    const creators = [Keypair.generate(), Keypair.generate()];
+    // 5% royalty split 60/40 between the two creators
+    const royaltyMetadata: [string, string][] = [
+      ['seller_fee_basis_points', '500'],
+      [`creator:${creators[0].publicKey.toBase58()}`, '60'],
+      [`creator:${creators[1].publicKey.toBase58()}`, '40'],
+    ];
+    const creatorAccounts = creators.map((creator) => ({
+      pubkey: creator.publicKey,
+      isSigner: false,
+      isWritable: true,
+    }));
+
+    it('pays the royalty of the metadata on ext_fulfill_sell', async () => {
+      const { mint, poolData, poolAta, sellState, solEscrowKey } =
+        await createPoolWithExampleT22ExtDeposits(
+          program,
+          connection,
+          wallet.payer,
+          'sell', // side
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            lpFeeBp: 0,
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+          },
+          false, // sharedEscrow
+          undefined, // sharedEscrowCount
+          1, // assetAmount
+          royaltyMetadata,
+        );
+      const buyerNftAtaAddress = await getAssociatedTokenAddress(
+        mint,
+        buyer.publicKey,
+        true,
+        TOKEN_2022_PROGRAM_ID,
+      );
+      const initCreatorBalances = await Promise.all(
+        creators.map((creator) => connection.getBalance(creator.publicKey)),
+      );
+
+      const tx = await program.methods
+        .solExtFulfillSell({
+          assetAmount: new anchor.BN(1),
+          maxPaymentAmount: new anchor.BN(1.05 * LAMPORTS_PER_SOL),
+          buysideCreatorRoyaltyBp: 10000,
+          allowlistAux: '',
+          takerFeeBp: 0,
+          makerFeeBp: 0,
+          maxPricePerItem: null,
+          partialFillOk: false,
+          royaltyBpOverride: null,
+        })
+        .accountsStrict({
+          payer: buyer.publicKey,
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
+          pool: poolData.poolKey,
+          buysideSolEscrowAccount: solEscrowKey,
+          assetMint: mint,
+          sellsideEscrowTokenAccount: poolAta,
+          payerAssetAccount: buyerNftAtaAddress,
+          sellState,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(creatorAccounts)
+        .transaction();
+
+      const blockhashData = await connection.getLatestBlockhash();
+      tx.feePayer = buyer.publicKey;
+      tx.recentBlockhash = blockhashData.blockhash;
+      tx.partialSign(cosigner, buyer);
+      await sendAndAssertTx(connection, tx, blockhashData, false);
+
+      const creatorBalances = await Promise.all(
+        creators.map((creator) => connection.getBalance(creator.publicKey)),
+      );
+      assert.equal(
+        creatorBalances[0],
+        initCreatorBalances[0] + 0.03 * LAMPORTS_PER_SOL,
+      );
+      assert.equal(
+        creatorBalances[1],
+        initCreatorBalances[1] + 0.02 * LAMPORTS_PER_SOL,
+      );
+    });
+
+    it('pays no royalty for a mint without creators', async () => {
+      // mpl-token-metadata rejects an empty creators list, but a token-2022 mint can
+      // have a royalty bp without any creator entries
+      const extraRecipient = Keypair.generate();
+      const { mint, poolData, poolAta, sellState, solEscrowKey } =
+        await createPoolWithExampleT22ExtDeposits(
+          program,
+          connection,
+          wallet.payer,
+          'sell', // side
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            lpFeeBp: 0,
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+            extraRoyaltyRecipients: [
+              { address: extraRecipient.publicKey, bp: 2000 },
+            ],
+          },
+          false, // sharedEscrow
+          undefined, // sharedEscrowCount
+          1, // assetAmount
+          [['seller_fee_basis_points', '500']],
+        );
+
+      // the max payment leaves no room for a royalty
+      await executeFulfillSell(
+        LAMPORTS_PER_SOL,
+        poolData.referral,
+        poolData.poolKey,
+        mint,
+        sellState,
+        solEscrowKey,
+        poolAta,
+        await getAssociatedTokenAddress(
+          mint,
+          buyer.publicKey,
+          true,
+          TOKEN_2022_PROGRAM_ID,
+        ),
+        0, // takerFeeBp
+        0, // makerFeeBp
+        1, // assetAmount
+        10000, // buysideCreatorRoyaltyBp
+      );
+      assert.equal(await connection.getBalance(extraRecipient.publicKey), 0);
+    });
+
+    [
+      { shares: [60, 40], ok: true },
+      { shares: [60, 30], ok: false },
+    ].forEach(({ shares, ok }) => {
+      const sum = shares[0] + shares[1];
+      it(`${ok ? 'pays' : 'rejects'} creator shares summing to ${sum}`, async () => {
+        const { mint, poolData, poolAta, sellState, solEscrowKey } =
+          await createPoolWithExampleT22ExtDeposits(
+            program,
+            connection,
+            wallet.payer,
+            'sell', // side
+            {
+              owner: wallet.publicKey,
+              cosigner,
+              curveType: CurveKind.linear,
+              curveDelta: new anchor.BN(0),
+              expiry: new anchor.BN(0),
+              lpFeeBp: 0,
+              reinvestFulfillBuy: false,
+              reinvestFulfillSell: false,
+            },
+            false, // sharedEscrow
+            undefined, // sharedEscrowCount
+            1, // assetAmount
+            [
+              ['seller_fee_basis_points', '500'],
+              ...creators.map(
+                (creator, index): [string, string] => [
+                  `creator:${creator.publicKey.toBase58()}`,
+                  `${shares[index]}`,
+                ],
+              ),
+            ],
+          );
+        const initCreatorBalances = await Promise.all(
+          creators.map((creator) => connection.getBalance(creator.publicKey)),
+        );
+
+        const fulfillSell = executeFulfillSell(
+          1.05 * LAMPORTS_PER_SOL,
+          poolData.referral,
+          poolData.poolKey,
+          mint,
+          sellState,
+          solEscrowKey,
+          poolAta,
+          await getAssociatedTokenAddress(
+            mint,
+            buyer.publicKey,
+            true,
+            TOKEN_2022_PROGRAM_ID,
+          ),
+          0, // takerFeeBp
+          0, // makerFeeBp
+          1, // assetAmount
+          10000, // buysideCreatorRoyaltyBp
+          creatorAccounts,
+        );
+        if (ok) {
+          await fulfillSell;
+        } else {
+          try {
+            await fulfillSell;
+            assert.fail('should have failed');
+          } catch (e) {
+            // 0x17ae InvalidCreatorShares
+            assert.include(`${e}`, '"Custom":6062');
+          }
+        }
+
+        const creatorBalances = await Promise.all(
+          creators.map((creator) => connection.getBalance(creator.publicKey)),
+        );
+        creators.forEach((_, index) =>
+          assert.equal(
+            creatorBalances[index],
+            initCreatorBalances[index] +
+              (ok ? ((LAMPORTS_PER_SOL / 20) * shares[index]) / 100 : 0),
+          ),
+        );
+      });
+    });
+
+    it('pays the royalty of the metadata on ext_fulfill_buy', async () => {
+      const seller = Keypair.generate();
+      await airdrop(connection, seller.publicKey, 10);
+      const { poolData, solEscrowKey, groupAddress } =
+        await createPoolWithExampleT22ExtDeposits(
+          program,
+          connection,
+          wallet.payer,
+          'buy', // side
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            lpFeeBp: 0,
+            buysideCreatorRoyaltyBp: 10000,
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+          },
+        );
+      const { mint, recipientTokenAccount } =
+        await createTestMintAndTokenT22VanillaExt(
+          connection,
+          wallet.payer,
+          seller.publicKey,
+          groupAddress,
+          undefined,
+          royaltyMetadata,
+        );
+      const [initSellerBalance, initReferralBalance, ...initCreatorBalances] =
+        await Promise.all([
+          connection.getBalance(seller.publicKey),
+          connection.getBalance(poolData.referral.publicKey),
+          ...creators.map((creator) =>
+            connection.getBalance(creator.publicKey),
+          ),
+        ]);
+
+      // the royalty comes out of what the seller receives, and the taker fee
+      // is priced on the whole price so the royalty can't shrink it
+      const expectedRoyalty = LAMPORTS_PER_SOL * 0.05;
+      const expectedTakerFee = LAMPORTS_PER_SOL * 0.01;
+      const tx = await program.methods
+        .solExtFulfillBuy({
+          assetAmount: new anchor.BN(1),
+          minPaymentAmount: new anchor.BN(0.94 * LAMPORTS_PER_SOL),
+          allowlistAux: '',
+          takerFeeBp: 100,
+          makerFeeBp: 0,
+          minPricePerItem: null,
+        })
+        .accountsStrict({
+          payer: seller.publicKey,
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
+          pool: poolData.poolKey,
+          buysideSolEscrowAccount: solEscrowKey,
+          assetMint: mint,
+          payerAssetAccount: recipientTokenAccount,
+          sellsideEscrowTokenAccount: await getAssociatedTokenAddress(
+            mint,
+            poolData.poolKey,
+            true,
+            TOKEN_2022_PROGRAM_ID,
+          ),
+          ownerTokenAccount: await getAssociatedTokenAddress(
+            mint,
+            wallet.publicKey,
+            true,
+            TOKEN_2022_PROGRAM_ID,
+          ),
+          allowlistAuxAccount: SystemProgram.programId,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+          sellState: getMMMSellStatePDA(
+            program.programId,
+            poolData.poolKey,
+            mint,
+          ).key,
+        })
+        .remainingAccounts(creatorAccounts)
+        .transaction();
+
+      const blockhashData = await connection.getLatestBlockhash();
+      tx.feePayer = seller.publicKey;
+      tx.recentBlockhash = blockhashData.blockhash;
+      tx.partialSign(cosigner, seller);
+      await sendAndAssertTx(connection, tx, blockhashData, false);
+
+      const [sellerBalance, referralBalance, ...creatorBalances] =
+        await Promise.all([
+          connection.getBalance(seller.publicKey),
+          connection.getBalance(poolData.referral.publicKey),
+          ...creators.map((creator) =>
+            connection.getBalance(creator.publicKey),
+          ),
+        ]);
+      assertIsBetween(
+        creatorBalances[0],
+        initCreatorBalances[0] + expectedRoyalty * 0.6,
+        PRICE_ERROR_RANGE,
+      );
+      assertIsBetween(
+        creatorBalances[1],
+        initCreatorBalances[1] + expectedRoyalty * 0.4,
+        PRICE_ERROR_RANGE,
+      );
+      assert.equal(referralBalance, initReferralBalance + expectedTakerFee);
+      assertIsBetween(
+        sellerBalance,
+        initSellerBalance +
+          LAMPORTS_PER_SOL -
+          expectedRoyalty -
+          expectedTakerFee -
+          SIGNATURE_FEE_LAMPORTS * 2,
+        PRICE_ERROR_RANGE,
+      );
+    });
+  });
 });
@@ -126,12 +126,16 @@ describe('mmm-fulfill-exp', () => {
             allowlistAux: '',
             takerFeeBp: 100,
             makerFeeBp: 0,
+            maxPricePerItem: null,
+            partialFillOk: false,
+            royaltyBpOverride: null,
           })
           .accountsStrict({
             payer: buyer.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: poolData.nft.metadataAddress,
@@ -251,6 +255,39 @@ describe('mmm-fulfill-exp', () => {
             referral: poolData.referral.publicKey,
             cosignerAnnotation: new Array(32).fill(0).map((_, index) => index),
             buysideCreatorRoyaltyBp: 0,
+            priceFloor: null,
+            priceCeiling: null,
+            lpFeeDecayEnd: null,
+            lpFeeBpFloor: null,
+            lpFeeTiers: null,
+            minEscrowBalanceBp: null,
+            extraRoyaltyRecipients: null,
+            maxSpotMoveBp: null,
+            lpFeeRounding: null,
+            maxFillDepth: null,
+            maxBuysideSpend: null,
+            poolSide: null,
+            requireSizedCollection: null,
+            rejectSelfReferral: null,
+            settleInWsol: null,
+            requireOneOfOne: null,
+            requireProgrammable: null,
+            curveDeltaSell: null,
+            royaltyEnforcementMode: null,
+            maxTotalPrice: null,
+            minFillAmount: null,
+            fillCooldownSecs: null,
+            sellStateRentToTaker: null,
+            allowlistAux: null,
+            royaltyFallbackAccount: null,
+            profitSkimBp: null,
+            oracle: null,
+            maxOracleDeviationBp: null,
+            requireAllCreatorsPaid: null,
+            curveDirection: null,
+            requireCollectionMetadata: null,
+            staticSpot: null,
+            allowRoyaltyFallback: null,
           })
           .accountsStrict({
             owner: wallet.publicKey,
@@ -278,12 +315,16 @@ describe('mmm-fulfill-exp', () => {
             allowlistAux: '',
             takerFeeBp: 150,
             makerFeeBp: 200,
+            maxPricePerItem: null,
+            partialFillOk: false,
+            royaltyBpOverride: null,
           })
           .accountsStrict({
             payer: buyer.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: poolData.sft.metadataAddress,
@@ -407,12 +448,16 @@ describe('mmm-fulfill-exp', () => {
             allowlistAux: '',
             makerFeeBp: 400,
             takerFeeBp: 0,
+            maxPricePerItem: null,
+            partialFillOk: false,
+            royaltyBpOverride: null,
           })
           .accountsStrict({
             payer: buyer.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: poolData.sft.metadataAddress,
@@ -516,12 +561,16 @@ describe('mmm-fulfill-exp', () => {
             allowlistAux: '',
             makerFeeBp: -50,
             takerFeeBp: 100,
+            maxPricePerItem: null,
+            partialFillOk: false,
+            royaltyBpOverride: null,
           })
           .accountsStrict({
             payer: buyer.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: poolData.sft.metadataAddress,
@@ -683,12 +732,14 @@ describe('mmm-fulfill-exp', () => {
             allowlistAux: '',
             takerFeeBp: 400,
             makerFeeBp: 100,
+            minPricePerItem: null,
           })
           .accountsStrict({
             payer: seller.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: poolData.extraSft.metadataAddress,
@@ -823,6 +874,39 @@ describe('mmm-fulfill-exp', () => {
             referral: poolData.referral.publicKey,
             cosignerAnnotation: new Array(32).fill(0).map((_, index) => index),
             buysideCreatorRoyaltyBp: 0,
+            priceFloor: null,
+            priceCeiling: null,
+            lpFeeDecayEnd: null,
+            lpFeeBpFloor: null,
+            lpFeeTiers: null,
+            minEscrowBalanceBp: null,
+            extraRoyaltyRecipients: null,
+            maxSpotMoveBp: null,
+            lpFeeRounding: null,
+            maxFillDepth: null,
+            maxBuysideSpend: null,
+            poolSide: null,
+            requireSizedCollection: null,
+            rejectSelfReferral: null,
+            settleInWsol: null,
+            requireOneOfOne: null,
+            requireProgrammable: null,
+            curveDeltaSell: null,
+            royaltyEnforcementMode: null,
+            maxTotalPrice: null,
+            minFillAmount: null,
+            fillCooldownSecs: null,
+            sellStateRentToTaker: null,
+            allowlistAux: null,
+            royaltyFallbackAccount: null,
+            profitSkimBp: null,
+            oracle: null,
+            maxOracleDeviationBp: null,
+            requireAllCreatorsPaid: null,
+            curveDirection: null,
+            requireCollectionMetadata: null,
+            staticSpot: null,
+            allowRoyaltyFallback: null,
           })
           .accountsStrict({
             owner: wallet.publicKey,
@@ -848,12 +932,14 @@ describe('mmm-fulfill-exp', () => {
             allowlistAux: '',
             makerFeeBp: 150,
             takerFeeBp: 0,
+            minPricePerItem: null,
           })
           .accountsStrict({
             payer: seller.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: poolData.extraNft.metadataAddress,
@@ -1038,12 +1124,14 @@ describe('mmm-fulfill-exp', () => {
             allowlistAux: null,
             takerFeeBp: 100,
             makerFeeBp: 0,
+            minPricePerItem: null,
           })
           .accountsStrict({
             payer: seller.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: poolData.extraNft.metadataAddress,
@@ -1140,12 +1228,16 @@ describe('mmm-fulfill-exp', () => {
             allowlistAux: '',
             takerFeeBp: 100,
             makerFeeBp: 100,
+            maxPricePerItem: null,
+            partialFillOk: false,
+            royaltyBpOverride: null,
           })
           .accountsStrict({
             payer: buyer.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: poolData.nft.metadataAddress,
@@ -1,40 +1,55 @@
 import * as anchor from '@project-serum/anchor';
 import {
+  createAssociatedTokenAccountInstruction,
+  createSyncNativeInstruction,
   getAssociatedTokenAddress,
+  getAssociatedTokenAddressSync,
   ASSOCIATED_TOKEN_PROGRAM_ID,
+  NATIVE_MINT,
   TOKEN_PROGRAM_ID,
   TOKEN_2022_PROGRAM_ID,
 } from '@solana/spl-token';
 import {
+  AccountMeta,
   Keypair,
   LAMPORTS_PER_SOL,
+  PublicKey,
+  sendAndConfirmTransaction,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
+  Transaction,
 } from '@solana/web3.js';
 import { assert } from 'chai';
 import {
   Mmm,
   AllowlistKind,
   CurveKind,
+  getMMMCollectionPoolPDA,
   getMMMSellStatePDA,
   IDL,
   MMMProgramID,
   getSolFulfillBuyPrices,
+  getStatsResetFromLogs,
+  PoolSide,
 } from '../sdk/src';
 import {
   airdrop,
   assertFailedTx,
   assertIsBetween,
   assertTx,
+  createPool,
   createPoolWithExampleDepositsUmi,
   getMetadataURI,
   getSellStatePDARent,
   getTokenAccount2022,
   getTokenAccountRent,
+  getTransactionLogs,
   IMMUTABLE_OWNER_EXTENSION_LAMPORTS,
   LAMPORT_ERROR_RANGE,
+  PoolData,
   sendAndAssertTx,
   SIGNATURE_FEE_LAMPORTS,
+  waitForChainTime,
 } from './utils';
 import { toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
 
@@ -57,6 +72,1091 @@ describe('mmm-fulfill-linear', () => {
     await airdrop(connection, wallet.publicKey, 50);
   });
 
+  describe('max_fill_depth', () => {
+    const createLinearPool = (curveDelta: anchor.BN, maxFillDepth: number) =>
+      createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        curveType: CurveKind.linear,
+        curveDelta,
+        maxFillDepth,
+        expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+      });
+
+    it('can not create a pool that reaches zero within the depth', async () => {
+      try {
+        // 10 fulfill_buy fills would move the price to zero
+        await createLinearPool(new anchor.BN(LAMPORTS_PER_SOL / 10), 10);
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidCurveDelta');
+      }
+    });
+
+    it('can create a pool that stays positive within the depth', async () => {
+      const { poolKey } = await createLinearPool(
+        new anchor.BN(LAMPORTS_PER_SOL / 10),
+        9,
+      );
+      const poolAccountInfo = await program.account.pool.fetch(poolKey);
+      assert.equal(poolAccountInfo.maxFillDepth, 9);
+    });
+  });
+
+  const sellExtraSft = async (
+    poolData: PoolData,
+    seller: Keypair,
+    assetAmount: number,
+    takerReferral = poolData.referral.publicKey,
+    remainingAccounts: AccountMeta[] = [],
+  ) => {
+    const extraSftMintAddress = toWeb3JsPublicKey(
+      poolData.extraSft.mintAddress,
+    );
+    const tx = await program.methods
+      .solFulfillBuy({
+        assetAmount: new anchor.BN(assetAmount),
+        minPaymentAmount: new anchor.BN(0),
+        allowlistAux: '',
+        takerFeeBp: 0,
+        makerFeeBp: 0,
+        minPricePerItem: null,
+      })
+      .accountsStrict({
+        payer: seller.publicKey,
+        owner: wallet.publicKey,
+        cosigner: cosigner.publicKey,
+        referral: poolData.referral.publicKey,
+        takerReferral,
+        pool: poolData.poolKey,
+        buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        assetMetadata: poolData.extraSft.metadataAddress,
+        assetMasterEdition: poolData.extraSft.masterEditionAddress,
+        assetMint: poolData.extraSft.mintAddress,
+        payerAssetAccount: poolData.extraSft.tokenAddress!,
+        sellsideEscrowTokenAccount: poolData.poolAtaExtraSft,
+        ownerTokenAccount: await getAssociatedTokenAddress(
+          extraSftMintAddress,
+          wallet.publicKey,
+          true,
+          TOKEN_PROGRAM_ID,
+        ),
+        allowlistAuxAccount: SystemProgram.programId,
+        sellState: getMMMSellStatePDA(
+          program.programId,
+          poolData.poolKey,
+          extraSftMintAddress,
+        ).key,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .remainingAccounts(remainingAccounts)
+      .transaction();
+
+    const blockhashData = await connection.getLatestBlockhash();
+    tx.feePayer = seller.publicKey;
+    tx.recentBlockhash = blockhashData.blockhash;
+    tx.partialSign(cosigner, seller);
+    const txId = await connection.sendRawTransaction(tx.serialize(), {
+      skipPreflight: true,
+    });
+    const confirmedTx = await connection.confirmTransaction(
+      {
+        signature: txId,
+        blockhash: blockhashData.blockhash,
+        lastValidBlockHeight: blockhashData.lastValidBlockHeight,
+      },
+      'processed',
+    );
+    return { txId, confirmedTx };
+  };
+
+  const buyNftFromPool = async (
+    poolData: PoolData,
+    buyer: Keypair,
+    makerFeeBp = 0,
+    takerFeeBp = 0,
+    remainingAccounts: AccountMeta[] = [],
+  ) => {
+    const nftMintAddress = toWeb3JsPublicKey(poolData.nft.mintAddress);
+    const tx = await program.methods
+      .solFulfillSell({
+        assetAmount: new anchor.BN(1),
+        maxPaymentAmount: new anchor.BN(2 * LAMPORTS_PER_SOL),
+        maxPricePerItem: null,
+        buysideCreatorRoyaltyBp: 0,
+        allowlistAux: '',
+        makerFeeBp,
+        takerFeeBp,
+        partialFillOk: false,
+        royaltyBpOverride: null,
+      })
+      .accountsStrict({
+        payer: buyer.publicKey,
+        owner: wallet.publicKey,
+        cosigner: cosigner.publicKey,
+        referral: poolData.referral.publicKey,
+        takerReferral: poolData.referral.publicKey,
+        pool: poolData.poolKey,
+        buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        assetMetadata: toWeb3JsPublicKey(poolData.nft.metadataAddress),
+        assetMasterEdition: toWeb3JsPublicKey(
+          poolData.nft.masterEditionAddress,
+        ),
+        assetMint: nftMintAddress,
+        sellsideEscrowTokenAccount: poolData.poolAtaNft,
+        payerAssetAccount: await getAssociatedTokenAddress(
+          nftMintAddress,
+          buyer.publicKey,
+          true,
+          TOKEN_PROGRAM_ID,
+        ),
+        allowlistAuxAccount: SystemProgram.programId,
+        sellState: getMMMSellStatePDA(
+          program.programId,
+          poolData.poolKey,
+          nftMintAddress,
+        ).key,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .remainingAccounts(remainingAccounts)
+      .transaction();
+
+    const blockhashData = await connection.getLatestBlockhash();
+    tx.feePayer = buyer.publicKey;
+    tx.recentBlockhash = blockhashData.blockhash;
+    tx.partialSign(cosigner, buyer);
+    const txId = await connection.sendRawTransaction(tx.serialize(), {
+      skipPreflight: true,
+    });
+    const confirmedTx = await connection.confirmTransaction(
+      {
+        signature: txId,
+        blockhash: blockhashData.blockhash,
+        lastValidBlockHeight: blockhashData.lastValidBlockHeight,
+      },
+      'processed',
+    );
+    return { txId, confirmedTx };
+  };
+
+  describe('max_buyside_spend', () => {
+    it('rejects fills past the cap', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mcc],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+            expiry: new anchor.BN(0),
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+            maxBuysideSpend: new anchor.BN(3.4 * LAMPORTS_PER_SOL),
+          },
+          'buy',
+          TOKEN_PROGRAM_ID,
+          seller.publicKey,
+        ),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+
+      const getBuysideSpent = async () =>
+        (
+          await program.account.pool.fetch(poolData.poolKey)
+        ).buysideSpent.toNumber();
+
+      // 1 + 0.9 + 0.8 = 2.7 SOL
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, seller, 3);
+        assertTx(txId, confirmedTx);
+        assert.equal(await getBuysideSpent(), 2.7 * LAMPORTS_PER_SOL);
+      }
+      // 0.7 + 0.6 = 1.3 SOL would go past the cap
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, seller, 2);
+        assertFailedTx(txId, confirmedTx);
+        assert.equal(await getBuysideSpent(), 2.7 * LAMPORTS_PER_SOL);
+      }
+      // 0.7 SOL reaches the cap exactly
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, seller, 1);
+        assertTx(txId, confirmedTx);
+        assert.equal(await getBuysideSpent(), 3.4 * LAMPORTS_PER_SOL);
+      }
+      // and nothing more fits, even though the escrow is still funded
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, seller, 1);
+        assertFailedTx(txId, confirmedTx);
+        assert.isAbove(
+          await connection.getBalance(poolData.poolPaymentEscrow),
+          LAMPORTS_PER_SOL,
+        );
+      }
+    });
+  });
+
+  describe('pool volume', () => {
+    it('accumulates the volume and fill count of buy and sell fills', async () => {
+      const trader = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mcc],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+            expiry: new anchor.BN(0),
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+          },
+          'both',
+          TOKEN_PROGRAM_ID,
+          trader.publicKey,
+        ),
+        airdrop(connection, trader.publicKey, 10),
+      ]);
+
+      const assertPoolVolume = async (volume: number, fillCount: number) => {
+        const pool = await program.account.pool.fetch(poolData.poolKey);
+        assert.equal(pool.totalVolumeSol.toNumber(), volume);
+        assert.equal(pool.fillCount.toNumber(), fillCount);
+      };
+      await assertPoolVolume(0, 0);
+
+      // fulfill buy of 2 at 1 + 0.9 SOL, the spot price goes to 0.8 SOL
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, trader, 2);
+        assertTx(txId, confirmedTx);
+        await assertPoolVolume((19 * LAMPORTS_PER_SOL) / 10, 1);
+      }
+      // fulfill sell of 1 at 0.9 SOL
+      {
+        const { txId, confirmedTx } = await buyNftFromPool(poolData, trader);
+        assertTx(txId, confirmedTx);
+        await assertPoolVolume((28 * LAMPORTS_PER_SOL) / 10, 2);
+      }
+      // fulfill buy of 1 at 0.9 SOL
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, trader, 1);
+        assertTx(txId, confirmedTx);
+        await assertPoolVolume((37 * LAMPORTS_PER_SOL) / 10, 3);
+      }
+      // a failed fill doesn't count
+      {
+        const { txId, confirmedTx } = await buyNftFromPool(poolData, trader);
+        assertFailedTx(txId, confirmedTx);
+        await assertPoolVolume((37 * LAMPORTS_PER_SOL) / 10, 3);
+      }
+    });
+
+    it('resets the chosen counters and logs their prior values', async () => {
+      const trader = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mcc],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+            expiry: new anchor.BN(0),
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+          },
+          'both',
+          TOKEN_PROGRAM_ID,
+          trader.publicKey,
+        ),
+        airdrop(connection, trader.publicKey, 10),
+      ]);
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, trader, 2);
+        assertTx(txId, confirmedTx);
+      }
+      const { lpFeeEarned } = await program.account.pool.fetch(
+        poolData.poolKey,
+      );
+      assert.isAbove(lpFeeEarned.toNumber(), 0);
+
+      const resetPoolStats = (owner: Keypair) =>
+        program.methods
+          .resetPoolStats({
+            totalVolumeSol: true,
+            fillCount: true,
+            lpFeeEarned: false,
+          })
+          .accountsStrict({ owner: owner.publicKey, pool: poolData.poolKey })
+          .signers([owner])
+          .rpc();
+
+      // only the owner can reset the counters
+      try {
+        await resetPoolStats(cosigner);
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'ConstraintSeeds');
+      }
+
+      const txId = await resetPoolStats(wallet.payer);
+      const statsReset = getStatsResetFromLogs(
+        await getTransactionLogs(connection, txId),
+      );
+      assert.isNotNull(statsReset);
+      assert.equal(statsReset!.pool.toBase58(), poolData.poolKey.toBase58());
+      assert.equal(
+        statsReset!.totalVolumeSol.toNumber(),
+        (19 * LAMPORTS_PER_SOL) / 10,
+      );
+      assert.equal(statsReset!.fillCount.toNumber(), 1);
+      assert.equal(statsReset!.lpFeeEarned.toNumber(), lpFeeEarned.toNumber());
+
+      // lp_fee_earned was not chosen, so it's kept
+      const pool = await program.account.pool.fetch(poolData.poolKey);
+      assert.equal(pool.totalVolumeSol.toNumber(), 0);
+      assert.equal(pool.fillCount.toNumber(), 0);
+      assert.equal(pool.lpFeeEarned.toNumber(), lpFeeEarned.toNumber());
+    });
+  });
+
+  describe('static_spot', () => {
+    it('prices along the curve without moving the spot price', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mcc],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+            expiry: new anchor.BN(0),
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+            staticSpot: true,
+          },
+          'buy',
+          TOKEN_PROGRAM_ID,
+          seller.publicKey,
+        ),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+
+      const assertPool = async (spotPrice: number, volume: number) => {
+        const pool = await program.account.pool.fetch(poolData.poolKey);
+        assert.isTrue(pool.staticSpot);
+        assert.equal(pool.spotPrice.toNumber(), spotPrice);
+        assert.equal(pool.totalVolumeSol.toNumber(), volume);
+      };
+
+      // fulfill buy of 2 at 1 + 0.9 SOL, the spot price stays at 1 SOL
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, seller, 2);
+        assertTx(txId, confirmedTx);
+        await assertPool(LAMPORTS_PER_SOL, (19 * LAMPORTS_PER_SOL) / 10);
+      }
+      // so the next fulfill buy is at 1 SOL again
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, seller, 1);
+        assertTx(txId, confirmedTx);
+        await assertPool(LAMPORTS_PER_SOL, (29 * LAMPORTS_PER_SOL) / 10);
+      }
+    });
+  });
+
+  describe('self referral', () => {
+    const createReferralPool = (
+      seller: PublicKey,
+      rejectSelfReferral: boolean,
+    ) =>
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mcc],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+          expiry: new anchor.BN(0),
+          reinvestFulfillBuy: false,
+          reinvestFulfillSell: false,
+          rejectSelfReferral,
+        },
+        'buy',
+        TOKEN_PROGRAM_ID,
+        seller,
+      );
+
+    it('rejects a taker referral that is the taker or the pool owner', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createReferralPool(seller.publicKey, true),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.isTrue(poolAccount.rejectSelfReferral);
+
+      for (const takerReferral of [seller.publicKey, wallet.publicKey]) {
+        const { txId, confirmedTx } = await sellExtraSft(
+          poolData,
+          seller,
+          1,
+          takerReferral,
+        );
+        assertFailedTx(txId, confirmedTx);
+      }
+      const { txId, confirmedTx } = await sellExtraSft(poolData, seller, 1);
+      assertTx(txId, confirmedTx);
+    });
+
+    it('accepts a self referral unless the pool opts in', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createReferralPool(seller.publicKey, false),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.isFalse(poolAccount.rejectSelfReferral);
+
+      // existing pools keep using the owner as their referral
+      const { txId, confirmedTx } = await sellExtraSft(
+        poolData,
+        seller,
+        1,
+        wallet.publicKey,
+      );
+      assertTx(txId, confirmedTx);
+    });
+  });
+
+  describe('maker rebate', () => {
+    const createRebatePool = (buyer: PublicKey) =>
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mcc],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+          expiry: new anchor.BN(0),
+          reinvestFulfillBuy: false,
+          reinvestFulfillSell: false,
+        },
+        'sell',
+        TOKEN_PROGRAM_ID,
+        buyer,
+      );
+
+    it('rejects a maker rebate above the taker fee', async () => {
+      const buyer = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createRebatePool(buyer.publicKey),
+        airdrop(connection, buyer.publicKey, 10),
+      ]);
+      const { txId, confirmedTx } = await buyNftFromPool(
+        poolData,
+        buyer,
+        -200,
+        100,
+      );
+      assertFailedTx(txId, confirmedTx);
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.equal(poolAccount.sellsideAssetAmount.toNumber(), 6);
+    });
+
+    it('funds a maker rebate equal to the taker fee from the taker fee', async () => {
+      const buyer = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createRebatePool(buyer.publicKey),
+        airdrop(connection, buyer.publicKey, 10),
+      ]);
+      const initReferralBalance = await connection.getBalance(
+        poolData.referral.publicKey,
+      );
+      const { txId, confirmedTx } = await buyNftFromPool(
+        poolData,
+        buyer,
+        -100,
+        100,
+      );
+      assertTx(txId, confirmedTx);
+      // the whole taker fee goes to the maker as the rebate
+      assert.equal(
+        await connection.getBalance(poolData.referral.publicKey),
+        initReferralBalance,
+      );
+      const poolAccount = await program.account.pool.fetch(poolData.poolKey);
+      assert.equal(poolAccount.sellsideAssetAmount.toNumber(), 5);
+    });
+  });
+
+  describe('wsol settlement', () => {
+    const createWsolPool = (side: 'buy' | 'sell', trader: PublicKey) =>
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mcc],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+          expiry: new anchor.BN(0),
+          lpFeeBp: 0,
+          reinvestFulfillBuy: false,
+          reinvestFulfillSell: false,
+          settleInWsol: true,
+        },
+        side,
+        TOKEN_PROGRAM_ID,
+        trader,
+      );
+
+    // payer_wsol_account, wsol_mint, wsol_token_program
+    const getWsolAccounts = (trader: PublicKey): AccountMeta[] => [
+      {
+        pubkey: getAssociatedTokenAddressSync(NATIVE_MINT, trader),
+        isSigner: false,
+        isWritable: true,
+      },
+      { pubkey: NATIVE_MINT, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    ];
+
+    const getWsolBalance = async (trader: PublicKey) =>
+      Number(
+        (
+          await getTokenAccount2022(
+            connection,
+            getAssociatedTokenAddressSync(NATIVE_MINT, trader),
+            TOKEN_PROGRAM_ID,
+          )
+        ).amount,
+      );
+
+    it('debits the taker wsol ata on a buy fill', async () => {
+      const buyer = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createWsolPool('sell', buyer.publicKey),
+        airdrop(connection, buyer.publicKey, 10),
+      ]);
+      const buyerWsolAccount = getAssociatedTokenAddressSync(
+        NATIVE_MINT,
+        buyer.publicKey,
+      );
+      await sendAndConfirmTransaction(
+        connection,
+        new Transaction().add(
+          createAssociatedTokenAccountInstruction(
+            buyer.publicKey,
+            buyerWsolAccount,
+            buyer.publicKey,
+            NATIVE_MINT,
+          ),
+          SystemProgram.transfer({
+            fromPubkey: buyer.publicKey,
+            toPubkey: buyerWsolAccount,
+            lamports: 3 * LAMPORTS_PER_SOL,
+          }),
+          createSyncNativeInstruction(buyerWsolAccount),
+        ),
+        [buyer],
+        { commitment: 'processed' },
+      );
+      const initBuyerBalance = await connection.getBalance(buyer.publicKey);
+
+      const { txId, confirmedTx } = await buyNftFromPool(
+        poolData,
+        buyer,
+        0,
+        0,
+        getWsolAccounts(buyer.publicKey),
+      );
+      assertTx(txId, confirmedTx);
+
+      // the 1.1 SOL price comes out of the wsol ata, and the lamports only pay
+      // for the tx and the nft ata
+      assert.equal(
+        await getWsolBalance(buyer.publicKey),
+        (19 * LAMPORTS_PER_SOL) / 10,
+      );
+      assert.equal(
+        initBuyerBalance - (await connection.getBalance(buyer.publicKey)),
+        SIGNATURE_FEE_LAMPORTS * 2 + (await getTokenAccountRent(connection)),
+      );
+    });
+
+    it('credits the taker wsol ata on a sell fill', async () => {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createWsolPool('buy', seller.publicKey),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+
+      const { txId, confirmedTx } = await sellExtraSft(
+        poolData,
+        seller,
+        1,
+        poolData.referral.publicKey,
+        getWsolAccounts(seller.publicKey),
+      );
+      assertTx(txId, confirmedTx);
+      assert.equal(await getWsolBalance(seller.publicKey), LAMPORTS_PER_SOL);
+    });
+
+    it('requires the wsol accounts', async () => {
+      const buyer = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createWsolPool('sell', buyer.publicKey),
+        airdrop(connection, buyer.publicKey, 10),
+      ]);
+      const { txId, confirmedTx } = await buyNftFromPool(poolData, buyer);
+      assertFailedTx(txId, confirmedTx);
+    });
+  });
+
+  describe('pool_side', () => {
+    const createSidedPool = (poolSide: PoolSide, trader: PublicKey) =>
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mcc],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+          expiry: new anchor.BN(0),
+          reinvestFulfillBuy: false,
+          reinvestFulfillSell: false,
+          poolSide,
+        },
+        'both',
+        TOKEN_PROGRAM_ID,
+        trader,
+      );
+
+    it('sell only pools reject fulfill buy', async () => {
+      const trader = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createSidedPool(PoolSide.sellOnly, trader.publicKey),
+        airdrop(connection, trader.publicKey, 10),
+      ]);
+
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, trader, 1);
+        assertFailedTx(txId, confirmedTx);
+      }
+      const { txId, confirmedTx } = await buyNftFromPool(poolData, trader);
+      assertTx(txId, confirmedTx);
+    });
+
+    it('buy only pools reject fulfill sell', async () => {
+      const trader = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createSidedPool(PoolSide.buyOnly, trader.publicKey),
+        airdrop(connection, trader.publicKey, 10),
+      ]);
+
+      {
+        const { txId, confirmedTx } = await buyNftFromPool(poolData, trader);
+        assertFailedTx(txId, confirmedTx);
+      }
+      const { txId, confirmedTx } = await sellExtraSft(poolData, trader, 1);
+      assertTx(txId, confirmedTx);
+    });
+
+    it('can not create a pool with an unknown side', async () => {
+      try {
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          poolSide: 3,
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidPoolSide');
+      }
+    });
+  });
+
+  describe('min_fill_amount', () => {
+    it('rejects fills below the minimum on both sides', async () => {
+      const trader = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mcc],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+            expiry: new anchor.BN(0),
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+            minFillAmount: new anchor.BN(2),
+          },
+          'both',
+          TOKEN_PROGRAM_ID,
+          trader.publicKey,
+        ),
+        airdrop(connection, trader.publicKey, 10),
+      ]);
+
+      // the nft can only be filled one at a time
+      {
+        const { txId, confirmedTx } = await buyNftFromPool(poolData, trader);
+        assertFailedTx(txId, confirmedTx);
+      }
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, trader, 1);
+        assertFailedTx(txId, confirmedTx);
+      }
+      const { txId, confirmedTx } = await sellExtraSft(poolData, trader, 2);
+      assertTx(txId, confirmedTx);
+      const poolAccountInfo = await program.account.pool.fetch(
+        poolData.poolKey,
+      );
+      assert.equal(poolAccountInfo.fillCount.toNumber(), 1);
+    });
+  });
+
+  describe('fill_cooldown_secs', () => {
+    it('rejects fills within the cooldown of the last fill', async () => {
+      const fillCooldownSecs = 2;
+      const trader = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mcc],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+            expiry: new anchor.BN(0),
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+            fillCooldownSecs: new anchor.BN(fillCooldownSecs),
+          },
+          'both',
+          TOKEN_PROGRAM_ID,
+          trader.publicKey,
+        ),
+        airdrop(connection, trader.publicKey, 10),
+      ]);
+
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, trader, 1);
+        assertTx(txId, confirmedTx);
+      }
+      const { lastFillTs } = await program.account.pool.fetch(
+        poolData.poolKey,
+      );
+      assert.isAbove(lastFillTs.toNumber(), 0);
+
+      // the cooldown is on the pool, so the other side is rejected as well
+      {
+        const { txId, confirmedTx } = await buyNftFromPool(poolData, trader);
+        assertFailedTx(txId, confirmedTx);
+      }
+      {
+        const { txId, confirmedTx } = await sellExtraSft(poolData, trader, 1);
+        assertFailedTx(txId, confirmedTx);
+      }
+
+      await waitForChainTime(
+        connection,
+        lastFillTs.toNumber() + fillCooldownSecs,
+      );
+      const { txId, confirmedTx } = await buyNftFromPool(poolData, trader);
+      assertTx(txId, confirmedTx);
+      const poolAccountInfo = await program.account.pool.fetch(
+        poolData.poolKey,
+      );
+      assert.equal(poolAccountInfo.fillCount.toNumber(), 2);
+      assert.isAtLeast(
+        poolAccountInfo.lastFillTs.toNumber(),
+        lastFillTs.toNumber() + fillCooldownSecs,
+      );
+    });
+
+    it('can not set a negative cooldown', async () => {
+      try {
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          fillCooldownSecs: new anchor.BN(-1),
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidFillCooldown');
+      }
+    });
+  });
+
+  describe('sell_state_rent_to_taker', () => {
+    // returns how much the buyer spent to buy the nft, which empties its sell state
+    const buyNftSpend = async (sellStateRentToTaker: boolean) => {
+      const buyer = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mcc],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+            expiry: new anchor.BN(0),
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+            sellStateRentToTaker,
+          },
+          'sell',
+          TOKEN_PROGRAM_ID,
+          buyer.publicKey,
+        ),
+        airdrop(connection, buyer.publicKey, 10),
+      ]);
+      const sellState = getMMMSellStatePDA(
+        program.programId,
+        poolData.poolKey,
+        toWeb3JsPublicKey(poolData.nft.mintAddress),
+      ).key;
+      const initBuyerBalance = await connection.getBalance(buyer.publicKey);
+
+      const { txId, confirmedTx } = await buyNftFromPool(poolData, buyer);
+      assertTx(txId, confirmedTx);
+      assert.equal(await connection.getBalance(sellState), 0);
+      return initBuyerBalance - (await connection.getBalance(buyer.publicKey));
+    };
+
+    it('refunds the sell state rent to the configured recipient', async () => {
+      const [ownerRefundSpend, takerRefundSpend] = await Promise.all([
+        buyNftSpend(false),
+        buyNftSpend(true),
+      ]);
+      assert.equal(
+        ownerRefundSpend - takerRefundSpend,
+        await getSellStatePDARent(connection),
+      );
+    });
+  });
+
+  describe('collection pool', () => {
+    it('fills the pool derived from the owner and the collection', async () => {
+      const buyer = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mcc],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+          },
+          'both',
+          TOKEN_PROGRAM_ID,
+          buyer.publicKey,
+          undefined, // sellerFeeBasisPoints
+          false, // sizedCollection
+          true, // collectionPool
+        ),
+        airdrop(connection, buyer.publicKey, 10),
+      ]);
+      const poolAccountInfo = await program.account.pool.fetch(
+        poolData.poolKey,
+      );
+      const collectionMint = poolAccountInfo.allowlists[0].value;
+      assert.equal(poolAccountInfo.uuid.toBase58(), collectionMint.toBase58());
+      assert.equal(
+        getMMMCollectionPoolPDA(
+          program.programId,
+          wallet.publicKey,
+          collectionMint,
+        ).key.toBase58(),
+        poolData.poolKey.toBase58(),
+      );
+
+      {
+        const { txId, confirmedTx } = await buyNftFromPool(poolData, buyer);
+        assertTx(txId, confirmedTx);
+      }
+      const { txId, confirmedTx } = await sellExtraSft(poolData, buyer, 1);
+      assertTx(txId, confirmedTx);
+    });
+  });
+
+  describe('oracle deviation', () => {
+    // tests/fixtures/oracle.json, an oracle account with a 1 SOL price
+    const ORACLE = new PublicKey(
+      'HQRVLfLQBn2hSnxT3PorKN8AA3MJCXhcVNtvB81a2QQ4',
+    );
+    const oracleAccount = {
+      pubkey: ORACLE,
+      isSigner: false,
+      isWritable: false,
+    };
+
+    const createOraclePool = async (spotPrice: number, buyer: Keypair) => {
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mcc],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            spotPrice: new anchor.BN(spotPrice),
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: false,
+            oracle: ORACLE,
+            maxOracleDeviationBp: 1000,
+          },
+          'both',
+          TOKEN_PROGRAM_ID,
+          buyer.publicKey,
+        ),
+        airdrop(connection, buyer.publicKey, 10),
+      ]);
+      return poolData;
+    };
+
+    it('fills when the spot price is within the deviation', async () => {
+      const buyer = Keypair.generate();
+      const poolData = await createOraclePool(1.05 * LAMPORTS_PER_SOL, buyer);
+      {
+        const { txId, confirmedTx } = await buyNftFromPool(
+          poolData,
+          buyer,
+          0,
+          0,
+          [oracleAccount],
+        );
+        assertTx(txId, confirmedTx);
+      }
+      const { txId, confirmedTx } = await sellExtraSft(
+        poolData,
+        buyer,
+        1,
+        undefined,
+        [oracleAccount],
+      );
+      assertTx(txId, confirmedTx);
+    });
+
+    it('rejects fills when the spot price is out of band', async () => {
+      const buyer = Keypair.generate();
+      const poolData = await createOraclePool(1.2 * LAMPORTS_PER_SOL, buyer);
+      {
+        const { txId, confirmedTx } = await buyNftFromPool(
+          poolData,
+          buyer,
+          0,
+          0,
+          [oracleAccount],
+        );
+        assertFailedTx(txId, confirmedTx);
+      }
+      const { txId, confirmedTx } = await sellExtraSft(
+        poolData,
+        buyer,
+        1,
+        undefined,
+        [oracleAccount],
+      );
+      assertFailedTx(txId, confirmedTx);
+    });
+
+    it('rejects fills without the oracle account', async () => {
+      const buyer = Keypair.generate();
+      const poolData = await createOraclePool(1.05 * LAMPORTS_PER_SOL, buyer);
+      const { txId, confirmedTx } = await buyNftFromPool(poolData, buyer);
+      assertFailedTx(txId, confirmedTx);
+    });
+  });
+
+  describe('profit_skim_bp', () => {
+    it('skims the bp of the proceeds to the owner and reinvests the rest', async () => {
+      const buyer = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mcc],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(0),
+            expiry: new anchor.BN(0),
+            lpFeeBp: 0,
+            reinvestFulfillBuy: false,
+            reinvestFulfillSell: true,
+            profitSkimBp: 2000,
+          },
+          'sell',
+          TOKEN_PROGRAM_ID,
+          buyer.publicKey,
+        ),
+        airdrop(connection, buyer.publicKey, 10),
+      ]);
+      const [initOwnerBalance, initEscrowBalance] = await Promise.all([
+        connection.getBalance(wallet.publicKey),
+        connection.getBalance(poolData.poolPaymentEscrow),
+      ]);
+
+      const { txId, confirmedTx } = await buyNftFromPool(poolData, buyer);
+      assertTx(txId, confirmedTx);
+
+      // the owner also gets the rent of the emptied escrow token account and sell state
+      const [ownerBalance, escrowBalance] = await Promise.all([
+        connection.getBalance(wallet.publicKey),
+        connection.getBalance(poolData.poolPaymentEscrow),
+      ]);
+      assert.equal(
+        ownerBalance - initOwnerBalance,
+        0.2 * LAMPORTS_PER_SOL +
+          (await getTokenAccountRent(connection)) +
+          (await getSellStatePDARent(connection)),
+      );
+      assert.equal(escrowBalance - initEscrowBalance, 0.8 * LAMPORTS_PER_SOL);
+    });
+
+    it('can not skim more than the proceeds', async () => {
+      try {
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          profitSkimBp: 10001,
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidBP');
+      }
+    });
+  });
+
   TOKEN_PROGRAM_IDS.forEach((tokenProgramId) => {
     describe(`Token program: ${tokenProgramId}`, () => {
       it('Sellside only', async () => {
@@ -114,12 +1214,16 @@ describe('mmm-fulfill-linear', () => {
               allowlistAux: '',
               takerFeeBp: 100,
               makerFeeBp: 0,
+              maxPricePerItem: null,
+              partialFillOk: false,
+              royaltyBpOverride: null,
             })
             .accountsStrict({
               payer: buyer.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.nft.metadataAddress,
@@ -247,6 +1351,39 @@ describe('mmm-fulfill-linear', () => {
                 .fill(0)
                 .map((_, index) => index),
               buysideCreatorRoyaltyBp: 0,
+              priceFloor: null,
+              priceCeiling: null,
+              lpFeeDecayEnd: null,
+              lpFeeBpFloor: null,
+              lpFeeTiers: null,
+              minEscrowBalanceBp: null,
+              extraRoyaltyRecipients: null,
+              maxSpotMoveBp: null,
+              lpFeeRounding: null,
+              maxFillDepth: null,
+              maxBuysideSpend: null,
+              poolSide: null,
+              requireSizedCollection: null,
+              rejectSelfReferral: null,
+              settleInWsol: null,
+              requireOneOfOne: null,
+              requireProgrammable: null,
+              curveDeltaSell: null,
+              royaltyEnforcementMode: null,
+              maxTotalPrice: null,
+              minFillAmount: null,
+              fillCooldownSecs: null,
+              sellStateRentToTaker: null,
+              allowlistAux: null,
+              royaltyFallbackAccount: null,
+              profitSkimBp: null,
+              oracle: null,
+              maxOracleDeviationBp: null,
+              requireAllCreatorsPaid: null,
+              curveDirection: null,
+              requireCollectionMetadata: null,
+              staticSpot: null,
+              allowRoyaltyFallback: null,
             })
             .accountsStrict({
               owner: wallet.publicKey,
@@ -273,12 +1410,16 @@ describe('mmm-fulfill-linear', () => {
               allowlistAux: '',
               takerFeeBp: 150,
               makerFeeBp: 200,
+              maxPricePerItem: null,
+              partialFillOk: false,
+              royaltyBpOverride: null,
             })
             .accountsStrict({
               payer: buyer.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.sft.metadataAddress,
@@ -400,12 +1541,16 @@ describe('mmm-fulfill-linear', () => {
               allowlistAux: '',
               makerFeeBp: 400,
               takerFeeBp: 0,
+              maxPricePerItem: null,
+              partialFillOk: false,
+              royaltyBpOverride: null,
             })
             .accountsStrict({
               payer: buyer.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.sft.metadataAddress,
@@ -549,12 +1694,14 @@ describe('mmm-fulfill-linear', () => {
               allowlistAux: '',
               takerFeeBp: 400,
               makerFeeBp: -100,
+              minPricePerItem: null,
             })
             .accountsStrict({
               payer: seller.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.extraSft.metadataAddress,
@@ -684,6 +1831,39 @@ describe('mmm-fulfill-linear', () => {
                 .fill(0)
                 .map((_, index) => index),
               buysideCreatorRoyaltyBp: 0,
+              priceFloor: null,
+              priceCeiling: null,
+              lpFeeDecayEnd: null,
+              lpFeeBpFloor: null,
+              lpFeeTiers: null,
+              minEscrowBalanceBp: null,
+              extraRoyaltyRecipients: null,
+              maxSpotMoveBp: null,
+              lpFeeRounding: null,
+              maxFillDepth: null,
+              maxBuysideSpend: null,
+              poolSide: null,
+              requireSizedCollection: null,
+              rejectSelfReferral: null,
+              settleInWsol: null,
+              requireOneOfOne: null,
+              requireProgrammable: null,
+              curveDeltaSell: null,
+              royaltyEnforcementMode: null,
+              maxTotalPrice: null,
+              minFillAmount: null,
+              fillCooldownSecs: null,
+              sellStateRentToTaker: null,
+              allowlistAux: null,
+              royaltyFallbackAccount: null,
+              profitSkimBp: null,
+              oracle: null,
+              maxOracleDeviationBp: null,
+              requireAllCreatorsPaid: null,
+              curveDirection: null,
+              requireCollectionMetadata: null,
+              staticSpot: null,
+              allowRoyaltyFallback: null,
             })
             .accountsStrict({
               owner: wallet.publicKey,
@@ -706,12 +1886,14 @@ describe('mmm-fulfill-linear', () => {
               allowlistAux: '',
               makerFeeBp: 150,
               takerFeeBp: 0,
+              minPricePerItem: null,
             })
             .accountsStrict({
               payer: seller.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.extraNft.metadataAddress,
@@ -895,12 +2077,14 @@ describe('mmm-fulfill-linear', () => {
               allowlistAux: null,
               takerFeeBp: 100,
               makerFeeBp: 0,
+              minPricePerItem: null,
             })
             .accountsStrict({
               payer: seller.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.extraNft.metadataAddress,
@@ -1002,12 +2186,16 @@ describe('mmm-fulfill-linear', () => {
               allowlistAux: '',
               takerFeeBp: 100,
               makerFeeBp: 100,
+              maxPricePerItem: null,
+              partialFillOk: false,
+              royaltyBpOverride: null,
             })
             .accountsStrict({
               payer: buyer.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.nft.metadataAddress,
@@ -1135,12 +2323,14 @@ describe('mmm-fulfill-linear', () => {
                 allowlistAux: allowlistAux,
                 takerFeeBp: 400,
                 makerFeeBp: 100,
+                minPricePerItem: null,
               })
               .accountsStrict({
                 payer: seller.publicKey,
                 owner: wallet.publicKey,
                 cosigner: cosigner.publicKey,
                 referral: poolData.referral.publicKey,
+                takerReferral: poolData.referral.publicKey,
                 pool: poolData.poolKey,
                 buysideSolEscrowAccount: poolData.poolPaymentEscrow,
                 assetMetadata: poolData.extraSft.metadataAddress,
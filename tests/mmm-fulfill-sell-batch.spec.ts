@@ -0,0 +1,286 @@
+import * as anchor from '@project-serum/anchor';
+import {
+  getAssociatedTokenAddress,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import {
+  AccountMeta,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js';
+import { assert } from 'chai';
+import {
+  Mmm,
+  AllowlistKind,
+  CurveKind,
+  getMMMSellStatePDA,
+  IDL,
+  MMMProgramID,
+} from '../sdk/src';
+import {
+  airdrop,
+  createPoolWithExampleDepositsUmi,
+  PoolData,
+  sendAndAssertTx,
+  SIGNATURE_FEE_LAMPORTS,
+} from './utils';
+import { toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
+
+describe('mmm-fulfill-sell-batch', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const cosigner = Keypair.generate();
+
+  beforeEach(async () => {
+    await airdrop(connection, wallet.publicKey, 50);
+  });
+
+  const createSellsidePool = async (buyer: Keypair) => {
+    const [poolData] = await Promise.all([
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mint],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+          expiry: new anchor.BN(0),
+          lpFeeBp: 200,
+          reinvestFulfillSell: false,
+        },
+        'sell',
+        TOKEN_PROGRAM_ID,
+        buyer.publicKey,
+      ),
+      airdrop(connection, buyer.publicKey, 10),
+    ]);
+    return poolData;
+  };
+
+  // the nft and one of the sft deposits, with the accounts of each mint
+  const getMintAccounts = async (poolData: PoolData, buyer: PublicKey) =>
+    Promise.all(
+      [
+        { asset: poolData.nft, poolAta: poolData.poolAtaNft },
+        { asset: poolData.sft, poolAta: poolData.poolAtaSft },
+      ].map(async ({ asset, poolAta }) => {
+        const assetMint = toWeb3JsPublicKey(asset.mintAddress);
+        return {
+          assetMint,
+          assetMetadata: toWeb3JsPublicKey(asset.metadataAddress),
+          assetMasterEdition: toWeb3JsPublicKey(asset.masterEditionAddress),
+          sellsideEscrowTokenAccount: poolAta,
+          payerAssetAccount: await getAssociatedTokenAddress(
+            assetMint,
+            buyer,
+            true,
+            TOKEN_PROGRAM_ID,
+          ),
+          sellState: getMMMSellStatePDA(
+            program.programId,
+            poolData.poolKey,
+            assetMint,
+          ).key,
+        };
+      }),
+    );
+
+  const sendTx = async (tx: anchor.web3.Transaction, buyer: Keypair) => {
+    const blockhashData = await connection.getLatestBlockhash();
+    tx.feePayer = buyer.publicKey;
+    tx.recentBlockhash = blockhashData.blockhash;
+    tx.partialSign(cosigner, buyer);
+    await sendAndAssertTx(connection, tx, blockhashData, false);
+  };
+
+  it('pays the same as sequential single fills', async () => {
+    const batchBuyer = Keypair.generate();
+    const singleBuyer = Keypair.generate();
+    const [batchPoolData, singlePoolData] = await Promise.all([
+      createSellsidePool(batchBuyer),
+      createSellsidePool(singleBuyer),
+    ]);
+    const [batchMints, singleMints] = await Promise.all([
+      getMintAccounts(batchPoolData, batchBuyer.publicKey),
+      getMintAccounts(singlePoolData, singleBuyer.publicKey),
+    ]);
+    const [initBatchBuyerBalance, initSingleBuyerBalance] = await Promise.all([
+      connection.getBalance(batchBuyer.publicKey),
+      connection.getBalance(singleBuyer.publicKey),
+    ]);
+
+    const fillArgs = {
+      maxPaymentAmount: new anchor.BN(3 * LAMPORTS_PER_SOL),
+      maxPricePerItem: null,
+      buysideCreatorRoyaltyBp: 0,
+      allowlistAux: '',
+      takerFeeBp: 100,
+      makerFeeBp: 0,
+    };
+    const fixedAccounts = (poolData: PoolData, buyer: Keypair) => ({
+      payer: buyer.publicKey,
+      owner: wallet.publicKey,
+      cosigner: cosigner.publicKey,
+      referral: poolData.referral.publicKey,
+      takerReferral: poolData.referral.publicKey,
+      pool: poolData.poolKey,
+      buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+      allowlistAuxAccount: SystemProgram.programId,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+    });
+    const creatorAccount: AccountMeta = {
+      pubkey: batchPoolData.nftCreator.publicKey,
+      isSigner: false,
+      isWritable: true,
+    };
+
+    await sendTx(
+      await program.methods
+        .solFulfillSellBatch({
+          assetAmount: new anchor.BN(batchMints.length),
+          ...fillArgs,
+        })
+        .accountsStrict(fixedAccounts(batchPoolData, batchBuyer))
+        .remainingAccounts(
+          batchMints.flatMap((mint) => [
+            { pubkey: mint.assetMint, isSigner: false, isWritable: false },
+            { pubkey: mint.assetMetadata, isSigner: false, isWritable: false },
+            {
+              pubkey: mint.assetMasterEdition,
+              isSigner: false,
+              isWritable: false,
+            },
+            {
+              pubkey: mint.sellsideEscrowTokenAccount,
+              isSigner: false,
+              isWritable: true,
+            },
+            {
+              pubkey: mint.payerAssetAccount,
+              isSigner: false,
+              isWritable: true,
+            },
+            { pubkey: mint.sellState, isSigner: false, isWritable: true },
+            creatorAccount,
+          ]),
+        )
+        .transaction(),
+      batchBuyer,
+    );
+
+    for (const mint of singleMints) {
+      await sendTx(
+        await program.methods
+          .solFulfillSell({
+            assetAmount: new anchor.BN(1),
+            partialFillOk: false,
+            ...fillArgs,
+            royaltyBpOverride: null,
+          })
+          .accountsStrict({
+            ...fixedAccounts(singlePoolData, singleBuyer),
+            ...mint,
+          })
+          .remainingAccounts([
+            {
+              pubkey: singlePoolData.nftCreator.publicKey,
+              isSigner: false,
+              isWritable: true,
+            },
+          ])
+          .transaction(),
+        singleBuyer,
+      );
+    }
+
+    const [
+      batchBuyerBalance,
+      singleBuyerBalance,
+      batchPoolAccountInfo,
+      singlePoolAccountInfo,
+    ] = await Promise.all([
+      connection.getBalance(batchBuyer.publicKey),
+      connection.getBalance(singleBuyer.publicKey),
+      program.account.pool.fetch(batchPoolData.poolKey),
+      program.account.pool.fetch(singlePoolData.poolKey),
+    ]);
+
+    // both buyers pay for the same atas, the single buyer signs one more tx
+    assert.equal(
+      initBatchBuyerBalance - batchBuyerBalance,
+      initSingleBuyerBalance -
+        singleBuyerBalance -
+        SIGNATURE_FEE_LAMPORTS * 2,
+    );
+    assert.equal(
+      batchPoolAccountInfo.spotPrice.toNumber(),
+      singlePoolAccountInfo.spotPrice.toNumber(),
+    );
+    assert.equal(
+      batchPoolAccountInfo.spotPrice.toNumber(),
+      1.2 * LAMPORTS_PER_SOL,
+    );
+    assert.equal(
+      batchPoolAccountInfo.lpFeeEarned.toNumber(),
+      singlePoolAccountInfo.lpFeeEarned.toNumber(),
+    );
+    assert.equal(
+      batchPoolAccountInfo.sellsideAssetAmount.toNumber(),
+      singlePoolAccountInfo.sellsideAssetAmount.toNumber(),
+    );
+    assert.equal(batchPoolAccountInfo.sellsideAssetAmount.toNumber(), 4);
+  });
+
+  it('can not fill more than the max batch size', async () => {
+    const buyer = Keypair.generate();
+    const poolData = await createSellsidePool(buyer);
+    try {
+      await program.methods
+        .solFulfillSellBatch({
+          assetAmount: new anchor.BN(6),
+          maxPaymentAmount: new anchor.BN(10 * LAMPORTS_PER_SOL),
+          maxPricePerItem: null,
+          buysideCreatorRoyaltyBp: 0,
+          allowlistAux: '',
+          takerFeeBp: 0,
+          makerFeeBp: 0,
+        })
+        .accountsStrict({
+          payer: buyer.publicKey,
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
+          pool: poolData.poolKey,
+          buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+          allowlistAuxAccount: SystemProgram.programId,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([cosigner, buyer])
+        .rpc();
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidBatchSize');
+    }
+  });
+});
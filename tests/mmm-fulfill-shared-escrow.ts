@@ -24,7 +24,9 @@ import {
   MMMProgramID,
   Mmm,
   getM2BuyerSharedEscrow,
+  getMMMBuysideSolEscrowPDA,
   getMMMSellStatePDA,
+  getSharedEscrowWithdrawFromLogs,
   getSolFulfillBuyPrices,
   getTokenRecordPDA,
 } from '../sdk/src';
@@ -34,11 +36,13 @@ import {
   airdrop,
   assertTx,
   createDefaultTokenAuthorizationRules,
+  createPool,
   createPoolWithExampleDeposits,
   createPoolWithExampleMip1Deposits,
   getMetaplexInstance,
   getSellStatePDARent,
   getTokenAccountRent,
+  getTransactionLogs,
   sendAndAssertTx,
 } from './utils';
 
@@ -118,12 +122,14 @@ describe('shared-escrow mmm-fulfill-linear', () => {
           allowlistAux: '',
           takerFeeBp: 400,
           makerFeeBp: -100,
+          minPricePerItem: null,
         })
         .accountsStrict({
           payer: seller.publicKey,
           owner: wallet.publicKey,
           cosigner: cosigner.publicKey,
           referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
           pool: poolData.poolKey,
           buysideSolEscrowAccount: poolData.poolPaymentEscrow,
           assetMetadata: poolData.extraSft.metadataAddress,
@@ -315,12 +321,14 @@ describe('shared-escrow mmm-fulfill-linear', () => {
         allowlistAux: null,
         makerFeeBp: 350,
         takerFeeBp: 50,
+        minPricePerItem: null,
       })
       .accountsStrict({
         payer: seller.publicKey,
         owner: wallet.publicKey,
         cosigner: cosigner.publicKey,
         referral: poolData.referral.publicKey,
+        takerReferral: poolData.referral.publicKey,
         pool: poolData.poolKey,
         buysideSolEscrowAccount: poolData.poolPaymentEscrow,
         assetMetadata: poolData.extraNft.metadataAddress,
@@ -506,12 +514,14 @@ describe('shared-escrow mmm-fulfill-linear', () => {
         allowlistAux: null,
         makerFeeBp: 350,
         takerFeeBp: 50,
+        minPricePerItem: null,
       })
       .accountsStrict({
         payer: seller.publicKey,
         owner: wallet.publicKey,
         cosigner: cosigner.publicKey,
         referral: poolData.referral.publicKey,
+        takerReferral: poolData.referral.publicKey,
         pool: poolData.poolKey,
         buysideSolEscrowAccount: poolData.poolPaymentEscrow,
         assetMetadata: poolData.extraNft.metadataAddress,
@@ -607,4 +617,238 @@ describe('shared-escrow mmm-fulfill-linear', () => {
     );
     assert.isNull(poolAccountInfo);
   });
+
+  describe('reconcile_shared_escrow', () => {
+    // every test gets its own owner, and so its own M2 shared escrow
+    const createSharedEscrowPool = async (sharedEscrowBalanceSol: number) => {
+      const owner = new anchor.Wallet(Keypair.generate());
+      const ownerProgram = new anchor.Program(
+        IDL,
+        MMMProgramID,
+        new anchor.AnchorProvider(connection, owner, {
+          commitment: 'processed',
+        }),
+      ) as anchor.Program<Mmm>;
+      const sharedEscrowAccount = getM2BuyerSharedEscrow(owner.publicKey).key;
+      await Promise.all([
+        airdrop(connection, owner.publicKey, 10),
+        ...(sharedEscrowBalanceSol > 0
+          ? [airdrop(connection, sharedEscrowAccount, sharedEscrowBalanceSol)]
+          : []),
+      ]);
+      const { poolKey } = await createPool(ownerProgram, {
+        owner: owner.publicKey,
+        cosigner,
+        curveType: CurveKind.linear,
+        curveDelta: new anchor.BN(LAMPORTS_PER_SOL).div(new anchor.BN(10)), // 0.1 SOL
+        reinvestFulfillBuy: false,
+        reinvestFulfillSell: false,
+      });
+      await ownerProgram.methods
+        .setSharedEscrow({ sharedEscrowCount: new anchor.BN(4) })
+        .accountsStrict({
+          owner: owner.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolKey,
+          sharedEscrowAccount,
+        })
+        .signers([cosigner])
+        .rpc();
+
+      const reconcile = () =>
+        ownerProgram.methods
+          .reconcileSharedEscrow()
+          .accountsStrict({ owner: owner.publicKey, pool: poolKey })
+          .remainingAccounts([
+            { pubkey: M2_PROGRAM, isWritable: false, isSigner: false },
+            { pubkey: sharedEscrowAccount, isWritable: false, isSigner: false },
+          ])
+          .rpc();
+      return { ownerProgram, owner, poolKey, reconcile };
+    };
+
+    it('lowers the count to the fills the shared escrow can pay', async () => {
+      // 1 + 0.9 SOL for two fills, and 2.7 SOL for three
+      const { ownerProgram, owner, poolKey, reconcile } =
+        await createSharedEscrowPool(2);
+      await reconcile();
+      let poolAccountInfo = await ownerProgram.account.pool.fetch(poolKey);
+      assert.equal(poolAccountInfo.sharedEscrowCount.toNumber(), 2);
+
+      // and it's never raised
+      await airdrop(
+        connection,
+        getM2BuyerSharedEscrow(owner.publicKey).key,
+        10,
+      );
+      await reconcile();
+      poolAccountInfo = await ownerProgram.account.pool.fetch(poolKey);
+      assert.equal(poolAccountInfo.sharedEscrowCount.toNumber(), 2);
+    });
+
+    it('unsticks the count of a drained shared escrow so the pool can close', async () => {
+      const { ownerProgram, owner, poolKey, reconcile } =
+        await createSharedEscrowPool(0);
+      await reconcile();
+      const poolAccountInfo = await ownerProgram.account.pool.fetch(poolKey);
+      assert.equal(poolAccountInfo.sharedEscrowCount.toNumber(), 0);
+
+      await ownerProgram.methods
+        .ownerDrainAndClose()
+        .accountsStrict({
+          owner: owner.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolKey,
+          buysideSolEscrowAccount: getMMMBuysideSolEscrowPDA(
+            ownerProgram.programId,
+            poolKey,
+          ).key,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([cosigner])
+        .rpc();
+      assert.isNull(await ownerProgram.account.pool.fetchNullable(poolKey));
+    });
+
+    it('can only reconcile pools on a shared escrow', async () => {
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+      });
+      try {
+        await program.methods
+          .reconcileSharedEscrow()
+          .accountsStrict({ owner: wallet.publicKey, pool: poolKey })
+          .remainingAccounts([
+            { pubkey: M2_PROGRAM, isWritable: false, isSigner: false },
+            {
+              pubkey: getM2BuyerSharedEscrow(wallet.publicKey).key,
+              isWritable: false,
+              isSigner: false,
+            },
+          ])
+          .rpc();
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidAccountState');
+      }
+    });
+  });
+
+  describe('shared escrow withdraw', () => {
+    // sells the extra nft into a fresh owner's pool on a 1 SOL shared escrow
+    // bid, the shared escrow is funded with sharedEscrowBalanceSol
+    const sellToSharedEscrowPool = async (sharedEscrowBalanceSol: number) => {
+      const owner = new anchor.Wallet(Keypair.generate());
+      const ownerProgram = new anchor.Program(
+        IDL,
+        MMMProgramID,
+        new anchor.AnchorProvider(connection, owner, {
+          commitment: 'processed',
+        }),
+      ) as anchor.Program<Mmm>;
+      const seller = Keypair.generate();
+      const sharedEscrowAccount = getM2BuyerSharedEscrow(owner.publicKey).key;
+      await Promise.all([
+        airdrop(connection, owner.publicKey, 10),
+        airdrop(connection, seller.publicKey, 10),
+        airdrop(connection, sharedEscrowAccount, sharedEscrowBalanceSol),
+      ]);
+      const poolData = await createPoolWithExampleDeposits(
+        ownerProgram,
+        connection,
+        [AllowlistKind.mint],
+        {
+          owner: owner.publicKey,
+          cosigner,
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(0),
+          expiry: new anchor.BN(0),
+          lpFeeBp: 0,
+          reinvestFulfillBuy: false,
+          reinvestFulfillSell: false,
+        },
+        'buy',
+        TOKEN_PROGRAM_ID,
+        seller.publicKey,
+        true, // sharedEscrow
+        1,
+      );
+
+      const txId = await ownerProgram.methods
+        .solFulfillBuy({
+          assetAmount: new anchor.BN(1),
+          minPaymentAmount: new anchor.BN(1),
+          allowlistAux: '',
+          takerFeeBp: 0,
+          makerFeeBp: 0,
+          minPricePerItem: null,
+        })
+        .accountsStrict({
+          payer: seller.publicKey,
+          owner: owner.publicKey,
+          cosigner: cosigner.publicKey,
+          referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
+          pool: poolData.poolKey,
+          buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+          assetMetadata: poolData.extraNft.metadataAddress,
+          assetMasterEdition: getMetaplexInstance(connection)
+            .nfts()
+            .pdas()
+            .masterEdition({ mint: poolData.extraNft.mintAddress }),
+          assetMint: poolData.extraNft.mintAddress,
+          payerAssetAccount: poolData.extraNft.tokenAddress!,
+          sellsideEscrowTokenAccount: poolData.poolAtaExtraNft,
+          ownerTokenAccount: await getAssociatedTokenAddress(
+            poolData.extraNft.mintAddress,
+            owner.publicKey,
+          ),
+          allowlistAuxAccount: SystemProgram.programId,
+          sellState: getMMMSellStatePDA(
+            ownerProgram.programId,
+            poolData.poolKey,
+            poolData.extraNft.mintAddress,
+          ).key,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .remainingAccounts([
+          { pubkey: M2_PROGRAM, isWritable: false, isSigner: false },
+          { pubkey: sharedEscrowAccount, isWritable: true, isSigner: false },
+        ])
+        .signers([cosigner, seller])
+        .rpc();
+      return { txId, poolData };
+    };
+
+    it('logs the withdraw from the shared escrow', async () => {
+      const { txId, poolData } = await sellToSharedEscrowPool(2);
+
+      const withdraw = getSharedEscrowWithdrawFromLogs(
+        await getTransactionLogs(connection, txId),
+      );
+      assert.isNotNull(withdraw);
+      assert.equal(withdraw!.pool.toBase58(), poolData.poolKey.toBase58());
+      assert.equal(
+        withdraw!.destination.toBase58(),
+        poolData.poolPaymentEscrow.toBase58(),
+      );
+      assert.equal(withdraw!.amount.toNumber(), LAMPORTS_PER_SOL);
+    });
+
+    it('can not withdraw more than the shared escrow holds', async () => {
+      try {
+        await sellToSharedEscrowPool(0.5);
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InsufficientSharedEscrowBalance');
+      }
+    });
+  });
 });
@@ -0,0 +1,341 @@
+import * as anchor from '@project-serum/anchor';
+import {
+  getAssociatedTokenAddress,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js';
+import { assert } from 'chai';
+import {
+  Mmm,
+  AllowlistKind,
+  CurveKind,
+  getMMMSellStatePDA,
+  IDL,
+  MMMProgramID,
+} from '../sdk/src';
+import {
+  airdrop,
+  assertFailedTx,
+  createPool,
+  createPoolWithExampleDepositsUmi,
+  sendAndAssertTx,
+} from './utils';
+import { toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
+
+// mirrors get_xyk_curve_total_and_next_price, with virtual reserves
+// x = spot * y and y = curveDelta
+const getXykPrices = (
+  spot: anchor.BN,
+  y: anchor.BN,
+  n: anchor.BN,
+  fulfillBuy: boolean,
+) => {
+  const yNext = fulfillBuy ? y.add(n) : y.sub(n);
+  const numerator = spot.mul(y).mul(n);
+  const totalPrice = fulfillBuy
+    ? numerator.div(yNext)
+    : numerator.add(yNext).subn(1).div(yNext);
+  const nextPrice = spot.mul(y).mul(y).div(yNext.mul(yNext));
+  return { totalPrice, nextPrice };
+};
+
+describe('mmm-fulfill-xyk', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const cosigner = Keypair.generate();
+
+  beforeEach(async () => {
+    await airdrop(connection, wallet.publicKey, 50);
+  });
+
+  it('can not create a xyk pool without virtual token reserves', async () => {
+    try {
+      await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        curveType: CurveKind.xyk,
+        curveDelta: new anchor.BN(0),
+        expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+      });
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidCurveDelta');
+    }
+  });
+
+  it('round trips buying then selling the same quantity', async () => {
+    const trader = Keypair.generate();
+    const spotPrice = new anchor.BN(LAMPORTS_PER_SOL);
+    const reserve = new anchor.BN(100);
+    const amount = new anchor.BN(3);
+    const [poolData] = await Promise.all([
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.fvca],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice,
+          curveType: CurveKind.xyk,
+          curveDelta: reserve,
+          expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+          lpFeeBp: 0,
+          referralBp: 0,
+          reinvestFulfillBuy: true,
+          reinvestFulfillSell: true,
+        },
+        'both',
+        TOKEN_PROGRAM_ID,
+        trader.publicKey,
+      ),
+      airdrop(connection, trader.publicKey, 10),
+    ]);
+
+    const extraSftMintAddress = toWeb3JsPublicKey(
+      poolData.extraSft.mintAddress,
+    );
+    const { key: sellState } = getMMMSellStatePDA(
+      program.programId,
+      poolData.poolKey,
+      extraSftMintAddress,
+    );
+    const ownerExtraSftAtaAddress = await getAssociatedTokenAddress(
+      extraSftMintAddress,
+      wallet.publicKey,
+      true,
+      TOKEN_PROGRAM_ID,
+    );
+    const initEscrowBalance = await connection.getBalance(
+      poolData.poolPaymentEscrow,
+    );
+
+    // the pool buys from the trader
+    const buyPrices = getXykPrices(spotPrice, reserve, amount, true);
+    {
+      const tx = await program.methods
+        .solFulfillBuy({
+          assetAmount: amount,
+          minPaymentAmount: buyPrices.totalPrice,
+          allowlistAux: '',
+          takerFeeBp: 0,
+          makerFeeBp: 0,
+          minPricePerItem: null,
+        })
+        .accountsStrict({
+          payer: trader.publicKey,
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
+          pool: poolData.poolKey,
+          buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+          assetMetadata: poolData.extraSft.metadataAddress,
+          assetMasterEdition: poolData.extraSft.masterEditionAddress,
+          assetMint: poolData.extraSft.mintAddress,
+          payerAssetAccount: poolData.extraSft.tokenAddress!,
+          sellsideEscrowTokenAccount: poolData.poolAtaExtraSft,
+          ownerTokenAccount: ownerExtraSftAtaAddress,
+          allowlistAuxAccount: SystemProgram.programId,
+          sellState,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .transaction();
+
+      const blockhashData = await connection.getLatestBlockhash();
+      tx.feePayer = trader.publicKey;
+      tx.recentBlockhash = blockhashData.blockhash;
+      tx.partialSign(cosigner, trader);
+      await sendAndAssertTx(connection, tx, blockhashData, false);
+    }
+
+    let poolAccountInfo = await program.account.pool.fetch(poolData.poolKey);
+    assert.equal(
+      poolAccountInfo.spotPrice.toString(),
+      buyPrices.nextPrice.toString(),
+    );
+    assert.equal(
+      poolAccountInfo.curveDelta.toNumber(),
+      reserve.add(amount).toNumber(),
+    );
+
+    // the trader buys the same quantity back
+    const sellPrices = getXykPrices(
+      poolAccountInfo.spotPrice,
+      poolAccountInfo.curveDelta,
+      amount,
+      false,
+    );
+    {
+      const traderExtraSftAtaAddress = await getAssociatedTokenAddress(
+        extraSftMintAddress,
+        trader.publicKey,
+        true,
+        TOKEN_PROGRAM_ID,
+      );
+      const tx = await program.methods
+        .solFulfillSell({
+          assetAmount: amount,
+          maxPaymentAmount: sellPrices.totalPrice.muln(2),
+          buysideCreatorRoyaltyBp: 0,
+          allowlistAux: '',
+          takerFeeBp: 0,
+          makerFeeBp: 0,
+          maxPricePerItem: null,
+          partialFillOk: false,
+          royaltyBpOverride: null,
+        })
+        .accountsStrict({
+          payer: trader.publicKey,
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
+          pool: poolData.poolKey,
+          buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+          assetMetadata: poolData.extraSft.metadataAddress,
+          assetMasterEdition: poolData.extraSft.masterEditionAddress,
+          assetMint: poolData.extraSft.mintAddress,
+          sellsideEscrowTokenAccount: poolData.poolAtaExtraSft,
+          payerAssetAccount: traderExtraSftAtaAddress,
+          allowlistAuxAccount: SystemProgram.programId,
+          sellState,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .transaction();
+
+      const blockhashData = await connection.getLatestBlockhash();
+      tx.feePayer = trader.publicKey;
+      tx.recentBlockhash = blockhashData.blockhash;
+      tx.partialSign(cosigner, trader);
+      await sendAndAssertTx(connection, tx, blockhashData, false);
+    }
+
+    poolAccountInfo = await program.account.pool.fetch(poolData.poolKey);
+    assert.equal(poolAccountInfo.curveDelta.toNumber(), reserve.toNumber());
+    // the spot price only drifts by the rounding of the intermediate spot price
+    assert.isAtMost(
+      spotPrice.sub(poolAccountInfo.spotPrice).abs().toNumber(),
+      2,
+    );
+
+    // the pool never loses on a round trip, and only gains the rounding dust
+    assert.isAtLeast(
+      sellPrices.totalPrice.toNumber(),
+      buyPrices.totalPrice.toNumber(),
+    );
+    assert.isAtMost(
+      sellPrices.totalPrice.sub(buyPrices.totalPrice).toNumber(),
+      buyPrices.totalPrice.divn(10000).toNumber(),
+    );
+    const escrowBalance = await connection.getBalance(
+      poolData.poolPaymentEscrow,
+    );
+    assert.equal(
+      escrowBalance - initEscrowBalance,
+      sellPrices.totalPrice.sub(buyPrices.totalPrice).toNumber(),
+    );
+  });
+
+  it('can not sell the whole virtual token reserve', async () => {
+    const buyer = Keypair.generate();
+    const [poolData] = await Promise.all([
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.fvca],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          curveType: CurveKind.xyk,
+          curveDelta: new anchor.BN(1),
+          expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+        },
+        'sell',
+        TOKEN_PROGRAM_ID,
+        buyer.publicKey,
+      ),
+      airdrop(connection, buyer.publicKey, 10),
+    ]);
+
+    const buyerNftAtaAddress = await getAssociatedTokenAddress(
+      toWeb3JsPublicKey(poolData.nft.mintAddress),
+      buyer.publicKey,
+      true,
+      TOKEN_PROGRAM_ID,
+    );
+    const { key: sellState } = getMMMSellStatePDA(
+      program.programId,
+      poolData.poolKey,
+      toWeb3JsPublicKey(poolData.nft.mintAddress),
+    );
+    const tx = await program.methods
+      .solFulfillSell({
+        assetAmount: new anchor.BN(1),
+        maxPaymentAmount: new anchor.BN(5 * LAMPORTS_PER_SOL),
+        buysideCreatorRoyaltyBp: 0,
+        allowlistAux: '',
+        takerFeeBp: 0,
+        makerFeeBp: 0,
+        maxPricePerItem: null,
+        partialFillOk: false,
+        royaltyBpOverride: null,
+      })
+      .accountsStrict({
+        payer: buyer.publicKey,
+        owner: wallet.publicKey,
+        cosigner: cosigner.publicKey,
+        referral: poolData.referral.publicKey,
+        takerReferral: poolData.referral.publicKey,
+        pool: poolData.poolKey,
+        buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        assetMetadata: poolData.nft.metadataAddress,
+        assetMasterEdition: poolData.nft.masterEditionAddress,
+        assetMint: poolData.nft.mintAddress,
+        sellsideEscrowTokenAccount: poolData.poolAtaNft,
+        payerAssetAccount: buyerNftAtaAddress,
+        allowlistAuxAccount: SystemProgram.programId,
+        sellState,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .transaction();
+
+    const blockhashData = await connection.getLatestBlockhash();
+    tx.feePayer = buyer.publicKey;
+    tx.recentBlockhash = blockhashData.blockhash;
+    tx.partialSign(cosigner, buyer);
+    const txId = await connection.sendRawTransaction(tx.serialize(), {
+      skipPreflight: true,
+    });
+    const confirmedTx = await connection.confirmTransaction(
+      {
+        signature: txId,
+        blockhash: blockhashData.blockhash,
+        lastValidBlockHeight: blockhashData.lastValidBlockHeight,
+      },
+      'processed',
+    );
+    assertFailedTx(txId, confirmedTx);
+  });
+});
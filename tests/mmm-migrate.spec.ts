@@ -0,0 +1,231 @@
+import * as anchor from '@project-serum/anchor';
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+} from '@solana/web3.js';
+import { assert } from 'chai';
+import {
+  IDL,
+  Mmm,
+  CurveKind,
+  CurveDirection,
+  AllowlistKind,
+  LpFeeRounding,
+  PoolSide,
+  RoyaltyEnforcementMode,
+  MMMProgramID,
+  getPoolAllowlists,
+} from '../sdk/src';
+import {
+  airdrop,
+  createPool,
+  createPoolWithExampleT22ExtDeposits,
+  getEmptyAllowLists,
+  getTestAuthorityKeypair,
+} from './utils';
+
+// tests/fixtures/v1-pool.json is a pool in the 849 byte layout from before
+// allowlists_ext, with the last of its 6 allowlists and fields after them set.
+// It's loaded into the validator through Anchor.toml
+const V1_POOL = new PublicKey('ARvVaw3pndutxqQYV4gN1jvWhxXoAQsNeyBhD7aSawMV');
+const V1_POOL_LEN = 849;
+
+describe('mmm-migrate', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const legacyOwner = getTestAuthorityKeypair();
+
+  beforeEach(async () => {
+    await Promise.all([
+      airdrop(connection, wallet.publicKey, 50),
+      airdrop(connection, legacyOwner.publicKey, 10),
+    ]);
+  });
+
+  const migratePool = (pool: PublicKey, owner: Keypair) =>
+    program.methods
+      .migratePool()
+      .accountsStrict({
+        owner: owner.publicKey,
+        pool,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  it('can not be migrated by someone else than the owner', async () => {
+    try {
+      await migratePool(V1_POOL, wallet.payer);
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidOwner');
+    }
+  });
+
+  it('migrates a pool from before allowlists_ext and keeps its fields', async () => {
+    const legacyAccountInfo = await connection.getAccountInfo(V1_POOL);
+    assert.equal(legacyAccountInfo!.data.length, V1_POOL_LEN);
+
+    await migratePool(V1_POOL, legacyOwner);
+
+    const accountInfo = await connection.getAccountInfo(V1_POOL);
+    assert.isAbove(accountInfo!.data.length, V1_POOL_LEN);
+    assert.equal(
+      accountInfo!.lamports,
+      await connection.getMinimumBalanceForRentExemption(
+        accountInfo!.data.length,
+      ),
+    );
+
+    const pool = await program.account.pool.fetch(V1_POOL);
+    // fields from the legacy layout
+    assert.equal(pool.spotPrice.toNumber(), LAMPORTS_PER_SOL);
+    assert.equal(pool.curveType, CurveKind.linear);
+    assert.equal(pool.curveDelta.toNumber(), LAMPORTS_PER_SOL / 10);
+    assert.isTrue(pool.reinvestFulfillBuy);
+    assert.isFalse(pool.reinvestFulfillSell);
+    assert.equal(pool.lpFeeBp, 200);
+    assert.equal(pool.buysideCreatorRoyaltyBp, 5000);
+    assert.deepEqual(
+      pool.cosignerAnnotation,
+      new Array(32).fill(0).map((_, index) => index),
+    );
+    assert.equal(pool.lpFeeEarned.toNumber(), 12345);
+    assert.equal(pool.owner.toBase58(), legacyOwner.publicKey.toBase58());
+    assert.equal(
+      pool.referral.toBase58(),
+      'HfCp3CSq6qrADj9eMcwAD1RWtqQ9Fa7eszFezycGGEhF',
+    );
+    assert.equal(
+      pool.cosigner.toBase58(),
+      'BUpYgCBRnSxcA4ugEsdvZTHMMDC6n51JP3uQ95UtxFHb',
+    );
+    assert.equal(
+      pool.uuid.toBase58(),
+      '6FHG3psqk9Cce3KMYL2D8A3GEwthmaFz2sVrTZ1yeAGV',
+    );
+    assert.equal(pool.allowlists[0].kind, AllowlistKind.fvca);
+    assert.equal(
+      pool.allowlists[0].value.toBase58(),
+      'Bm6Fp2BMNYtwYYcxAdLgNPkL35Li6kLsyUi9z7dhvk39',
+    );
+    assert.isTrue(
+      pool.allowlists
+        .slice(1, 5)
+        .every((allowlist) => allowlist.kind === AllowlistKind.empty),
+    );
+    assert.equal(pool.allowlists[5].kind, AllowlistKind.mint);
+    assert.equal(
+      pool.allowlists[5].value.toBase58(),
+      'FLJJE6EbhTMuhy9irKcEgQZNWboUwN7eLVezrMbDfHWG',
+    );
+    assert.equal(pool.priceFloor.toNumber(), LAMPORTS_PER_SOL / 2);
+    assert.equal(pool.maxOracleDeviationBp, 500);
+    // the allowlists_ext are empty and the unset fields stay zero
+    assert.deepEqual(getPoolAllowlists(pool).slice(6), getEmptyAllowLists(2));
+    assert.equal(pool.priceCeiling.toNumber(), 0);
+    assert.equal(pool.allowlistMatchMode, 0);
+    assert.equal(pool.lpFeeDecayEnd.toNumber(), 0);
+    assert.equal(pool.lpFeeBpFloor, 0);
+    assert.isTrue(pool.lpFeeTiers.every((tier) => tier.lpFeeBp === 0));
+    assert.equal(pool.minEscrowBalanceBp, 0);
+    assert.equal(pool.maxSpotMoveBp, 0);
+    assert.equal(pool.lpFeeRounding, LpFeeRounding.floor);
+    assert.equal(pool.maxFillDepth, 0);
+    assert.equal(pool.maxBuysideSpend.toNumber(), 0);
+    assert.equal(pool.buysideSpent.toNumber(), 0);
+    assert.equal(pool.poolSide, PoolSide.twoSided);
+    assert.isFalse(pool.requireSizedCollection);
+    assert.equal(pool.totalVolumeSol.toNumber(), 0);
+    assert.equal(pool.fillCount.toNumber(), 0);
+    assert.isFalse(pool.rejectSelfReferral);
+    assert.isFalse(pool.settleInWsol);
+    assert.isFalse(pool.requireOneOfOne);
+    assert.isFalse(pool.requireProgrammable);
+    assert.equal(pool.curveDeltaSell.toNumber(), 0);
+    assert.equal(pool.royaltyEnforcementMode, RoyaltyEnforcementMode.optional);
+    assert.equal(pool.maxTotalPrice.toNumber(), 0);
+    assert.equal(pool.minFillAmount.toNumber(), 0);
+    assert.equal(pool.fillCooldownSecs.toNumber(), 0);
+    assert.equal(pool.lastFillTs.toNumber(), 0);
+    assert.isFalse(pool.sellStateRentToTaker);
+    assert.isTrue(pool.allowlistAux.every((b) => b === 0));
+    assert.isTrue(pool.royaltyFallbackAccount.equals(PublicKey.default));
+    assert.equal(pool.profitSkimBp, 0);
+    assert.isTrue(pool.oracle.equals(PublicKey.default));
+    assert.isFalse(pool.requireAllCreatorsPaid);
+    assert.equal(pool.curveDirection, CurveDirection.normal);
+    assert.isFalse(pool.requireCollectionMetadata);
+    assert.isFalse(pool.staticSpot);
+    assert.isFalse(pool.allowRoyaltyFallback);
+    assert.equal(pool.logCurveBasePrice.toNumber(), 0);
+    assert.isTrue(
+      pool.extraRoyaltyRecipients.every((recipient) =>
+        recipient.address.equals(PublicKey.default),
+      ),
+    );
+    assert.equal(pool.version, 2);
+
+    // and it can only be migrated once
+    try {
+      await migratePool(V1_POOL, legacyOwner);
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'PoolAlreadyMigrated');
+    }
+  });
+
+  it('can not migrate a pool created with the current layout', async () => {
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner: Keypair.generate(),
+    });
+    const pool = await program.account.pool.fetch(poolKey);
+    assert.equal(pool.version, 2);
+
+    try {
+      await migratePool(poolKey, wallet.payer);
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'PoolAlreadyMigrated');
+    }
+  });
+
+  it('can not migrate an account that is not a pool', async () => {
+    // a sell state is owned by the program too, but it must not be grown and
+    // shifted as if it was a legacy pool
+    const { sellState } = await createPoolWithExampleT22ExtDeposits(
+      program,
+      connection,
+      wallet.payer,
+      'sell',
+      {
+        owner: wallet.publicKey,
+        cosigner: Keypair.generate(),
+      },
+    );
+    const sellStateLen = (await connection.getAccountInfo(sellState))!.data
+      .length;
+
+    try {
+      await migratePool(sellState, wallet.payer);
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'AccountDiscriminatorMismatch');
+    }
+    assert.equal(
+      (await connection.getAccountInfo(sellState))!.data.length,
+      sellStateLen,
+    );
+  });
+});
@@ -46,8 +46,10 @@ import {
   getTokenAccountRent,
   IMMUTABLE_OWNER_EXTENSION_LAMPORTS,
   MIP1_COMPUTE_UNITS,
+  Nft,
   sendAndAssertTx,
   SIGNATURE_FEE_LAMPORTS,
+  umiMintNfts,
 } from './utils';
 import { PROGRAM_ID as AUTH_RULES_PROGRAM_ID } from '@metaplex-foundation/mpl-token-auth-rules';
 import { MPL_TOKEN_METADATA_PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID } from '@metaplex-foundation/mpl-token-metadata';
@@ -134,7 +136,7 @@ describe('mmm-mip1', () => {
               value: toWeb3JsPublicKey(creator.publicKey),
               kind: AllowlistKind.fvca,
             },
-            ...getEmptyAllowLists(5),
+            ...getEmptyAllowLists(7),
           ],
         });
 
@@ -350,6 +352,7 @@ describe('mmm-mip1', () => {
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: poolData.nft.metadataAddress,
@@ -510,12 +513,14 @@ describe('mmm-mip1', () => {
             allowlistAux: null,
             makerFeeBp: 350,
             takerFeeBp: 50,
+            minPricePerItem: null,
           })
           .accountsStrict({
             payer: seller.publicKey,
             owner: wallet.publicKey,
             cosigner: cosigner.publicKey,
             referral: poolData.referral.publicKey,
+            takerReferral: poolData.referral.publicKey,
             pool: poolData.poolKey,
             buysideSolEscrowAccount: poolData.poolPaymentEscrow,
             assetMetadata: poolData.extraNft.metadataAddress,
@@ -718,12 +723,14 @@ describe('mmm-mip1', () => {
               allowlistAux: null,
               makerFeeBp: 250,
               takerFeeBp: 30,
+              minPricePerItem: null,
             })
             .accountsStrict({
               payer: seller.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.extraNft.metadataAddress,
@@ -873,6 +880,7 @@ describe('mmm-mip1', () => {
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.nft.metadataAddress,
@@ -1063,12 +1071,14 @@ describe('mmm-mip1', () => {
               allowlistAux: null,
               makerFeeBp: -30,
               takerFeeBp: 50,
+              minPricePerItem: null,
             })
             .accountsStrict({
               payer: seller.publicKey,
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.extraNft.metadataAddress,
@@ -1218,6 +1228,7 @@ describe('mmm-mip1', () => {
               owner: wallet.publicKey,
               cosigner: cosigner.publicKey,
               referral: poolData.referral.publicKey,
+              takerReferral: poolData.referral.publicKey,
               pool: poolData.poolKey,
               buysideSolEscrowAccount: poolData.poolPaymentEscrow,
               assetMetadata: poolData.nft.metadataAddress,
@@ -1324,4 +1335,154 @@ describe('mmm-mip1', () => {
       });
     });
   });
+
+  describe('require_programmable', () => {
+    const setup = async (requireProgrammable: boolean) => {
+      const umi = (await createUmi('http://127.0.0.1:8899')).use(
+        mplTokenMetadata(),
+      );
+      const creator = generateSigner(umi);
+      DEFAULT_ACCOUNTS.tokenProgram = TOKEN_PROGRAM_ID;
+      const [{ poolKey }, pnft, [nft]] = await Promise.all([
+        createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          allowlists: [
+            {
+              value: toWeb3JsPublicKey(creator.publicKey),
+              kind: AllowlistKind.fvca,
+            },
+            ...getEmptyAllowLists(7),
+          ],
+          requireProgrammable,
+        }),
+        createProgrammableNftUmi(
+          umi,
+          creator,
+          wallet.publicKey,
+          TOKEN_PROGRAM_ID,
+          some(fromWeb3JsPublicKey(defaultRules)),
+        ),
+        umiMintNfts(
+          umi,
+          {
+            numNfts: 1,
+            verifyCollection: false,
+            creatorSigner: creator,
+            creators: [
+              { address: creator.publicKey, share: 100, verified: false },
+            ],
+            recipient: fromWeb3JsPublicKey(wallet.publicKey),
+          },
+          TOKEN_PROGRAM_ID,
+        ),
+      ]);
+      return { poolKey, pnft, nft };
+    };
+
+    const depositPnft = async (
+      poolKey: PublicKey,
+      pnft: Awaited<ReturnType<typeof createProgrammableNftUmi>>,
+    ) => {
+      const poolAta = await getAssociatedTokenAddress(
+        pnft.mintAddress,
+        poolKey,
+        true,
+        TOKEN_PROGRAM_ID,
+      );
+      await program.methods
+        .mip1DepositSell({
+          assetAmount: new anchor.BN(1),
+          allowlistAux: null,
+        })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolKey,
+          assetMetadata: pnft.metadataAddress,
+          assetMint: pnft.mintAddress,
+          assetMasterEdition: pnft.masterEditionAddress,
+          assetTokenAccount: pnft.tokenAddress,
+          sellsideEscrowTokenAccount: poolAta,
+          sellState: getMMMSellStatePDA(
+            program.programId,
+            poolKey,
+            pnft.mintAddress,
+          ).key,
+          allowlistAuxAccount: SystemProgram.programId,
+          authorizationRules: defaultRules,
+          ownerTokenRecord: getTokenRecordPDA(
+            pnft.mintAddress,
+            pnft.tokenAddress,
+          ).key,
+          destinationTokenRecord: getTokenRecordPDA(pnft.mintAddress, poolAta)
+            .key,
+
+          ...DEFAULT_ACCOUNTS,
+        })
+        .preInstructions([
+          ComputeBudgetProgram.setComputeUnitLimit({
+            units: MIP1_COMPUTE_UNITS,
+          }),
+        ])
+        .signers([cosigner])
+        .rpc();
+    };
+
+    const depositNft = async (poolKey: PublicKey, nft: Nft) => {
+      const assetMint = toWeb3JsPublicKey(nft.mintAddress);
+      await program.methods
+        .depositSell({ assetAmount: new anchor.BN(1), allowlistAux: '' })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolKey,
+          assetMetadata: toWeb3JsPublicKey(nft.metadataAddress),
+          assetMasterEdition: toWeb3JsPublicKey(nft.masterEditionAddress),
+          assetMint,
+          assetTokenAccount: toWeb3JsPublicKey(nft.tokenAddress),
+          sellsideEscrowTokenAccount: await getAssociatedTokenAddress(
+            assetMint,
+            poolKey,
+            true,
+            TOKEN_PROGRAM_ID,
+          ),
+          allowlistAuxAccount: SystemProgram.programId,
+          sellState: getMMMSellStatePDA(program.programId, poolKey, assetMint)
+            .key,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([cosigner])
+        .rpc();
+    };
+
+    it('only takes programmable nfts when the flag is on', async () => {
+      const { poolKey, pnft, nft } = await setup(true);
+
+      try {
+        await depositNft(poolKey, nft);
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidTokenStandard');
+      }
+
+      await depositPnft(poolKey, pnft);
+      const poolAccountInfo = await program.account.pool.fetch(poolKey);
+      assert.isTrue(poolAccountInfo.requireProgrammable);
+      assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 1);
+    });
+
+    it('takes both kinds of nfts when the flag is off', async () => {
+      const { poolKey, pnft, nft } = await setup(false);
+
+      await depositNft(poolKey, nft);
+      await depositPnft(poolKey, pnft);
+      const poolAccountInfo = await program.account.pool.fetch(poolKey);
+      assert.isFalse(poolAccountInfo.requireProgrammable);
+      assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 2);
+    });
+  });
 });
@@ -23,6 +23,7 @@ import {
   MMMProgramID,
   CurveKind,
   getSolFulfillBuyPrices,
+  getPoolQuoteFromLogs,
 } from '../sdk/src';
 import {
   airdrop,
@@ -82,7 +83,7 @@ describe('mmm-ocp', () => {
       cosigner,
       allowlists: [
         { value: creator.publicKey, kind: AllowlistKind.fvca },
-        ...getEmptyAllowLists(5),
+        ...getEmptyAllowLists(7),
       ],
     });
     const poolAta = await getAssociatedTokenAddress(
@@ -256,6 +257,7 @@ describe('mmm-ocp', () => {
         owner: wallet.publicKey,
         cosigner: cosigner.publicKey,
         referral: poolData.referral.publicKey,
+        takerReferral: poolData.referral.publicKey,
         pool: poolData.poolKey,
         buysideSolEscrowAccount: poolData.poolPaymentEscrow,
         assetMetadata: poolData.nft.metadataAddress,
@@ -400,12 +402,14 @@ describe('mmm-ocp', () => {
         allowlistAux: null,
         makerFeeBp: 350,
         takerFeeBp: 50,
+        minPricePerItem: null,
       })
       .accountsStrict({
         payer: seller.publicKey,
         owner: wallet.publicKey,
         cosigner: cosigner.publicKey,
         referral: poolData.referral.publicKey,
+        takerReferral: poolData.referral.publicKey,
         pool: poolData.poolKey,
         buysideSolEscrowAccount: poolData.poolPaymentEscrow,
         assetMetadata: poolData.extraNft.metadataAddress,
@@ -497,6 +501,143 @@ describe('mmm-ocp', () => {
     assert.equal(sellStateAccountInfo.assetAmount.toNumber(), 1);
   });
 
+  it('previews the dynamic royalty bp that fulfill buy charges', async () => {
+    const seller = Keypair.generate();
+    // the royalty steps down to half of the metadata royalty above 2 SOL
+    const policy = await createPolicyFixture(connection, wallet.payer, {
+      startMultiplierBp: 10000,
+      endMultiplierBp: 5000,
+      startPrice: new anchor.BN(2 * LAMPORTS_PER_SOL),
+      endPrice: new anchor.BN(2 * LAMPORTS_PER_SOL),
+    });
+    const [poolData] = await Promise.all([
+      createPoolWithExampleOcpDeposits(
+        program,
+        connection,
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice: new anchor.BN(2.2 * LAMPORTS_PER_SOL),
+          curveDelta: new anchor.BN(1 * LAMPORTS_PER_SOL),
+          curveType: CurveKind.linear,
+          reinvestFulfillBuy: true,
+        },
+        'buy',
+        seller.publicKey,
+        policy,
+      ),
+      airdrop(connection, seller.publicKey, 10),
+    ]);
+
+    const getQuoteRoyaltyBp = async () => {
+      const { raw } = await program.methods
+        .getPoolQuote({
+          fulfillBuy: true,
+          assetAmount: new anchor.BN(1),
+          metadataRoyaltyBp: 0,
+          makerFeeBp: 350,
+          takerFeeBp: 50,
+          saturating: false,
+        })
+        .accountsStrict({
+          payer: seller.publicKey,
+          owner: wallet.publicKey,
+          pool: poolData.poolKey,
+          buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        })
+        .remainingAccounts([
+          {
+            pubkey: poolData.extraNft.metadataAddress,
+            isSigner: false,
+            isWritable: false,
+          },
+          { pubkey: policy, isSigner: false, isWritable: false },
+        ])
+        .simulate();
+      const quote = getPoolQuoteFromLogs([...raw]);
+      assert.isNotNull(quote);
+      return quote!.royaltyBp;
+    };
+
+    // 2.2 SOL is above the threshold, so the royalty is 500 * 0.5 = 250 bp
+    const royaltyBp = await getQuoteRoyaltyBp();
+    assert.equal(royaltyBp, 250);
+
+    const initCreatorBalance = await connection.getBalance(
+      poolData.nftCreator.publicKey,
+    );
+    const expectedBuyPrices = getSolFulfillBuyPrices({
+      totalPriceLamports: 2.2 * LAMPORTS_PER_SOL,
+      takerFeeBp: 50,
+      metadataRoyaltyBp: royaltyBp,
+      buysideCreatorRoyaltyBp: 10000,
+      lpFeeBp: 0,
+      makerFeeBp: 350,
+    });
+    const tx = await program.methods
+      .solOcpFulfillBuy({
+        assetAmount: new anchor.BN(1),
+        minPaymentAmount: expectedBuyPrices.sellerReceives,
+        allowlistAux: null,
+        makerFeeBp: 350,
+        takerFeeBp: 50,
+        minPricePerItem: null,
+      })
+      .accountsStrict({
+        payer: seller.publicKey,
+        owner: wallet.publicKey,
+        cosigner: cosigner.publicKey,
+        referral: poolData.referral.publicKey,
+        takerReferral: poolData.referral.publicKey,
+        pool: poolData.poolKey,
+        buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        assetMetadata: poolData.extraNft.metadataAddress,
+        assetMint: poolData.extraNft.mintAddress,
+        payerAssetAccount: poolData.extraNft.tokenAddress,
+        sellsideEscrowTokenAccount: poolData.poolAtaExtraNft,
+        ownerTokenAccount: await getAssociatedTokenAddress(
+          poolData.extraNft.mintAddress,
+          wallet.publicKey,
+        ),
+        allowlistAuxAccount: SystemProgram.programId,
+        sellState: getMMMSellStatePDA(
+          program.programId,
+          poolData.poolKey,
+          poolData.extraNft.mintAddress,
+        ).key,
+        ocpMintState: poolData.extraNft.ocpMintState,
+        ocpPolicy: poolData.extraNft.ocpPolicy,
+        ocpFreezeAuthority: poolData.extraNft.ocpFreezeAuth,
+        ...DEFAULT_ACCOUNTS,
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: OCP_COMPUTE_UNITS }),
+      ])
+      .remainingAccounts([
+        {
+          pubkey: poolData.nftCreator.publicKey,
+          isSigner: false,
+          isWritable: true,
+        },
+      ])
+      .transaction();
+
+    const blockhashData = await connection.getLatestBlockhash();
+    tx.feePayer = seller.publicKey;
+    tx.recentBlockhash = blockhashData.blockhash;
+    tx.partialSign(cosigner, seller);
+    await sendAndAssertTx(connection, tx, blockhashData, false);
+
+    // the creator is paid with the previewed royalty bp
+    assert.equal(
+      await connection.getBalance(poolData.nftCreator.publicKey),
+      initCreatorBalance + expectedBuyPrices.royaltyPaid.toNumber(),
+    );
+
+    // the spot price is now 1.2 SOL, below the threshold, so the full royalty applies
+    assert.equal(await getQuoteRoyaltyBp(), 500);
+  });
+
   it('can fulfill two sided with negative maker fees - happy path', async () => {
     const seller = Keypair.generate();
     const buyer = Keypair.generate();
@@ -575,12 +716,14 @@ describe('mmm-ocp', () => {
           allowlistAux: null,
           makerFeeBp: -30,
           takerFeeBp: 30,
+          minPricePerItem: null,
         })
         .accountsStrict({
           payer: seller.publicKey,
           owner: wallet.publicKey,
           cosigner: cosigner.publicKey,
           referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
           pool: poolData.poolKey,
           buysideSolEscrowAccount: poolData.poolPaymentEscrow,
           assetMetadata: poolData.extraNft.metadataAddress,
@@ -708,6 +851,7 @@ describe('mmm-ocp', () => {
           owner: wallet.publicKey,
           cosigner: cosigner.publicKey,
           referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
           pool: poolData.poolKey,
           buysideSolEscrowAccount: poolData.poolPaymentEscrow,
           assetMetadata: poolData.nft.metadataAddress,
@@ -0,0 +1,223 @@
+import * as anchor from '@project-serum/anchor';
+import {
+  getAssociatedTokenAddress,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js';
+import { assert } from 'chai';
+import {
+  Mmm,
+  AllowlistKind,
+  CurveKind,
+  getFillSummaryFromLogs,
+  getMMMSellStatePDA,
+  IDL,
+  MMMProgramID,
+} from '../sdk/src';
+import {
+  airdrop,
+  assertFailedTx,
+  assertTx,
+  createPoolWithExampleDepositsUmi,
+  PoolData,
+} from './utils';
+import { toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
+
+describe('mmm-partial-fill', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const cosigner = Keypair.generate();
+
+  beforeEach(async () => {
+    await airdrop(connection, wallet.publicKey, 50);
+  });
+
+  // 1 nft and 5 sfts on the sellside
+  const createSellsidePool = async (buyer: Keypair): Promise<PoolData> => {
+    const [poolData] = await Promise.all([
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mint],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+          expiry: new anchor.BN(0),
+          lpFeeBp: 0,
+          reinvestFulfillSell: false,
+        },
+        'sell',
+        TOKEN_PROGRAM_ID,
+        buyer.publicKey,
+      ),
+      airdrop(connection, buyer.publicKey, 10),
+    ]);
+    return poolData;
+  };
+
+  const buySfts = async (
+    poolData: PoolData,
+    buyer: Keypair,
+    assetAmount: number,
+    partialFillOk: boolean,
+  ) => {
+    const sftMint = toWeb3JsPublicKey(poolData.sft.mintAddress);
+    const tx = await program.methods
+      .solFulfillSell({
+        assetAmount: new anchor.BN(assetAmount),
+        maxPaymentAmount: new anchor.BN(10 * LAMPORTS_PER_SOL),
+        maxPricePerItem: null,
+        buysideCreatorRoyaltyBp: 0,
+        allowlistAux: '',
+        makerFeeBp: 0,
+        takerFeeBp: 0,
+        partialFillOk,
+        royaltyBpOverride: null,
+      })
+      .accountsStrict({
+        payer: buyer.publicKey,
+        owner: wallet.publicKey,
+        cosigner: cosigner.publicKey,
+        referral: poolData.referral.publicKey,
+        takerReferral: poolData.referral.publicKey,
+        pool: poolData.poolKey,
+        buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        assetMetadata: toWeb3JsPublicKey(poolData.sft.metadataAddress),
+        assetMasterEdition: toWeb3JsPublicKey(
+          poolData.sft.masterEditionAddress,
+        ),
+        assetMint: sftMint,
+        sellsideEscrowTokenAccount: poolData.poolAtaSft,
+        payerAssetAccount: await getAssociatedTokenAddress(
+          sftMint,
+          buyer.publicKey,
+          true,
+          TOKEN_PROGRAM_ID,
+        ),
+        allowlistAuxAccount: SystemProgram.programId,
+        sellState: getMMMSellStatePDA(
+          program.programId,
+          poolData.poolKey,
+          sftMint,
+        ).key,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .transaction();
+
+    const blockhashData = await connection.getLatestBlockhash();
+    tx.feePayer = buyer.publicKey;
+    tx.recentBlockhash = blockhashData.blockhash;
+    tx.partialSign(cosigner, buyer);
+    const txId = await connection.sendRawTransaction(tx.serialize(), {
+      skipPreflight: true,
+    });
+    const confirmedTx = await connection.confirmTransaction(
+      {
+        signature: txId,
+        blockhash: blockhashData.blockhash,
+        lastValidBlockHeight: blockhashData.lastValidBlockHeight,
+      },
+      'confirmed',
+    );
+    return { txId, confirmedTx };
+  };
+
+  it('fills what the pool escrows when over-requested', async () => {
+    const buyer = Keypair.generate();
+    const poolData = await createSellsidePool(buyer);
+    const initBuyerBalance = await connection.getBalance(buyer.publicKey);
+
+    const { txId, confirmedTx } = await buySfts(poolData, buyer, 7, true);
+    assertTx(txId, confirmedTx);
+
+    const [poolAccountInfo, buyerSftAccount, buyerBalance, txInfo] =
+      await Promise.all([
+        program.account.pool.fetch(poolData.poolKey),
+        getAssociatedTokenAddress(
+          toWeb3JsPublicKey(poolData.sft.mintAddress),
+          buyer.publicKey,
+          true,
+          TOKEN_PROGRAM_ID,
+        ).then((ata) => connection.getTokenAccountBalance(ata)),
+        connection.getBalance(buyer.publicKey),
+        connection.getTransaction(txId, { commitment: 'confirmed' }),
+      ]);
+    // only the 5 escrowed sfts are filled, the pool keeps the nft and stays open
+    assert.equal(buyerSftAccount.value.amount, '5');
+    assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 1);
+    // the spot price moves 5 steps
+    assert.equal(
+      poolAccountInfo.spotPrice.toNumber(),
+      (LAMPORTS_PER_SOL / 10) * 15,
+    );
+
+    // 1.1 + 1.2 + 1.3 + 1.4 + 1.5
+    const fillSummary = getFillSummaryFromLogs(txInfo!.meta!.logMessages!);
+    assert.isNotNull(fillSummary);
+    assert.equal(fillSummary!.assetAmount.toNumber(), 5);
+    assert.equal(
+      fillSummary!.totalPrice.toNumber(),
+      (LAMPORTS_PER_SOL / 10) * 65,
+    );
+    assert.isAtLeast(
+      initBuyerBalance - buyerBalance,
+      (LAMPORTS_PER_SOL / 10) * 65,
+    );
+  });
+
+  it('logs the spot price before and after the fill', async () => {
+    const buyer = Keypair.generate();
+    const poolData = await createSellsidePool(buyer);
+
+    const { txId, confirmedTx } = await buySfts(poolData, buyer, 2, false);
+    assertTx(txId, confirmedTx);
+
+    const [poolAccountInfo, txInfo] = await Promise.all([
+      program.account.pool.fetch(poolData.poolKey),
+      connection.getTransaction(txId, { commitment: 'confirmed' }),
+    ]);
+    const fillSummary = getFillSummaryFromLogs(txInfo!.meta!.logMessages!);
+    assert.isNotNull(fillSummary);
+    assert.equal(fillSummary!.spotPriceBefore.toNumber(), LAMPORTS_PER_SOL);
+    // two steps of curve_delta, and it's where the pool is left at
+    assert.equal(
+      fillSummary!.spotPriceAfter.sub(fillSummary!.spotPriceBefore).toNumber(),
+      (LAMPORTS_PER_SOL / 10) * 2,
+    );
+    assert.equal(
+      fillSummary!.spotPriceAfter.toNumber(),
+      poolAccountInfo.spotPrice.toNumber(),
+    );
+  });
+
+  it('rejects an over-request without partial_fill_ok', async () => {
+    const buyer = Keypair.generate();
+    const poolData = await createSellsidePool(buyer);
+
+    const { txId, confirmedTx } = await buySfts(poolData, buyer, 7, false);
+    assertFailedTx(txId, confirmedTx);
+
+    const poolAccountInfo = await program.account.pool.fetch(poolData.poolKey);
+    assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 6);
+    assert.equal(poolAccountInfo.spotPrice.toNumber(), LAMPORTS_PER_SOL);
+  });
+});
@@ -0,0 +1,1423 @@
+import * as anchor from '@project-serum/anchor';
+import {
+  getAssociatedTokenAddress,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import {
+  AccountMeta,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js';
+import { assert } from 'chai';
+import {
+  Mmm,
+  AllowlistKind,
+  CurveKind,
+  CurveDirection,
+  IDL,
+  MMMProgramID,
+  getM2BuyerSharedEscrow,
+  getMMMBuysideSolEscrowPDA,
+  getMMMSellStatePDA,
+  getMaxAffordableItemsFromLogs,
+  getPoolNotionalValueFromLogs,
+  getPoolQuoteFromLogs,
+  getPoolSolvencyFromLogs,
+  getSellStateSummaryFromLogs,
+  LpFeeRounding,
+  M2_PROGRAM,
+} from '../sdk/src';
+import {
+  airdrop,
+  assertTx,
+  createPool,
+  createPoolWithExampleDepositsUmi,
+  getChainTime,
+  sellExtraNftToPool,
+  sendAndAssertTx,
+  umiMintNfts,
+  waitForChainTime,
+} from './utils';
+import {
+  fromWeb3JsPublicKey,
+  toWeb3JsPublicKey,
+} from '@metaplex-foundation/umi-web3js-adapters';
+import { createUmi } from '@metaplex-foundation/umi-bundle-tests';
+import { mplTokenMetadata } from '@metaplex-foundation/mpl-token-metadata';
+
+describe('mmm-quote', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const cosigner = Keypair.generate();
+
+  beforeEach(async () => {
+    await airdrop(connection, wallet.publicKey, 50);
+  });
+
+  const getQuote = async (
+    poolKey: anchor.web3.PublicKey,
+    fulfillBuy: boolean,
+    assetAmount: number,
+    saturating = false,
+  ) => {
+    const { key: buysideSolEscrowAccount } = getMMMBuysideSolEscrowPDA(
+      program.programId,
+      poolKey,
+    );
+    const { raw } = await program.methods
+      .getPoolQuote({
+        fulfillBuy,
+        assetAmount: new anchor.BN(assetAmount),
+        metadataRoyaltyBp: 0,
+        makerFeeBp: 0,
+        takerFeeBp: 100,
+        saturating,
+      })
+      .accountsStrict({
+        payer: wallet.publicKey,
+        owner: wallet.publicKey,
+        pool: poolKey,
+        buysideSolEscrowAccount,
+      })
+      .simulate();
+    const quote = getPoolQuoteFromLogs([...raw]);
+    assert.isNotNull(quote);
+    return quote!;
+  };
+
+  const getMaxAffordableItems = async (
+    poolKey: anchor.web3.PublicKey,
+    budget: anchor.BN,
+    takerFeeBp: number,
+  ) => {
+    const { key: buysideSolEscrowAccount } = getMMMBuysideSolEscrowPDA(
+      program.programId,
+      poolKey,
+    );
+    const { raw } = await program.methods
+      .getMaxAffordableItems({
+        budget,
+        makerFeeBp: 0,
+        takerFeeBp,
+      })
+      .accountsStrict({
+        owner: wallet.publicKey,
+        pool: poolKey,
+        buysideSolEscrowAccount,
+      })
+      .simulate();
+    const affordable = getMaxAffordableItemsFromLogs([...raw]);
+    assert.isNotNull(affordable);
+    return affordable!.assetAmount.toNumber();
+  };
+
+  const checkPoolSolvency = async (
+    poolKey: anchor.web3.PublicKey,
+    fulfillBuy: boolean,
+    assetAmount: number,
+    remainingAccounts: AccountMeta[] = [],
+  ) => {
+    const { key: buysideSolEscrowAccount } = getMMMBuysideSolEscrowPDA(
+      program.programId,
+      poolKey,
+    );
+    const { raw } = await program.methods
+      .checkPoolSolvency({
+        fulfillBuy,
+        assetAmount: new anchor.BN(assetAmount),
+        makerFeeBp: 0,
+        takerFeeBp: 100,
+      })
+      .accountsStrict({
+        owner: wallet.publicKey,
+        pool: poolKey,
+        buysideSolEscrowAccount,
+      })
+      .remainingAccounts(remainingAccounts)
+      .simulate();
+    const solvency = getPoolSolvencyFromLogs([...raw]);
+    assert.isNotNull(solvency);
+    return solvency!;
+  };
+
+  const createSellsidePool = async (
+    curveType: CurveKind,
+    curveDelta: anchor.BN,
+  ) => {
+    const buyer = Keypair.generate();
+    const poolData = await createPoolWithExampleDepositsUmi(
+      program,
+      [AllowlistKind.mint],
+      {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        curveType,
+        curveDelta,
+        expiry: new anchor.BN(0),
+      },
+      'sell',
+      TOKEN_PROGRAM_ID,
+      buyer.publicKey,
+    );
+    // the nft and 5 sfts
+    const poolAccountInfo = await program.account.pool.fetch(poolData.poolKey);
+    assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 6);
+    return poolData;
+  };
+
+  it('quotes fulfill buy and fulfill sell', async () => {
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner,
+      spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+      curveType: CurveKind.linear,
+      curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+      expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+    });
+    // 1 + 0.9
+    const buyQuote = await getQuote(poolKey, true, 2);
+    assert.equal(buyQuote.totalPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 19);
+    assert.equal(buyQuote.nextPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 8);
+    assert.equal(buyQuote.lpFee.toNumber(), 0);
+    assert.equal(buyQuote.takerFee.toNumber(), (LAMPORTS_PER_SOL / 1000) * 19);
+    assert.equal(
+      buyQuote.referralFee.toNumber(),
+      (LAMPORTS_PER_SOL / 1000) * 19,
+    );
+
+    // 1.1 + 1.2
+    const sellQuote = await getQuote(poolKey, false, 2);
+    assert.equal(sellQuote.totalPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 23);
+    assert.equal(sellQuote.nextPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 12);
+    assert.equal(sellQuote.lpFee.toNumber(), 0);
+    assert.equal(sellQuote.makerFee.toNumber(), 0);
+    assert.equal(sellQuote.takerFee.toNumber(), (LAMPORTS_PER_SOL / 1000) * 23);
+    assert.equal(
+      sellQuote.referralFee.toNumber(),
+      (LAMPORTS_PER_SOL / 1000) * 23,
+    );
+
+    // the quote does not move the pool
+    const poolAccountInfo = await program.account.pool.fetch(poolKey);
+    assert.equal(poolAccountInfo.spotPrice.toNumber(), LAMPORTS_PER_SOL);
+  });
+
+  it('never quotes below the price floor', async () => {
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner,
+      spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+      curveType: CurveKind.exp,
+      curveDelta: new anchor.BN(5000), // 50%
+      priceFloor: new anchor.BN((LAMPORTS_PER_SOL / 10) * 3),
+      expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+    });
+
+    // 1 + 0.666666666.. + 0.444444444.. + 0.3 + 0.3
+    const buyQuote = await getQuote(poolKey, true, 5);
+    assert.equal(buyQuote.totalPrice.toNumber(), 2_711_111_111);
+    assert.equal(buyQuote.nextPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 3);
+
+    for (let n = 1; n <= 10; n++) {
+      const { nextPrice } = await getQuote(poolKey, true, n);
+      assert.isAtLeast(nextPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 3);
+    }
+  });
+
+  it('caps linear fulfill sell step prices at the price ceiling', async () => {
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner,
+      spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+      curveType: CurveKind.linear,
+      curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+      priceCeiling: new anchor.BN((LAMPORTS_PER_SOL / 100) * 125),
+      expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+    });
+
+    // 1.1 + 1.2 + 1.25 + 1.25
+    const sellQuote = await getQuote(poolKey, false, 4);
+    assert.equal(
+      sellQuote.totalPrice.toNumber(),
+      (LAMPORTS_PER_SOL / 10) * 48,
+    );
+    assert.equal(
+      sellQuote.nextPrice.toNumber(),
+      (LAMPORTS_PER_SOL / 100) * 125,
+    );
+
+    // the ceiling is not reached yet
+    const smallSellQuote = await getQuote(poolKey, false, 2);
+    assert.equal(
+      smallSellQuote.totalPrice.toNumber(),
+      (LAMPORTS_PER_SOL / 10) * 23,
+    );
+  });
+
+  it('quotes n * spot_price in both directions on a flat curve', async () => {
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner,
+      spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+      curveType: CurveKind.flat,
+      // ignored by the flat curve
+      curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+      expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+    });
+
+    for (const fulfillBuy of [true, false]) {
+      for (const n of [1, 3]) {
+        const quote = await getQuote(poolKey, fulfillBuy, n);
+        assert.equal(quote.totalPrice.toNumber(), n * LAMPORTS_PER_SOL);
+        assert.equal(quote.nextPrice.toNumber(), LAMPORTS_PER_SOL);
+      }
+    }
+  });
+
+  it('steps linear fulfill sell prices by curve_delta_sell', async () => {
+    // 0 falls back to curve_delta
+    const createLinearPool = (curveDeltaSell = new anchor.BN(0)) =>
+      createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        curveType: CurveKind.linear,
+        curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+        curveDeltaSell,
+        expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+      });
+    const [{ poolKey: symmetricPoolKey }, { poolKey }] = await Promise.all([
+      createLinearPool(),
+      createLinearPool(new anchor.BN(LAMPORTS_PER_SOL / 4)),
+    ]);
+    const poolAccountInfo = await program.account.pool.fetch(poolKey);
+    assert.equal(
+      poolAccountInfo.curveDeltaSell.toNumber(),
+      LAMPORTS_PER_SOL / 4,
+    );
+
+    // fulfill buy keeps curve_delta, 1 + 0.9
+    const buyQuote = await getQuote(poolKey, true, 2);
+    assert.equal(buyQuote.totalPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 19);
+    assert.equal(buyQuote.nextPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 8);
+
+    // fulfill sell steps by curve_delta_sell, 1.25 + 1.5
+    const sellQuote = await getQuote(poolKey, false, 2);
+    assert.equal(
+      sellQuote.totalPrice.toNumber(),
+      (LAMPORTS_PER_SOL / 100) * 275,
+    );
+    assert.equal(sellQuote.nextPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 15);
+
+    // the spread between the next prices after a buy and after a sell widens
+    // from 0.2 to 0.35
+    const getSpread = async (key: anchor.web3.PublicKey) => {
+      const [buy, sell] = await Promise.all([
+        getQuote(key, true, 1),
+        getQuote(key, false, 1),
+      ]);
+      return sell.nextPrice.sub(buy.nextPrice).toNumber();
+    };
+    assert.equal(
+      await getSpread(symmetricPoolKey),
+      (LAMPORTS_PER_SOL / 100) * 20,
+    );
+    assert.equal(await getSpread(poolKey), (LAMPORTS_PER_SOL / 100) * 35);
+  });
+
+  it('can not set curve_delta_sell on a non linear curve', async () => {
+    try {
+      await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        curveType: CurveKind.exp,
+        curveDelta: new anchor.BN(1000),
+        curveDeltaSell: new anchor.BN(2000),
+        expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+      });
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidCurveDelta');
+    }
+  });
+
+  it('steps fulfill buy prices up on an inverted linear curve', async () => {
+    const createInvertedPool = (priceCeiling = new anchor.BN(0)) =>
+      createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        curveType: CurveKind.linear,
+        curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+        curveDirection: CurveDirection.inverted,
+        priceCeiling,
+        expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+      });
+    const [{ poolKey }, { poolKey: cappedPoolKey }] = await Promise.all([
+      createInvertedPool(),
+      createInvertedPool(new anchor.BN((LAMPORTS_PER_SOL / 100) * 125)),
+    ]);
+    const poolAccountInfo = await program.account.pool.fetch(poolKey);
+    assert.equal(poolAccountInfo.curveDirection, CurveDirection.inverted);
+
+    // 1 + 1.1
+    const buyQuote = await getQuote(poolKey, true, 2);
+    assert.equal(buyQuote.totalPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 21);
+    assert.equal(buyQuote.nextPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 12);
+
+    // the next price keeps rising with the fill size
+    let lastNextPrice = LAMPORTS_PER_SOL;
+    for (let n = 1; n <= 5; n++) {
+      const { nextPrice } = await getQuote(poolKey, true, n);
+      assert.isAbove(nextPrice.toNumber(), lastNextPrice);
+      lastNextPrice = nextPrice.toNumber();
+    }
+
+    // 1 + 1.1 + 1.2 + 1.25, the steps above the ceiling are pinned to it
+    const cappedBuyQuote = await getQuote(cappedPoolKey, true, 4);
+    assert.equal(
+      cappedBuyQuote.totalPrice.toNumber(),
+      (LAMPORTS_PER_SOL / 100) * 455,
+    );
+    assert.equal(
+      cappedBuyQuote.nextPrice.toNumber(),
+      (LAMPORTS_PER_SOL / 100) * 125,
+    );
+
+    // fulfill sell is not inverted, 1.1 + 1.2
+    const sellQuote = await getQuote(poolKey, false, 2);
+    assert.equal(sellQuote.totalPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 23);
+  });
+
+  it('can only invert a linear curve', async () => {
+    for (const [curveType, curveDirection] of [
+      [CurveKind.exp, CurveDirection.inverted],
+      [CurveKind.linear, 2],
+    ]) {
+      try {
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+          curveType,
+          curveDelta: new anchor.BN(1000),
+          curveDirection,
+          expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidCurveDirection');
+      }
+    }
+  });
+
+  it('caps the total price of a fill at the pool max_total_price', async () => {
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner,
+      spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+      curveType: CurveKind.flat,
+      curveDelta: new anchor.BN(0),
+      maxTotalPrice: new anchor.BN(3 * LAMPORTS_PER_SOL),
+      expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+    });
+
+    for (const fulfillBuy of [true, false]) {
+      const quote = await getQuote(poolKey, fulfillBuy, 3);
+      assert.equal(quote.totalPrice.toNumber(), 3 * LAMPORTS_PER_SOL);
+      try {
+        await getQuote(poolKey, fulfillBuy, 4);
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'NumericOverflow');
+      }
+    }
+
+    // a saturating quote stops at the cap
+    const saturatingQuote = await getQuote(poolKey, false, 10, true);
+    assert.equal(saturatingQuote.assetAmount.toNumber(), 3);
+  });
+
+  it('can not set the max total price below the spot price', async () => {
+    try {
+      await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        maxTotalPrice: new anchor.BN(LAMPORTS_PER_SOL - 1),
+      });
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidMaxTotalPrice');
+    }
+  });
+
+  it('does not drift on long exp curves', async () => {
+    const spotPrice = 1_000_000_007;
+    const curveDelta = 7;
+    const n = 100;
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner,
+      spotPrice: new anchor.BN(spotPrice),
+      curveType: CurveKind.exp,
+      curveDelta: new anchor.BN(curveDelta),
+      expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+    });
+
+    // the exact geometric series as a single fraction, truncated once
+    const expectedTotal = (fulfillBuy: boolean) => {
+      const [up, down] = fulfillBuy
+        ? [new anchor.BN(10000), new anchor.BN(10000 + curveDelta)]
+        : [new anchor.BN(10000 + curveDelta), new anchor.BN(10000)];
+      let numerator = new anchor.BN(0);
+      for (let i = 0; i < n; i++) {
+        // fulfill buy starts at the spot price, fulfill sell one step above it
+        const k = fulfillBuy ? i : i + 1;
+        numerator = numerator.add(
+          new anchor.BN(spotPrice)
+            .mul(up.pow(new anchor.BN(k)))
+            .mul(down.pow(new anchor.BN(n - k))),
+        );
+      }
+      return numerator.div(down.pow(new anchor.BN(n)));
+    };
+
+    for (const fulfillBuy of [true, false]) {
+      const quote = await getQuote(poolKey, fulfillBuy, n);
+      // at most the last lamport is lost to the intermediate precision
+      const diff = quote.totalPrice.sub(expectedTotal(fulfillBuy)).toNumber();
+      assert.isAtLeast(diff, -1);
+      assert.isAtMost(diff, 0);
+    }
+  });
+
+  it('rejects exp and log fills above the max fill items', async () => {
+    for (const curveType of [CurveKind.exp, CurveKind.log]) {
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        curveType,
+        curveDelta: new anchor.BN(1),
+        expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+      });
+      for (const fulfillBuy of [true, false]) {
+        try {
+          // the total price would stay far below the max total price
+          await getQuote(poolKey, fulfillBuy, 501);
+          assert.fail('should have failed');
+        } catch (e) {
+          assert.include(`${e}`, 'FillExceedsMaxItems');
+        }
+      }
+    }
+  });
+
+  it('keeps the log curve shape across fills', async () => {
+    const curveDelta = 5000; // 50%
+    for (const n of [1, 2, 10]) {
+      const umi = (await createUmi('http://127.0.0.1:8899')).use(
+        mplTokenMetadata(),
+      );
+      const seller = Keypair.generate();
+      const [poolData, nfts] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.any],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+            curveType: CurveKind.log,
+            curveDelta: new anchor.BN(curveDelta),
+            lpFeeBp: 0,
+            expiry: new anchor.BN(0),
+          },
+          'buy',
+          TOKEN_PROGRAM_ID,
+          seller.publicKey,
+        ),
+        umiMintNfts(
+          umi,
+          {
+            numNfts: n,
+            creators: null,
+            verifyCollection: false,
+            recipient: fromWeb3JsPublicKey(seller.publicKey),
+          },
+          TOKEN_PROGRAM_ID,
+        ),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const closedFormQuote = await getQuote(poolData.poolKey, true, n);
+
+      // sells the nfts into the pool one at a time, summing the step prices
+      let summedSteps = new anchor.BN(0);
+      for (const nft of nfts) {
+        const quote = await getQuote(poolData.poolKey, true, 1);
+        summedSteps = summedSteps.add(quote.totalPrice);
+        const { txId, confirmedTx } = await sellExtraNftToPool(
+          program,
+          {
+            ...poolData,
+            extraNft: nft,
+            poolAtaExtraNft: await getAssociatedTokenAddress(
+              toWeb3JsPublicKey(nft.mintAddress),
+              poolData.poolKey,
+              true,
+              TOKEN_PROGRAM_ID,
+            ),
+          },
+          wallet.publicKey,
+          cosigner,
+          seller,
+        );
+        assertTx(txId, confirmedTx);
+      }
+
+      // sum of spot / (1 + delta * log2(1 + i)) for i in 0..n, up to the
+      // fixed point precision of log2
+      let expectedTotal = 0;
+      for (let i = 0; i < n; i++) {
+        expectedTotal +=
+          LAMPORTS_PER_SOL / (1 + (curveDelta / 10000) * Math.log2(1 + i));
+      }
+      assert.equal(
+        summedSteps.toNumber(),
+        closedFormQuote.totalPrice.toNumber(),
+        `n ${n}`,
+      );
+      assert.closeTo(
+        summedSteps.toNumber(),
+        expectedTotal,
+        expectedTotal / 10000 + n,
+        `n ${n}`,
+      );
+      const pool = await program.account.pool.fetch(poolData.poolKey);
+      assert.equal(
+        pool.spotPrice.toNumber(),
+        closedFormQuote.nextPrice.toNumber(),
+        `n ${n}`,
+      );
+    }
+  });
+
+  it('can not set the price floor above the spot price', async () => {
+    try {
+      await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        priceFloor: new anchor.BN(2 * LAMPORTS_PER_SOL),
+        expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+      });
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidPriceFloor');
+    }
+  });
+
+  it('can not set the price ceiling below the spot price', async () => {
+    try {
+      await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        priceCeiling: new anchor.BN(LAMPORTS_PER_SOL / 2),
+        expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+      });
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidPriceCeiling');
+    }
+  });
+
+  it('decays the lp fee linearly to the floor over the decay window', async () => {
+    const seller = Keypair.generate();
+    const [poolData] = await Promise.all([
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mcc],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(0),
+          lpFeeBp: 1000,
+          lpFeeBpFloor: 200,
+          // minting the example nfts takes a while, so leave enough room
+          lpFeeDecayEnd: new anchor.BN((await getChainTime(connection)) + 60),
+          expiry: new anchor.BN(0),
+        },
+        'both',
+        TOKEN_PROGRAM_ID,
+        seller.publicKey,
+      ),
+      airdrop(connection, seller.publicKey, 10),
+    ]);
+    const poolAccountInfo = await program.account.pool.fetch(poolData.poolKey);
+    const decayStart = poolAccountInfo.lpFeeDecayStart.toNumber();
+    const decayEnd = poolAccountInfo.lpFeeDecayEnd.toNumber();
+    assert.isAbove(decayEnd, decayStart);
+    assert.equal(poolAccountInfo.lpFeeBpFloor, 200);
+
+    const expectedLpFeeBp = (now: number) => {
+      if (now <= decayStart) return 1000;
+      if (now >= decayEnd) return 200;
+      return (
+        1000 - Math.floor((800 * (now - decayStart)) / (decayEnd - decayStart))
+      );
+    };
+    // the fulfill sell total price is 1 SOL, so the lp fee maps to bp directly
+    const quoteLpFeeBp = async () => {
+      const now = await getChainTime(connection);
+      const { lpFee } = await getQuote(poolData.poolKey, false, 1);
+      return { now, lpFeeBp: lpFee.toNumber() / (LAMPORTS_PER_SOL / 10000) };
+    };
+
+    // start of the window, the simulation may run a couple of seconds later
+    {
+      const { now, lpFeeBp } = await quoteLpFeeBp();
+      assert.isAtMost(lpFeeBp, expectedLpFeeBp(now));
+      assert.isAtLeast(lpFeeBp, expectedLpFeeBp(now + 2));
+      assert.isAbove(lpFeeBp, 900);
+    }
+
+    // midpoint of the window
+    await waitForChainTime(
+      connection,
+      Math.floor((decayStart + decayEnd) / 2) - 1,
+    );
+    {
+      const { now, lpFeeBp } = await quoteLpFeeBp();
+      assert.isAtMost(lpFeeBp, expectedLpFeeBp(now));
+      assert.isAtLeast(lpFeeBp, expectedLpFeeBp(now + 2));
+      assert.isAbove(lpFeeBp, 500);
+      assert.isBelow(lpFeeBp, 700);
+    }
+
+    // end of the window
+    await waitForChainTime(connection, decayEnd);
+    {
+      const { lpFeeBp } = await quoteLpFeeBp();
+      assert.equal(lpFeeBp, 200);
+    }
+  });
+
+  it('can not set the lp fee floor above the lp fee', async () => {
+    try {
+      await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        lpFeeBp: 100,
+        lpFeeBpFloor: 200,
+        lpFeeDecayEnd: new anchor.BN((await getChainTime(connection)) + 60),
+      });
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidLpFeeDecay');
+    }
+  });
+
+  it('applies the lp fee tier at and above its threshold', async () => {
+    const seller = Keypair.generate();
+    const [poolData] = await Promise.all([
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mcc],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(0),
+          lpFeeBp: 100,
+          lpFeeTiers: [
+            { minAssetAmount: new anchor.BN(5), lpFeeBp: 800 },
+            { minAssetAmount: new anchor.BN(3), lpFeeBp: 500 },
+          ],
+          expiry: new anchor.BN(0),
+        },
+        'both',
+        TOKEN_PROGRAM_ID,
+        seller.publicKey,
+      ),
+      airdrop(connection, seller.publicKey, 10),
+    ]);
+
+    // every step costs 1 SOL, so the lp fee maps to bp of the asset amount
+    const expectedLpFeeBp = [
+      [1, 100],
+      [2, 100],
+      [3, 500],
+      [4, 500],
+      [5, 800],
+      [6, 800],
+    ];
+    for (const [assetAmount, lpFeeBp] of expectedLpFeeBp) {
+      const { lpFee } = await getQuote(poolData.poolKey, false, assetAmount);
+      assert.equal(
+        lpFee.toNumber(),
+        (assetAmount * lpFeeBp * LAMPORTS_PER_SOL) / 10000,
+        `asset amount ${assetAmount}`,
+      );
+    }
+  });
+
+  it('rounds the lp fee per the pool lp_fee_rounding', async () => {
+    // 1_000_000_020 * 250 / 10000 = 25_000_000.5
+    const spotPrice = new anchor.BN(1_000_000_020);
+    const expectedLpFees = [
+      [LpFeeRounding.floor, 25_000_000],
+      [LpFeeRounding.ceil, 25_000_001],
+      [LpFeeRounding.nearest, 25_000_001],
+    ];
+    for (const [lpFeeRounding, expectedLpFee] of expectedLpFees) {
+      const poolData = await createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mcc],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice,
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(0),
+          lpFeeBp: 250,
+          lpFeeRounding,
+          expiry: new anchor.BN(0),
+        },
+        'both',
+        TOKEN_PROGRAM_ID,
+        wallet.publicKey,
+      );
+      for (const fulfillBuy of [true, false]) {
+        const { lpFee } = await getQuote(poolData.poolKey, fulfillBuy, 1);
+        assert.equal(lpFee.toNumber(), expectedLpFee, `${lpFeeRounding}`);
+      }
+    }
+  });
+
+  it('can not set an unknown lp fee rounding', async () => {
+    try {
+      await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        lpFeeRounding: 3,
+      });
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidLpFeeRounding');
+    }
+  });
+
+  it('quotes exactly what sol_fulfill_buy pays out', async () => {
+    for (const { curveType, curveDelta } of [
+      { curveType: CurveKind.linear, curveDelta: LAMPORTS_PER_SOL / 10 },
+      { curveType: CurveKind.exp, curveDelta: 1000 }, // 10%
+      { curveType: CurveKind.xyk, curveDelta: 10 },
+    ]) {
+      const seller = Keypair.generate();
+      const [poolData] = await Promise.all([
+        createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mint],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+            curveType,
+            curveDelta: new anchor.BN(curveDelta),
+            lpFeeBp: 200,
+            reinvestFulfillBuy: false,
+            expiry: new anchor.BN(0),
+          },
+          'buy',
+          TOKEN_PROGRAM_ID,
+          seller.publicKey,
+        ),
+        airdrop(connection, seller.publicKey, 10),
+      ]);
+      const quote = await getQuote(poolData.poolKey, true, 1);
+      const [initReferralBalance, initPoolAccountInfo] = await Promise.all([
+        connection.getBalance(poolData.referral.publicKey),
+        program.account.pool.fetch(poolData.poolKey),
+      ]);
+
+      const assetMint = toWeb3JsPublicKey(poolData.extraNft.mintAddress);
+      const tx = await program.methods
+        .solFulfillBuy({
+          assetAmount: new anchor.BN(1),
+          // the quote is the exact payment, so there is no slack for a diverging fill
+          minPaymentAmount: quote.totalPrice
+            .sub(quote.lpFee)
+            .sub(quote.takerFee),
+          allowlistAux: '',
+          makerFeeBp: 0,
+          takerFeeBp: 100,
+          minPricePerItem: null,
+        })
+        .accountsStrict({
+          payer: seller.publicKey,
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          referral: poolData.referral.publicKey,
+          takerReferral: poolData.referral.publicKey,
+          pool: poolData.poolKey,
+          buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+          assetMetadata: toWeb3JsPublicKey(poolData.extraNft.metadataAddress),
+          assetMasterEdition: toWeb3JsPublicKey(
+            poolData.extraNft.masterEditionAddress,
+          ),
+          assetMint,
+          payerAssetAccount: toWeb3JsPublicKey(poolData.extraNft.tokenAddress),
+          sellsideEscrowTokenAccount: poolData.poolAtaExtraNft,
+          ownerTokenAccount: await getAssociatedTokenAddress(
+            assetMint,
+            wallet.publicKey,
+            true,
+            TOKEN_PROGRAM_ID,
+          ),
+          allowlistAuxAccount: SystemProgram.programId,
+          sellState: getMMMSellStatePDA(
+            program.programId,
+            poolData.poolKey,
+            assetMint,
+          ).key,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .transaction();
+      const blockhashData = await connection.getLatestBlockhash();
+      tx.feePayer = seller.publicKey;
+      tx.recentBlockhash = blockhashData.blockhash;
+      tx.partialSign(cosigner, seller);
+      await sendAndAssertTx(connection, tx, blockhashData, false);
+
+      const [referralBalance, poolAccountInfo] = await Promise.all([
+        connection.getBalance(poolData.referral.publicKey),
+        program.account.pool.fetch(poolData.poolKey),
+      ]);
+      assert.equal(
+        referralBalance - initReferralBalance,
+        quote.referralFee.toNumber(),
+        `curve ${curveType}`,
+      );
+      assert.equal(
+        poolAccountInfo.lpFeeEarned.toNumber() -
+          initPoolAccountInfo.lpFeeEarned.toNumber(),
+        quote.lpFee.toNumber(),
+        `curve ${curveType}`,
+      );
+      assert.equal(
+        poolAccountInfo.spotPrice.toNumber(),
+        quote.nextPrice.toNumber(),
+        `curve ${curveType}`,
+      );
+    }
+  });
+
+  it('clamps a saturating quote to the largest amount without an overflow', async () => {
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner,
+      spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+      curveType: CurveKind.linear,
+      curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+      expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+    });
+    const assetAmount = 1_000_000_000_000;
+    try {
+      await getQuote(poolKey, false, assetAmount);
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'NumericOverflow');
+    }
+
+    const saturatingQuote = await getQuote(poolKey, false, assetAmount, true);
+    const clampedAmount = saturatingQuote.assetAmount.toNumber();
+    assert.isAbove(clampedAmount, 0);
+    assert.isBelow(clampedAmount, assetAmount);
+
+    // the clamped amount quotes like a strict quote, and one more overflows
+    const quote = await getQuote(poolKey, false, clampedAmount);
+    assert.equal(quote.assetAmount.toNumber(), clampedAmount);
+    assert.equal(
+      quote.totalPrice.toString(),
+      saturatingQuote.totalPrice.toString(),
+    );
+    try {
+      await getQuote(poolKey, false, clampedAmount + 1);
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'NumericOverflow');
+    }
+  });
+
+  it('does not clamp a saturating quote that fits', async () => {
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner,
+      spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+      curveType: CurveKind.linear,
+      curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+      expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+    });
+    // 1.1 + 1.2
+    const quote = await getQuote(poolKey, false, 2, true);
+    assert.equal(quote.assetAmount.toNumber(), 2);
+    assert.equal(quote.totalPrice.toNumber(), (LAMPORTS_PER_SOL / 10) * 23);
+  });
+
+  it('rejects a maker rebate above the taker fee', async () => {
+    const { poolKey } = await createPool(program, {
+      owner: wallet.publicKey,
+      cosigner,
+      spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+      curveType: CurveKind.linear,
+      curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+      expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+    });
+    try {
+      await program.methods
+        .getPoolQuote({
+          fulfillBuy: false,
+          assetAmount: new anchor.BN(1),
+          metadataRoyaltyBp: 0,
+          makerFeeBp: -200,
+          takerFeeBp: 100,
+          saturating: false,
+        })
+        .accountsStrict({
+          payer: wallet.publicKey,
+          owner: wallet.publicKey,
+          pool: poolKey,
+          buysideSolEscrowAccount: getMMMBuysideSolEscrowPDA(
+            program.programId,
+            poolKey,
+          ).key,
+        })
+        .simulate();
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'MakerRebateExceedsTakerFee');
+    }
+  });
+
+  describe('steep exp curve', () => {
+    it('steps the price by more than 2x', async () => {
+      // 20000 bp, every step triples the price
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        curveType: CurveKind.exp,
+        curveDelta: new anchor.BN(20000),
+        expiry: new anchor.BN(0),
+      });
+
+      // 3 + 9 + 27 + 81 + 243
+      const sellQuote = await getQuote(poolKey, false, 5);
+      assert.equal(sellQuote.totalPrice.toNumber(), 363 * LAMPORTS_PER_SOL);
+      assert.equal(sellQuote.nextPrice.toNumber(), 243 * LAMPORTS_PER_SOL);
+
+      // 1 + 1/3 + 1/9 + 1/27 + 1/81
+      const buyQuote = await getQuote(poolKey, true, 5);
+      assert.equal(buyQuote.totalPrice.toNumber(), 1_493_827_160);
+      assert.equal(buyQuote.nextPrice.toNumber(), 4_115_226);
+    });
+
+    it('can not step the price by more than 10x', async () => {
+      try {
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          curveType: CurveKind.exp,
+          curveDelta: new anchor.BN(90001),
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidCurveDelta');
+      }
+    });
+
+    it('can not overflow the max total price within 5 steps', async () => {
+      try {
+        // 100_000 SOL * 10^5
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice: new anchor.BN(100_000 * LAMPORTS_PER_SOL),
+          curveType: CurveKind.exp,
+          curveDelta: new anchor.BN(90000),
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidCurveDelta');
+      }
+    });
+
+    it('can not overflow the max total price of the pool within 5 steps', async () => {
+      try {
+        // 1 SOL * 3^5 = 243 SOL, above the 100 SOL cap of the pool
+        await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+          curveType: CurveKind.exp,
+          curveDelta: new anchor.BN(20000),
+          maxTotalPrice: new anchor.BN(100 * LAMPORTS_PER_SOL),
+        });
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'InvalidCurveDelta');
+      }
+    });
+  });
+
+  describe('max_affordable_items', () => {
+    it('includes the taker fee on a linear curve', async () => {
+      const { poolKey } = await createSellsidePool(
+        CurveKind.linear,
+        new anchor.BN(LAMPORTS_PER_SOL / 10),
+      );
+      // (1.1 + 1.2 + 1.3 + 1.4) * 1.01
+      const fourItemsCost = new anchor.BN((LAMPORTS_PER_SOL / 100) * 505);
+      assert.equal(await getMaxAffordableItems(poolKey, fourItemsCost, 100), 4);
+      assert.equal(
+        await getMaxAffordableItems(poolKey, fourItemsCost.subn(1), 100),
+        3,
+      );
+      assert.equal(
+        await getMaxAffordableItems(poolKey, new anchor.BN(0), 100),
+        0,
+      );
+    });
+
+    it('stops at the sellside asset amount on an exp curve', async () => {
+      // every step doubles the price
+      const { poolKey } = await createSellsidePool(
+        CurveKind.exp,
+        new anchor.BN(10000),
+      );
+      // 2 + 4 + 8 + 16
+      const fourItemsCost = new anchor.BN(30 * LAMPORTS_PER_SOL);
+      assert.equal(await getMaxAffordableItems(poolKey, fourItemsCost, 0), 4);
+      assert.equal(
+        await getMaxAffordableItems(poolKey, fourItemsCost.subn(1), 0),
+        3,
+      );
+      assert.equal(
+        await getMaxAffordableItems(
+          poolKey,
+          new anchor.BN('18446744073709551615'),
+          0,
+        ),
+        6,
+      );
+    });
+  });
+
+  describe('check_pool_solvency', () => {
+    const createBuysidePool = async (paymentAmount: number) => {
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        curveType: CurveKind.linear,
+        curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+        expiry: new anchor.BN(0),
+      });
+      await program.methods
+        .solDepositBuy({ paymentAmount: new anchor.BN(paymentAmount) })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          cosigner: cosigner.publicKey,
+          pool: poolKey,
+          buysideSolEscrowAccount: getMMMBuysideSolEscrowPDA(
+            program.programId,
+            poolKey,
+          ).key,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([cosigner])
+        .rpc();
+      return poolKey;
+    };
+
+    it('flags an underfunded buy pool', async () => {
+      const poolKey = await createBuysidePool(1.5 * LAMPORTS_PER_SOL);
+
+      // 1 + 0.9
+      const solvency = await checkPoolSolvency(poolKey, true, 2);
+      assert.isFalse(solvency.solvent);
+      assert.equal(solvency.required.toNumber(), (LAMPORTS_PER_SOL / 10) * 19);
+      assert.equal(solvency.available.toNumber(), 1.5 * LAMPORTS_PER_SOL);
+
+      assert.isTrue((await checkPoolSolvency(poolKey, true, 1)).solvent);
+    });
+
+    it('confirms a well funded buy pool', async () => {
+      const poolKey = await createBuysidePool(5 * LAMPORTS_PER_SOL);
+
+      const solvency = await checkPoolSolvency(poolKey, true, 2);
+      assert.isTrue(solvency.solvent);
+      assert.equal(solvency.required.toNumber(), (LAMPORTS_PER_SOL / 10) * 19);
+      assert.equal(solvency.available.toNumber(), 5 * LAMPORTS_PER_SOL);
+    });
+
+    it('compares a fulfill sell against the sellside asset amount', async () => {
+      const { poolKey } = await createSellsidePool(
+        CurveKind.linear,
+        new anchor.BN(LAMPORTS_PER_SOL / 10),
+      );
+      assert.isTrue((await checkPoolSolvency(poolKey, false, 6)).solvent);
+
+      const solvency = await checkPoolSolvency(poolKey, false, 7);
+      assert.isFalse(solvency.solvent);
+      assert.equal(solvency.required.toNumber(), 7);
+      assert.equal(solvency.available.toNumber(), 6);
+    });
+
+    describe('shared escrow', () => {
+      const sharedEscrowAccount = getM2BuyerSharedEscrow(
+        wallet.publicKey,
+      ).key;
+      const m2Accounts: AccountMeta[] = [
+        { pubkey: M2_PROGRAM, isSigner: false, isWritable: false },
+        { pubkey: sharedEscrowAccount, isSigner: false, isWritable: false },
+      ];
+
+      // a buyside pool without its own deposit that fills from the m2 shared
+      // escrow of the owner at most sharedEscrowCount times
+      const createSharedEscrowPool = async (sharedEscrowCount: number) => {
+        const { poolKey } = await createPool(program, {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+          expiry: new anchor.BN(0),
+        });
+        await program.methods
+          .setSharedEscrow({
+            sharedEscrowCount: new anchor.BN(sharedEscrowCount),
+          })
+          .accountsStrict({
+            owner: wallet.publicKey,
+            cosigner: cosigner.publicKey,
+            pool: poolKey,
+            sharedEscrowAccount,
+          })
+          .signers([cosigner])
+          .rpc();
+        return poolKey;
+      };
+
+      it('uses the m2 shared escrow balance as the available budget', async () => {
+        const poolKey = await createSharedEscrowPool(4);
+        await airdrop(connection, sharedEscrowAccount, 1.5);
+        const sharedEscrowBalance = await connection.getBalance(
+          sharedEscrowAccount,
+        );
+
+        // 1 + 0.9 is above the shared escrow balance, even though the pool
+        // escrow is empty the quote only looks at the shared escrow
+        const solvency = await checkPoolSolvency(poolKey, true, 2, m2Accounts);
+        assert.isFalse(solvency.solvent);
+        assert.equal(
+          solvency.required.toNumber(),
+          (LAMPORTS_PER_SOL / 10) * 19,
+        );
+        assert.equal(solvency.available.toNumber(), sharedEscrowBalance);
+
+        const single = await checkPoolSolvency(poolKey, true, 1, m2Accounts);
+        assert.isTrue(single.solvent);
+        assert.equal(single.available.toNumber(), sharedEscrowBalance);
+        assert.equal(
+          await connection.getBalance(
+            getMMMBuysideSolEscrowPDA(program.programId, poolKey).key,
+          ),
+          0,
+        );
+      });
+
+      it('is bound by the shared escrow count', async () => {
+        const poolKey = await createSharedEscrowPool(1);
+        await airdrop(connection, sharedEscrowAccount, 10);
+
+        assert.isTrue(
+          (await checkPoolSolvency(poolKey, true, 1, m2Accounts)).solvent,
+        );
+        const solvency = await checkPoolSolvency(poolKey, true, 2, m2Accounts);
+        assert.isFalse(solvency.solvent);
+        assert.isAbove(
+          solvency.available.toNumber(),
+          solvency.required.toNumber(),
+        );
+      });
+
+      it('requires the m2 accounts', async () => {
+        const poolKey = await createSharedEscrowPool(2);
+        try {
+          await checkPoolSolvency(poolKey, true, 1);
+          assert.fail('should have failed');
+        } catch (e) {
+          assert.include(`${e}`, 'InvalidRemainingAccounts');
+        }
+      });
+    });
+  });
+
+  describe('get_pool_notional_value', () => {
+    const getPoolNotionalValue = async (
+      poolKey: anchor.web3.PublicKey,
+      makerFeeBp: number,
+    ) => {
+      const { raw } = await program.methods
+        .getPoolNotionalValue({ makerFeeBp, takerFeeBp: 100 })
+        .accountsStrict({
+          owner: wallet.publicKey,
+          pool: poolKey,
+          buysideSolEscrowAccount: getMMMBuysideSolEscrowPDA(
+            program.programId,
+            poolKey,
+          ).key,
+        })
+        .simulate();
+      const notional = getPoolNotionalValueFromLogs([...raw]);
+      assert.isNotNull(notional);
+      assert.equal(notional!.pool.toBase58(), poolKey.toBase58());
+      return notional!;
+    };
+
+    it('sums the buyside escrow and the sellside inventory', async () => {
+      const buyer = Keypair.generate();
+      const { poolKey, poolPaymentEscrow } =
+        await createPoolWithExampleDepositsUmi(
+          program,
+          [AllowlistKind.mint],
+          {
+            owner: wallet.publicKey,
+            cosigner,
+            spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+            curveType: CurveKind.linear,
+            curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+            expiry: new anchor.BN(0),
+          },
+          'both',
+          TOKEN_PROGRAM_ID,
+          buyer.publicKey,
+        );
+      const escrowBalance = await connection.getBalance(poolPaymentEscrow);
+      assert.isAbove(escrowBalance, 0);
+
+      // the nft and 5 sfts sold at 1.1 + 1.2 + 1.3 + 1.4 + 1.5 + 1.6
+      const sellsideValue = (LAMPORTS_PER_SOL / 10) * 81;
+      {
+        const notional = await getPoolNotionalValue(poolKey, 0);
+        assert.equal(notional.buysideValue.toNumber(), escrowBalance);
+        assert.equal(notional.sellsideValue.toNumber(), sellsideValue);
+        assert.equal(notional.feesOwed.toNumber(), 0);
+        assert.equal(
+          notional.notionalValue.toNumber(),
+          escrowBalance + sellsideValue,
+        );
+      }
+      // a 1% maker fee comes out of the sellside proceeds
+      {
+        const notional = await getPoolNotionalValue(poolKey, 100);
+        assert.equal(notional.feesOwed.toNumber(), sellsideValue / 100);
+        assert.equal(
+          notional.notionalValue.toNumber(),
+          escrowBalance + sellsideValue - sellsideValue / 100,
+        );
+      }
+    });
+
+    it('only counts the buyside escrow of a pool without inventory', async () => {
+      const { poolKey } = await createPool(program, {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        curveType: CurveKind.linear,
+        curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+        expiry: new anchor.BN(0),
+      });
+      const notional = await getPoolNotionalValue(poolKey, 0);
+      assert.equal(notional.buysideValue.toNumber(), 0);
+      assert.equal(notional.sellsideValue.toNumber(), 0);
+      assert.equal(notional.notionalValue.toNumber(), 0);
+    });
+  });
+
+  describe('log_sell_state', () => {
+    it('logs the bookkeeping of the sell states of a pool', async () => {
+      const { poolKey, nft, sft } = await createSellsidePool(
+        CurveKind.linear,
+        new anchor.BN(LAMPORTS_PER_SOL / 10),
+      );
+
+      for (const [asset, assetAmount] of [
+        [nft, 1],
+        [sft, 5],
+      ] as const) {
+        const assetMint = toWeb3JsPublicKey(asset.mintAddress);
+        const { key: sellState } = getMMMSellStatePDA(
+          program.programId,
+          poolKey,
+          assetMint,
+        );
+        const { raw } = await program.methods
+          .logSellState()
+          .accountsStrict({ sellState })
+          .simulate();
+        const summary = getSellStateSummaryFromLogs([...raw]);
+        assert.isNotNull(summary);
+        assert.equal(summary!.pool.toBase58(), poolKey.toBase58());
+        assert.equal(summary!.assetMint.toBase58(), assetMint.toBase58());
+        assert.equal(summary!.assetAmount.toNumber(), assetAmount);
+
+        const sellStateAccount = await program.account.sellState.fetch(
+          sellState,
+        );
+        assert.equal(
+          summary!.assetAmount.toNumber(),
+          sellStateAccount.assetAmount.toNumber(),
+        );
+      }
+    });
+
+    it('can not log an account that is not a sell state', async () => {
+      const { poolKey } = await createSellsidePool(
+        CurveKind.linear,
+        new anchor.BN(LAMPORTS_PER_SOL / 10),
+      );
+      try {
+        await program.methods
+          .logSellState()
+          .accountsStrict({ sellState: poolKey })
+          .simulate();
+        assert.fail('should have failed');
+      } catch (e) {
+        assert.include(`${e}`, 'AccountDiscriminatorMismatch');
+      }
+    });
+  });
+});
@@ -0,0 +1,304 @@
+import * as anchor from '@project-serum/anchor';
+import {
+  getAssociatedTokenAddress,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import {
+  AccountMeta,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js';
+import { assert } from 'chai';
+import {
+  Mmm,
+  AllowlistKind,
+  CurveKind,
+  getMMMSellStatePDA,
+  getMMMTakerFeeExemptionPDA,
+  IDL,
+  MMMProgramID,
+} from '../sdk/src';
+import {
+  airdrop,
+  assertTx,
+  createPoolWithExampleDepositsUmi,
+  PoolData,
+} from './utils';
+import { toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
+
+describe('mmm-referral', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const cosigner = Keypair.generate();
+  const spotPrice = new anchor.BN(LAMPORTS_PER_SOL);
+
+  beforeEach(async () => {
+    await Promise.all([
+      airdrop(connection, wallet.publicKey, 50),
+      // the cosigner pays the rent of the taker fee exemptions
+      airdrop(connection, cosigner.publicKey, 1),
+    ]);
+  });
+
+  const createSellsidePool = async (buyer: Keypair): Promise<PoolData> => {
+    const [poolData] = await Promise.all([
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mint],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice,
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(0),
+          expiry: new anchor.BN(0),
+          lpFeeBp: 0,
+          reinvestFulfillSell: false,
+        },
+        'sell',
+        TOKEN_PROGRAM_ID,
+        buyer.publicKey,
+      ),
+      airdrop(connection, buyer.publicKey, 10),
+    ]);
+    return poolData;
+  };
+
+  const buyNftFromPool = async (
+    poolData: PoolData,
+    buyer: Keypair,
+    takerReferral: PublicKey,
+    makerFeeBp: number,
+    takerFeeBp: number,
+    remainingAccounts: AccountMeta[] = [],
+  ) => {
+    const nftMint = toWeb3JsPublicKey(poolData.nft.mintAddress);
+    const buyerNftAtaAddress = await getAssociatedTokenAddress(
+      nftMint,
+      buyer.publicKey,
+      true,
+      TOKEN_PROGRAM_ID,
+    );
+    const { key: sellState } = getMMMSellStatePDA(
+      program.programId,
+      poolData.poolKey,
+      nftMint,
+    );
+    const tx = await program.methods
+      .solFulfillSell({
+        assetAmount: new anchor.BN(1),
+        maxPaymentAmount: spotPrice.muln(2),
+        maxPricePerItem: null,
+        buysideCreatorRoyaltyBp: 0,
+        allowlistAux: '',
+        makerFeeBp,
+        takerFeeBp,
+        partialFillOk: false,
+        royaltyBpOverride: null,
+      })
+      .accountsStrict({
+        payer: buyer.publicKey,
+        owner: wallet.publicKey,
+        cosigner: cosigner.publicKey,
+        referral: poolData.referral.publicKey,
+        takerReferral,
+        pool: poolData.poolKey,
+        buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        assetMetadata: toWeb3JsPublicKey(poolData.nft.metadataAddress),
+        assetMasterEdition: toWeb3JsPublicKey(
+          poolData.nft.masterEditionAddress,
+        ),
+        assetMint: nftMint,
+        sellsideEscrowTokenAccount: poolData.poolAtaNft,
+        payerAssetAccount: buyerNftAtaAddress,
+        allowlistAuxAccount: SystemProgram.programId,
+        sellState,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .remainingAccounts(remainingAccounts)
+      .transaction();
+
+    const blockhashData = await connection.getLatestBlockhash();
+    tx.feePayer = buyer.publicKey;
+    tx.recentBlockhash = blockhashData.blockhash;
+    tx.partialSign(cosigner, buyer);
+    const txId = await connection.sendRawTransaction(tx.serialize(), {
+      skipPreflight: true,
+    });
+    const confirmedTx = await connection.confirmTransaction(
+      {
+        signature: txId,
+        blockhash: blockhashData.blockhash,
+        lastValidBlockHeight: blockhashData.lastValidBlockHeight,
+      },
+      'processed',
+    );
+    return { txId, confirmedTx };
+  };
+
+  // exempts the wallet, it passes the exemption as the last remaining account
+  const exemptFromTakerFee = async (poolData: PoolData, taker: PublicKey) => {
+    const { key: takerFeeExemption } = getMMMTakerFeeExemptionPDA(
+      program.programId,
+      poolData.poolKey,
+      taker,
+    );
+    await program.methods
+      .addTakerFeeExemption({ taker })
+      .accountsStrict({
+        cosigner: cosigner.publicKey,
+        owner: wallet.publicKey,
+        pool: poolData.poolKey,
+        takerFeeExemption,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([cosigner])
+      .rpc();
+    return takerFeeExemption;
+  };
+
+  // returns the balance change of the pool referral, the taker referral and the pool owner
+  // exempt passes the buyer's exemption, or exempts another wallet instead
+  const fillWithFees = async (
+    makerFeeBp: number,
+    takerFeeBp: number,
+    exempt: 'none' | 'buyer' | 'other' = 'none',
+  ) => {
+    const buyer = Keypair.generate();
+    const takerReferral = Keypair.generate().publicKey;
+    const poolData = await createSellsidePool(buyer);
+    const remainingAccounts: AccountMeta[] = [];
+    if (exempt === 'buyer') {
+      const takerFeeExemption = await exemptFromTakerFee(
+        poolData,
+        buyer.publicKey,
+      );
+      remainingAccounts.push({
+        pubkey: takerFeeExemption,
+        isSigner: false,
+        isWritable: false,
+      });
+    } else if (exempt === 'other') {
+      await exemptFromTakerFee(poolData, Keypair.generate().publicKey);
+    }
+    const { key: sellState } = getMMMSellStatePDA(
+      program.programId,
+      poolData.poolKey,
+      toWeb3JsPublicKey(poolData.nft.mintAddress),
+    );
+    const accounts = [
+      poolData.referral.publicKey,
+      takerReferral,
+      wallet.publicKey,
+    ];
+    const [initBalances, sellStateRent, poolAtaRent] = await Promise.all([
+      Promise.all(accounts.map((account) => connection.getBalance(account))),
+      connection.getBalance(sellState),
+      connection.getBalance(poolData.poolAtaNft),
+    ]);
+
+    const { txId, confirmedTx } = await buyNftFromPool(
+      poolData,
+      buyer,
+      takerReferral,
+      makerFeeBp,
+      takerFeeBp,
+      remainingAccounts,
+    );
+    assertTx(txId, confirmedTx);
+
+    const balances = await Promise.all(
+      accounts.map((account) => connection.getBalance(account)),
+    );
+    const [referralDelta, takerReferralDelta, ownerDelta] = balances.map(
+      (balance, i) => balance - initBalances[i],
+    );
+    // the sell state and the pool ata of the nft are closed to the owner
+    return {
+      referralDelta,
+      takerReferralDelta,
+      ownerDelta: ownerDelta - sellStateRent - poolAtaRent,
+    };
+  };
+
+  it('pays the maker fee and the taker fee to separate referrals', async () => {
+    const { referralDelta, takerReferralDelta, ownerDelta } =
+      await fillWithFees(100, 200);
+    assert.equal(referralDelta, LAMPORTS_PER_SOL / 100);
+    assert.equal(takerReferralDelta, (LAMPORTS_PER_SOL / 100) * 2);
+    assert.equal(ownerDelta, (LAMPORTS_PER_SOL / 100) * 99);
+  });
+
+  it('funds the maker rebate from the taker referral fee', async () => {
+    const { referralDelta, takerReferralDelta, ownerDelta } =
+      await fillWithFees(-100, 300);
+    assert.equal(referralDelta, 0);
+    assert.equal(takerReferralDelta, (LAMPORTS_PER_SOL / 100) * 2);
+    assert.equal(ownerDelta, (LAMPORTS_PER_SOL / 100) * 101);
+  });
+
+  it('waives the taker fee of an exempt taker', async () => {
+    const { referralDelta, takerReferralDelta, ownerDelta } =
+      await fillWithFees(100, 200, 'buyer');
+    assert.equal(referralDelta, LAMPORTS_PER_SOL / 100);
+    assert.equal(takerReferralDelta, 0);
+    assert.equal(ownerDelta, (LAMPORTS_PER_SOL / 100) * 99);
+  });
+
+  it('charges the taker fee of a taker that is not exempt', async () => {
+    const { referralDelta, takerReferralDelta, ownerDelta } =
+      await fillWithFees(100, 200, 'other');
+    assert.equal(referralDelta, LAMPORTS_PER_SOL / 100);
+    assert.equal(takerReferralDelta, (LAMPORTS_PER_SOL / 100) * 2);
+    assert.equal(ownerDelta, (LAMPORTS_PER_SOL / 100) * 99);
+  });
+
+  it('clamps the maker rebate of an exempt taker to 0', async () => {
+    const { referralDelta, takerReferralDelta, ownerDelta } =
+      await fillWithFees(-100, 300, 'buyer');
+    assert.equal(referralDelta, 0);
+    assert.equal(takerReferralDelta, 0);
+    assert.equal(ownerDelta, LAMPORTS_PER_SOL);
+  });
+
+  it('closes a removed taker fee exemption to the cosigner', async () => {
+    const buyer = Keypair.generate();
+    const poolData = await createSellsidePool(buyer);
+    const takerFeeExemption = await exemptFromTakerFee(
+      poolData,
+      buyer.publicKey,
+    );
+    const exemption = await program.account.takerFeeExemption.fetch(
+      takerFeeExemption,
+    );
+    assert.equal(exemption.pool.toBase58(), poolData.poolKey.toBase58());
+    assert.equal(exemption.taker.toBase58(), buyer.publicKey.toBase58());
+
+    await program.methods
+      .removeTakerFeeExemption()
+      .accountsStrict({
+        cosigner: cosigner.publicKey,
+        owner: wallet.publicKey,
+        pool: poolData.poolKey,
+        takerFeeExemption,
+      })
+      .signers([cosigner])
+      .rpc();
+    assert.isNull(await connection.getAccountInfo(takerFeeExemption));
+  });
+});
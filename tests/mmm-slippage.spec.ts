@@ -0,0 +1,256 @@
+import * as anchor from '@project-serum/anchor';
+import {
+  getAssociatedTokenAddress,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js';
+import { assert } from 'chai';
+import {
+  Mmm,
+  AllowlistKind,
+  CurveKind,
+  getMMMSellStatePDA,
+  IDL,
+  MMMProgramID,
+} from '../sdk/src';
+import {
+  airdrop,
+  assertFailedTx,
+  assertTx,
+  createPoolWithExampleDepositsUmi,
+  PoolData,
+  sellExtraNftToPool,
+} from './utils';
+import { toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
+
+describe('mmm-slippage', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const cosigner = Keypair.generate();
+  const spotPrice = new anchor.BN(LAMPORTS_PER_SOL);
+
+  beforeEach(async () => {
+    await airdrop(connection, wallet.publicKey, 50);
+  });
+
+  const createTestPool = async (
+    side: 'buy' | 'sell',
+    taker: Keypair,
+    poolArgs: { curveDelta?: anchor.BN; maxSpotMoveBp?: number } = {},
+  ): Promise<PoolData> => {
+    const [poolData] = await Promise.all([
+      createPoolWithExampleDepositsUmi(
+        program,
+        [AllowlistKind.mint],
+        {
+          owner: wallet.publicKey,
+          cosigner,
+          spotPrice,
+          curveType: CurveKind.linear,
+          curveDelta: new anchor.BN(0),
+          expiry: new anchor.BN(0),
+          lpFeeBp: 0,
+          reinvestFulfillSell: false,
+          ...poolArgs,
+        },
+        side,
+        TOKEN_PROGRAM_ID,
+        taker.publicKey,
+      ),
+      airdrop(connection, taker.publicKey, 10),
+    ]);
+    return poolData;
+  };
+
+  const buyNftFromPool = async (
+    poolData: PoolData,
+    buyer: Keypair,
+    maxPricePerItem: anchor.BN,
+  ) => {
+    const nftMint = toWeb3JsPublicKey(poolData.nft.mintAddress);
+    const buyerNftAtaAddress = await getAssociatedTokenAddress(
+      nftMint,
+      buyer.publicKey,
+      true,
+      TOKEN_PROGRAM_ID,
+    );
+    const { key: sellState } = getMMMSellStatePDA(
+      program.programId,
+      poolData.poolKey,
+      nftMint,
+    );
+    const tx = await program.methods
+      .solFulfillSell({
+        assetAmount: new anchor.BN(1),
+        maxPaymentAmount: spotPrice.muln(2),
+        buysideCreatorRoyaltyBp: 0,
+        allowlistAux: '',
+        takerFeeBp: 0,
+        makerFeeBp: 0,
+        maxPricePerItem,
+        partialFillOk: false,
+        royaltyBpOverride: null,
+      })
+      .accountsStrict({
+        payer: buyer.publicKey,
+        owner: wallet.publicKey,
+        cosigner: cosigner.publicKey,
+        referral: poolData.referral.publicKey,
+        takerReferral: poolData.referral.publicKey,
+        pool: poolData.poolKey,
+        buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        assetMetadata: toWeb3JsPublicKey(poolData.nft.metadataAddress),
+        assetMasterEdition: toWeb3JsPublicKey(
+          poolData.nft.masterEditionAddress,
+        ),
+        assetMint: nftMint,
+        sellsideEscrowTokenAccount: poolData.poolAtaNft,
+        payerAssetAccount: buyerNftAtaAddress,
+        allowlistAuxAccount: SystemProgram.programId,
+        sellState,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .transaction();
+
+    const blockhashData = await connection.getLatestBlockhash();
+    tx.feePayer = buyer.publicKey;
+    tx.recentBlockhash = blockhashData.blockhash;
+    tx.partialSign(cosigner, buyer);
+    const txId = await connection.sendRawTransaction(tx.serialize(), {
+      skipPreflight: true,
+    });
+    const confirmedTx = await connection.confirmTransaction(
+      {
+        signature: txId,
+        blockhash: blockhashData.blockhash,
+        lastValidBlockHeight: blockhashData.lastValidBlockHeight,
+      },
+      'processed',
+    );
+    return { txId, confirmedTx };
+  };
+
+  describe('min_price_per_item', () => {
+    it('can fill at exactly the min price', async () => {
+      const seller = Keypair.generate();
+      const poolData = await createTestPool('buy', seller);
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+        [],
+        spotPrice,
+      );
+      assertTx(txId, confirmedTx);
+    });
+
+    it('can not fill one lamport below the min price', async () => {
+      const seller = Keypair.generate();
+      const poolData = await createTestPool('buy', seller);
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+        [],
+        spotPrice.addn(1),
+      );
+      assertFailedTx(txId, confirmedTx);
+    });
+  });
+
+  describe('max_price_per_item', () => {
+    it('can fill at exactly the max price', async () => {
+      const buyer = Keypair.generate();
+      const poolData = await createTestPool('sell', buyer);
+      const { txId, confirmedTx } = await buyNftFromPool(
+        poolData,
+        buyer,
+        spotPrice,
+      );
+      assertTx(txId, confirmedTx);
+    });
+
+    it('can not fill one lamport above the max price', async () => {
+      const buyer = Keypair.generate();
+      const poolData = await createTestPool('sell', buyer);
+      const { txId, confirmedTx } = await buyNftFromPool(
+        poolData,
+        buyer,
+        spotPrice.subn(1),
+      );
+      assertFailedTx(txId, confirmedTx);
+    });
+  });
+
+  describe('max_spot_move_bp', () => {
+    // every fill moves the spot price by 10%
+    const curveDelta = spotPrice.divn(10);
+
+    it('can fill when the spot price moves up to the cap', async () => {
+      const seller = Keypair.generate();
+      const poolData = await createTestPool('buy', seller, {
+        curveDelta,
+        maxSpotMoveBp: 1000,
+      });
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+      );
+      assertTx(txId, confirmedTx);
+
+      const poolAccountInfo = await program.account.pool.fetch(
+        poolData.poolKey,
+      );
+      assert.equal(poolAccountInfo.maxSpotMoveBp, 1000);
+      assert.equal(
+        poolAccountInfo.spotPrice.toNumber(),
+        spotPrice.sub(curveDelta).toNumber(),
+      );
+    });
+
+    it('can not fill when the spot price moves beyond the cap', async () => {
+      const seller = Keypair.generate();
+      const poolData = await createTestPool('buy', seller, {
+        curveDelta,
+        maxSpotMoveBp: 999,
+      });
+      const { txId, confirmedTx } = await sellExtraNftToPool(
+        program,
+        poolData,
+        wallet.publicKey,
+        cosigner,
+        seller,
+      );
+      assertFailedTx(txId, confirmedTx);
+
+      const poolAccountInfo = await program.account.pool.fetch(
+        poolData.poolKey,
+      );
+      assert.equal(poolAccountInfo.spotPrice.toNumber(), spotPrice.toNumber());
+    });
+  });
+});
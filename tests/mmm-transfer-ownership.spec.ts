@@ -0,0 +1,335 @@
+import * as anchor from '@project-serum/anchor';
+import {
+  getAssociatedTokenAddress,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import {
+  AccountMeta,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js';
+import { assert } from 'chai';
+import {
+  Mmm,
+  AllowlistKind,
+  CurveKind,
+  getMMMBuysideSolEscrowPDA,
+  getMMMPoolPDA,
+  getMMMSellStatePDA,
+  IDL,
+  MMMProgramID,
+} from '../sdk/src';
+import {
+  airdrop,
+  assertFailedTx,
+  assertTx,
+  createPoolWithExampleDepositsUmi,
+  PoolData,
+} from './utils';
+import { toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
+
+describe('mmm-transfer-ownership', () => {
+  const { connection } = anchor.AnchorProvider.env();
+  const wallet = new anchor.Wallet(Keypair.generate());
+  const provider = new anchor.AnchorProvider(connection, wallet, {
+    commitment: 'processed',
+  });
+  const program = new anchor.Program(
+    IDL,
+    MMMProgramID,
+    provider,
+  ) as anchor.Program<Mmm>;
+  const cosigner = Keypair.generate();
+
+  beforeEach(async () => {
+    await airdrop(connection, wallet.publicKey, 50);
+  });
+
+  const createTestPool = (buyer: PublicKey) =>
+    createPoolWithExampleDepositsUmi(
+      program,
+      [AllowlistKind.mint],
+      {
+        owner: wallet.publicKey,
+        cosigner,
+        spotPrice: new anchor.BN(LAMPORTS_PER_SOL),
+        curveType: CurveKind.linear,
+        curveDelta: new anchor.BN(LAMPORTS_PER_SOL / 10),
+        expiry: new anchor.BN(0),
+        lpFeeBp: 0,
+        reinvestFulfillSell: false,
+      },
+      'both',
+      TOKEN_PROGRAM_ID,
+      buyer,
+    );
+
+  const transferPoolOwnership = async (
+    poolData: PoolData,
+    newOwner: Keypair,
+    assets = [poolData.nft, poolData.sft],
+  ) => {
+    const { key: newPool } = getMMMPoolPDA(
+      program.programId,
+      newOwner.publicKey,
+      poolData.uuid.publicKey,
+    );
+    // asset_mint, sellside_escrow_token_account, new_sellside_escrow_token_account,
+    // sell_state, new_sell_state
+    const remainingAccounts: AccountMeta[] = (
+      await Promise.all(
+        assets.map(async (asset) => {
+          const mint = toWeb3JsPublicKey(asset.mintAddress);
+          return [
+            { pubkey: mint, isSigner: false, isWritable: false },
+            {
+              pubkey: await getAssociatedTokenAddress(
+                mint,
+                poolData.poolKey,
+                true,
+                TOKEN_PROGRAM_ID,
+              ),
+              isSigner: false,
+              isWritable: true,
+            },
+            {
+              pubkey: await getAssociatedTokenAddress(
+                mint,
+                newPool,
+                true,
+                TOKEN_PROGRAM_ID,
+              ),
+              isSigner: false,
+              isWritable: true,
+            },
+            {
+              pubkey: getMMMSellStatePDA(
+                program.programId,
+                poolData.poolKey,
+                mint,
+              ).key,
+              isSigner: false,
+              isWritable: true,
+            },
+            {
+              pubkey: getMMMSellStatePDA(program.programId, newPool, mint).key,
+              isSigner: false,
+              isWritable: true,
+            },
+          ];
+        }),
+      )
+    ).flat();
+
+    await program.methods
+      .transferPoolOwnership()
+      .accountsStrict({
+        owner: wallet.publicKey,
+        newOwner: newOwner.publicKey,
+        cosigner: cosigner.publicKey,
+        pool: poolData.poolKey,
+        newPool,
+        buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+        newBuysideSolEscrowAccount: getMMMBuysideSolEscrowPDA(
+          program.programId,
+          newPool,
+        ).key,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([newOwner, cosigner])
+      .rpc();
+    return newPool;
+  };
+
+  const buyNftFromPool = async (
+    poolData: PoolData,
+    pool: PublicKey,
+    owner: PublicKey,
+    buyer: Keypair,
+  ) => {
+    const nftMint = toWeb3JsPublicKey(poolData.nft.mintAddress);
+    const tx = await program.methods
+      .solFulfillSell({
+        assetAmount: new anchor.BN(1),
+        maxPaymentAmount: new anchor.BN(2 * LAMPORTS_PER_SOL),
+        maxPricePerItem: null,
+        buysideCreatorRoyaltyBp: 0,
+        allowlistAux: '',
+        makerFeeBp: 0,
+        takerFeeBp: 0,
+        partialFillOk: false,
+        royaltyBpOverride: null,
+      })
+      .accountsStrict({
+        payer: buyer.publicKey,
+        owner,
+        cosigner: cosigner.publicKey,
+        referral: poolData.referral.publicKey,
+        takerReferral: poolData.referral.publicKey,
+        pool,
+        buysideSolEscrowAccount: getMMMBuysideSolEscrowPDA(
+          program.programId,
+          pool,
+        ).key,
+        assetMetadata: toWeb3JsPublicKey(poolData.nft.metadataAddress),
+        assetMasterEdition: toWeb3JsPublicKey(
+          poolData.nft.masterEditionAddress,
+        ),
+        assetMint: nftMint,
+        sellsideEscrowTokenAccount: await getAssociatedTokenAddress(
+          nftMint,
+          pool,
+          true,
+          TOKEN_PROGRAM_ID,
+        ),
+        payerAssetAccount: await getAssociatedTokenAddress(
+          nftMint,
+          buyer.publicKey,
+          true,
+          TOKEN_PROGRAM_ID,
+        ),
+        allowlistAuxAccount: SystemProgram.programId,
+        sellState: getMMMSellStatePDA(program.programId, pool, nftMint).key,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .transaction();
+
+    const blockhashData = await connection.getLatestBlockhash();
+    tx.feePayer = buyer.publicKey;
+    tx.recentBlockhash = blockhashData.blockhash;
+    tx.partialSign(cosigner, buyer);
+    const txId = await connection.sendRawTransaction(tx.serialize(), {
+      skipPreflight: true,
+    });
+    const confirmedTx = await connection.confirmTransaction(
+      {
+        signature: txId,
+        blockhash: blockhashData.blockhash,
+        lastValidBlockHeight: blockhashData.lastValidBlockHeight,
+      },
+      'processed',
+    );
+    return { txId, confirmedTx };
+  };
+
+  it('moves the pool and its inventory to the new owner', async () => {
+    const buyer = Keypair.generate();
+    const newOwner = Keypair.generate();
+    const [poolData] = await Promise.all([
+      createTestPool(buyer.publicKey),
+      airdrop(connection, buyer.publicKey, 10),
+      airdrop(connection, newOwner.publicKey, 1),
+    ]);
+    const poolAccountInfo = await program.account.pool.fetch(poolData.poolKey);
+
+    const newPool = await transferPoolOwnership(poolData, newOwner);
+
+    // the old pool and everything derived from it is closed
+    const nftMint = toWeb3JsPublicKey(poolData.nft.mintAddress);
+    for (const account of [
+      poolData.poolKey,
+      poolData.poolPaymentEscrow,
+      poolData.poolAtaNft,
+      poolData.poolAtaSft,
+      getMMMSellStatePDA(program.programId, poolData.poolKey, nftMint).key,
+    ]) {
+      assert.equal(await connection.getBalance(account), 0);
+    }
+
+    const newPoolAccountInfo = await program.account.pool.fetch(newPool);
+    assert.equal(
+      newPoolAccountInfo.owner.toBase58(),
+      newOwner.publicKey.toBase58(),
+    );
+    assert.equal(
+      newPoolAccountInfo.cosigner.toBase58(),
+      cosigner.publicKey.toBase58(),
+    );
+    assert.equal(
+      newPoolAccountInfo.uuid.toBase58(),
+      poolData.uuid.publicKey.toBase58(),
+    );
+    assert.equal(
+      newPoolAccountInfo.spotPrice.toNumber(),
+      poolAccountInfo.spotPrice.toNumber(),
+    );
+    assert.equal(newPoolAccountInfo.sellsideAssetAmount.toNumber(), 6);
+    assert.equal(
+      newPoolAccountInfo.buysidePaymentAmount.toNumber(),
+      10 * LAMPORTS_PER_SOL,
+    );
+    assert.equal(
+      await connection.getBalance(
+        getMMMBuysideSolEscrowPDA(program.programId, newPool).key,
+      ),
+      10 * LAMPORTS_PER_SOL,
+    );
+    const newSellState = await program.account.sellState.fetch(
+      getMMMSellStatePDA(program.programId, newPool, nftMint).key,
+    );
+    assert.equal(newSellState.pool.toBase58(), newPool.toBase58());
+    assert.equal(
+      newSellState.poolOwner.toBase58(),
+      newOwner.publicKey.toBase58(),
+    );
+    assert.equal(newSellState.assetAmount.toNumber(), 1);
+
+    // fills authorize against the new owner, and the payment goes to them
+    {
+      const { txId, confirmedTx } = await buyNftFromPool(
+        poolData,
+        newPool,
+        wallet.publicKey,
+        buyer,
+      );
+      assertFailedTx(txId, confirmedTx);
+    }
+    const initNewOwnerBalance = await connection.getBalance(
+      newOwner.publicKey,
+    );
+    const { txId, confirmedTx } = await buyNftFromPool(
+      poolData,
+      newPool,
+      newOwner.publicKey,
+      buyer,
+    );
+    assertTx(txId, confirmedTx);
+    assert.isAtLeast(
+      (await connection.getBalance(newOwner.publicKey)) - initNewOwnerBalance,
+      1.1 * LAMPORTS_PER_SOL,
+    );
+  });
+
+  it('can not transfer the pool to its owner', async () => {
+    const poolData = await createTestPool(wallet.publicKey);
+    try {
+      await transferPoolOwnership(poolData, wallet.payer);
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'InvalidOwner');
+    }
+  });
+
+  it('can not transfer the pool without all of its sellside assets', async () => {
+    const newOwner = Keypair.generate();
+    const poolData = await createTestPool(wallet.publicKey);
+    try {
+      // the sft is left out, so the old pool would still hold it
+      await transferPoolOwnership(poolData, newOwner, [poolData.nft]);
+      assert.fail('should have failed');
+    } catch (e) {
+      assert.include(`${e}`, 'NotEmptySellsideAssetAmount');
+    }
+  });
+});
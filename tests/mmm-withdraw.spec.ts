@@ -6,8 +6,10 @@ import {
   TOKEN_2022_PROGRAM_ID,
 } from '@solana/spl-token';
 import {
+  AccountMeta,
   Keypair,
   LAMPORTS_PER_SOL,
+  PublicKey,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
 } from '@solana/web3.js';
@@ -26,6 +28,7 @@ import {
   createPoolWithExampleDepositsUmi,
   getTokenAccount2022,
   LAMPORT_ERROR_RANGE,
+  PoolData,
   SIGNATURE_FEE_LAMPORTS,
 } from './utils';
 import { toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
@@ -233,6 +236,155 @@ describe('mmm-withdraw', () => {
         assert.equal(Number(ownerNftAta.amount), 1);
         assert.equal(ownerNftAta.owner.toBase58(), wallet.publicKey.toBase58());
       });
+
+      describe('owner_drain_and_close', () => {
+        const createDrainPool = () =>
+          createPoolWithExampleDepositsUmi(
+            program,
+            [AllowlistKind.mint],
+            {
+              owner: wallet.publicKey,
+              cosigner,
+              curveType: CurveKind.linear,
+              curveDelta: new anchor.BN(LAMPORTS_PER_SOL).div(
+                new anchor.BN(10),
+              ), // 0.1 SOL
+              expiry: new anchor.BN(new Date().getTime() / 1000 + 1000),
+            },
+            'both',
+            tokenProgramId,
+            wallet.publicKey,
+          );
+
+        const drainAndClose = async (
+          poolData: PoolData,
+          assets: { mint: PublicKey; poolAta: PublicKey }[],
+        ) => {
+          // asset_mint, sellside_escrow_token_account, owner_asset_account, sell_state
+          const remainingAccounts: AccountMeta[] = (
+            await Promise.all(
+              assets.map(async ({ mint, poolAta }) => [
+                { pubkey: mint, isSigner: false, isWritable: false },
+                { pubkey: poolAta, isSigner: false, isWritable: true },
+                {
+                  pubkey: await getAssociatedTokenAddress(
+                    mint,
+                    wallet.publicKey,
+                    true,
+                    tokenProgramId,
+                  ),
+                  isSigner: false,
+                  isWritable: true,
+                },
+                {
+                  pubkey: getMMMSellStatePDA(
+                    program.programId,
+                    poolData.poolKey,
+                    mint,
+                  ).key,
+                  isSigner: false,
+                  isWritable: true,
+                },
+              ]),
+            )
+          ).flat();
+
+          await program.methods
+            .ownerDrainAndClose()
+            .accountsStrict({
+              owner: wallet.publicKey,
+              cosigner: cosigner.publicKey,
+              pool: poolData.poolKey,
+              buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+              systemProgram: SystemProgram.programId,
+              tokenProgram: tokenProgramId,
+              associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+              rent: SYSVAR_RENT_PUBKEY,
+            })
+            .remainingAccounts(remainingAccounts)
+            .signers([cosigner])
+            .rpc();
+        };
+
+        it('returns the escrow and the sellside assets and closes the pool', async () => {
+          const poolData = await createDrainPool();
+          const nftMint = toWeb3JsPublicKey(poolData.nft.mintAddress);
+          const sftMint = toWeb3JsPublicKey(poolData.sft.mintAddress);
+          const [ownerNftAtaAddress, ownerSftAtaAddress] = await Promise.all(
+            [nftMint, sftMint].map((mint) =>
+              getAssociatedTokenAddress(
+                mint,
+                wallet.publicKey,
+                true,
+                tokenProgramId,
+              ),
+            ),
+          );
+          const [initWalletBalance, initOwnerSftAta] = await Promise.all([
+            connection.getBalance(wallet.publicKey),
+            getTokenAccount2022(connection, ownerSftAtaAddress, tokenProgramId),
+          ]);
+
+          await drainAndClose(poolData, [
+            { mint: nftMint, poolAta: poolData.poolAtaNft },
+            { mint: sftMint, poolAta: poolData.poolAtaSft },
+          ]);
+
+          const [ownerNftAta, ownerSftAta] = await Promise.all([
+            getTokenAccount2022(connection, ownerNftAtaAddress, tokenProgramId),
+            getTokenAccount2022(connection, ownerSftAtaAddress, tokenProgramId),
+          ]);
+          assert.equal(Number(ownerNftAta.amount), 1);
+          assert.equal(
+            Number(ownerSftAta.amount),
+            Number(initOwnerSftAta.amount) + 5,
+          );
+
+          const closedAccounts = [
+            poolData.poolKey,
+            poolData.poolPaymentEscrow,
+            poolData.poolAtaNft,
+            poolData.poolAtaSft,
+            getMMMSellStatePDA(program.programId, poolData.poolKey, nftMint)
+              .key,
+            getMMMSellStatePDA(program.programId, poolData.poolKey, sftMint)
+              .key,
+          ];
+          for (const account of closedAccounts) {
+            assert.equal(await connection.getBalance(account), 0);
+          }
+          assert.isAtLeast(
+            await connection.getBalance(wallet.publicKey),
+            initWalletBalance +
+              10 * LAMPORTS_PER_SOL -
+              2 * SIGNATURE_FEE_LAMPORTS,
+          );
+        });
+
+        it('can not close the pool with sellside assets left', async () => {
+          const poolData = await createDrainPool();
+          try {
+            await drainAndClose(poolData, [
+              {
+                mint: toWeb3JsPublicKey(poolData.nft.mintAddress),
+                poolAta: poolData.poolAtaNft,
+              },
+            ]);
+            assert.fail('should have failed');
+          } catch (e) {
+            assert.include(`${e}`, 'NotEmptySellsideAssetAmount');
+          }
+
+          const poolAccountInfo = await program.account.pool.fetch(
+            poolData.poolKey,
+          );
+          assert.equal(poolAccountInfo.sellsideAssetAmount.toNumber(), 6);
+          assert.equal(
+            poolAccountInfo.buysidePaymentAmount.toNumber(),
+            10 * LAMPORTS_PER_SOL,
+          );
+        });
+      });
     });
   });
 });
@@ -1,10 +1,12 @@
 import { Account, AccountLayout, unpackAccount } from '@solana/spl-token';
 import * as anchor from '@project-serum/anchor';
 import {
+  AddressLookupTableProgram,
   Connection,
   Keypair,
   LAMPORTS_PER_SOL,
   PublicKey,
+  sendAndConfirmTransaction,
   Transaction,
   VersionedTransaction,
 } from '@solana/web3.js';
@@ -80,7 +82,9 @@ export const getPoolRent = async (conn: Connection) => {
   if (poolRent) {
     return poolRent;
   }
-  poolRent = await conn.getMinimumBalanceForRentExemption(849);
+  poolRent = await conn.getMinimumBalanceForRentExemption(
+    915, // see Pool::LEN
+  );
   return poolRent;
 };
 
@@ -116,6 +120,78 @@ export const airdrop = async (
   });
 };
 
+// the validator clock can drift from the local clock, so use the chain time
+export const getChainTime = async (connection: Connection) => {
+  const blockTime = await connection.getBlockTime(
+    await connection.getSlot('processed'),
+  );
+  return blockTime!;
+};
+
+export const waitForChainTime = async (
+  connection: Connection,
+  timestamp: number,
+) => {
+  while ((await getChainTime(connection)) <= timestamp) {
+    await new Promise((resolve) => setTimeout(resolve, 500));
+  }
+};
+
+// batch instructions pass more accounts than fit in a legacy transaction,
+// so they are sent as v0 transactions with the accounts in a lookup table
+export const createLookupTable = async (
+  connection: Connection,
+  authority: Keypair,
+  addresses: PublicKey[],
+) => {
+  const [createIx, lookupTable] = AddressLookupTableProgram.createLookupTable({
+    authority: authority.publicKey,
+    payer: authority.publicKey,
+    recentSlot: await connection.getSlot('finalized'),
+  });
+  await sendAndConfirmTransaction(
+    connection,
+    new Transaction().add(createIx),
+    [authority],
+    { commitment: 'processed' },
+  );
+  for (let i = 0; i < addresses.length; i += 20) {
+    const extendIx = AddressLookupTableProgram.extendLookupTable({
+      lookupTable,
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      addresses: addresses.slice(i, i + 20),
+    });
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(extendIx),
+      [authority],
+      { commitment: 'processed' },
+    );
+  }
+  // the addresses can only be looked up from the slot after the last extension
+  const slot = await connection.getSlot('processed');
+  while ((await connection.getSlot('processed')) <= slot) {
+    await new Promise((resolve) => setTimeout(resolve, 100));
+  }
+  return (await connection.getAddressLookupTable(lookupTable)).value!;
+};
+
+export const getTransactionLogs = async (
+  connection: Connection,
+  txId: string,
+) => {
+  await connection.confirmTransaction(
+    { signature: txId, ...(await connection.getLatestBlockhash()) },
+    'confirmed',
+  );
+  const tx = await connection.getTransaction(txId, {
+    commitment: 'confirmed',
+    maxSupportedTransactionVersion: 0,
+  });
+  return tx?.meta?.logMessages ?? [];
+};
+
 export const sendAndAssertTx = async (
   conn: Connection,
   tx: Transaction | VersionedTransaction,
@@ -17,6 +17,7 @@ import { mplTokenMetadata } from '@metaplex-foundation/mpl-token-metadata';
 import * as anchor from '@project-serum/anchor';
 import { Program } from '@project-serum/anchor';
 import {
+  AccountMeta,
   ComputeBudgetProgram,
   Connection,
   Keypair,
@@ -41,6 +42,7 @@ import {
 import { PROGRAM_ID as AUTHORIZATION_RULES_PROGRAM_ID } from '@metaplex-foundation/mpl-token-auth-rules';
 import {
   AllowlistKind,
+  AllowlistMatchMode,
   CurveKind,
   getM2BuyerSharedEscrow,
   getMMMBuysideSolEscrowPDA,
@@ -106,6 +108,40 @@ export const createPool = async (
     uuid?: PublicKey;
     paymentMint?: PublicKey;
     buysideCreatorRoyaltyBp?: number;
+    priceFloor?: anchor.BN;
+    priceCeiling?: anchor.BN;
+    allowlistMatchMode?: AllowlistMatchMode;
+    lpFeeDecayEnd?: anchor.BN;
+    lpFeeBpFloor?: number;
+    lpFeeTiers?: { minAssetAmount: anchor.BN; lpFeeBp: number }[];
+    minEscrowBalanceBp?: number;
+    extraRoyaltyRecipients?: { address: PublicKey; bp: number }[];
+    maxSpotMoveBp?: number;
+    lpFeeRounding?: number;
+    maxFillDepth?: number;
+    maxBuysideSpend?: anchor.BN;
+    poolSide?: number;
+    requireSizedCollection?: boolean;
+    rejectSelfReferral?: boolean;
+    settleInWsol?: boolean;
+    requireOneOfOne?: boolean;
+    requireProgrammable?: boolean;
+    curveDeltaSell?: anchor.BN;
+    royaltyEnforcementMode?: number;
+    maxTotalPrice?: anchor.BN;
+    minFillAmount?: anchor.BN;
+    fillCooldownSecs?: anchor.BN;
+    sellStateRentToTaker?: boolean;
+    allowlistAux?: string;
+    royaltyFallbackAccount?: PublicKey;
+    profitSkimBp?: number;
+    oracle?: PublicKey;
+    maxOracleDeviationBp?: number;
+    requireAllCreatorsPaid?: boolean;
+    curveDirection?: number;
+    requireCollectionMetadata?: boolean;
+    staticSpot?: boolean;
+    allowRoyaltyFallback?: boolean;
   },
 ) => {
   const referral = Keypair.generate();
@@ -113,11 +149,11 @@ export const createPool = async (
   const { key: poolKey } = getMMMPoolPDA(
     program.programId,
     args.owner,
-    uuid.publicKey,
+    args.uuid ?? uuid.publicKey,
   );
   const allowlists = [
     { kind: AllowlistKind.fvca, value: referral.publicKey },
-    ...getEmptyAllowLists(5),
+    ...getEmptyAllowLists(7),
   ];
   const defaults = {
     spotPrice: new anchor.BN(1 * LAMPORTS_PER_SOL),
@@ -131,6 +167,40 @@ export const createPool = async (
     referralBp: 300,
     cosignerAnnotation: new Array(32).fill(0),
     buysideCreatorRoyaltyBp: 0,
+    priceFloor: null,
+    priceCeiling: null,
+    allowlistMatchMode: null,
+    lpFeeDecayEnd: null,
+    lpFeeBpFloor: null,
+    lpFeeTiers: null,
+    minEscrowBalanceBp: null,
+    extraRoyaltyRecipients: null,
+    maxSpotMoveBp: null,
+    lpFeeRounding: null,
+    maxFillDepth: null,
+    maxBuysideSpend: null,
+    poolSide: null,
+    requireSizedCollection: null,
+    rejectSelfReferral: null,
+    settleInWsol: null,
+    requireOneOfOne: null,
+    requireProgrammable: null,
+    curveDeltaSell: null,
+    royaltyEnforcementMode: null,
+    maxTotalPrice: null,
+    minFillAmount: null,
+    fillCooldownSecs: null,
+    sellStateRentToTaker: null,
+    allowlistAux: null,
+    royaltyFallbackAccount: null,
+    profitSkimBp: null,
+    oracle: null,
+    maxOracleDeviationBp: null,
+    requireAllCreatorsPaid: null,
+    curveDirection: null,
+    requireCollectionMetadata: null,
+    staticSpot: null,
+    allowRoyaltyFallback: null,
 
     owner: args.owner,
     cosigner: args.cosigner?.publicKey ?? args.owner,
@@ -163,6 +233,8 @@ export const createPoolWithExampleT22ExtDeposits = async (
   poolArgs: Parameters<typeof createPool>[1],
   sharedEscrow?: boolean,
   sharedEscrowCount?: number,
+  assetAmount: number = 1, // minted and deposited on the sellside
+  additionalMetadata: [string, string][] = [],
 ) => {
   const { groupAddress } = await createTestGroupMintExt(connection, payer);
   const { mint, recipientTokenAccount } =
@@ -171,6 +243,10 @@ export const createPoolWithExampleT22ExtDeposits = async (
       payer,
       poolArgs.owner,
       groupAddress,
+      undefined,
+      additionalMetadata,
+      'example.com',
+      assetAmount,
     );
 
   const poolData = await createPool(program, {
@@ -183,7 +259,10 @@ export const createPoolWithExampleT22ExtDeposits = async (
         kind: AllowlistKind.group,
         value: groupAddress,
       },
-      ...getEmptyAllowLists(4),
+      // ext pools only accept a supply above 1 for explicitly allowlisted mints
+      ...(assetAmount > 1
+        ? [{ kind: AllowlistKind.mint, value: mint }, ...getEmptyAllowLists(5)]
+        : getEmptyAllowLists(6)),
     ],
     ...poolArgs,
   });
@@ -204,7 +283,7 @@ export const createPoolWithExampleT22ExtDeposits = async (
   if (!sharedEscrow && (side === 'both' || side === 'sell')) {
     await program.methods
       .extDepositSell({
-        assetAmount: new anchor.BN(1),
+        assetAmount: new anchor.BN(assetAmount),
         allowlistAux: 'example.com',
       })
       .accountsStrict({
@@ -443,7 +522,7 @@ export const createPoolWithExampleDeposits = async (
         }
       })
       .flat(),
-    6,
+    8,
   );
 
   const poolData = await createPool(program, {
@@ -622,7 +701,7 @@ export const createPoolWithExampleOcpDeposits = async (
 
   const allowlists = [
     { kind: AllowlistKind.fvca, value: creator.publicKey },
-    ...getEmptyAllowLists(5),
+    ...getEmptyAllowLists(7),
   ];
   const poolData = await createPool(program, {
     ...poolArgs,
@@ -733,6 +812,9 @@ export async function createPoolWithExampleDepositsUmi(
   side: 'buy' | 'sell' | 'both',
   tokenProgramId: PublicKey,
   nftRecipient: PublicKey,
+  sellerFeeBasisPoints?: number,
+  sizedCollection = false,
+  collectionPool = false, // use the mcc collection mint as the uuid
 ): Promise<PoolData> {
   const umi = (await createUmi('http://127.0.0.1:8899')).use(
     mplTokenMetadata(),
@@ -768,7 +850,7 @@ export async function createPoolWithExampleDepositsUmi(
           {
             numNfts: 0,
             verifyCollection: false,
-            legacy: true,
+            legacy: !sizedCollection,
           },
           tokenProgramId,
         )
@@ -787,6 +869,7 @@ export async function createPoolWithExampleDepositsUmi(
           ],
           verifyCollection: kindToUse === AllowlistKind.mcc, // only verify collection if we're using mcc
           collectionAddress: collection ? collection.mintAddress : undefined,
+          sellerFeeBasisPoints,
         },
         tokenProgramId,
       )),
@@ -804,6 +887,7 @@ export async function createPoolWithExampleDepositsUmi(
           sftAmount: 10,
           verifyCollection: kindToUse === AllowlistKind.mcc,
           collectionAddress: collection ? collection.mintAddress : undefined,
+          sellerFeeBasisPoints,
         },
         tokenProgramId,
       )),
@@ -820,6 +904,7 @@ export async function createPoolWithExampleDepositsUmi(
           creatorSigner: creator,
           verifyCollection: kindToUse === AllowlistKind.mcc,
           collectionAddress: collection ? collection.mintAddress : undefined,
+          sellerFeeBasisPoints,
         },
         tokenProgramId,
       )),
@@ -837,6 +922,7 @@ export async function createPoolWithExampleDepositsUmi(
           sftAmount: 10,
           verifyCollection: kindToUse === AllowlistKind.mcc,
           collectionAddress: collection ? collection.mintAddress : undefined,
+          sellerFeeBasisPoints,
         },
         tokenProgramId,
       )),
@@ -907,12 +993,13 @@ export async function createPoolWithExampleDepositsUmi(
         }
       })
       .flat(),
-    6,
+    8,
   );
 
   const poolData = await createPool(program, {
     ...poolArgs,
     allowlists,
+    uuid: collectionPool ? allowlistValue! : poolArgs.uuid,
   });
   const poolKey = poolData.poolKey;
 
@@ -1074,7 +1161,7 @@ export const createPoolWithExampleMip1Deposits = async (
 
   const allowlists = [
     { kind: AllowlistKind.fvca, value: nftCreator.publicKey },
-    ...getEmptyAllowLists(5),
+    ...getEmptyAllowLists(7),
   ];
   const poolData = await createPool(program, {
     ...poolArgs,
@@ -1185,3 +1272,81 @@ export const createPoolWithExampleMip1Deposits = async (
     ...poolData,
   };
 };
+
+// sells the example extra nft into a buyside pool and returns the confirmed tx,
+// remainingAccounts are the royalty recipients when the pool pays royalty
+export const sellExtraNftToPool = async (
+  program: Program<Mmm>,
+  poolData: PoolData,
+  owner: PublicKey,
+  cosigner: Keypair,
+  seller: Keypair,
+  remainingAccounts: AccountMeta[] = [],
+  minPricePerItem: anchor.BN | null = null,
+) => {
+  const connection = program.provider.connection;
+  const extraNftMint = toWeb3JsPublicKey(poolData.extraNft.mintAddress);
+  const ownerExtraNftAtaAddress = await getAssociatedTokenAddress(
+    extraNftMint,
+    owner,
+    true,
+    TOKEN_PROGRAM_ID,
+  );
+  const { key: sellState } = getMMMSellStatePDA(
+    program.programId,
+    poolData.poolKey,
+    extraNftMint,
+  );
+  const tx = await program.methods
+    .solFulfillBuy({
+      assetAmount: new anchor.BN(1),
+      minPaymentAmount: new anchor.BN(1),
+      allowlistAux: null,
+      takerFeeBp: 0,
+      makerFeeBp: 0,
+      minPricePerItem,
+    })
+    .accountsStrict({
+      payer: seller.publicKey,
+      owner,
+      cosigner: cosigner.publicKey,
+      referral: poolData.referral.publicKey,
+      takerReferral: poolData.referral.publicKey,
+      pool: poolData.poolKey,
+      buysideSolEscrowAccount: poolData.poolPaymentEscrow,
+      assetMetadata: toWeb3JsPublicKey(poolData.extraNft.metadataAddress),
+      assetMasterEdition: toWeb3JsPublicKey(
+        poolData.extraNft.masterEditionAddress,
+      ),
+      assetMint: extraNftMint,
+      payerAssetAccount: toWeb3JsPublicKey(poolData.extraNft.tokenAddress!),
+      sellsideEscrowTokenAccount: poolData.poolAtaExtraNft,
+      ownerTokenAccount: ownerExtraNftAtaAddress,
+      allowlistAuxAccount: SystemProgram.programId,
+      sellState,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .remainingAccounts(remainingAccounts)
+    .transaction();
+
+  const blockhashData = await connection.getLatestBlockhash();
+  tx.feePayer = seller.publicKey;
+  tx.recentBlockhash = blockhashData.blockhash;
+  tx.partialSign(cosigner, seller);
+  const txId = await connection.sendRawTransaction(tx.serialize(), {
+    skipPreflight: true,
+  });
+  const confirmedTx = await connection.confirmTransaction(
+    {
+      signature: txId,
+      blockhash: blockhashData.blockhash,
+      lastValidBlockHeight: blockhashData.lastValidBlockHeight,
+    },
+    'processed',
+  );
+  return { txId, confirmedTx };
+};
+
@@ -24,6 +24,9 @@ import {
   getAssociatedTokenAddressSync,
   getMintLen,
   createInitializeGroupPointerInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializeTransferHookInstruction,
+  createUpdateFieldInstruction,
 } from '@solana/spl-token';
 import {
   createInitializeGroupInstruction,
@@ -138,6 +141,11 @@ export async function createTestMintAndTokenT22VanillaExt(
   recipient?: PublicKey,
   groupAddress?: PublicKey,
   groupMemberAddress?: PublicKey,
+  additionalMetadata: [string, string][] = [],
+  uri: string = 'example.com',
+  amount: number = 1,
+  transferFeeBp?: number,
+  transferHookProgramId?: PublicKey,
 ) {
   const mintKeypair = Keypair.generate();
   const effectiveGroupAddress = groupAddress ?? Keypair.generate().publicKey;
@@ -153,9 +161,16 @@ export async function createTestMintAndTokenT22VanillaExt(
   const mintSpace = getMintLen([
     ExtensionType.MetadataPointer,
     ExtensionType.GroupMemberPointer,
+    ...(transferFeeBp !== undefined ? [ExtensionType.TransferFeeConfig] : []),
+    ...(transferHookProgramId !== undefined ? [ExtensionType.TransferHook] : []),
   ]);
+  // each additional metadata field is stored as two length prefixed strings
+  const additionalMetadataSpace = additionalMetadata.reduce(
+    (acc, [key, value]) => acc + 8 + key.length + value.length,
+    0,
+  );
   const mintLamports = await connection.getMinimumBalanceForRentExemption(
-    mintSpace * 2,
+    mintSpace * 2 + additionalMetadataSpace,
   );
 
   const createMintAccountIx = SystemProgram.createAccount({
@@ -180,6 +195,30 @@ export async function createTestMintAndTokenT22VanillaExt(
       memberAddress,
       tokenProgramId,
     );
+  const createTransferFeeConfigIxs =
+    transferFeeBp !== undefined
+      ? [
+          createInitializeTransferFeeConfigInstruction(
+            mintKeypair.publicKey,
+            payer.publicKey,
+            payer.publicKey,
+            transferFeeBp,
+            BigInt(amount),
+            tokenProgramId,
+          ),
+        ]
+      : [];
+  const createTransferHookIxs =
+    transferHookProgramId !== undefined
+      ? [
+          createInitializeTransferHookInstruction(
+            mintKeypair.publicKey,
+            payer.publicKey,
+            transferHookProgramId,
+            tokenProgramId,
+          ),
+        ]
+      : [];
   const createInitMintIx = createInitializeMint2Instruction(
     mintKeypair.publicKey,
     0,
@@ -195,9 +234,18 @@ export async function createTestMintAndTokenT22VanillaExt(
     mintAuthority: payer.publicKey,
     name: 'xyzname',
     symbol: 'xyz',
-    uri: 'example.com',
+    uri,
     programId: tokenProgramId,
   });
+  const updateFieldIxs = additionalMetadata.map(([field, value]) =>
+    createUpdateFieldInstruction({
+      metadata: mintKeypair.publicKey,
+      updateAuthority: payer.publicKey,
+      field,
+      value,
+      programId: tokenProgramId,
+    }),
+  );
 
   const createGroupMemberIx = createInitializeMemberInstruction({
     programId: tokenProgramId,
@@ -220,7 +268,7 @@ export async function createTestMintAndTokenT22VanillaExt(
     mintKeypair.publicKey,
     targetTokenAccount,
     payer.publicKey,
-    1, // amount
+    amount,
     [],
     tokenProgramId,
   );
@@ -230,8 +278,11 @@ export async function createTestMintAndTokenT22VanillaExt(
     createMintAccountIx,
     createGroupMemberPointerIx,
     createMetadataPointerIx,
+    ...createTransferFeeConfigIxs,
+    ...createTransferHookIxs,
     createInitMintIx,
     createMetadataIx,
+    ...updateFieldIxs,
     createGroupMemberIx,
     createAtaIx,
     mintToIx,
@@ -258,7 +258,16 @@ const createNewMintTransaction = async (
   return createNewTokenTransaction;
 };
 
-export const createPolicyFixture = async (conn: Connection, payer: Keypair) => {
+export const createPolicyFixture = async (
+  conn: Connection,
+  payer: Keypair,
+  dynamicRoyalty = {
+    startMultiplierBp: 10000,
+    endMultiplierBp: 0,
+    startPrice: new BN(0),
+    endPrice: new BN(5 * LAMPORTS_PER_SOL),
+  },
+) => {
   const uuid = Keypair.generate().publicKey;
   const policy = findPolicyPk(uuid);
   const jsonRule = JSON.stringify({
@@ -294,12 +303,7 @@ export const createPolicyFixture = async (conn: Connection, payer: Keypair) => {
       ],
     },
   });
-  const dr = createDynamicRoyaltyStruct({
-    startMultiplierBp: 10000,
-    endMultiplierBp: 0,
-    startPrice: new BN(0),
-    endPrice: new BN(5 * LAMPORTS_PER_SOL),
-  });
+  const dr = createDynamicRoyaltyStruct(dynamicRoyalty);
   const ix = createInitPolicyInstruction(
     { policy, uuid, authority: payer.publicKey },
     { arg: { jsonRule, dynamicRoyalty: dr } },
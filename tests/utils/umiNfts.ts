@@ -12,6 +12,7 @@ import {
   findMetadataPda,
   findMasterEditionPda,
   mintV1,
+  printSupply,
   TokenStandard,
   createV1,
   Collection,
@@ -37,12 +38,15 @@ export async function umiMintNfts(
   umi: Umi,
   config: {
     numNfts: number;
-    creators?: Creator[];
+    creators?: Creator[] | null; // null mints without creators
     recipient?: PublicKey;
     collectionAddress?: PublicKey;
     verifyCollection: boolean;
     creatorSigner?: KeypairSigner;
     sftAmount?: number; // if this is set, will mint sft instread of nft
+    sellerFeeBasisPoints?: number; // defaults to 100
+    isCollection?: boolean; // mints sized collection parents
+    printSupply?: number; // max prints of the master edition, defaults to 0
   },
   splTokenProgramId: Web3PublicKey,
 ): Promise<Nft[]> {
@@ -90,10 +94,19 @@ export async function umiMintNfts(
             mint: nft.mintSigner,
             name: `TEST #${i}`,
             uri: `nft://${i}.json`,
-            sellerFeeBasisPoints: createAmount(100, '%', 2),
+            sellerFeeBasisPoints: createAmount(
+              config.sellerFeeBasisPoints ?? 100,
+              '%',
+              2,
+            ),
             collection,
             creators: config.creators,
             tokenStandard: TokenStandard.NonFungible,
+            isCollection: config.isCollection,
+            printSupply:
+              config.printSupply === undefined
+                ? undefined
+                : printSupply('Limited', [config.printSupply]),
             splTokenProgram,
           }).sendAndConfirm(umi, { send: { skipPreflight: true } });
           await mintV1(umi, {
@@ -111,7 +124,11 @@ export async function umiMintNfts(
             authority: umi.identity,
             name: `TEST #${i}`,
             uri: `nft://${i}.json`,
-            sellerFeeBasisPoints: createAmount(100, '%', 2),
+            sellerFeeBasisPoints: createAmount(
+              config.sellerFeeBasisPoints ?? 100,
+              '%',
+              2,
+            ),
             collection,
             creators: config.creators,
             splTokenProgram,
@@ -168,6 +185,7 @@ export const umiMintCollection = async (
         numNfts: 1,
         creators: config.creators,
         verifyCollection: config.verifyCollection,
+        isCollection: !config.legacy,
       },
       tokenProgramId,
     )